@@ -1,3 +1,4 @@
+use super::config_area::ConfigArea;
 use super::memory::MemoryRegion;
 use crate::{serialize::hex_option, CoreType};
 use serde::{Deserialize, Serialize};
@@ -73,6 +74,9 @@ pub struct Chip {
     pub scan_chain: Option<Vec<ScanChainElement>>,
     /// The default binary format for this chip
     pub default_binary_format: Option<BinaryFormat>,
+    /// The configuration areas of the chip, such as option bytes, UICR or eFuses.
+    #[serde(default)]
+    pub config_areas: Vec<ConfigArea>,
 }
 
 impl Chip {
@@ -94,6 +98,7 @@ impl Chip {
             rtt_scan_ranges: None,
             scan_chain: Some(vec![]),
             default_binary_format: Some(BinaryFormat::Raw),
+            config_areas: vec![],
         }
     }
 }
@@ -142,8 +147,16 @@ pub struct ArmCoreAccessOptions {
 }
 
 /// The data required to access a Risc-V core
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RiscvCoreAccessOptions {}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RiscvCoreAccessOptions {
+    /// The index of the hart in the debug module.
+    /// Defaults to the index of the core in the chip.
+    pub hart_id: Option<u32>,
+    /// Put all harts of the debug module into one halt group, so that all of them halt
+    /// as soon as one of them halts, e.g. on a breakpoint.
+    #[serde(default)]
+    pub halt_group: bool,
+}
 
 /// The data required to access an Xtensa core
 #[derive(Debug, Clone, Serialize, Deserialize)]
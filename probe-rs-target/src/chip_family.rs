@@ -1,6 +1,7 @@
 use crate::CoreAccessOptions;
 
 use super::chip::Chip;
+use super::config_area::ConfigArea;
 use super::flash_algorithm::RawFlashAlgorithm;
 use jep106::JEP106Code;
 
@@ -91,6 +92,10 @@ pub enum InstructionSet {
     RV32,
     /// RISC-V 32-bit compressed instruction sets (RV32C) - covers all ISA variants that allow compressed 16-bit instructions.
     RV32C,
+    /// RISC-V 64-bit uncompressed instruction sets (RV64) - covers all ISA variants that use 32-bit instructions.
+    RV64,
+    /// RISC-V 64-bit compressed instruction sets (RV64C) - covers all ISA variants that allow compressed 16-bit instructions.
+    RV64C,
     /// Xtensa instruction set
     Xtensa,
 }
@@ -105,8 +110,8 @@ impl InstructionSet {
             }
             InstructionSet::A32 => 4,
             InstructionSet::A64 => 4,
-            InstructionSet::RV32 => 4,
-            InstructionSet::RV32C => 2,
+            InstructionSet::RV32 | InstructionSet::RV64 => 4,
+            InstructionSet::RV32C | InstructionSet::RV64C => 2,
             InstructionSet::Xtensa => 2,
         }
     }
@@ -142,6 +147,13 @@ pub struct ChipFamily {
     pub variants: Vec<Chip>,
     /// This vector holds all available algorithms.
     pub flash_algorithms: Vec<RawFlashAlgorithm>,
+    /// The configuration areas shared by all variants, such as option bytes, UICR or eFuses.
+    ///
+    /// The fields of a configuration area of a variant with the same name as one of the family
+    /// are added to the fields of the family's area, so a variant only has to describe the
+    /// fields which are specific to it.
+    #[serde(default)]
+    pub config_areas: Vec<ConfigArea>,
     #[serde(skip, default = "default_source")]
     /// Source of the target description, used for diagnostics
     pub source: TargetDescriptionSource,
@@ -0,0 +1,122 @@
+use crate::serialize::hex_u_int;
+use core::ops::Range;
+use serde::{Deserialize, Serialize};
+
+/// Describes a device configuration area, such as option bytes, UICR, eFuses or a customer
+/// configuration page.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ConfigArea {
+    /// The name of the area, e.g. `UICR`.
+    pub name: String,
+    /// A short description of the area.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// The address of the start of the area.
+    #[serde(serialize_with = "hex_u_int")]
+    pub address: u64,
+    /// The fields contained in the area.
+    pub fields: Vec<ConfigField>,
+}
+
+impl ConfigArea {
+    /// Returns the field with the given name.
+    pub fn field(&self, name: &str) -> Option<&ConfigField> {
+        self.fields
+            .iter()
+            .find(|field| field.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// A named field of a [`ConfigArea`], stored in some bits of a 32-bit word of the area.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ConfigField {
+    /// The name of the field, e.g. `APPROTECT`.
+    pub name: String,
+    /// A short description of the field.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// The offset of the 32-bit word containing the field, relative to the start of the area.
+    #[serde(serialize_with = "hex_u_int")]
+    pub offset: u64,
+    /// The bits of the word which make up the field, e.g. `0..8` for the lowest byte.
+    pub bits: Range<u8>,
+    /// True if writing the field is irreversible or can render the device unusable,
+    /// e.g. because it disables the debug access.
+    #[serde(default)]
+    pub dangerous: bool,
+}
+
+impl ConfigField {
+    /// Returns the mask of the bits of the word which make up the field.
+    pub fn mask(&self) -> u32 {
+        let width = self.width();
+        let ones = if width >= 32 {
+            u32::MAX
+        } else {
+            (1 << width) - 1
+        };
+        ones.checked_shl(self.bits.start as u32).unwrap_or(0)
+    }
+
+    /// Returns the number of bits of the field.
+    pub fn width(&self) -> u32 {
+        self.bits.end.saturating_sub(self.bits.start) as u32
+    }
+
+    /// Extracts the value of the field from the word containing it.
+    pub fn extract(&self, word: u32) -> u32 {
+        (word & self.mask())
+            .checked_shr(self.bits.start as u32)
+            .unwrap_or(0)
+    }
+
+    /// Replaces the value of the field in the word containing it.
+    ///
+    /// Returns `None` if `value` does not fit into the field.
+    pub fn insert(&self, word: u32, value: u32) -> Option<u32> {
+        let shifted = value.checked_shl(self.bits.start as u32)?;
+        if shifted & !self.mask() != 0 || shifted >> self.bits.start != value {
+            return None;
+        }
+
+        Some((word & !self.mask()) | shifted)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ConfigField;
+
+    fn field(bits: core::ops::Range<u8>) -> ConfigField {
+        ConfigField {
+            name: "FIELD".to_string(),
+            description: None,
+            offset: 0,
+            bits,
+            dangerous: false,
+        }
+    }
+
+    #[test]
+    fn field_masks() {
+        assert_eq!(field(0..8).mask(), 0x0000_00FF);
+        assert_eq!(field(4..6).mask(), 0x0000_0030);
+        assert_eq!(field(31..32).mask(), 0x8000_0000);
+        assert_eq!(field(0..32).mask(), 0xFFFF_FFFF);
+    }
+
+    #[test]
+    fn extract_and_insert() {
+        let field = field(4..8);
+
+        assert_eq!(field.extract(0x1234_56A7), 0xA);
+        assert_eq!(field.insert(0x1234_56A7, 0x5), Some(0x1234_5657));
+        assert_eq!(field.insert(0x1234_56A7, 0x10), None);
+
+        let full = super::ConfigField {
+            bits: 0..32,
+            ..field
+        };
+        assert_eq!(full.insert(0, 0xDEAD_BEEF), Some(0xDEAD_BEEF));
+    }
+}
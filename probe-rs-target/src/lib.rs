@@ -13,6 +13,7 @@
 
 mod chip;
 mod chip_family;
+mod config_area;
 mod flash_algorithm;
 mod flash_properties;
 mod memory;
@@ -25,6 +26,7 @@ pub use chip::{
 pub use chip_family::{
     Architecture, ChipFamily, CoreType, InstructionSet, TargetDescriptionSource,
 };
+pub use config_area::{ConfigArea, ConfigField};
 pub use flash_algorithm::{RawFlashAlgorithm, TransferEncoding};
 pub use flash_properties::FlashProperties;
 pub use memory::{
@@ -1,6 +1,6 @@
 //! Register types and the core interface for armv6-M
 
-use super::{registers::cortex_m::*, CortexMState, Dfsr};
+use super::{cortex_m::DwtLayout, registers::cortex_m::*, CortexMState, Dfsr};
 use crate::{
     architecture::arm::{
         memory::adi_v5_memory_interface::ArmProbe, sequences::ArmDebugSequence, ArmError,
@@ -9,7 +9,8 @@ use crate::{
     error::Error,
     memory::valid_32bit_address,
     Architecture, CoreInformation, CoreInterface, CoreRegister, CoreStatus, CoreType,
-    DebugProbeError, HaltReason, InstructionSet, MemoryInterface, MemoryMappedRegister,
+    DebugProbeError, HaltReason, InstructionSet, MemoryInterface, MemoryMappedRegister, Watchpoint,
+    WatchpointKind,
 };
 use anyhow::Result;
 use bitfield::bitfield;
@@ -785,6 +786,35 @@ impl<'probe> CoreInterface for Armv6m<'probe> {
         Ok(())
     }
 
+    fn available_watchpoint_units(&mut self) -> Result<u32, Error> {
+        super::cortex_m::available_watchpoint_units(&mut *self.memory)
+    }
+
+    fn watchpoints(&mut self) -> Result<Vec<Option<Watchpoint>>, Error> {
+        super::cortex_m::watchpoints(&mut *self.memory, DwtLayout::Armv7m)
+    }
+
+    fn set_watchpoint(
+        &mut self,
+        unit_index: usize,
+        address: u64,
+        len: u64,
+        kind: WatchpointKind,
+    ) -> Result<(), Error> {
+        super::cortex_m::set_watchpoint(
+            &mut *self.memory,
+            DwtLayout::Armv7m,
+            unit_index,
+            address,
+            len,
+            kind,
+        )
+    }
+
+    fn clear_watchpoint(&mut self, unit_index: usize) -> Result<(), Error> {
+        super::cortex_m::clear_watchpoint(&mut *self.memory, unit_index)
+    }
+
     fn registers(&self) -> &'static CoreRegisters {
         &CORTEX_M_CORE_REGISTERS
     }
@@ -12,7 +12,7 @@ use super::{
         },
         cortex_m::{FP, PC, RA, SP},
     },
-    CortexAState,
+    watchpoint_kind, watchpoint_lsc, CortexAState, WatchpointRange,
 };
 use crate::{
     architecture::arm::{
@@ -23,7 +23,7 @@ use crate::{
     error::Error,
     memory::valid_32bit_address,
     Architecture, CoreInformation, CoreInterface, CoreRegister, CoreStatus, CoreType,
-    InstructionSet, MemoryInterface,
+    InstructionSet, MemoryInterface, Watchpoint, WatchpointKind,
 };
 use anyhow::Result;
 use num_traits::Zero;
@@ -61,6 +61,8 @@ pub struct Armv7a<'probe> {
 
     num_breakpoints: Option<u32>,
 
+    num_watchpoints: Option<u32>,
+
     itr_enabled: bool,
 
     id: usize,
@@ -100,6 +102,7 @@ impl<'probe> Armv7a<'probe> {
             base_address,
             sequence,
             num_breakpoints: None,
+            num_watchpoints: None,
             itr_enabled: false,
             id,
         };
@@ -729,6 +732,95 @@ impl<'probe> CoreInterface for Armv7a<'probe> {
         Ok(())
     }
 
+    fn available_watchpoint_units(&mut self) -> Result<u32, Error> {
+        if self.num_watchpoints.is_none() {
+            let address = Dbgdidr::get_mmio_address_from_base(self.base_address)?;
+            let dbgdidr = Dbgdidr(self.memory.read_word_32(address)?);
+
+            self.num_watchpoints = Some(dbgdidr.wrps() + 1);
+        }
+        Ok(self.num_watchpoints.unwrap())
+    }
+
+    fn watchpoints(&mut self) -> Result<Vec<Option<Watchpoint>>, Error> {
+        let mut watchpoints = vec![];
+        let num_watchpoints = self.available_watchpoint_units()? as usize;
+
+        for wp_unit_index in 0..num_watchpoints {
+            let wp_value_addr = Dbgwvr::get_mmio_address_from_base(self.base_address)?
+                + (wp_unit_index * size_of::<u32>()) as u64;
+            let wp_value = self.memory.read_word_32(wp_value_addr)?;
+
+            let wp_control_addr = Dbgwcr::get_mmio_address_from_base(self.base_address)?
+                + (wp_unit_index * size_of::<u32>()) as u64;
+            let wp_control = Dbgwcr(self.memory.read_word_32(wp_control_addr)?);
+
+            let watchpoint = match watchpoint_kind(wp_control.lsc()) {
+                Some(kind) if wp_control.e() => {
+                    let range = WatchpointRange::from_registers(
+                        wp_value as u64,
+                        wp_control.bas(),
+                        wp_control.mask(),
+                    );
+                    let (address, len) = range.range();
+
+                    Some(Watchpoint { address, len, kind })
+                }
+                _ => None,
+            };
+            watchpoints.push(watchpoint);
+        }
+        Ok(watchpoints)
+    }
+
+    fn set_watchpoint(
+        &mut self,
+        wp_unit_index: usize,
+        address: u64,
+        len: u64,
+        kind: WatchpointKind,
+    ) -> Result<(), Error> {
+        valid_32bit_address(address)?;
+        let range = WatchpointRange::new(address, len, 4)
+            .ok_or(Error::UnsupportedWatchpoint { address, len })?;
+
+        let wp_value_addr = Dbgwvr::get_mmio_address_from_base(self.base_address)?
+            + (wp_unit_index * size_of::<u32>()) as u64;
+        let wp_control_addr = Dbgwcr::get_mmio_address_from_base(self.base_address)?
+            + (wp_unit_index * size_of::<u32>()) as u64;
+        let mut wp_control = Dbgwcr(0);
+
+        wp_control.set_mask(range.mask());
+        // Match on all modes
+        wp_control.set_hmc(true);
+        wp_control.set_pac(0b11);
+        wp_control.set_bas(range.bas());
+        wp_control.set_lsc(watchpoint_lsc(kind));
+        // Enable
+        wp_control.set_e(true);
+
+        // Disable the watchpoint while its address is changed.
+        self.memory.write_word_32(wp_control_addr, 0)?;
+        self.memory
+            .write_word_32(wp_value_addr, range.value() as u32)?;
+        self.memory
+            .write_word_32(wp_control_addr, wp_control.into())?;
+
+        Ok(())
+    }
+
+    fn clear_watchpoint(&mut self, wp_unit_index: usize) -> Result<(), Error> {
+        let wp_value_addr = Dbgwvr::get_mmio_address_from_base(self.base_address)?
+            + (wp_unit_index * size_of::<u32>()) as u64;
+        let wp_control_addr = Dbgwcr::get_mmio_address_from_base(self.base_address)?
+            + (wp_unit_index * size_of::<u32>()) as u64;
+
+        self.memory.write_word_32(wp_control_addr, 0)?;
+        self.memory.write_word_32(wp_value_addr, 0)?;
+
+        Ok(())
+    }
+
     fn registers(&self) -> &'static CoreRegisters {
         match self.state.fp_reg_count {
             16 => &AARCH32_WITH_FP_16_CORE_REGSISTERS,
@@ -757,6 +849,33 @@ impl<'probe> CoreInterface for Armv7a<'probe> {
         true
     }
 
+    fn invalidate_instruction_cache(&mut self, address: u64, len: u64) -> Result<(), Error> {
+        // Memory is written through the core, so the new instructions may still be in the data cache.
+        self.prepare_r0_for_clobber()?;
+
+        for line_address in [address, address + len.max(1) - 1] {
+            self.set_r0(valid_32bit_address(line_address)?)?;
+
+            // DCCMVAU, clean the data cache line to the point of unification
+            let instruction = build_mcr(15, 0, 0, 7, 11, 1);
+            self.execute_instruction(instruction)?;
+        }
+
+        // DSB
+        let instruction = build_mcr(15, 0, 0, 7, 10, 4);
+        self.execute_instruction(instruction)?;
+
+        // ICIALLU, invalidate the instruction cache
+        let instruction = build_mcr(15, 0, 0, 7, 5, 0);
+        self.execute_instruction(instruction)?;
+
+        // BPIALL, invalidate the branch predictor
+        let instruction = build_mcr(15, 0, 0, 7, 5, 6);
+        self.execute_instruction(instruction)?;
+
+        Ok(())
+    }
+
     fn architecture(&self) -> Architecture {
         Architecture::Arm
     }
@@ -944,7 +1063,7 @@ impl<'probe> MemoryInterface for Armv7a<'probe> {
 
     fn write_8(&mut self, address: u64, data: &[u8]) -> Result<(), Error> {
         for (i, byte) in data.iter().enumerate() {
-            self.write_word_8(address + ((i as u64) * 4), *byte)?;
+            self.write_word_8(address + (i as u64), *byte)?;
         }
 
         Ok(())
@@ -1859,6 +1978,128 @@ mod test {
         armv7a.clear_hw_breakpoint(0).unwrap();
     }
 
+    #[test]
+    fn armv7a_watchpoints() {
+        const WP_COUNT: u32 = 2;
+        let mut probe = MockProbe::new();
+        let mut state = CortexAState::new();
+
+        // Add expectations
+        add_status_expectations(&mut probe, true);
+        add_enable_itr_expectations(&mut probe);
+        add_read_reg_expectations(&mut probe, 0, 0);
+        add_read_fp_count_expectations(&mut probe);
+
+        // Read watchpoint count
+        let mut dbgdidr = Dbgdidr(0);
+        dbgdidr.set_wrps(WP_COUNT - 1);
+        probe.expected_read(
+            Dbgdidr::get_mmio_address_from_base(TEST_BASE_ADDRESS).unwrap(),
+            dbgdidr.into(),
+        );
+
+        // Read WP values and controls
+        let mut dbgwcr = Dbgwcr(0);
+        dbgwcr.set_bas(0b1100);
+        dbgwcr.set_lsc(0b10);
+        dbgwcr.set_e(true);
+        probe.expected_read(
+            Dbgwvr::get_mmio_address_from_base(TEST_BASE_ADDRESS).unwrap(),
+            0x2000_0000,
+        );
+        probe.expected_read(
+            Dbgwcr::get_mmio_address_from_base(TEST_BASE_ADDRESS).unwrap(),
+            dbgwcr.into(),
+        );
+
+        probe.expected_read(
+            Dbgwvr::get_mmio_address_from_base(TEST_BASE_ADDRESS).unwrap() + 4,
+            0,
+        );
+        probe.expected_read(
+            Dbgwcr::get_mmio_address_from_base(TEST_BASE_ADDRESS).unwrap() + 4,
+            0,
+        );
+
+        let mock_mem = Box::new(probe) as _;
+
+        let mut armv7a = Armv7a::new(
+            mock_mem,
+            &mut state,
+            TEST_BASE_ADDRESS,
+            DefaultArmSequence::create(),
+            0,
+        )
+        .unwrap();
+
+        let results = armv7a.watchpoints().unwrap();
+        assert_eq!(
+            Some(Watchpoint {
+                address: 0x2000_0002,
+                len: 2,
+                kind: WatchpointKind::Write
+            }),
+            results[0]
+        );
+        assert_eq!(None, results[1]);
+    }
+
+    #[test]
+    fn armv7a_set_watchpoint() {
+        let mut probe = MockProbe::new();
+        let mut state = CortexAState::new();
+
+        // Add expectations
+        add_status_expectations(&mut probe, true);
+        add_enable_itr_expectations(&mut probe);
+        add_read_reg_expectations(&mut probe, 0, 0);
+        add_read_fp_count_expectations(&mut probe);
+
+        // Update WP value and control
+        let mut dbgwcr = Dbgwcr(0);
+        // Match on all modes
+        dbgwcr.set_hmc(true);
+        dbgwcr.set_pac(0b11);
+        // Match on the second byte
+        dbgwcr.set_bas(0b0010);
+        // Match on reads and writes
+        dbgwcr.set_lsc(0b11);
+        // Enable
+        dbgwcr.set_e(true);
+
+        probe.expected_write(
+            Dbgwcr::get_mmio_address_from_base(TEST_BASE_ADDRESS).unwrap() + 4,
+            0,
+        );
+        probe.expected_write(
+            Dbgwvr::get_mmio_address_from_base(TEST_BASE_ADDRESS).unwrap() + 4,
+            0x2000_0010,
+        );
+        probe.expected_write(
+            Dbgwcr::get_mmio_address_from_base(TEST_BASE_ADDRESS).unwrap() + 4,
+            dbgwcr.into(),
+        );
+
+        let mock_mem = Box::new(probe) as _;
+
+        let mut armv7a = Armv7a::new(
+            mock_mem,
+            &mut state,
+            TEST_BASE_ADDRESS,
+            DefaultArmSequence::create(),
+            0,
+        )
+        .unwrap();
+
+        armv7a
+            .set_watchpoint(1, 0x2000_0011, 1, WatchpointKind::Access)
+            .unwrap();
+        assert!(matches!(
+            armv7a.set_watchpoint(1, 0x2000_0011, 4, WatchpointKind::Access),
+            Err(Error::UnsupportedWatchpoint { .. })
+        ));
+    }
+
     #[test]
     fn armv7a_read_word_32() {
         const MEMORY_VALUE: u32 = 0xBA5EBA11;
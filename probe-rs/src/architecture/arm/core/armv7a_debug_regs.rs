@@ -249,7 +249,7 @@ memory_mapped_bitfield_register! {
     impl From;
 
     /// The number of watchpoints implemented. The number of implemented watchpoints is one more than the value of this field.
-    pub wrps, _: 31, 28;
+    pub wrps, set_wrps: 31, 28;
 
     /// The number of breakpoints implemented. The number of implemented breakpoints is one more than value of this field.
     pub brps, set_brps: 27, 24;
@@ -359,6 +359,50 @@ memory_mapped_bitfield_register! {
     pub e, set_e: 0;
 }
 
+memory_mapped_bitfield_register! {
+    /// DBGWVR - Watchpoint Value Register
+    pub struct Dbgwvr(u32);
+    0x180, "DBGWVR",
+    impl From;
+
+    /// Watchpoint address
+    pub value, set_value : 31, 0;
+}
+
+memory_mapped_bitfield_register! {
+    /// DBGWCR - Watchpoint Control Register
+    pub struct Dbgwcr(u32);
+    0x1C0, "DBGWCR",
+    impl From;
+
+    /// Address range mask. Whether masking is supported is implementation defined.
+    pub mask, set_mask : 28, 24;
+
+    /// Watchpoint type
+    pub wt, set_wt : 20;
+
+    /// Linked breakpoint number
+    pub lbn, set_lbn : 19, 16;
+
+    /// Security state control
+    pub ssc, set_ssc : 15, 14;
+
+    /// Hyp mode control bit
+    pub hmc, set_hmc: 13;
+
+    /// Byte address select
+    pub bas, set_bas: 12, 5;
+
+    /// Load/store access control
+    pub lsc, set_lsc: 4, 3;
+
+    /// Privileged access control
+    pub pac, set_pac: 2, 1;
+
+    /// Watchpoint enable
+    pub e, set_e: 0;
+}
+
 memory_mapped_bitfield_register! {
     /// DBGLAR - Lock Access Register
     pub struct Dbglar(u32);
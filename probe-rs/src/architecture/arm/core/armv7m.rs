@@ -1,6 +1,7 @@
 //! Register types and the core interface for armv7-M
 
 use super::{
+    cortex_m::DwtLayout,
     cortex_m::Mvfr0,
     registers::cortex_m::{
         CORTEX_M_CORE_REGISTERS, CORTEX_M_WITH_FP_CORE_REGISTERS, FP, PC, RA, SP,
@@ -18,7 +19,8 @@ use crate::{
     },
     error::Error,
     memory::valid_32bit_address,
-    CoreRegister, CoreType, DebugProbeError, InstructionSet, MemoryInterface,
+    CoreRegister, CoreType, DebugProbeError, InstructionSet, MemoryInterface, Watchpoint,
+    WatchpointKind,
 };
 use anyhow::{anyhow, Result};
 use bitfield::bitfield;
@@ -317,6 +319,94 @@ impl MemoryMappedRegister<u32> for Aircr {
     const NAME: &'static str = "AIRCR";
 }
 
+/// Vector Table Offset Register, VTOR (see armv7-M Architecture Reference Manual B3.2.5)
+#[derive(Debug, Copy, Clone)]
+pub struct Vtor(pub u32);
+
+impl From<u32> for Vtor {
+    fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Vtor> for u32 {
+    fn from(value: Vtor) -> Self {
+        value.0
+    }
+}
+
+impl MemoryMappedRegister<u32> for Vtor {
+    const ADDRESS_OFFSET: u64 = 0xE000_ED08;
+    const NAME: &'static str = "VTOR";
+}
+
+bitfield! {
+    /// Configuration and Control Register, CCR (see armv7-M Architecture Reference Manual B3.2.8)
+    #[derive(Copy, Clone)]
+    pub struct Ccr(u32);
+    impl Debug;
+    /// Instruction cache enable
+    pub ic, set_ic: 17;
+    /// Data and unified cache enable
+    pub dc, set_dc: 16;
+}
+
+impl From<u32> for Ccr {
+    fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Ccr> for u32 {
+    fn from(value: Ccr) -> Self {
+        value.0
+    }
+}
+
+impl MemoryMappedRegister<u32> for Ccr {
+    const ADDRESS_OFFSET: u64 = 0xE000_ED14;
+    const NAME: &'static str = "CCR";
+}
+
+/// Instruction cache invalidate all to the Point of Unification, ICIALLU
+/// (see armv7-M Architecture Reference Manual B2.2.7)
+///
+/// Writing any value invalidates the whole instruction cache.
+#[derive(Debug, Copy, Clone)]
+pub struct Iciallu(pub u32);
+
+impl From<u32> for Iciallu {
+    fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Iciallu> for u32 {
+    fn from(value: Iciallu) -> Self {
+        value.0
+    }
+}
+
+impl MemoryMappedRegister<u32> for Iciallu {
+    const ADDRESS_OFFSET: u64 = 0xE000_EF50;
+    const NAME: &'static str = "ICIALLU";
+}
+
+/// Invalidates the instruction cache of a Cortex-M core, if it has one and it is enabled.
+///
+/// The debugger accesses memory through the data side of the core, so only the instruction
+/// cache can hold stale contents.
+pub(crate) fn invalidate_instruction_cache(memory: &mut dyn ArmProbe) -> Result<(), Error> {
+    let ccr = Ccr(memory.read_word_32(Ccr::get_mmio_address())?);
+
+    // Cores without an instruction cache, like the Cortex-M3 and M4, read the bit as zero.
+    if ccr.ic() {
+        memory.write_word_32(Iciallu::get_mmio_address(), Iciallu(0).into())?;
+    }
+
+    Ok(())
+}
+
 bitfield! {
     /// Debug Exception and Monitor Control Register, DEMCR (see armv7-M Architecture Reference Manual C1.6.5)
     #[derive(Copy, Clone)]
@@ -1011,6 +1101,35 @@ impl<'probe> CoreInterface for Armv7m<'probe> {
         Ok(())
     }
 
+    fn available_watchpoint_units(&mut self) -> Result<u32, Error> {
+        super::cortex_m::available_watchpoint_units(&mut *self.memory)
+    }
+
+    fn watchpoints(&mut self) -> Result<Vec<Option<Watchpoint>>, Error> {
+        super::cortex_m::watchpoints(&mut *self.memory, DwtLayout::Armv7m)
+    }
+
+    fn set_watchpoint(
+        &mut self,
+        unit_index: usize,
+        address: u64,
+        len: u64,
+        kind: WatchpointKind,
+    ) -> Result<(), Error> {
+        super::cortex_m::set_watchpoint(
+            &mut *self.memory,
+            DwtLayout::Armv7m,
+            unit_index,
+            address,
+            len,
+            kind,
+        )
+    }
+
+    fn clear_watchpoint(&mut self, unit_index: usize) -> Result<(), Error> {
+        super::cortex_m::clear_watchpoint(&mut *self.memory, unit_index)
+    }
+
     fn registers(&self) -> &'static CoreRegisters {
         if self.state.fp_present {
             &CORTEX_M_WITH_FP_CORE_REGISTERS
@@ -1039,6 +1158,10 @@ impl<'probe> CoreInterface for Armv7m<'probe> {
         self.state.hw_breakpoints_enabled
     }
 
+    fn invalidate_instruction_cache(&mut self, _address: u64, _len: u64) -> Result<(), Error> {
+        invalidate_instruction_cache(&mut *self.memory)
+    }
+
     fn architecture(&self) -> Architecture {
         Architecture::Arm
     }
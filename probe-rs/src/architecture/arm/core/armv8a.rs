@@ -3,10 +3,12 @@
 use super::{
     instructions::{
         aarch64,
-        thumb2::{build_ldr, build_mcr, build_mrc, build_str, build_vmov, build_vmrs},
+        thumb2::{
+            build_dsb_sy, build_ldr, build_mcr, build_mrc, build_str, build_vmov, build_vmrs,
+        },
     },
     registers::{aarch32::AARCH32_WITH_FP_32_CORE_REGSISTERS, aarch64::AARCH64_CORE_REGSISTERS},
-    CortexAState,
+    watchpoint_kind, watchpoint_lsc, CortexAState, WatchpointRange,
 };
 use crate::{
     architecture::arm::{
@@ -19,7 +21,7 @@ use crate::{
     error::Error,
     memory::valid_32bit_address,
     Architecture, CoreInformation, CoreInterface, CoreRegister, CoreStatus, CoreType,
-    InstructionSet, MemoryInterface,
+    InstructionSet, MemoryInterface, Watchpoint, WatchpointKind,
 };
 use anyhow::Result;
 use std::{
@@ -62,6 +64,8 @@ pub struct Armv8a<'probe> {
 
     num_breakpoints: Option<u32>,
 
+    num_watchpoints: Option<u32>,
+
     id: usize,
 }
 
@@ -104,6 +108,7 @@ impl<'probe> Armv8a<'probe> {
             cti_address,
             sequence,
             num_breakpoints: None,
+            num_watchpoints: None,
             id,
         };
 
@@ -1054,6 +1059,99 @@ impl<'probe> CoreInterface for Armv8a<'probe> {
         Ok(())
     }
 
+    fn available_watchpoint_units(&mut self) -> Result<u32, Error> {
+        if self.num_watchpoints.is_none() {
+            let address = Eddfr::get_mmio_address_from_base(self.base_address)?;
+            let eddfr = Eddfr(self.memory.read_word_32(address)?);
+
+            self.num_watchpoints = Some(eddfr.wrps() + 1);
+        }
+        Ok(self.num_watchpoints.unwrap())
+    }
+
+    fn watchpoints(&mut self) -> Result<Vec<Option<Watchpoint>>, Error> {
+        let mut watchpoints = vec![];
+        let num_watchpoints = self.available_watchpoint_units()? as usize;
+
+        for wp_unit_index in 0..num_watchpoints {
+            let wp_value_addr = Dbgwvr::get_mmio_address_from_base(self.base_address)?
+                + (wp_unit_index * 16) as u64;
+            let mut wp_value = self.memory.read_word_32(wp_value_addr)? as u64;
+            wp_value |= (self.memory.read_word_32(wp_value_addr + 4)? as u64) << 32;
+
+            let wp_control_addr = Dbgwcr::get_mmio_address_from_base(self.base_address)?
+                + (wp_unit_index * 16) as u64;
+            let wp_control = Dbgwcr(self.memory.read_word_32(wp_control_addr)?);
+
+            let watchpoint = match watchpoint_kind(wp_control.lsc()) {
+                Some(kind) if wp_control.e() => {
+                    let range = WatchpointRange::from_registers(
+                        wp_value,
+                        wp_control.bas(),
+                        wp_control.mask(),
+                    );
+                    let (address, len) = range.range();
+
+                    Some(Watchpoint { address, len, kind })
+                }
+                _ => None,
+            };
+            watchpoints.push(watchpoint);
+        }
+        Ok(watchpoints)
+    }
+
+    fn set_watchpoint(
+        &mut self,
+        wp_unit_index: usize,
+        address: u64,
+        len: u64,
+        kind: WatchpointKind,
+    ) -> Result<(), Error> {
+        let range = WatchpointRange::new(address, len, 8)
+            .ok_or(Error::UnsupportedWatchpoint { address, len })?;
+
+        let wp_value_addr =
+            Dbgwvr::get_mmio_address_from_base(self.base_address)? + (wp_unit_index * 16) as u64;
+        let wp_control_addr =
+            Dbgwcr::get_mmio_address_from_base(self.base_address)? + (wp_unit_index * 16) as u64;
+        let mut wp_control = Dbgwcr(0);
+
+        wp_control.set_mask(range.mask());
+        // Match on all modes
+        wp_control.set_hmc(true);
+        wp_control.set_pac(0b11);
+        wp_control.set_bas(range.bas());
+        wp_control.set_lsc(watchpoint_lsc(kind));
+        // Enable
+        wp_control.set_e(true);
+
+        let addr_low = range.value() as u32;
+        let addr_high = (range.value() >> 32) as u32;
+
+        // Disable the watchpoint while its address is changed.
+        self.memory.write_word_32(wp_control_addr, 0)?;
+        self.memory.write_word_32(wp_value_addr, addr_low)?;
+        self.memory.write_word_32(wp_value_addr + 4, addr_high)?;
+        self.memory
+            .write_word_32(wp_control_addr, wp_control.into())?;
+
+        Ok(())
+    }
+
+    fn clear_watchpoint(&mut self, wp_unit_index: usize) -> Result<(), Error> {
+        let wp_value_addr =
+            Dbgwvr::get_mmio_address_from_base(self.base_address)? + (wp_unit_index * 16) as u64;
+        let wp_control_addr =
+            Dbgwcr::get_mmio_address_from_base(self.base_address)? + (wp_unit_index * 16) as u64;
+
+        self.memory.write_word_32(wp_control_addr, 0)?;
+        self.memory.write_word_32(wp_value_addr, 0)?;
+        self.memory.write_word_32(wp_value_addr + 4, 0)?;
+
+        Ok(())
+    }
+
     fn registers(&self) -> &'static CoreRegisters {
         if self.state.is_64_bit {
             &AARCH64_CORE_REGSISTERS
@@ -1098,6 +1196,39 @@ impl<'probe> CoreInterface for Armv8a<'probe> {
         true
     }
 
+    fn invalidate_instruction_cache(&mut self, address: u64, len: u64) -> Result<(), Error> {
+        // Memory is written through the core, so the new instructions may still be in the data cache.
+        self.prepare_for_clobber(0)?;
+
+        for line_address in [address, address + len.max(1) - 1] {
+            self.set_reg_value(0, line_address)?;
+
+            // Clean the data cache line to the point of unification
+            let instruction = if self.state.is_64_bit {
+                // DC CVAU, X0
+                aarch64::build_sys(3, 7, 11, 1, 0)
+            } else {
+                // DCCMVAU
+                build_mcr(15, 0, 0, 7, 11, 1)
+            };
+            self.execute_instruction(instruction)?;
+        }
+
+        // Wait for the cleaning to complete, then invalidate the instruction cache
+        let instructions = if self.state.is_64_bit {
+            // DSB SY, IC IALLU
+            [aarch64::build_dsb_sy(), aarch64::build_sys(0, 7, 5, 0, 31)]
+        } else {
+            // DSB SY, ICIALLU
+            [build_dsb_sy(), build_mcr(15, 0, 0, 7, 5, 0)]
+        };
+        for instruction in instructions {
+            self.execute_instruction(instruction)?;
+        }
+
+        Ok(())
+    }
+
     fn architecture(&self) -> Architecture {
         Architecture::Arm
     }
@@ -1282,7 +1413,7 @@ impl<'probe> MemoryInterface for Armv8a<'probe> {
 
     fn write_8(&mut self, address: u64, data: &[u8]) -> Result<(), Error> {
         for (i, byte) in data.iter().enumerate() {
-            self.write_word_8(address + ((i as u64) * 4), *byte)?;
+            self.write_word_8(address + (i as u64), *byte)?;
         }
 
         Ok(())
@@ -2456,6 +2587,63 @@ mod test {
         armv8a.clear_hw_breakpoint(0).unwrap();
     }
 
+    #[test]
+    fn armv8a_set_watchpoint() {
+        const WP_VALUE: u64 = 0x1_2000_0100;
+        let mut probe = MockProbe::new(false);
+        let mut state = CortexAState::new();
+
+        // Add expectations
+        add_status_expectations(&mut probe, true);
+
+        // Update WP value and control
+        let mut dbgwcr = Dbgwcr(0);
+        // Watch 256 bytes
+        dbgwcr.set_mask(8);
+        // Match on all modes
+        dbgwcr.set_hmc(true);
+        dbgwcr.set_pac(0b11);
+        // Match on all bytes
+        dbgwcr.set_bas(0xFF);
+        // Match on reads
+        dbgwcr.set_lsc(0b01);
+        // Enable
+        dbgwcr.set_e(true);
+
+        probe.expected_write(
+            Dbgwcr::get_mmio_address_from_base(TEST_BASE_ADDRESS).unwrap(),
+            0,
+        );
+        probe.expected_write(
+            Dbgwvr::get_mmio_address_from_base(TEST_BASE_ADDRESS).unwrap(),
+            WP_VALUE as u32,
+        );
+        probe.expected_write(
+            Dbgwvr::get_mmio_address_from_base(TEST_BASE_ADDRESS).unwrap() + 4,
+            1,
+        );
+        probe.expected_write(
+            Dbgwcr::get_mmio_address_from_base(TEST_BASE_ADDRESS).unwrap(),
+            dbgwcr.into(),
+        );
+
+        let mock_mem = Box::new(probe) as _;
+
+        let mut armv8a = Armv8a::new(
+            mock_mem,
+            &mut state,
+            TEST_BASE_ADDRESS,
+            TEST_CTI_ADDRESS,
+            DefaultArmSequence::create(),
+            0,
+        )
+        .unwrap();
+
+        armv8a
+            .set_watchpoint(0, WP_VALUE, 0x100, WatchpointKind::Read)
+            .unwrap();
+    }
+
     #[test]
     fn armv8a_read_word_32() {
         const MEMORY_VALUE: u32 = 0xBA5EBA11;
@@ -1,6 +1,7 @@
 //! Register types and the core interface for armv8-M
 
 use super::{
+    cortex_m::DwtLayout,
     cortex_m::{IdPfr1, Mvfr0},
     registers::cortex_m::{
         CORTEX_M_CORE_REGISTERS, CORTEX_M_WITH_FP_CORE_REGISTERS, FP, PC, RA, SP,
@@ -16,7 +17,7 @@ use crate::{
     error::Error,
     memory::valid_32bit_address,
     Architecture, CoreInformation, CoreInterface, CoreRegister, CoreStatus, CoreType, HaltReason,
-    InstructionSet, MemoryInterface, MemoryMappedRegister,
+    InstructionSet, MemoryInterface, MemoryMappedRegister, Watchpoint, WatchpointKind,
 };
 use anyhow::Result;
 use bitfield::bitfield;
@@ -400,6 +401,35 @@ impl<'probe> CoreInterface for Armv8m<'probe> {
         Ok(())
     }
 
+    fn available_watchpoint_units(&mut self) -> Result<u32, Error> {
+        super::cortex_m::available_watchpoint_units(&mut *self.memory)
+    }
+
+    fn watchpoints(&mut self) -> Result<Vec<Option<Watchpoint>>, Error> {
+        super::cortex_m::watchpoints(&mut *self.memory, DwtLayout::Armv8m)
+    }
+
+    fn set_watchpoint(
+        &mut self,
+        unit_index: usize,
+        address: u64,
+        len: u64,
+        kind: WatchpointKind,
+    ) -> Result<(), Error> {
+        super::cortex_m::set_watchpoint(
+            &mut *self.memory,
+            DwtLayout::Armv8m,
+            unit_index,
+            address,
+            len,
+            kind,
+        )
+    }
+
+    fn clear_watchpoint(&mut self, unit_index: usize) -> Result<(), Error> {
+        super::cortex_m::clear_watchpoint(&mut *self.memory, unit_index)
+    }
+
     fn registers(&self) -> &'static CoreRegisters {
         if self.state.fp_present {
             &CORTEX_M_WITH_FP_CORE_REGISTERS
@@ -428,6 +458,11 @@ impl<'probe> CoreInterface for Armv8m<'probe> {
         self.state.hw_breakpoints_enabled
     }
 
+    fn invalidate_instruction_cache(&mut self, _address: u64, _len: u64) -> Result<(), Error> {
+        // The cache maintenance registers of armv8-M are at the same addresses as on armv7-M.
+        super::armv7m::invalidate_instruction_cache(&mut *self.memory)
+    }
+
     fn architecture(&self) -> Architecture {
         Architecture::Arm
     }
@@ -3,8 +3,9 @@
 use crate::{
     architecture::arm::{memory::adi_v5_memory_interface::ArmProbe, ArmError},
     core::RegisterId,
+    memory::valid_32bit_address,
     memory_mapped_bitfield_register, BreakpointCause, CoreInterface, Error, HaltReason,
-    MemoryMappedRegister,
+    MemoryMappedRegister, Watchpoint, WatchpointKind,
 };
 use std::time::{Duration, Instant};
 
@@ -142,6 +143,216 @@ impl IdPfr1 {
     }
 }
 
+memory_mapped_bitfield_register! {
+    /// Debug Exception and Monitor Control Register
+    pub struct Demcr(u32);
+    0xE000_EDFC, "DEMCR",
+    impl From;
+    /// Global enable for DWT and ITM features
+    pub trcena, set_trcena: 24;
+}
+
+memory_mapped_bitfield_register! {
+    /// DWT Control Register
+    pub struct DwtCtrl(u32);
+    0xE000_1000, "DWT_CTRL",
+    impl From;
+    /// The number of comparators implemented.
+    pub numcomp, _: 31, 28;
+}
+
+memory_mapped_bitfield_register! {
+    /// DWT Comparator Register of comparator 0. The registers of comparator `n` are
+    /// located at a `16 * n` bytes offset.
+    pub struct DwtComp(u32);
+    0xE000_1020, "DWT_COMP0",
+    impl From;
+}
+
+memory_mapped_bitfield_register! {
+    /// DWT Comparator Mask Register of comparator 0. Not present on ARMv8-M.
+    pub struct DwtMask(u32);
+    0xE000_1024, "DWT_MASK0",
+    impl From;
+    /// The size of the ignore mask applied to the address range matching.
+    pub mask, set_mask: 4, 0;
+}
+
+memory_mapped_bitfield_register! {
+    /// DWT Comparator Function Register of comparator 0.
+    pub struct DwtFunction(u32);
+    0xE000_1028, "DWT_FUNCTION0",
+    impl From;
+    /// The size of the watched data. Only used for watchpoints on ARMv8-M.
+    pub datavsize, set_datavsize: 11, 10;
+    /// The action on a match. Only present on ARMv8-M.
+    pub action, set_action: 5, 4;
+    /// The function of the comparator on ARMv6-M and ARMv7-M, the match type on ARMv8-M.
+    pub function, set_function: 3, 0;
+}
+
+/// The layout of the DWT comparators of a Cortex-M core.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DwtLayout {
+    /// The comparators of ARMv6-M and ARMv7-M cores, which watch naturally aligned ranges
+    /// using the DWT_MASK registers.
+    Armv7m,
+    /// The comparators of ARMv8-M cores, which watch naturally aligned bytes, halfwords or words.
+    Armv8m,
+}
+
+impl DwtLayout {
+    /// Returns the values of the DWT_MASK and DWT_FUNCTION registers of a watchpoint,
+    /// or `None` if the watchpoint is not supported.
+    fn encode(self, address: u64, len: u64, kind: WatchpointKind) -> Option<(u32, DwtFunction)> {
+        if !len.is_power_of_two() || address & (len - 1) != 0 {
+            return None;
+        }
+
+        let mut function = DwtFunction(0);
+        match self {
+            DwtLayout::Armv7m => {
+                function.set_function(match kind {
+                    WatchpointKind::Read => 0b0101,
+                    WatchpointKind::Write => 0b0110,
+                    WatchpointKind::Access => 0b0111,
+                });
+            }
+            DwtLayout::Armv8m => {
+                if len > 4 {
+                    return None;
+                }
+                function.set_function(match kind {
+                    WatchpointKind::Access => 0b0100,
+                    WatchpointKind::Write => 0b0101,
+                    WatchpointKind::Read => 0b0110,
+                });
+                // Generate a debug event
+                function.set_action(0b01);
+                function.set_datavsize(len.trailing_zeros());
+            }
+        }
+
+        Some((len.trailing_zeros(), function))
+    }
+
+    /// Returns the watchpoint configured in a comparator, if any.
+    fn decode(self, comp: u32, mask: u32, function: DwtFunction) -> Option<Watchpoint> {
+        let (kind, len) = match self {
+            DwtLayout::Armv7m => {
+                let kind = match function.function() {
+                    0b0101 => WatchpointKind::Read,
+                    0b0110 => WatchpointKind::Write,
+                    0b0111 => WatchpointKind::Access,
+                    _ => return None,
+                };
+                (kind, 1 << mask)
+            }
+            DwtLayout::Armv8m => {
+                if function.action() != 0b01 {
+                    return None;
+                }
+                let kind = match function.function() {
+                    0b0100 => WatchpointKind::Access,
+                    0b0101 => WatchpointKind::Write,
+                    0b0110 => WatchpointKind::Read,
+                    _ => return None,
+                };
+                (kind, 1 << function.datavsize())
+            }
+        };
+
+        Some(Watchpoint {
+            address: comp as u64,
+            len,
+            kind,
+        })
+    }
+}
+
+/// Returns the number of DWT comparators, which can be used as watchpoints.
+pub(crate) fn available_watchpoint_units(memory: &mut dyn ArmProbe) -> Result<u32, Error> {
+    let ctrl = DwtCtrl(memory.read_word_32(DwtCtrl::get_mmio_address())?);
+
+    Ok(ctrl.numcomp())
+}
+
+/// Reads the watchpoints configured in the DWT comparators.
+pub(crate) fn watchpoints(
+    memory: &mut dyn ArmProbe,
+    layout: DwtLayout,
+) -> Result<Vec<Option<Watchpoint>>, Error> {
+    let mut watchpoints = vec![];
+
+    for unit_index in 0..available_watchpoint_units(memory)? as u64 {
+        let offset = unit_index * 16;
+        let function = DwtFunction(memory.read_word_32(DwtFunction::get_mmio_address() + offset)?);
+        let comp = memory.read_word_32(DwtComp::get_mmio_address() + offset)?;
+        let mask = match layout {
+            DwtLayout::Armv7m => {
+                DwtMask(memory.read_word_32(DwtMask::get_mmio_address() + offset)?).mask()
+            }
+            DwtLayout::Armv8m => 0,
+        };
+
+        watchpoints.push(layout.decode(comp, mask, function));
+    }
+
+    Ok(watchpoints)
+}
+
+/// Configures the DWT comparator `unit_index` as watchpoint.
+pub(crate) fn set_watchpoint(
+    memory: &mut dyn ArmProbe,
+    layout: DwtLayout,
+    unit_index: usize,
+    address: u64,
+    len: u64,
+    kind: WatchpointKind,
+) -> Result<(), Error> {
+    let comp = valid_32bit_address(address)?;
+    let (mask, function) = layout
+        .encode(address, len, kind)
+        .ok_or(Error::UnsupportedWatchpoint { address, len })?;
+
+    // The DWT is only accessible if it is enabled.
+    let mut demcr = Demcr(memory.read_word_32(Demcr::get_mmio_address())?);
+    if !demcr.trcena() {
+        demcr.set_trcena(true);
+        memory.write_word_32(Demcr::get_mmio_address(), demcr.into())?;
+    }
+
+    let offset = (unit_index * 16) as u64;
+
+    // Disable the comparator while it is reconfigured.
+    memory.write_word_32(DwtFunction::get_mmio_address() + offset, 0)?;
+    memory.write_word_32(DwtComp::get_mmio_address() + offset, comp)?;
+
+    if layout == DwtLayout::Armv7m {
+        let mut dwt_mask = DwtMask(0);
+        dwt_mask.set_mask(mask);
+        memory.write_word_32(DwtMask::get_mmio_address() + offset, dwt_mask.into())?;
+
+        // The maximum mask size is implementation defined, larger values are truncated.
+        let actual = DwtMask(memory.read_word_32(DwtMask::get_mmio_address() + offset)?);
+        if actual.mask() != mask {
+            return Err(Error::UnsupportedWatchpoint { address, len });
+        }
+    }
+
+    memory.write_word_32(DwtFunction::get_mmio_address() + offset, function.into())?;
+
+    Ok(())
+}
+
+/// Disables the DWT comparator `unit_index`.
+pub(crate) fn clear_watchpoint(memory: &mut dyn ArmProbe, unit_index: usize) -> Result<(), Error> {
+    let offset = (unit_index * 16) as u64;
+    memory.write_word_32(DwtFunction::get_mmio_address() + offset, 0)?;
+
+    Ok(())
+}
+
 pub(crate) fn read_core_reg(memory: &mut dyn ArmProbe, addr: RegisterId) -> Result<u32, Error> {
     // Write the DCRSR value to select the register we want to read.
     let mut dcrsr_val = Dcrsr(0);
@@ -239,3 +450,59 @@ fn wait_for_core_register_transfer(
     }
     Err(ArmError::Timeout)
 }
+
+#[cfg(test)]
+mod test {
+    use super::{DwtFunction, DwtLayout};
+    use crate::{Watchpoint, WatchpointKind};
+
+    #[test]
+    fn armv7m_watchpoint_encoding() {
+        let (mask, function) = DwtLayout::Armv7m
+            .encode(0x2000_0100, 0x40, WatchpointKind::Write)
+            .unwrap();
+        assert_eq!(mask, 6);
+        assert_eq!(function.function(), 0b0110);
+
+        assert_eq!(
+            DwtLayout::Armv7m.decode(0x2000_0100, mask, function),
+            Some(Watchpoint {
+                address: 0x2000_0100,
+                len: 0x40,
+                kind: WatchpointKind::Write
+            })
+        );
+
+        // Data trace comparators are no watchpoints.
+        assert_eq!(
+            DwtLayout::Armv7m.decode(0x2000_0100, 0, DwtFunction(0b11)),
+            None
+        );
+        assert!(DwtLayout::Armv7m
+            .encode(0x2000_0102, 4, WatchpointKind::Read)
+            .is_none());
+    }
+
+    #[test]
+    fn armv8m_watchpoint_encoding() {
+        let (_, function) = DwtLayout::Armv8m
+            .encode(0x2000_0102, 2, WatchpointKind::Read)
+            .unwrap();
+        assert_eq!(function.function(), 0b0110);
+        assert_eq!(function.action(), 0b01);
+        assert_eq!(function.datavsize(), 1);
+
+        assert_eq!(
+            DwtLayout::Armv8m.decode(0x2000_0102, 0, function),
+            Some(Watchpoint {
+                address: 0x2000_0102,
+                len: 2,
+                kind: WatchpointKind::Read
+            })
+        );
+
+        assert!(DwtLayout::Armv8m
+            .encode(0x2000_0100, 8, WatchpointKind::Access)
+            .is_none());
+    }
+}
@@ -211,6 +211,10 @@ pub(crate) mod thumb2 {
         ret
     }
 
+    pub(crate) fn build_dsb_sy() -> u32 {
+        0b1111_0011_1011_1111_1000_1111_0100_1111
+    }
+
     #[cfg(test)]
     mod tests {
         use super::*;
@@ -223,6 +227,12 @@ pub(crate) mod thumb2 {
             assert_eq!(0xF8532B04, instr);
         }
 
+        #[test]
+        fn gen_dsb_instruction() {
+            // DSB SY
+            assert_eq!(0xF3BF8F4F, build_dsb_sy());
+        }
+
         #[test]
         fn gen_str_instruction() {
             let instr = build_str(2, 3, 4);
@@ -300,6 +310,22 @@ pub(crate) mod aarch64 {
         ret
     }
 
+    pub(crate) fn build_sys(op1: u8, crn: u8, crm: u8, op2: u8, reg: u16) -> u32 {
+        let mut ret = 0b1101_0101_0000_1000_0000_0000_0000_0000;
+
+        ret |= (op1 as u32) << 16;
+        ret |= (crn as u32) << 12;
+        ret |= (crm as u32) << 8;
+        ret |= (op2 as u32) << 5;
+        ret |= reg as u32;
+
+        ret
+    }
+
+    pub(crate) fn build_dsb_sy() -> u32 {
+        0b1101_0101_0000_0011_0011_1111_1001_1111
+    }
+
     pub(crate) fn build_ins_fp_to_gp(reg_target: u16, reg_source: u16, index: u16) -> u32 {
         let mut ret = 0b0100_1110_0000_1000_0011_1100_0000_0000;
 
@@ -371,6 +397,21 @@ pub(crate) mod aarch64 {
             assert_eq!(0xB8004462, instr);
         }
 
+        #[test]
+        fn gen_sys_instruction() {
+            // IC IALLU
+            assert_eq!(0xD508751F, build_sys(0, 7, 5, 0, 31));
+
+            // DC CVAU, x3
+            assert_eq!(0xD50B7B23, build_sys(3, 7, 11, 1, 3));
+        }
+
+        #[test]
+        fn gen_dsb_instruction() {
+            // DSB SY
+            assert_eq!(0xD5033F9F, build_dsb_sy());
+        }
+
         #[test]
         fn gen_ins_gp_to_fp_instruction() {
             let instr = build_ins_gp_to_fp(3, 2, 1);
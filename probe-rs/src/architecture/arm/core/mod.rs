@@ -2,7 +2,7 @@
 
 use crate::{
     core::{BreakpointCause, RegisterValue},
-    memory_mapped_bitfield_register, CoreStatus, HaltReason,
+    memory_mapped_bitfield_register, CoreStatus, HaltReason, WatchpointKind,
 };
 
 pub mod armv6m;
@@ -213,3 +213,132 @@ pub fn update_core_status<
     }
     *current_status = new_status;
 }
+
+/// The address related configuration of an ARMv7-A or ARMv8-A watchpoint unit, i.e. the
+/// contents of its DBGWVR and the BAS and MASK fields of its DBGWCR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WatchpointRange {
+    /// The watchpoint value, which is aligned to the size of the BAS field.
+    value: u64,
+    /// The byte address select field.
+    bas: u32,
+    /// The address range mask field.
+    mask: u32,
+}
+
+impl WatchpointRange {
+    /// Computes the configuration of a watchpoint on the `len` bytes starting at `address`.
+    ///
+    /// `granule` is the number of bytes the byte address select field covers, which is 4
+    /// on ARMv7-A and 8 on ARMv8-A. Ranges within a single granule are selected byte by byte,
+    /// larger ranges have to be naturally aligned powers of two and are selected using the mask.
+    pub(crate) fn new(address: u64, len: u64, granule: u64) -> Option<Self> {
+        let offset = address % granule;
+
+        if len == 0 {
+            None
+        } else if offset + len <= granule {
+            Some(Self {
+                value: address - offset,
+                bas: ((1 << len) - 1) << offset,
+                mask: 0,
+            })
+        } else if len.is_power_of_two() && address & (len - 1) == 0 && len <= 1 << 31 {
+            Some(Self {
+                value: address,
+                bas: (1 << granule) - 1,
+                mask: len.trailing_zeros(),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Creates the configuration from the values read from a watchpoint unit.
+    pub(crate) fn from_registers(value: u64, bas: u32, mask: u32) -> Self {
+        Self { value, bas, mask }
+    }
+
+    /// The value of the DBGWVR register.
+    pub(crate) fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// The value of the BAS field of the DBGWCR register.
+    pub(crate) fn bas(&self) -> u32 {
+        self.bas
+    }
+
+    /// The value of the MASK field of the DBGWCR register.
+    pub(crate) fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    /// Returns the address of the first watched byte and the number of watched bytes.
+    pub(crate) fn range(&self) -> (u64, u64) {
+        if self.mask != 0 {
+            (self.value, 1 << self.mask)
+        } else {
+            let offset = self.bas.trailing_zeros().min(31);
+            let len = (self.bas >> offset).trailing_ones();
+            (self.value + offset as u64, len as u64)
+        }
+    }
+}
+
+/// Returns the value of the load/store access control field of a DBGWCR register.
+pub(crate) fn watchpoint_lsc(kind: WatchpointKind) -> u32 {
+    match kind {
+        WatchpointKind::Read => 0b01,
+        WatchpointKind::Write => 0b10,
+        WatchpointKind::Access => 0b11,
+    }
+}
+
+/// Returns the kind of a watchpoint from the load/store access control field of a DBGWCR register.
+pub(crate) fn watchpoint_kind(lsc: u32) -> Option<WatchpointKind> {
+    match lsc {
+        0b01 => Some(WatchpointKind::Read),
+        0b10 => Some(WatchpointKind::Write),
+        0b11 => Some(WatchpointKind::Access),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::WatchpointRange;
+
+    #[test]
+    fn watchpoint_range_within_granule() {
+        let range = WatchpointRange::new(0x2000_0005, 2, 4).unwrap();
+        assert_eq!(range.value(), 0x2000_0004);
+        assert_eq!(range.bas(), 0b0110);
+        assert_eq!(range.mask(), 0);
+        assert_eq!(range.range(), (0x2000_0005, 2));
+
+        let range = WatchpointRange::new(0x2000_0000, 8, 8).unwrap();
+        assert_eq!(range.bas(), 0xFF);
+        assert_eq!(range.range(), (0x2000_0000, 8));
+    }
+
+    #[test]
+    fn watchpoint_range_masked() {
+        let range = WatchpointRange::new(0x2000_0100, 0x100, 4).unwrap();
+        assert_eq!(range.value(), 0x2000_0100);
+        assert_eq!(range.bas(), 0b1111);
+        assert_eq!(range.mask(), 8);
+        assert_eq!(range.range(), (0x2000_0100, 0x100));
+    }
+
+    #[test]
+    fn watchpoint_range_unsupported() {
+        assert_eq!(WatchpointRange::new(0x2000_0000, 0, 4), None);
+        // Crosses a word boundary.
+        assert_eq!(WatchpointRange::new(0x2000_0003, 2, 4), None);
+        // Not a power of two.
+        assert_eq!(WatchpointRange::new(0x2000_0000, 12, 4), None);
+        // Not naturally aligned.
+        assert_eq!(WatchpointRange::new(0x2000_0008, 16, 8), None);
+    }
+}
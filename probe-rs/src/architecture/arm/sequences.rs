@@ -10,7 +10,10 @@ use std::{
 
 use probe_rs_target::CoreType;
 
-use crate::{architecture::arm::ArmProbeInterface, DebugProbeError, MemoryMappedRegister};
+use crate::{
+    architecture::arm::ArmProbeInterface, flashing::ConfigAreaSequence, DebugProbeError,
+    MemoryMappedRegister,
+};
 
 use super::{
     ap::{AccessPortError, MemoryAp},
@@ -751,6 +754,11 @@ pub trait ArmDebugSequence: Send + Sync + Debug {
     fn debug_erase_sequence(&self) -> Option<Arc<dyn DebugEraseSequence>> {
         None
     }
+
+    /// Return the sequence for writing the configuration areas of the device if it exists
+    fn config_area_sequence(&self) -> Option<Arc<dyn ConfigAreaSequence>> {
+        None
+    }
 }
 
 /// Chip-Erase Handling via the Device's Debug Interface
@@ -3,6 +3,10 @@
 /// RISC-V breakpoint instruction
 pub const EBREAK: u32 = 0b000000000001_00000_000_00000_1110011;
 
+/// RISC-V instruction fetch fence, which synchronizes the instruction fetches of the hart
+/// with the preceding stores to memory.
+pub const FENCE_I: u32 = 0b000000000000_00000_001_00000_0001111;
+
 /// Assemble a `lw` instruction.
 pub fn lw(offset: u16, base: u8, width: u8, destination: u8) -> u32 {
     let opcode = 0b000_0011;
@@ -158,4 +162,26 @@ mod test {
 
         assert_eq!(assembled, expected);
     }
+
+    #[test]
+    fn assemble_sd() {
+        // Assembly output of assembly 'sd      s1, 0(s0)'
+        //
+        let expected = 0x00943023;
+
+        let assembled = sw(0, 8, 3, 9);
+
+        assert_eq!(assembled, expected);
+    }
+
+    #[test]
+    fn assemble_ld() {
+        // Assembly output of assembly 'ld      s0, 0(s0)'
+        //
+        let expected = 0x00043403;
+
+        let assembled = lw(0, 8, 3, 8);
+
+        assert_eq!(assembled, expected);
+    }
 }
@@ -6,7 +6,7 @@
 
 use super::{
     dtm::{DmiOperation, DmiOperationStatus, Dtm},
-    registers, Dmcontrol, Dmstatus,
+    registers, Dmcontrol, Dmcs2, Dmstatus,
 };
 use crate::{
     architecture::riscv::*,
@@ -69,6 +69,12 @@ pub enum RiscvError {
     /// The target does not support halt after reset.
     #[error("The target does not support halt after reset.")]
     ResetHaltRequestNotSupported,
+    /// The target has no trigger which can halt the hart on exceptions.
+    #[error("The target has no trigger which can halt on exceptions.")]
+    ExceptionTriggerNotSupported,
+    /// The hart with the given index does not exist.
+    #[error("Hart {0} does not exist.")]
+    HartUnavailable(u32),
 }
 
 impl From<RiscvError> for ProbeRsError {
@@ -176,7 +182,7 @@ impl CoreRegisterAbstractCmdSupport {
 
 #[derive(Debug)]
 struct ScratchState {
-    stack: Vec<(bool, u32)>,
+    stack: Vec<(bool, u64)>,
     should_save: bool,
 }
 
@@ -190,12 +196,12 @@ impl Default for ScratchState {
 }
 
 impl ScratchState {
-    fn push(&mut self, value: u32) {
+    fn push(&mut self, value: u64) {
         self.stack.push((self.should_save, value));
         self.should_save = false;
     }
 
-    fn pop(&mut self) -> Option<u32> {
+    fn pop(&mut self) -> Option<u64> {
         let (should_save, value) = self.stack.pop()?;
 
         self.should_save = should_save;
@@ -236,6 +242,16 @@ pub struct RiscvCommunicationInterfaceState {
     /// Number of harts
     num_harts: u32,
 
+    /// The hart selected in `dmcontrol`
+    current_hart: u32,
+
+    /// Width of the general purpose registers (XLEN) of each hart, determined
+    /// with the first register access while the hart is halted.
+    xlen: HashMap<u32, RiscvBusAccess>,
+
+    /// Width of system bus addresses in bits
+    sbasize: u8,
+
     memory_access_info: HashMap<RiscvBusAccess, MemoryAccessMethod>,
 
     /// describes, if the given register can be read / written with an
@@ -281,6 +297,12 @@ impl RiscvCommunicationInterfaceState {
             // We assume only a singe hart exisits initially
             num_harts: 1,
 
+            current_hart: 0,
+
+            xlen: HashMap::new(),
+
+            sbasize: 0,
+
             memory_access_info: HashMap::new(),
 
             abstract_cmd_register_info: HashMap::new(),
@@ -340,6 +362,73 @@ impl RiscvCommunicationInterface {
         self.dtm.read_idcode()
     }
 
+    /// Returns the number of harts connected to the debug module.
+    pub fn num_harts(&self) -> u32 {
+        self.state.num_harts
+    }
+
+    /// Returns the index of the currently selected hart.
+    pub fn current_hart(&self) -> u32 {
+        self.state.current_hart
+    }
+
+    /// Selects the hart which is accessed by all following operations.
+    pub fn select_hart(&mut self, hart: u32) -> Result<(), RiscvError> {
+        if hart >= self.state.num_harts {
+            return Err(RiscvError::HartUnavailable(hart));
+        }
+
+        if self.state.current_hart == hart {
+            return Ok(());
+        }
+
+        tracing::debug!("Selecting hart {}", hart);
+
+        self.state.current_hart = hart;
+        self.write_dm_register(self.dmcontrol())
+    }
+
+    /// Returns a `dmcontrol` value which keeps the debug module
+    /// active and the current hart selected.
+    pub(super) fn dmcontrol(&self) -> Dmcontrol {
+        let mut control = Dmcontrol(0);
+        control.set_dmactive(true);
+        control.set_hartsel(self.state.current_hart);
+        control
+    }
+
+    /// Adds all harts to one halt group, so that all harts halt as soon as one of them halts,
+    /// e.g. on a breakpoint. If `enabled` is false, the harts are removed from the halt group.
+    ///
+    /// Returns false if the debug module does not support halt groups.
+    pub fn set_halt_group(&mut self, enabled: bool) -> Result<bool, RiscvError> {
+        let group = u32::from(enabled);
+        let mut supported = true;
+
+        for hart in 0..self.state.num_harts {
+            let mut control = Dmcontrol(0);
+            control.set_dmactive(true);
+            control.set_hartsel(hart);
+            self.write_dm_register(control)?;
+
+            let mut dmcs2 = Dmcs2(0);
+            dmcs2.set_group(group);
+            dmcs2.set_hgwrite(true);
+            self.write_dm_register(dmcs2)?;
+
+            // Debug modules without halt groups ignore the write.
+            let readback: Dmcs2 = self.read_dm_register()?;
+            supported &= readback.group() == group;
+        }
+
+        tracing::debug!("Support for halt groups: {}", supported);
+
+        // Select the current hart again
+        self.write_dm_register(self.dmcontrol())?;
+
+        Ok(supported)
+    }
+
     /// Mark S0 to be saved on the next `save_s0` call.
     #[allow(unused)]
     fn should_save_s0(&mut self, should_save: bool) {
@@ -347,7 +436,7 @@ impl RiscvCommunicationInterface {
     }
 
     fn save_s0(&mut self) -> Result<bool, RiscvError> {
-        let s0 = self.abstract_cmd_register_read(&registers::S0)?;
+        let s0 = self.abstract_cmd_register_read_xlen(&registers::S0)?;
 
         self.state.s0.push(s0);
 
@@ -358,7 +447,7 @@ impl RiscvCommunicationInterface {
         if saved {
             let s0 = self.state.s0.pop().unwrap();
 
-            self.abstract_cmd_register_write(&registers::S0, s0)?;
+            self.abstract_cmd_register_write_xlen(&registers::S0, s0)?;
         }
 
         Ok(())
@@ -371,7 +460,7 @@ impl RiscvCommunicationInterface {
     }
 
     fn save_s1(&mut self) -> Result<bool, RiscvError> {
-        let s1 = self.abstract_cmd_register_read(&registers::S1)?;
+        let s1 = self.abstract_cmd_register_read_xlen(&registers::S1)?;
 
         self.state.s1.push(s1);
 
@@ -382,7 +471,7 @@ impl RiscvCommunicationInterface {
         if saved {
             let s1 = self.state.s1.pop().unwrap();
 
-            self.abstract_cmd_register_write(&registers::S1, s1)?;
+            self.abstract_cmd_register_write_xlen(&registers::S1, s1)?;
         }
 
         Ok(())
@@ -537,6 +626,8 @@ impl RiscvCommunicationInterface {
         // the system bus access conforms to the debug
         // specification 13.2.
         if sbcs.sbversion() == 1 {
+            self.state.sbasize = sbcs.sbasize() as u8;
+
             // When possible, we use system bus access for memory access
 
             if sbcs.sbaccess8() {
@@ -698,9 +789,9 @@ impl RiscvCommunicationInterface {
     }
 
     /// Perform a single read from a memory location, using system bus access.
-    fn perform_memory_read_sysbus<V: RiscvValue32>(
+    fn perform_memory_read_sysbus<V: RiscvMemoryValue>(
         &mut self,
-        address: u32,
+        address: u64,
     ) -> Result<V, RiscvError> {
         let mut sbcs = Sbcs(0);
 
@@ -708,9 +799,9 @@ impl RiscvCommunicationInterface {
         sbcs.set_sbreadonaddr(true);
 
         self.schedule_write_dm_register(sbcs)?;
-        self.schedule_write_dm_register(Sbaddress0(address))?;
+        self.schedule_write_sbaddress(address)?;
 
-        let data_idx = self.schedule_read_large_dtm_register::<V, Sbdata>()?;
+        let data_idx = self.schedule_read_value::<V, Sbdata>()?;
 
         // Check that the read was succesful
         let sbcs = self.read_dm_register::<Sbcs>()?;
@@ -718,18 +809,16 @@ impl RiscvCommunicationInterface {
         if sbcs.sberror() != 0 {
             Err(RiscvError::SystemBusAccess)
         } else {
-            let data = V::from_register_value(self.dtm.read_deferred_result(data_idx)?.as_u32());
-
-            Ok(data)
+            self.read_deferred_value(data_idx)
         }
     }
 
     /// Perform multiple reads from consecutive memory locations
     /// using system bus access.
-    /// Only reads up to a width of 32 bits are currently supported.
-    fn perform_memory_read_multiple_sysbus<V: RiscvValue32>(
+    /// Only reads up to a width of 64 bits are currently supported.
+    fn perform_memory_read_multiple_sysbus<V: RiscvMemoryValue>(
         &mut self,
-        address: u32,
+        address: u64,
         data: &mut [V],
     ) -> Result<(), RiscvError> {
         let mut sbcs = Sbcs(0);
@@ -743,13 +832,13 @@ impl RiscvCommunicationInterface {
 
         self.schedule_write_dm_register(sbcs)?;
 
-        self.schedule_write_dm_register(Sbaddress0(address))?;
+        self.schedule_write_sbaddress(address)?;
 
         let data_len = data.len();
 
-        let mut read_results: Vec<DeferredResultIndex> = vec![];
+        let mut read_results: Vec<DeferredValue> = vec![];
         for _ in data[..data_len - 1].iter() {
-            let idx = self.schedule_read_large_dtm_register::<V, Sbdata>()?;
+            let idx = self.schedule_read_value::<V, Sbdata>()?;
             read_results.push(idx);
         }
 
@@ -757,12 +846,12 @@ impl RiscvCommunicationInterface {
         self.schedule_write_dm_register(sbcs)?;
 
         // Read last value
-        read_results.push(self.schedule_read_large_dtm_register::<V, Sbdata>()?);
+        read_results.push(self.schedule_read_value::<V, Sbdata>()?);
 
         let sbcs = self.read_dm_register::<Sbcs>()?;
 
         for (out_index, idx) in read_results.into_iter().enumerate() {
-            data[out_index] = V::from_register_value(self.dtm.read_deferred_result(idx)?.as_u32());
+            data[out_index] = self.read_deferred_value(idx)?;
         }
 
         // Check that the read was succesful
@@ -774,10 +863,10 @@ impl RiscvCommunicationInterface {
     }
 
     /// Perform memory read from a single location using the program buffer.
-    /// Only reads up to a width of 32 bits are currently supported.
-    fn perform_memory_read_progbuf<V: RiscvValue32>(
+    /// Only reads up to a width of 64 bits are currently supported.
+    fn perform_memory_read_progbuf<V: RiscvMemoryValue>(
         &mut self,
-        address: u32,
+        address: u64,
     ) -> Result<V, RiscvError> {
         // assemble
         //  lb s1, 0(s0)
@@ -788,7 +877,8 @@ impl RiscvCommunicationInterface {
 
         self.schedule_setup_program_buffer(&[lw_command])?;
 
-        self.schedule_write_dm_register(Data0(address))?;
+        let xlen = self.xlen()?;
+        self.schedule_write_argument(xlen, address)?;
 
         // Write s0, then execute program buffer
         let mut command = AccessRegisterCommand(0);
@@ -796,8 +886,8 @@ impl RiscvCommunicationInterface {
         command.set_transfer(true);
         command.set_write(true);
 
-        // registers are 32 bit, so we have size 2 here
-        command.set_aarsize(RiscvBusAccess::A32);
+        // registers are XLEN bits wide
+        command.set_aarsize(xlen);
         command.set_postexec(true);
 
         // register s0, ie. 0x1008
@@ -808,7 +898,7 @@ impl RiscvCommunicationInterface {
         let abstractcs_idx = self.schedule_read_dm_register::<Abstractcs>()?;
 
         // Read back s0
-        let value = self.abstract_cmd_register_read(&registers::S0)?;
+        let value = self.abstract_cmd_register_read_xlen(&registers::S0)?;
 
         let abstractcs = Abstractcs(self.dtm.read_deferred_result(abstractcs_idx)?.as_u32());
         if abstractcs.cmderr() != 0 {
@@ -823,9 +913,9 @@ impl RiscvCommunicationInterface {
         Ok(V::from_register_value(value))
     }
 
-    fn perform_memory_read_multiple_progbuf<V: RiscvValue32>(
+    fn perform_memory_read_multiple_progbuf<V: RiscvMemoryValue>(
         &mut self,
-        address: u32,
+        address: u64,
         data: &mut [V],
     ) -> Result<(), RiscvError> {
         // Backup registers s0 and s1
@@ -840,7 +930,8 @@ impl RiscvCommunicationInterface {
             assembly::addi(8, 8, V::WIDTH.byte_width() as i16),
         ])?;
 
-        self.schedule_write_dm_register(Data0(address))?;
+        let xlen = self.xlen()?;
+        self.schedule_write_argument(xlen, address)?;
 
         // Write s0, then execute program buffer
         let mut command = AccessRegisterCommand(0);
@@ -848,8 +939,8 @@ impl RiscvCommunicationInterface {
         command.set_transfer(true);
         command.set_write(true);
 
-        // registers are 32 bit, so we have size 2 here
-        command.set_aarsize(RiscvBusAccess::A32);
+        // registers are XLEN bits wide
+        command.set_aarsize(xlen);
         command.set_postexec(true);
 
         // register s0, ie. 0x1008
@@ -866,8 +957,8 @@ impl RiscvCommunicationInterface {
             command.set_transfer(true);
             command.set_write(false);
 
-            // registers are 32 bit, so we have size 2 here
-            command.set_aarsize(RiscvBusAccess::A32);
+            // registers are XLEN bits wide
+            command.set_aarsize(xlen);
             command.set_postexec(true);
 
             command.set_regno((registers::S1).id.0 as u32);
@@ -875,20 +966,18 @@ impl RiscvCommunicationInterface {
             self.schedule_write_dm_register(command)?;
 
             // Read back s1
-            let value_idx = self.schedule_read_dm_register::<Data0>()?;
+            let value_idx = self.schedule_read_value::<V, Arg0>()?;
 
             result_idxs.push((out_idx, value_idx));
         }
 
         // Specifically read the last value first. The result is that this last read is still
         // part of the command queue we just assembled.
-        let last_value = self.abstract_cmd_register_read(&registers::S1)?;
+        let last_value = self.abstract_cmd_register_read_xlen(&registers::S1)?;
         data[data.len() - 1] = V::from_register_value(last_value);
 
         for (out_idx, value_idx) in result_idxs {
-            let value = Data0::from(self.dtm.read_deferred_result(value_idx)?.as_u32());
-
-            data[out_idx] = V::from_register_value(value.0);
+            data[out_idx] = self.read_deferred_value(value_idx)?;
         }
 
         let status: Abstractcs = self.read_dm_register()?;
@@ -908,7 +997,7 @@ impl RiscvCommunicationInterface {
     /// Memory write using system bus
     fn perform_memory_write_sysbus<V: RiscvValue>(
         &mut self,
-        address: u32,
+        address: u64,
         data: &[V],
     ) -> Result<(), RiscvError> {
         let mut sbcs = Sbcs(0);
@@ -919,7 +1008,7 @@ impl RiscvCommunicationInterface {
 
         self.schedule_write_dm_register(sbcs)?;
 
-        self.schedule_write_dm_register(Sbaddress0(address))?;
+        self.schedule_write_sbaddress(address)?;
 
         for value in data {
             self.schedule_write_large_dtm_register::<V, Sbdata>(*value)?;
@@ -936,10 +1025,10 @@ impl RiscvCommunicationInterface {
     }
 
     /// Perform memory write to a single location using the program buffer.
-    /// Only writes up to a width of 32 bits are currently supported.
-    fn perform_memory_write_progbuf<V: RiscvValue32>(
+    /// Only writes up to a width of 64 bits are currently supported.
+    fn perform_memory_write_progbuf<V: RiscvMemoryValue>(
         &mut self,
-        address: u32,
+        address: u64,
         data: V,
     ) -> Result<(), RiscvError> {
         tracing::debug!(
@@ -957,10 +1046,11 @@ impl RiscvCommunicationInterface {
         self.schedule_setup_program_buffer(&[sw_command])?;
 
         // write address into s0
-        self.abstract_cmd_register_write(&registers::S0, address)?;
+        self.abstract_cmd_register_write_xlen(&registers::S0, address)?;
 
         // write data into data 0
-        self.schedule_write_dm_register(Data0(data.into()))?;
+        let xlen = self.xlen()?;
+        self.schedule_write_argument(xlen, data.into())?;
 
         // Write s1, then execute program buffer
         let mut command = AccessRegisterCommand(0);
@@ -968,8 +1058,8 @@ impl RiscvCommunicationInterface {
         command.set_transfer(true);
         command.set_write(true);
 
-        // registers are 32 bit, so we have size 2 here
-        command.set_aarsize(RiscvBusAccess::A32);
+        // registers are XLEN bits wide
+        command.set_aarsize(xlen);
         command.set_postexec(true);
 
         // register s1, ie. 0x1009
@@ -998,10 +1088,10 @@ impl RiscvCommunicationInterface {
     }
 
     /// Perform multiple memory writes to consecutive locations using the program buffer.
-    /// Only writes up to a width of 32 bits are currently supported.
-    fn perform_memory_write_multiple_progbuf<V: RiscvValue32>(
+    /// Only writes up to a width of 64 bits are currently supported.
+    fn perform_memory_write_multiple_progbuf<V: RiscvMemoryValue>(
         &mut self,
-        address: u32,
+        address: u64,
         data: &[V],
     ) -> Result<(), RiscvError> {
         let s0 = self.save_s0()?;
@@ -1016,11 +1106,13 @@ impl RiscvCommunicationInterface {
         ])?;
 
         // write address into s0
-        self.abstract_cmd_register_write(&registers::S0, address)?;
+        self.abstract_cmd_register_write_xlen(&registers::S0, address)?;
+
+        let xlen = self.xlen()?;
 
         for value in data {
             // write address into data 0
-            self.schedule_write_dm_register(Data0((*value).into()))?;
+            self.schedule_write_argument(xlen, (*value).into())?;
 
             // Write s0, then execute program buffer
             let mut command = AccessRegisterCommand(0);
@@ -1028,8 +1120,8 @@ impl RiscvCommunicationInterface {
             command.set_transfer(true);
             command.set_write(true);
 
-            // registers are 32 bit, so we have size 2 here
-            command.set_aarsize(RiscvBusAccess::A32);
+            // registers are XLEN bits wide
+            command.set_aarsize(xlen);
             command.set_postexec(true);
 
             // register s1
@@ -1067,11 +1159,10 @@ impl RiscvCommunicationInterface {
         // resumereq    = 0
         // ackhavereset = 0
 
-        let mut dmcontrol = Dmcontrol(0);
+        let mut dmcontrol = self.dmcontrol();
         dmcontrol.set_haltreq(false);
         dmcontrol.set_resumereq(false);
         dmcontrol.set_ackhavereset(false);
-        dmcontrol.set_dmactive(true);
         self.schedule_write_dm_register(dmcontrol)?;
 
         // Clear any previous command errors.
@@ -1139,10 +1230,10 @@ impl RiscvCommunicationInterface {
     }
 
     // Read a core register using an abstract command
-    pub(crate) fn abstract_cmd_register_read(
+    pub(crate) fn abstract_cmd_register_read<V: RiscvValue>(
         &mut self,
         regno: impl Into<RegisterId>,
-    ) -> Result<u32, RiscvError> {
+    ) -> Result<V, RiscvError> {
         let regno = regno.into();
 
         // Check if the register was already tried via abstract cmd
@@ -1156,7 +1247,7 @@ impl RiscvCommunicationInterface {
         let mut command = AccessRegisterCommand(0);
         command.set_cmd_type(0);
         command.set_transfer(true);
-        command.set_aarsize(RiscvBusAccess::A32);
+        command.set_aarsize(V::WIDTH);
 
         command.set_regno(regno.0 as u32);
 
@@ -1173,9 +1264,7 @@ impl RiscvCommunicationInterface {
             Err(e) => return Err(e),
         }
 
-        let register_value: Data0 = self.read_dm_register()?;
-
-        Ok(register_value.into())
+        V::read_from_register::<Arg0>(self)
     }
 
     pub(crate) fn abstract_cmd_register_write<V: RiscvValue>(
@@ -1217,8 +1306,141 @@ impl RiscvCommunicationInterface {
         }
     }
 
+    /// Returns the width of the general purpose registers (XLEN) of the hart.
+    ///
+    /// The width is determined with the first call, by reading `s0` with a 64-bit
+    /// abstract command, which fails on 32-bit harts. This requires the hart to be halted.
+    pub(crate) fn xlen(&mut self) -> Result<RiscvBusAccess, RiscvError> {
+        if let Some(xlen) = self.cached_xlen() {
+            return Ok(xlen);
+        }
+
+        let mut command = AccessRegisterCommand(0);
+        command.set_cmd_type(0);
+        command.set_transfer(true);
+        command.set_aarsize(RiscvBusAccess::A64);
+        command.set_regno((registers::S0).id.0 as u32);
+
+        let xlen = match self.execute_abstract_command(command.0) {
+            Ok(()) => RiscvBusAccess::A64,
+            Err(RiscvError::AbstractCommand(
+                AbstractCommandErrorKind::NotSupported | AbstractCommandErrorKind::Exception,
+            )) => RiscvBusAccess::A32,
+            Err(e) => return Err(e),
+        };
+
+        tracing::debug!("XLEN: {}", xlen.byte_width() * 8);
+        self.state.xlen.insert(self.state.current_hart, xlen);
+
+        Ok(xlen)
+    }
+
+    /// Returns the width of the general purpose registers of the hart,
+    /// if it has already been determined.
+    pub(crate) fn cached_xlen(&self) -> Option<RiscvBusAccess> {
+        self.state.xlen.get(&self.state.current_hart).copied()
+    }
+
+    /// Read a core register with the width of the general purpose registers, using an abstract command.
+    pub(crate) fn abstract_cmd_register_read_xlen(
+        &mut self,
+        regno: impl Into<RegisterId>,
+    ) -> Result<u64, RiscvError> {
+        match self.xlen()? {
+            RiscvBusAccess::A64 => self.abstract_cmd_register_read(regno),
+            _ => self.abstract_cmd_register_read::<u32>(regno).map(u64::from),
+        }
+    }
+
+    /// Write a core register with the width of the general purpose registers, using an abstract command.
+    pub(crate) fn abstract_cmd_register_write_xlen(
+        &mut self,
+        regno: impl Into<RegisterId>,
+        value: u64,
+    ) -> Result<(), RiscvError> {
+        match self.xlen()? {
+            RiscvBusAccess::A64 => self.abstract_cmd_register_write(regno, value),
+            _ => self.abstract_cmd_register_write(regno, value as u32),
+        }
+    }
+
+    /// Schedules a write of the argument of an abstract command with the given width.
+    fn schedule_write_argument(
+        &mut self,
+        width: RiscvBusAccess,
+        value: u64,
+    ) -> Result<(), RiscvError> {
+        if width == RiscvBusAccess::A64 {
+            self.schedule_write_large_dtm_register::<u64, Arg0>(value)?;
+        } else {
+            self.schedule_write_large_dtm_register::<u32, Arg0>(value as u32)?;
+        }
+
+        Ok(())
+    }
+
+    /// Schedules a write of the system bus address. The upper bits are only written
+    /// if the system bus is wider than 32 bits, as writing `sbaddress0` starts the access.
+    fn schedule_write_sbaddress(&mut self, address: u64) -> Result<(), RiscvError> {
+        if self.state.sbasize > 32 {
+            self.schedule_write_dm_register(Sbaddress1((address >> 32) as u32))?;
+        }
+
+        self.schedule_write_dm_register(Sbaddress0(address as u32))
+    }
+
+    /// Schedules a read of a value of up to 64 bits from a large register.
+    fn schedule_read_value<V, R>(&mut self) -> Result<DeferredValue, RiscvError>
+    where
+        V: RiscvMemoryValue,
+        R: LargeRegister,
+    {
+        // The lowest word has to be read last, as reading it can start the next access.
+        let upper = if V::WIDTH == RiscvBusAccess::A64 {
+            Some(self.schedule_read_dm_register_untyped(R::R1_ADDRESS as u64)?)
+        } else {
+            None
+        };
+        let lower = self.schedule_read_large_dtm_register::<u32, R>()?;
+
+        Ok(DeferredValue { lower, upper })
+    }
+
+    fn read_deferred_value<V: RiscvMemoryValue>(
+        &mut self,
+        value: DeferredValue,
+    ) -> Result<V, RiscvError> {
+        let mut result = self.dtm.read_deferred_result(value.lower)?.as_u32() as u64;
+        if let Some(upper) = value.upper {
+            result |= (self.dtm.read_deferred_result(upper)?.as_u32() as u64) << 32;
+        }
+
+        Ok(V::from_register_value(result))
+    }
+
+    /// Checks that `address` fits into the address space of the hart or the system bus.
+    fn valid_address(&self, address: u64) -> Result<u64, crate::Error> {
+        if self.cached_xlen() == Some(RiscvBusAccess::A64) || self.state.sbasize > 32 {
+            Ok(address)
+        } else {
+            valid_32bit_address(address).map(u64::from)
+        }
+    }
+
+    /// Execute a `fence.i` instruction in the program buffer, so that the hart fetches
+    /// the instructions which were written by the debugger from memory again.
+    pub(crate) fn execute_fence_i(&mut self) -> Result<(), RiscvError> {
+        self.schedule_setup_program_buffer(&[assembly::FENCE_I])?;
+
+        // command: postexec
+        let mut postexec_cmd = AccessRegisterCommand(0);
+        postexec_cmd.set_postexec(true);
+
+        self.execute_abstract_command(postexec_cmd.0)
+    }
+
     /// Read the CSR `progbuf` register.
-    pub fn read_csr_progbuf(&mut self, address: u16) -> Result<u32, RiscvError> {
+    pub fn read_csr_progbuf(&mut self, address: u16) -> Result<u64, RiscvError> {
         tracing::debug!("Reading CSR {:#04x}", address);
 
         // Validate that the CSR address is valid
@@ -1240,7 +1462,7 @@ impl RiscvCommunicationInterface {
         self.execute_abstract_command(postexec_cmd.0)?;
 
         // read the s0 value
-        let reg_value = self.abstract_cmd_register_read(&registers::S0)?;
+        let reg_value = self.abstract_cmd_register_read_xlen(&registers::S0)?;
 
         // restore original value in s0
         self.restore_s0(s0)?;
@@ -1249,7 +1471,7 @@ impl RiscvCommunicationInterface {
     }
 
     /// Write the CSR `progbuf` register.
-    pub fn write_csr_progbuf(&mut self, address: u16, value: u32) -> Result<(), RiscvError> {
+    pub fn write_csr_progbuf(&mut self, address: u16, value: u64) -> Result<(), RiscvError> {
         tracing::debug!("Writing CSR {:#04x}={}", address, value);
 
         // Validate that the CSR address is valid
@@ -1261,7 +1483,7 @@ impl RiscvCommunicationInterface {
         let s0 = self.save_s0()?;
 
         // Write value into s0
-        self.abstract_cmd_register_write(&registers::S0, value)?;
+        self.abstract_cmd_register_write_xlen(&registers::S0, value)?;
 
         // Built the CSRW command to write into the program buffer
         let csrw_cmd = assembly::csrw(address, 8);
@@ -1280,7 +1502,7 @@ impl RiscvCommunicationInterface {
         Ok(())
     }
 
-    fn read_word<V: RiscvValue32>(&mut self, address: u32) -> Result<V, crate::Error> {
+    fn read_word<V: RiscvMemoryValue>(&mut self, address: u64) -> Result<V, crate::Error> {
         let result = match self.state.memory_access_method(V::WIDTH) {
             MemoryAccessMethod::ProgramBuffer => self.perform_memory_read_progbuf(address)?,
             MemoryAccessMethod::SystemBus => self.perform_memory_read_sysbus(address)?,
@@ -1292,14 +1514,14 @@ impl RiscvCommunicationInterface {
         Ok(result)
     }
 
-    fn read_multiple<V: RiscvValue32>(
+    fn read_multiple<V: RiscvMemoryValue>(
         &mut self,
-        address: u32,
+        address: u64,
         data: &mut [V],
     ) -> Result<(), crate::Error> {
-        tracing::debug!("read_32 from {:#08x}", address);
+        tracing::debug!("read_multiple from {:#08x}", address);
 
-        match self.state.memory_access_method(RiscvBusAccess::A32) {
+        match self.state.memory_access_method(V::WIDTH) {
             MemoryAccessMethod::ProgramBuffer => {
                 self.perform_memory_read_multiple_progbuf(address, data)?;
             }
@@ -1314,7 +1536,11 @@ impl RiscvCommunicationInterface {
         Ok(())
     }
 
-    fn write_word<V: RiscvValue32>(&mut self, address: u32, data: V) -> Result<(), crate::Error> {
+    fn write_word<V: RiscvMemoryValue>(
+        &mut self,
+        address: u64,
+        data: V,
+    ) -> Result<(), crate::Error> {
         match self.state.memory_access_method(V::WIDTH) {
             MemoryAccessMethod::ProgramBuffer => {
                 self.perform_memory_write_progbuf(address, data)?
@@ -1328,9 +1554,9 @@ impl RiscvCommunicationInterface {
         Ok(())
     }
 
-    fn write_multiple<V: RiscvValue32>(
+    fn write_multiple<V: RiscvMemoryValue>(
         &mut self,
-        address: u32,
+        address: u64,
         data: &[V],
     ) -> Result<(), crate::Error> {
         match self.state.memory_access_method(V::WIDTH) {
@@ -1457,23 +1683,35 @@ impl LargeRegister for Arg0 {
     const R3_ADDRESS: u8 = Data3::ADDRESS_OFFSET as u8;
 }
 
-/// Helper trait, limited to RiscvValue no larger than 32 bits
-pub(crate) trait RiscvValue32: RiscvValue + Into<u32> {
-    fn from_register_value(value: u32) -> Self;
+/// The scheduled reads of a value of up to 64 bits from a [`LargeRegister`].
+struct DeferredValue {
+    lower: DeferredResultIndex,
+    upper: Option<DeferredResultIndex>,
 }
 
-impl RiscvValue32 for u8 {
-    fn from_register_value(value: u32) -> Self {
+/// Helper trait, limited to RiscvValue no larger than 64 bits,
+/// which can be transferred to and from memory.
+pub(crate) trait RiscvMemoryValue: RiscvValue + Into<u64> {
+    fn from_register_value(value: u64) -> Self;
+}
+
+impl RiscvMemoryValue for u8 {
+    fn from_register_value(value: u64) -> Self {
         value as u8
     }
 }
-impl RiscvValue32 for u16 {
-    fn from_register_value(value: u32) -> Self {
+impl RiscvMemoryValue for u16 {
+    fn from_register_value(value: u64) -> Self {
         value as u16
     }
 }
-impl RiscvValue32 for u32 {
-    fn from_register_value(value: u32) -> Self {
+impl RiscvMemoryValue for u32 {
+    fn from_register_value(value: u64) -> Self {
+        value as u32
+    }
+}
+impl RiscvMemoryValue for u64 {
+    fn from_register_value(value: u64) -> Self {
         value
     }
 }
@@ -1778,11 +2016,21 @@ impl RiscvValue for u128 {
 
 impl MemoryInterface for RiscvCommunicationInterface {
     fn supports_native_64bit_access(&mut self) -> bool {
-        false
+        // 64-bit values are accessed with a 64-bit system bus access,
+        // or with `ld` and `sd` on 64-bit harts.
+        self.cached_xlen() == Some(RiscvBusAccess::A64)
+            || matches!(
+                self.state.memory_access_info.get(&RiscvBusAccess::A64),
+                Some(MemoryAccessMethod::SystemBus)
+            )
     }
 
     fn read_word_64(&mut self, address: u64) -> Result<u64, crate::error::Error> {
-        let address = valid_32bit_address(address)?;
+        let address = self.valid_address(address)?;
+        if self.supports_native_64bit_access() {
+            return self.read_word(address);
+        }
+
         let mut ret = self.read_word::<u32>(address)? as u64;
         ret |= (self.read_word::<u32>(address + 4)? as u64) << 32;
 
@@ -1790,47 +2038,55 @@ impl MemoryInterface for RiscvCommunicationInterface {
     }
 
     fn read_word_32(&mut self, address: u64) -> Result<u32, crate::Error> {
-        let address = valid_32bit_address(address)?;
+        let address = self.valid_address(address)?;
         self.read_word(address)
     }
 
     fn read_word_8(&mut self, address: u64) -> Result<u8, crate::Error> {
-        let address = valid_32bit_address(address)?;
+        let address = self.valid_address(address)?;
         tracing::debug!("read_word_8 from {:#08x}", address);
         self.read_word(address)
     }
 
     fn read_64(&mut self, address: u64, data: &mut [u64]) -> Result<(), crate::error::Error> {
-        let address = valid_32bit_address(address)?;
+        let address = self.valid_address(address)?;
         tracing::debug!("read_64 from {:#08x}", address);
 
+        if self.supports_native_64bit_access() {
+            return self.read_multiple(address, data);
+        }
+
         for (i, d) in data.iter_mut().enumerate() {
-            *d = self.read_word_64((address + (i as u32 * 8)).into())?;
+            *d = self.read_word_64(address + (i as u64 * 8))?;
         }
 
         Ok(())
     }
 
     fn read_32(&mut self, address: u64, data: &mut [u32]) -> Result<(), crate::Error> {
-        let address = valid_32bit_address(address)?;
+        let address = self.valid_address(address)?;
         tracing::debug!("read_32 from {:#08x}", address);
         self.read_multiple(address, data)
     }
 
     fn read_8(&mut self, address: u64, data: &mut [u8]) -> Result<(), crate::Error> {
-        let address = valid_32bit_address(address)?;
+        let address = self.valid_address(address)?;
         tracing::debug!("read_8 from {:#08x}", address);
 
         self.read_multiple(address, data)
     }
 
     fn read(&mut self, address: u64, data: &mut [u8]) -> Result<(), crate::Error> {
-        let address = valid_32bit_address(address)?;
+        let address = self.valid_address(address)?;
         self.read_multiple(address, data)
     }
 
     fn write_word_64(&mut self, address: u64, data: u64) -> Result<(), crate::error::Error> {
-        let address = valid_32bit_address(address)?;
+        let address = self.valid_address(address)?;
+        if self.supports_native_64bit_access() {
+            return self.write_word(address, data);
+        }
+
         let low_word = data as u32;
         let high_word = (data >> 32) as u32;
 
@@ -1839,42 +2095,46 @@ impl MemoryInterface for RiscvCommunicationInterface {
     }
 
     fn write_word_32(&mut self, address: u64, data: u32) -> Result<(), crate::Error> {
-        let address = valid_32bit_address(address)?;
+        let address = self.valid_address(address)?;
         self.write_word(address, data)
     }
 
     fn write_word_8(&mut self, address: u64, data: u8) -> Result<(), crate::Error> {
-        let address = valid_32bit_address(address)?;
+        let address = self.valid_address(address)?;
         self.write_word(address, data)
     }
 
     fn write_64(&mut self, address: u64, data: &[u64]) -> Result<(), crate::error::Error> {
-        let address = valid_32bit_address(address)?;
+        let address = self.valid_address(address)?;
         tracing::debug!("write_64 to {:#08x}", address);
 
+        if self.supports_native_64bit_access() {
+            return self.write_multiple(address, data);
+        }
+
         for (i, d) in data.iter().enumerate() {
-            self.write_word_64((address + (i as u32 * 8)).into(), *d)?;
+            self.write_word_64(address + (i as u64 * 8), *d)?;
         }
 
         Ok(())
     }
 
     fn write_32(&mut self, address: u64, data: &[u32]) -> Result<(), crate::Error> {
-        let address = valid_32bit_address(address)?;
+        let address = self.valid_address(address)?;
         tracing::debug!("write_32 to {:#08x}", address);
 
         self.write_multiple(address, data)
     }
 
     fn write_8(&mut self, address: u64, data: &[u8]) -> Result<(), crate::Error> {
-        let address = valid_32bit_address(address)?;
+        let address = self.valid_address(address)?;
         tracing::debug!("write_8 to {:#08x}", address);
 
         self.write_multiple(address, data)
     }
 
     fn write(&mut self, address: u64, data: &[u8]) -> Result<(), crate::Error> {
-        let address = valid_32bit_address(address)?;
+        let address = self.valid_address(address)?;
         self.write_multiple(address, data)
     }
 
@@ -9,12 +9,15 @@ use crate::{
     },
     memory::valid_32bit_address,
     memory_mapped_bitfield_register, CoreInterface, CoreRegister, CoreStatus, CoreType, Error,
-    HaltReason, InstructionSet, MemoryInterface, MemoryMappedRegister,
+    HaltReason, InstructionSet, MemoryInterface, MemoryMappedRegister, VectorCatchCondition,
+    Watchpoint, WatchpointKind,
 };
 use anyhow::{anyhow, Result};
 use bitfield::bitfield;
-use communication_interface::{AbstractCommandErrorKind, RiscvCommunicationInterface, RiscvError};
-use registers::RISCV_CORE_REGSISTERS;
+use communication_interface::{
+    AbstractCommandErrorKind, RiscvBusAccess, RiscvCommunicationInterface, RiscvError,
+};
+use registers::{RISCV64_CORE_REGSISTERS, RISCV_CORE_REGSISTERS};
 use std::time::{Duration, Instant};
 
 #[macro_use]
@@ -25,6 +28,26 @@ pub mod communication_interface;
 mod dtm;
 pub mod sequences;
 
+/// The debug control and status register, which is 32 bits wide on all harts.
+const DCSR: u16 = 0x7b0;
+
+/// The trigger data register holding the configuration of the selected trigger.
+const TDATA1: u16 = 0x7a1;
+
+/// Exception causes which halt the hart when faults are caught: misaligned accesses, access faults,
+/// illegal instructions and page faults. Breakpoints and environment calls are left out, because
+/// they are used for debugging and semihosting.
+const FAULT_EXCEPTIONS: u64 = (1 << 0)
+    | (1 << 1)
+    | (1 << 2)
+    | (1 << 4)
+    | (1 << 5)
+    | (1 << 6)
+    | (1 << 7)
+    | (1 << 12)
+    | (1 << 13)
+    | (1 << 15);
+
 /// An interface to operate RISC-V cores.
 pub struct Riscv32<'probe> {
     interface: &'probe mut RiscvCommunicationInterface,
@@ -46,7 +69,31 @@ impl<'probe> Riscv32<'probe> {
         }
     }
 
+    /// Returns true if the hart is known to be a 64-bit (RV64) hart.
+    fn is_64_bit(&self) -> bool {
+        self.interface.cached_xlen() == Some(RiscvBusAccess::A64)
+    }
+
+    /// Returns the width of a register, which is XLEN for all registers except `dcsr`.
+    fn register_width(&mut self, address: u16) -> Result<RiscvBusAccess, RiscvError> {
+        if address == DCSR {
+            Ok(RiscvBusAccess::A32)
+        } else {
+            self.interface.xlen()
+        }
+    }
+
+    /// Reads the lower 32 bits of a CSR.
     fn read_csr(&mut self, address: u16) -> Result<u32, RiscvError> {
+        self.read_register(address).map(|value| value as u32)
+    }
+
+    /// Writes a CSR, zero extending the value on 64-bit harts.
+    fn write_csr(&mut self, address: u16, value: u32) -> Result<(), RiscvError> {
+        self.write_register(address, value.into())
+    }
+
+    fn read_register(&mut self, address: u16) -> Result<u64, RiscvError> {
         // We need to use the "Access Register Command",
         // which has cmdtype 0
 
@@ -55,9 +102,17 @@ impl<'probe> Riscv32<'probe> {
 
         tracing::debug!("Reading CSR {:#x}", address);
 
+        let result = match self.register_width(address)? {
+            RiscvBusAccess::A64 => self.interface.abstract_cmd_register_read(address),
+            _ => self
+                .interface
+                .abstract_cmd_register_read::<u32>(address)
+                .map(u64::from),
+        };
+
         // always try to read register with abstract command, fallback to program buffer,
         // if not supported
-        match self.interface.abstract_cmd_register_read(address) {
+        match result {
             Err(RiscvError::AbstractCommand(AbstractCommandErrorKind::NotSupported)) => {
                 tracing::debug!("Could not read core register {:#x} with abstract command, falling back to program buffer", address);
                 self.interface.read_csr_progbuf(address)
@@ -66,10 +121,17 @@ impl<'probe> Riscv32<'probe> {
         }
     }
 
-    fn write_csr(&mut self, address: u16, value: u32) -> Result<(), RiscvError> {
+    fn write_register(&mut self, address: u16, value: u64) -> Result<(), RiscvError> {
         tracing::debug!("Writing CSR {:#x}", address);
 
-        match self.interface.abstract_cmd_register_write(address, value) {
+        let result = match self.register_width(address)? {
+            RiscvBusAccess::A64 => self.interface.abstract_cmd_register_write(address, value),
+            _ => self
+                .interface
+                .abstract_cmd_register_write(address, value as u32),
+        };
+
+        match result {
             Err(RiscvError::AbstractCommand(AbstractCommandErrorKind::NotSupported)) => {
                 tracing::debug!("Could not write core register {:#x} with abstract command, falling back to program buffer", address);
                 self.interface.write_csr_progbuf(address, value)
@@ -78,12 +140,188 @@ impl<'probe> Riscv32<'probe> {
         }
     }
 
+    /// Reads `tdata1` of the selected trigger as an `mcontrol` trigger.
+    fn read_mcontrol(&mut self) -> Result<Mcontrol, RiscvError> {
+        let value = self.read_register(TDATA1)?;
+
+        Ok(Mcontrol::from_tdata1(value, self.is_64_bit()))
+    }
+
+    /// Returns true if the trigger `trigger_index` is used to halt on exceptions,
+    /// or if `used_by` returns true for it.
+    fn trigger_in_use(
+        &mut self,
+        trigger_index: u32,
+        used_by: fn(&Mcontrol) -> bool,
+    ) -> Result<bool, Error> {
+        if self.state.exception_trigger == Some(trigger_index) {
+            return Ok(true);
+        }
+
+        self.write_csr(0x7a0, trigger_index)?;
+        let tdata_value = self.read_mcontrol()?;
+
+        Ok(used_by(&tdata_value))
+    }
+
+    /// Writes `tdata1` of the selected trigger as an `mcontrol` trigger.
+    fn write_mcontrol(&mut self, mcontrol: Mcontrol) -> Result<(), RiscvError> {
+        let value = mcontrol.to_tdata1(self.is_64_bit());
+
+        self.write_register(TDATA1, value)
+    }
+
+    /// Sets up an exception trigger (`etrigger`), which halts the hart when it takes one of the
+    /// [`FAULT_EXCEPTIONS`].
+    ///
+    /// Triggers which can only be used for exceptions are preferred, and triggers in use for
+    /// breakpoints or watchpoints are never taken over.
+    fn set_exception_trigger(&mut self) -> Result<(), crate::Error> {
+        if self.state.exception_trigger.is_some() {
+            return Ok(());
+        }
+
+        let tselect = 0x7a0;
+        let tdata2 = 0x7a2;
+        let tinfo = 0x7a4;
+
+        let mut exception_trigger = None;
+        for trigger_index in 0..self.available_breakpoint_units()? {
+            self.write_csr(tselect, trigger_index)?;
+
+            let supported_types = match self.read_csr(tinfo) {
+                Ok(tinfo_val) => tinfo_val & 0xffff,
+                // Without `tinfo`, the trigger only supports its current type.
+                Err(RiscvError::AbstractCommand(AbstractCommandErrorKind::Exception)) => {
+                    1 << self.read_mcontrol()?.type_()
+                }
+                Err(other) => return Err(other.into()),
+            };
+            if supported_types & (1 << Etrigger::TYPE) == 0 {
+                continue;
+            }
+
+            let tdata_value = self.read_mcontrol()?;
+            if tdata_value.type_() == 0b10
+                && (tdata_value.execute() || tdata_value.load() || tdata_value.store())
+            {
+                continue;
+            }
+
+            let dedicated = supported_types & (1 << 0b10) == 0;
+            if exception_trigger.is_none() || dedicated {
+                exception_trigger = Some(trigger_index);
+            }
+            if dedicated {
+                break;
+            }
+        }
+
+        let Some(trigger_index) = exception_trigger else {
+            return Err(RiscvError::ExceptionTriggerNotSupported.into());
+        };
+
+        tracing::debug!("Using trigger {} to halt on exceptions", trigger_index);
+
+        self.write_csr(tselect, trigger_index)?;
+
+        let mut etrigger = Etrigger(0);
+        etrigger.set_type(Etrigger::TYPE);
+        etrigger.set_dmode(true);
+        // Enter debug mode
+        etrigger.set_action(1);
+        etrigger.set_m(true);
+        etrigger.set_s(true);
+        etrigger.set_u(true);
+
+        self.write_register(TDATA1, etrigger.to_tdata1(self.is_64_bit()))?;
+        self.write_register(tdata2, FAULT_EXCEPTIONS)?;
+
+        self.state.exception_trigger = Some(trigger_index);
+        Ok(())
+    }
+
+    /// Clears the exception trigger set up by [`Self::set_exception_trigger`].
+    fn clear_exception_trigger(&mut self) -> Result<(), crate::Error> {
+        if let Some(trigger_index) = self.state.exception_trigger.take() {
+            self.clear_hw_breakpoint(trigger_index as usize)?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks if the exception trigger caused the last halt, and clears its `hit` bit.
+    fn exception_trigger_hit(&mut self) -> Result<bool, crate::Error> {
+        let Some(trigger_index) = self.state.exception_trigger else {
+            return Ok(false);
+        };
+
+        let tselect = 0x7a0;
+        self.write_csr(tselect, trigger_index)?;
+
+        let is_64_bit = self.is_64_bit();
+        let mut etrigger = Etrigger::from_tdata1(self.read_register(TDATA1)?, is_64_bit);
+        if !etrigger.hit() {
+            return Ok(false);
+        }
+
+        etrigger.set_hit(false);
+        self.write_register(TDATA1, etrigger.to_tdata1(is_64_bit))?;
+
+        Ok(true)
+    }
+
+    /// Checks if one of the watchpoint triggers caused the last halt, and clears its `hit` bit.
+    ///
+    /// Implementing the `hit` bit is optional, so this can return `false` even if a watchpoint was hit.
+    fn watchpoint_trigger_hit(&mut self) -> Result<bool, crate::Error> {
+        let tselect = 0x7a0;
+
+        let num_triggers = self.available_breakpoint_units()?;
+        for trigger_index in 0..num_triggers {
+            if Some(trigger_index) == self.state.exception_trigger {
+                continue;
+            }
+
+            self.write_csr(tselect, trigger_index)?;
+
+            let mut mcontrol = self.read_mcontrol()?;
+            let is_watchpoint = mcontrol.type_() == 0b10
+                && mcontrol.action() == 1
+                && !mcontrol.execute()
+                && (mcontrol.load() || mcontrol.store());
+            if is_watchpoint && mcontrol.hit() {
+                mcontrol.set_hit(false);
+                self.write_mcontrol(mcontrol)?;
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Returns the size of the EBREAK or C.EBREAK instruction at `address`,
+    /// or `None` if there is a different instruction.
+    fn ebreak_size(&mut self, address: u64) -> Result<Option<usize>, crate::Error> {
+        let mut instruction = [0; 4];
+        self.read_8(address, &mut instruction[..2])?;
+        if instruction[..2] == [0x02, 0x90] {
+            return Ok(Some(2));
+        }
+
+        self.read_8(address + 2, &mut instruction[2..])?;
+        if u32::from_le_bytes(instruction) == 0x0010_0073 {
+            return Ok(Some(4));
+        }
+
+        Ok(None)
+    }
+
     // Resume the core.
     fn resume_core(&mut self) -> Result<(), crate::Error> {
         // set resume request.
-        let mut dmcontrol = Dmcontrol(0);
+        let mut dmcontrol = self.interface.dmcontrol();
         dmcontrol.set_resumereq(true);
-        dmcontrol.set_dmactive(true);
         self.interface.write_dm_register(dmcontrol)?;
 
         // check if request has been acknowleged.
@@ -93,8 +331,7 @@ impl<'probe> Riscv32<'probe> {
         };
 
         // clear resume request.
-        let mut dmcontrol = Dmcontrol(0);
-        dmcontrol.set_dmactive(true);
+        let dmcontrol = self.interface.dmcontrol();
         self.interface.write_dm_register(dmcontrol)?;
 
         Ok(())
@@ -126,7 +363,7 @@ impl<'probe> Riscv32<'probe> {
         #[cfg(feature = "rtt")]
         {
             use crate::rtt::decode_semihosting_syscall;
-            let pc: u32 = core.read_core_reg(core.program_counter().id)?.try_into()?;
+            let pc: u64 = core.read_core_reg(core.program_counter().id)?.try_into()?;
 
             // The Riscv Semihosting Specification, specificies the following sequence of instructions,
             // to trigger a semihosting call:
@@ -140,7 +377,7 @@ impl<'probe> Riscv32<'probe> {
 
             // Read the actual instructions, starting at the instruction before the ebreak (PC-4)
             let mut actual_instructions = [0u32; 3];
-            core.read_32((pc - 4) as u64, &mut actual_instructions)?;
+            core.read_32(pc - 4, &mut actual_instructions)?;
             let actual_instructions = actual_instructions.as_slice();
 
             tracing::debug!(
@@ -200,6 +437,9 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
         let status: Dmstatus = self.interface.read_dm_register()?;
 
         if status.allhalted() {
+            // The register width can only be determined while the hart is halted.
+            self.interface.xlen()?;
+
             // determine reason for halt
             let dcsr = Dcsr(self.read_core_reg(RegisterId::from(0x7b0))?.try_into()?);
 
@@ -211,13 +451,23 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
                     // TODO: Add testcase to probe-rs-debugger-test to validate semihosting exit/abort work and unknown semihosting operations are skipped
                 }
                 // Trigger module caused halt
-                2 => HaltReason::Breakpoint(BreakpointCause::Hardware),
+                2 => {
+                    if self.exception_trigger_hit()? {
+                        HaltReason::Exception
+                    } else if self.watchpoint_trigger_hit()? {
+                        HaltReason::Watchpoint
+                    } else {
+                        HaltReason::Breakpoint(BreakpointCause::Hardware)
+                    }
+                }
                 // Debugger requested a halt
                 3 => HaltReason::Request,
                 // Core halted after single step
                 4 => HaltReason::Step,
                 // Core halted directly after reset
                 5 => HaltReason::Exception,
+                // Another hart of the halt group halted
+                6 => HaltReason::External,
                 // Reserved for future use in specification
                 _ => HaltReason::Unknown,
             };
@@ -247,19 +497,16 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
             );
         }
 
-        let mut dmcontrol = Dmcontrol(0);
+        let mut dmcontrol = self.interface.dmcontrol();
 
         dmcontrol.set_haltreq(true);
-        dmcontrol.set_dmactive(true);
 
         self.interface.write_dm_register(dmcontrol)?;
 
         self.wait_for_core_halted(timeout)?;
 
         // clear the halt request
-        let mut dmcontrol = Dmcontrol(0);
-
-        dmcontrol.set_dmactive(true);
+        let dmcontrol = self.interface.dmcontrol();
 
         self.interface.write_dm_register(dmcontrol)?;
 
@@ -296,8 +543,7 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
     ) -> Result<crate::core::CoreInformation, crate::Error> {
         tracing::debug!("Resetting core, setting hartreset bit");
 
-        let mut dmcontrol = Dmcontrol(0);
-        dmcontrol.set_dmactive(true);
+        let mut dmcontrol = self.interface.dmcontrol();
         dmcontrol.set_hartreset(true);
         dmcontrol.set_haltreq(true);
 
@@ -319,16 +565,14 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
             //
             // TODO: Cache this
             tracing::debug!("Hartreset bit not supported, using ndmreset");
-            let mut dmcontrol = Dmcontrol(0);
-            dmcontrol.set_dmactive(true);
+            let mut dmcontrol = self.interface.dmcontrol();
             dmcontrol.set_ndmreset(true);
             dmcontrol.set_haltreq(true);
 
             self.interface.write_dm_register(dmcontrol)?;
 
             tracing::debug!("Clearing ndmreset bit");
-            let mut dmcontrol = Dmcontrol(0);
-            dmcontrol.set_dmactive(true);
+            let mut dmcontrol = self.interface.dmcontrol();
             dmcontrol.set_ndmreset(false);
             dmcontrol.set_haltreq(true);
 
@@ -351,8 +595,7 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
         }
 
         // acknowledge the reset, clear the halt request
-        let mut dmcontrol = Dmcontrol(0);
-        dmcontrol.set_dmactive(true);
+        let mut dmcontrol = self.interface.dmcontrol();
         dmcontrol.set_ackhavereset(true);
 
         self.interface.write_dm_register(dmcontrol)?;
@@ -377,18 +620,20 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
         {
             // If we are halted on a software breakpoint AND we have passed the flashing operation, we can skip the single step and manually advance the dpc.
             let mut debug_pc = self.read_core_reg(RegisterId(0x7b1))?;
-            // Advance the dpc by the size of the EBREAK (ebreak or c.ebreak) instruction.
-            if matches!(self.instruction_set()?, InstructionSet::RV32C) {
-                debug_pc.increment_address(2)?;
-            } else {
-                debug_pc.increment_address(4)?;
+
+            // The EBREAK may have been replaced by the original instruction in the meantime,
+            // which then has to be executed.
+            if let Some(ebreak_size) = self.ebreak_size(debug_pc.try_into()?)? {
+                debug_pc.increment_address(ebreak_size)?;
+
+                self.write_core_reg(RegisterId(0x7b1), debug_pc)?;
+                return Ok(CoreInformation {
+                    pc: debug_pc.try_into()?,
+                });
             }
+        }
 
-            self.write_core_reg(RegisterId(0x7b1), debug_pc)?;
-            return Ok(CoreInformation {
-                pc: debug_pc.try_into()?,
-            });
-        } else if matches!(
+        if matches!(
             halt_reason,
             CoreStatus::Halted(HaltReason::Breakpoint(BreakpointCause::Hardware))
         ) {
@@ -431,9 +676,12 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
     }
 
     fn read_core_reg(&mut self, address: RegisterId) -> Result<RegisterValue, crate::Error> {
-        self.read_csr(address.0)
-            .map(|v| v.into())
-            .map_err(|e| e.into())
+        let value = self.read_register(address.0)?;
+
+        match self.register_width(address.0)? {
+            RiscvBusAccess::A64 => Ok(RegisterValue::U64(value)),
+            _ => Ok(RegisterValue::U32(value as u32)),
+        }
     }
 
     fn write_core_reg(
@@ -441,8 +689,8 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
         address: RegisterId,
         value: RegisterValue,
     ) -> Result<(), crate::Error> {
-        let value: u32 = value.try_into()?;
-        self.write_csr(address.0, value).map_err(|e| e.into())
+        let value: u64 = value.try_into()?;
+        self.write_register(address.0, value).map_err(|e| e.into())
     }
 
     fn available_breakpoint_units(&mut self) -> Result<u32, crate::Error> {
@@ -451,7 +699,6 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
         tracing::debug!("Determining number of HW breakpoints supported");
 
         let tselect = 0x7a0;
-        let tdata1 = 0x7a1;
         let tinfo = 0x7a4;
 
         let mut tselect_index = 0;
@@ -486,14 +733,9 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
                     }
                 }
                 Err(RiscvError::AbstractCommand(AbstractCommandErrorKind::Exception)) => {
-                    // An exception means we have to read tdata1 to discover the type
-                    let tdata_val = self.read_csr(tdata1)?;
-
-                    // Read the mxl field from the misa register (see RISC-V Privileged Spec, 3.1.1)
-                    let misa_value = Misa(self.read_csr(0x301)?);
-                    let xlen = u32::pow(2, misa_value.mxl() + 4);
-
-                    let trigger_type = tdata_val >> (xlen - 4);
+                    // An exception means we have to read tdata1 to discover the type,
+                    // which is in the topmost bits for all types of triggers.
+                    let trigger_type = self.read_mcontrol()?.type_();
 
                     if trigger_type == 0 {
                         break;
@@ -520,7 +762,6 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
     /// NOTE: For riscv, this assumes that only execution breakpoints are used.
     fn hw_breakpoints(&mut self) -> Result<Vec<Option<u64>>, Error> {
         let tselect = 0x7a0;
-        let tdata1 = 0x7a1;
         let tdata2 = 0x7a2;
 
         let mut breakpoints = vec![];
@@ -530,25 +771,23 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
             self.write_csr(tselect, bp_unit_index as u32)?;
 
             // Read the trigger "configuration" data.
-            let tdata_value = Mcontrol(self.read_csr(tdata1)?);
+            let tdata_value = self.read_mcontrol()?;
 
             tracing::warn!("Breakpoint {}: {:?}", bp_unit_index, tdata_value);
 
             // The trigger must be active in at least a single mode
             let trigger_any_mode_active = tdata_value.m() || tdata_value.s() || tdata_value.u();
 
-            let trigger_any_action_enabled =
-                tdata_value.execute() || tdata_value.store() || tdata_value.load();
-
             // Only return if the trigger if it is for an execution debug action in all modes.
+            // Triggers which halt on data accesses are watchpoints.
             if tdata_value.type_() == 0b10
                 && tdata_value.action() == 1
                 && tdata_value.match_() == 0
                 && trigger_any_mode_active
-                && trigger_any_action_enabled
+                && tdata_value.is_breakpoint()
             {
-                let breakpoint = self.read_csr(tdata2)?;
-                breakpoints.push(Some(breakpoint as u64));
+                let breakpoint = self.read_register(tdata2)?;
+                breakpoints.push(Some(breakpoint));
             } else {
                 breakpoints.push(None);
             }
@@ -560,14 +799,13 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
     fn enable_breakpoints(&mut self, state: bool) -> Result<(), crate::Error> {
         // Loop through all triggers, and enable/disable them.
         let tselect = 0x7a0;
-        let tdata1 = 0x7a1;
 
         for bp_unit_index in 0..self.available_breakpoint_units()? as usize {
             // Select the trigger.
             self.write_csr(tselect, bp_unit_index as u32)?;
 
             // Read the trigger "configuration" data.
-            let mut tdata_value = Mcontrol(self.read_csr(tdata1)?);
+            let mut tdata_value = self.read_mcontrol()?;
 
             // Only modify the trigger if it is for an execution debug action in all modes(probe-rs enabled it) or no modes (we previously disabled it).
             if tdata_value.type_() == 0b10
@@ -584,7 +822,7 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
                 );
                 tdata_value.set_m(state);
                 tdata_value.set_u(state);
-                self.write_csr(tdata1, tdata_value.0)?;
+                self.write_mcontrol(tdata_value)?;
             }
         }
 
@@ -593,7 +831,9 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
     }
 
     fn set_hw_breakpoint(&mut self, bp_unit_index: usize, addr: u64) -> Result<(), crate::Error> {
-        let addr = valid_32bit_address(addr)?;
+        if !self.is_64_bit() {
+            valid_32bit_address(addr)?;
+        }
 
         if !self.hw_breakpoints_enabled() {
             self.enable_breakpoints(true)?;
@@ -601,7 +841,6 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
 
         // select requested trigger
         let tselect = 0x7a0;
-        let tdata1 = 0x7a1;
         let tdata2 = 0x7a2;
 
         tracing::warn!("Setting breakpoint {}", bp_unit_index);
@@ -610,7 +849,7 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
 
         // verify the trigger has the correct type
 
-        let tdata_value = Mcontrol(self.read_csr(tdata1)?);
+        let tdata_value = self.read_mcontrol()?;
 
         // This should not happen
         let trigger_type = tdata_value.type_();
@@ -640,8 +879,8 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
         // Match address
         instruction_breakpoint.set_select(false);
 
-        self.write_csr(tdata1, instruction_breakpoint.0)?;
-        self.write_csr(tdata2, addr)?;
+        self.write_mcontrol(instruction_breakpoint)?;
+        self.write_register(tdata2, addr)?;
 
         Ok(())
     }
@@ -658,24 +897,201 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
         Ok(())
     }
 
+    fn available_watchpoint_units(&mut self) -> Result<u32, crate::Error> {
+        // Watchpoints use the same triggers as breakpoints. To avoid conflicts, watchpoint
+        // units are mapped to the triggers starting with the last one.
+        self.available_breakpoint_units()
+    }
+
+    fn watchpoint_unit_in_use_by_breakpoint(&mut self, unit_index: usize) -> Result<bool, Error> {
+        let num_triggers = self.available_breakpoint_units()? as usize;
+        let Some(trigger_index) = num_triggers.checked_sub(unit_index + 1) else {
+            return Ok(false);
+        };
+
+        self.trigger_in_use(trigger_index as u32, Mcontrol::is_breakpoint)
+    }
+
+    fn breakpoint_unit_in_use_by_watchpoint(&mut self, unit_index: usize) -> Result<bool, Error> {
+        self.trigger_in_use(unit_index as u32, Mcontrol::is_watchpoint)
+    }
+
+    fn watchpoints(&mut self) -> Result<Vec<Option<Watchpoint>>, Error> {
+        let tselect = 0x7a0;
+        let tdata2 = 0x7a2;
+
+        let mut watchpoints = vec![];
+        let num_triggers = self.available_breakpoint_units()?;
+        for wp_unit_index in 0..num_triggers {
+            self.write_csr(tselect, num_triggers - 1 - wp_unit_index)?;
+
+            let tdata_value = self.read_mcontrol()?;
+
+            let kind = match (tdata_value.load(), tdata_value.store()) {
+                (true, true) => WatchpointKind::Access,
+                (true, false) => WatchpointKind::Read,
+                (false, true) => WatchpointKind::Write,
+                (false, false) => {
+                    watchpoints.push(None);
+                    continue;
+                }
+            };
+
+            // Only return the trigger if it is a data debug action, as set by `set_watchpoint`.
+            if tdata_value.type_() != 0b10 || tdata_value.action() != 1 || tdata_value.execute() {
+                watchpoints.push(None);
+                continue;
+            }
+
+            let value = self.read_register(tdata2)?;
+            let watchpoint = match tdata_value.match_() {
+                0 => Some(Watchpoint {
+                    address: value,
+                    len: 1,
+                    kind,
+                }),
+                1 => {
+                    // The number of trailing ones encodes the size of the range.
+                    let len = 1u64 << (value.trailing_ones() + 1);
+                    Some(Watchpoint {
+                        address: value & !(len - 1),
+                        len,
+                        kind,
+                    })
+                }
+                _ => None,
+            };
+            watchpoints.push(watchpoint);
+        }
+
+        Ok(watchpoints)
+    }
+
+    fn set_watchpoint(
+        &mut self,
+        wp_unit_index: usize,
+        address: u64,
+        len: u64,
+        kind: WatchpointKind,
+    ) -> Result<(), crate::Error> {
+        if !self.is_64_bit() {
+            valid_32bit_address(address)?;
+        }
+
+        // Match either the exact address, or a naturally aligned power of two range (NAPOT).
+        let (match_, value) = if len == 1 {
+            (0, address)
+        } else if len.is_power_of_two() && address & (len - 1) == 0 && len <= 1 << 31 {
+            (1, address | (len / 2 - 1))
+        } else {
+            return Err(Error::UnsupportedWatchpoint { address, len });
+        };
+
+        let tselect = 0x7a0;
+        let tdata2 = 0x7a2;
+
+        let num_triggers = self.available_breakpoint_units()? as usize;
+        let trigger_index = num_triggers
+            .checked_sub(wp_unit_index + 1)
+            .ok_or_else(|| anyhow!("Watchpoint unit {} does not exist", wp_unit_index))?;
+
+        self.write_csr(tselect, trigger_index as u32)?;
+
+        // verify the trigger has the correct type
+        let tdata_value = self.read_mcontrol()?;
+
+        let trigger_type = tdata_value.type_();
+        if trigger_type != 0b10 {
+            return Err(RiscvError::UnexpectedTriggerType(trigger_type).into());
+        }
+
+        if match_ == 1 && len.trailing_zeros() > tdata_value.maskmax() {
+            return Err(Error::UnsupportedWatchpoint { address, len });
+        }
+
+        // Setup the trigger
+
+        let mut data_watchpoint = Mcontrol(0);
+
+        data_watchpoint.set_type(0b10);
+
+        // Enter debug mode
+        data_watchpoint.set_action(1);
+
+        data_watchpoint.set_match(match_);
+
+        data_watchpoint.set_m(true);
+
+        data_watchpoint.set_u(true);
+
+        // Trigger on the requested data accesses
+        data_watchpoint.set_load(matches!(
+            kind,
+            WatchpointKind::Read | WatchpointKind::Access
+        ));
+        data_watchpoint.set_store(matches!(
+            kind,
+            WatchpointKind::Write | WatchpointKind::Access
+        ));
+
+        data_watchpoint.set_dmode(true);
+
+        // Match address
+        data_watchpoint.set_select(false);
+
+        self.write_mcontrol(data_watchpoint)?;
+        self.write_register(tdata2, value)?;
+
+        Ok(())
+    }
+
+    fn clear_watchpoint(&mut self, wp_unit_index: usize) -> Result<(), crate::Error> {
+        let num_triggers = self.available_breakpoint_units()? as usize;
+        let trigger_index = num_triggers
+            .checked_sub(wp_unit_index + 1)
+            .ok_or_else(|| anyhow!("Watchpoint unit {} does not exist", wp_unit_index))?;
+
+        self.clear_hw_breakpoint(trigger_index)
+    }
+
     fn registers(&self) -> &'static CoreRegisters {
-        &RISCV_CORE_REGSISTERS
+        if self.is_64_bit() {
+            &RISCV64_CORE_REGSISTERS
+        } else {
+            &RISCV_CORE_REGSISTERS
+        }
     }
 
     fn program_counter(&self) -> &'static CoreRegister {
-        &PC
+        if self.is_64_bit() {
+            &RV64_PC
+        } else {
+            &PC
+        }
     }
 
     fn frame_pointer(&self) -> &'static CoreRegister {
-        &FP
+        if self.is_64_bit() {
+            &RV64_FP
+        } else {
+            &FP
+        }
     }
 
     fn stack_pointer(&self) -> &'static CoreRegister {
-        &SP
+        if self.is_64_bit() {
+            &RV64_SP
+        } else {
+            &SP
+        }
     }
 
     fn return_address(&self) -> &'static CoreRegister {
-        &RA
+        if self.is_64_bit() {
+            &RV64_RA
+        } else {
+            &RA
+        }
     }
 
     fn hw_breakpoints_enabled(&self) -> bool {
@@ -692,6 +1108,21 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
         self.write_csr(0x7b0, dcsr.0).map_err(|e| e.into())
     }
 
+    fn invalidate_instruction_cache(&mut self, _address: u64, _len: u64) -> Result<(), Error> {
+        match self.interface.execute_fence_i() {
+            // Harts without a program buffer or the Zifencei extension do not cache instructions
+            // in a way the debugger has to care about.
+            Err(RiscvError::ProgramBufferTooSmall)
+            | Err(RiscvError::AbstractCommand(AbstractCommandErrorKind::Exception)) => {
+                tracing::debug!(
+                    "fence.i is not available, not synchronizing the instruction cache"
+                );
+                Ok(())
+            }
+            result => result.map_err(|e| e.into()),
+        }
+    }
+
     fn architecture(&self) -> Architecture {
         Architecture::Riscv
     }
@@ -704,10 +1135,13 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
         let misa_value = Misa(self.read_csr(0x301)?);
 
         // Check if the Bit at position 2 (signifies letter C, for compressed) is set.
-        if misa_value.extensions() & (1 << 2) != 0 {
-            Ok(InstructionSet::RV32C)
-        } else {
-            Ok(InstructionSet::RV32)
+        let compressed = misa_value.extensions() & (1 << 2) != 0;
+
+        match (self.interface.xlen()?, compressed) {
+            (RiscvBusAccess::A64, true) => Ok(InstructionSet::RV64C),
+            (RiscvBusAccess::A64, false) => Ok(InstructionSet::RV64),
+            (_, true) => Ok(InstructionSet::RV32C),
+            (_, false) => Ok(InstructionSet::RV32),
         }
     }
 
@@ -765,6 +1199,40 @@ impl<'probe> CoreInterface for Riscv32<'probe> {
         self.debug_on_sw_breakpoint(false)?;
         Ok(())
     }
+
+    fn enable_vector_catch(&mut self, condition: VectorCatchCondition) -> Result<(), Error> {
+        match condition {
+            VectorCatchCondition::HardFault => self.set_exception_trigger(),
+            VectorCatchCondition::CoreReset => self.reset_catch_set(),
+            VectorCatchCondition::SecureFault => {
+                Err(Error::NotImplemented("SecureFault vector catch on RISC-V"))
+            }
+            VectorCatchCondition::All => {
+                self.set_exception_trigger()?;
+                if self.supports_reset_halt_req()? {
+                    self.reset_catch_set()?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn disable_vector_catch(&mut self, condition: VectorCatchCondition) -> Result<(), Error> {
+        match condition {
+            VectorCatchCondition::HardFault => self.clear_exception_trigger(),
+            VectorCatchCondition::CoreReset => self.reset_catch_clear(),
+            VectorCatchCondition::SecureFault => {
+                Err(Error::NotImplemented("SecureFault vector catch on RISC-V"))
+            }
+            VectorCatchCondition::All => {
+                self.clear_exception_trigger()?;
+                if self.supports_reset_halt_req()? {
+                    self.reset_catch_clear()?;
+                }
+                Ok(())
+            }
+        }
+    }
 }
 
 impl<'probe> MemoryInterface for Riscv32<'probe> {
@@ -841,6 +1309,9 @@ pub struct RiscVState {
 
     /// Store the value of the `hasresethaltreq` bit of the `dmcstatus` register.
     hasresethaltreq: Option<bool>,
+
+    /// The index of the trigger used to halt on exceptions, if enabled.
+    exception_trigger: Option<u32>,
 }
 
 impl RiscVState {
@@ -848,6 +1319,7 @@ impl RiscVState {
         Self {
             hw_breakpoints_enabled: false,
             hasresethaltreq: None,
+            exception_trigger: None,
         }
     }
 }
@@ -889,6 +1361,20 @@ impl Dmcontrol {
     }
 }
 
+memory_mapped_bitfield_register! {
+    /// `dmcs2` register, located at address 0x32.
+    ///
+    /// Used to configure the halt and resume groups of the selected harts.
+    pub struct Dmcs2(u32);
+    0x32, "dmcs2",
+    impl From;
+    grouptype, set_grouptype: 11;
+    dmexttrigger, set_dmexttrigger: 10, 7;
+    group, set_group: 6, 2;
+    _, set_hgwrite: 1;
+    hgselect, set_hgselect: 0;
+}
+
 memory_mapped_bitfield_register! {
     /// Readonly `dmstatus` register.
     ///
@@ -1012,6 +1498,87 @@ bitfield! {
     load, set_load: 0;
 }
 
+impl Mcontrol {
+    /// The fields in the upper bits of `tdata1`, which are moved
+    /// to the upper word of the register on 64-bit harts.
+    const UPPER_FIELDS: u32 = 0xffe0_0000;
+
+    /// Creates the trigger from the value of `tdata1` of a hart, with the given register width.
+    fn from_tdata1(value: u64, is_64_bit: bool) -> Self {
+        Mcontrol(tdata1_to_u32(value, Self::UPPER_FIELDS, is_64_bit))
+    }
+
+    /// Returns the value of `tdata1` for a hart with the given register width.
+    fn to_tdata1(&self, is_64_bit: bool) -> u64 {
+        tdata1_from_u32(self.0, Self::UPPER_FIELDS, is_64_bit)
+    }
+
+    /// Returns true if the trigger halts on the execution of an instruction, like a breakpoint.
+    fn is_breakpoint(&self) -> bool {
+        self.type_() == 0b10 && self.execute()
+    }
+
+    /// Returns true if the trigger halts on data accesses, like a watchpoint.
+    fn is_watchpoint(&self) -> bool {
+        self.type_() == 0b10 && !self.execute() && (self.load() || self.store())
+    }
+}
+
+bitfield! {
+    /// An exception trigger, which fires when the hart takes one of the exceptions
+    /// selected in `tdata2`.
+    struct Etrigger(u32);
+    impl Debug;
+
+    type_, set_type: 31, 28;
+    dmode, set_dmode: 27;
+    hit, set_hit: 26;
+    nmi, set_nmi: 10;
+    m, set_m: 9;
+    s, set_s: 7;
+    u, set_u: 6;
+    action, set_action: 5, 0;
+}
+
+impl Etrigger {
+    /// The value of the `type` field for exception triggers.
+    const TYPE: u32 = 5;
+
+    /// The fields in the upper bits of `tdata1`, which are moved
+    /// to the upper word of the register on 64-bit harts.
+    const UPPER_FIELDS: u32 = 0xfc00_0000;
+
+    /// Creates the trigger from the value of `tdata1` of a hart, with the given register width.
+    fn from_tdata1(value: u64, is_64_bit: bool) -> Self {
+        Etrigger(tdata1_to_u32(value, Self::UPPER_FIELDS, is_64_bit))
+    }
+
+    /// Returns the value of `tdata1` for a hart with the given register width.
+    fn to_tdata1(&self, is_64_bit: bool) -> u64 {
+        tdata1_from_u32(self.0, Self::UPPER_FIELDS, is_64_bit)
+    }
+}
+
+/// Converts the value of `tdata1` to the layout of a 32-bit hart, where the `upper_fields`
+/// are stored in the upper word of the register on 64-bit harts.
+fn tdata1_to_u32(value: u64, upper_fields: u32, is_64_bit: bool) -> u32 {
+    if is_64_bit {
+        ((value >> 32) as u32 & upper_fields) | (value as u32 & !upper_fields)
+    } else {
+        value as u32
+    }
+}
+
+/// Converts a value in the layout of a 32-bit hart to the value of `tdata1` for a hart
+/// with the given register width.
+fn tdata1_from_u32(value: u32, upper_fields: u32, is_64_bit: bool) -> u64 {
+    if is_64_bit {
+        ((value & upper_fields) as u64) << 32 | (value & !upper_fields) as u64
+    } else {
+        value as u64
+    }
+}
+
 memory_mapped_bitfield_register! {
     /// Isa and Extensions (see RISC-V Privileged Spec, 3.1.1)
     pub struct Misa(u32);
@@ -1023,3 +1590,79 @@ memory_mapped_bitfield_register! {
     /// Standard RISC-V extensions
     extensions, _: 25, 0;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Etrigger, Mcontrol};
+
+    #[test]
+    fn mcontrol_tdata1_rv32() {
+        let mut mcontrol = Mcontrol(0);
+        mcontrol.set_type(0b10);
+        mcontrol.set_dmode(true);
+        mcontrol.set_execute(true);
+
+        assert_eq!(mcontrol.to_tdata1(false), 0x2800_0004);
+        assert_eq!(Mcontrol::from_tdata1(0x2800_0004, false).0, mcontrol.0);
+    }
+
+    #[test]
+    fn mcontrol_tdata1_rv64() {
+        let mut mcontrol = Mcontrol(0);
+        mcontrol.set_type(0b10);
+        mcontrol.set_dmode(true);
+        mcontrol.set_execute(true);
+
+        assert_eq!(mcontrol.to_tdata1(true), 0x2800_0000_0000_0004);
+
+        // maskmax is in bits 58:53 on 64-bit harts
+        let mcontrol = Mcontrol::from_tdata1(0x2ba0_0000_0000_1044, true);
+        assert_eq!(mcontrol.type_(), 0b10);
+        assert!(mcontrol.dmode());
+        assert_eq!(mcontrol.maskmax(), 0x1d);
+        assert_eq!(mcontrol.action(), 1);
+        assert!(mcontrol.m());
+        assert!(mcontrol.execute());
+    }
+
+    #[test]
+    fn mcontrol_trigger_use() {
+        let mut breakpoint = Mcontrol(0);
+        breakpoint.set_type(0b10);
+        breakpoint.set_action(1);
+        breakpoint.set_m(true);
+        breakpoint.set_execute(true);
+        assert!(breakpoint.is_breakpoint());
+        assert!(!breakpoint.is_watchpoint());
+
+        let mut watchpoint = Mcontrol(0);
+        watchpoint.set_type(0b10);
+        watchpoint.set_action(1);
+        watchpoint.set_m(true);
+        watchpoint.set_store(true);
+        assert!(!watchpoint.is_breakpoint());
+        assert!(watchpoint.is_watchpoint());
+
+        let mut unused = Mcontrol(0);
+        unused.set_type(0b10);
+        assert!(!unused.is_breakpoint());
+        assert!(!unused.is_watchpoint());
+    }
+
+    #[test]
+    fn etrigger_tdata1() {
+        let mut etrigger = Etrigger(0);
+        etrigger.set_type(Etrigger::TYPE);
+        etrigger.set_dmode(true);
+        etrigger.set_action(1);
+        etrigger.set_m(true);
+
+        assert_eq!(etrigger.to_tdata1(false), 0x5800_0201);
+        assert_eq!(etrigger.to_tdata1(true), 0x5800_0000_0000_0201);
+
+        let etrigger = Etrigger::from_tdata1(0x5c00_0000_0000_0201, true);
+        assert_eq!(etrigger.type_(), Etrigger::TYPE);
+        assert!(etrigger.hit());
+        assert!(etrigger.m());
+    }
+}
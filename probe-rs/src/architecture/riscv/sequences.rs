@@ -1,6 +1,7 @@
 //! Debug sequences to operate special requirements RISC-V targets.
 
 use super::communication_interface::RiscvCommunicationInterface;
+use crate::flashing::ConfigAreaSequence;
 use std::fmt::Debug;
 use std::sync::Arc;
 
@@ -20,6 +21,11 @@ pub trait RiscvDebugSequence: Send + Sync + Debug {
     ) -> Result<Option<usize>, crate::Error> {
         Ok(None)
     }
+
+    /// Return the sequence for writing the configuration areas of the device if it exists
+    fn config_area_sequence(&self) -> Option<Arc<dyn ConfigAreaSequence>> {
+        None
+    }
 }
 
 /// The default sequences that is used for RISC-V chips that do not specify a specific sequence.
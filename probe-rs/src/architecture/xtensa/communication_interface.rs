@@ -104,6 +104,7 @@ pub struct XtensaCommunicationInterface {
     state: XtensaCommunicationInterfaceState,
 
     hw_breakpoint_num: u32,
+    hw_watchpoint_num: u32,
     debug_level: DebugLevel,
 }
 
@@ -120,6 +121,7 @@ impl XtensaCommunicationInterface {
             },
             // TODO chip-specific configuration
             hw_breakpoint_num: 2,
+            hw_watchpoint_num: 2,
             debug_level: DebugLevel::L6,
         };
 
@@ -152,6 +154,13 @@ impl XtensaCommunicationInterface {
         self.hw_breakpoint_num
     }
 
+    /// Returns the number of data breakpoints the target supports.
+    ///
+    /// On the Xtensa architecture this is the `NDBREAK` configuration parameter.
+    pub fn available_watchpoint_units(&self) -> u32 {
+        self.hw_watchpoint_num
+    }
+
     /// Enters OCD mode and halts the core.
     pub fn enter_ocd_mode(&mut self) -> Result<(), XtensaError> {
         self.xdm.halt()?;
@@ -944,6 +953,22 @@ u32_register!(ProgramStatus, Register::CurrentPs);
 pub struct IBreakEn(pub u32);
 u32_register!(IBreakEn, SpecialRegister::IBreakEnable);
 
+bitfield::bitfield! {
+    /// The `DBREAKC` (Data Breakpoint Control) registers.
+    #[derive(Copy, Clone)]
+    pub struct DBreakC(u32);
+    impl Debug;
+
+    /// Break on stores
+    pub sb,   set_sb  : 31;
+
+    /// Break on loads
+    pub lb,   set_lb  : 30;
+
+    /// The address bits compared with `DBREAKA`. Cleared low bits are ignored.
+    pub mask, set_mask: 5, 0;
+}
+
 /// The `ICOUNT` (Instruction Counter) register.
 #[derive(Copy, Clone, Debug)]
 pub struct ICount(pub u32);
@@ -7,11 +7,13 @@ use probe_rs_target::{Architecture, CoreType, InstructionSet};
 use crate::{
     architecture::xtensa::{
         arch::{Register, SpecialRegister},
-        communication_interface::{DebugCause, IBreakEn},
+        communication_interface::{DBreakC, DebugCause, IBreakEn},
         registers::{FP, PC, RA, SP, XTENSA_CORE_REGSISTERS},
     },
     core::registers::{CoreRegisters, RegisterId, RegisterValue},
+    memory::valid_32bit_address,
     CoreInformation, CoreInterface, CoreRegister, CoreStatus, Error, MemoryInterface,
+    VectorCatchCondition, Watchpoint, WatchpointKind,
 };
 
 use self::communication_interface::XtensaCommunicationInterface;
@@ -63,6 +65,10 @@ pub struct Xtensa<'probe> {
 impl<'probe> Xtensa<'probe> {
     const IBREAKA_REGS: [SpecialRegister; 2] =
         [SpecialRegister::IBreakA0, SpecialRegister::IBreakA1];
+    const DBREAKA_REGS: [SpecialRegister; 2] =
+        [SpecialRegister::DBreakA0, SpecialRegister::DBreakA1];
+    const DBREAKC_REGS: [SpecialRegister; 2] =
+        [SpecialRegister::DBreakC0, SpecialRegister::DBreakC1];
 
     /// Create a new Xtensa interface.
     pub fn new(
@@ -83,6 +89,18 @@ impl<'probe> Xtensa<'probe> {
         Ok(CoreInformation { pc: pc.try_into()? })
     }
 
+    /// Returns an error if there is no watchpoint unit `unit_index`.
+    fn check_watchpoint_unit(&mut self, unit_index: usize) -> Result<(), Error> {
+        if unit_index >= self.available_watchpoint_units()? as usize {
+            return Err(Error::Other(anyhow::anyhow!(
+                "Watchpoint unit {} does not exist",
+                unit_index
+            )));
+        }
+
+        Ok(())
+    }
+
     fn skip_breakpoint_instruction(&mut self) -> Result<(), Error> {
         if !self.state.pc_written {
             let debug_cause = self.interface.read_register::<DebugCause>()?;
@@ -320,6 +338,91 @@ impl<'probe> CoreInterface for Xtensa<'probe> {
         Ok(())
     }
 
+    fn available_watchpoint_units(&mut self) -> Result<u32, Error> {
+        Ok(self.interface.available_watchpoint_units())
+    }
+
+    fn watchpoints(&mut self) -> Result<Vec<Option<Watchpoint>>, Error> {
+        let mut watchpoints = Vec::with_capacity(self.available_watchpoint_units()? as usize);
+
+        for i in 0..self.available_watchpoint_units()? as usize {
+            let control = DBreakC(
+                self.interface
+                    .read_register_untyped(Self::DBREAKC_REGS[i])?,
+            );
+
+            let kind = match (control.lb(), control.sb()) {
+                (true, true) => Some(WatchpointKind::Access),
+                (true, false) => Some(WatchpointKind::Read),
+                (false, true) => Some(WatchpointKind::Write),
+                (false, false) => None,
+            };
+
+            let watchpoint = match kind {
+                Some(kind) => {
+                    let address = self
+                        .interface
+                        .read_register_untyped(Self::DBREAKA_REGS[i])?;
+
+                    Some(Watchpoint {
+                        address: address as u64,
+                        len: (!control.mask() & 0x3F) as u64 + 1,
+                        kind,
+                    })
+                }
+                None => None,
+            };
+
+            watchpoints.push(watchpoint);
+        }
+
+        Ok(watchpoints)
+    }
+
+    fn set_watchpoint(
+        &mut self,
+        unit_index: usize,
+        address: u64,
+        len: u64,
+        kind: WatchpointKind,
+    ) -> Result<(), Error> {
+        self.check_watchpoint_unit(unit_index)?;
+        let addr = valid_32bit_address(address)?;
+
+        // The watched range has to be a naturally aligned power of two of at most 64 bytes.
+        if !len.is_power_of_two() || len > 64 || address & (len - 1) != 0 {
+            return Err(Error::UnsupportedWatchpoint { address, len });
+        }
+
+        let mut control = DBreakC(0);
+        control.set_mask(0x3F & !(len as u32 - 1));
+        control.set_lb(matches!(
+            kind,
+            WatchpointKind::Read | WatchpointKind::Access
+        ));
+        control.set_sb(matches!(
+            kind,
+            WatchpointKind::Write | WatchpointKind::Access
+        ));
+
+        self.interface
+            .write_register_untyped(Self::DBREAKC_REGS[unit_index], 0)?;
+        self.interface
+            .write_register_untyped(Self::DBREAKA_REGS[unit_index], addr)?;
+        self.interface
+            .write_register_untyped(Self::DBREAKC_REGS[unit_index], control.0)?;
+
+        Ok(())
+    }
+
+    fn clear_watchpoint(&mut self, unit_index: usize) -> Result<(), Error> {
+        self.check_watchpoint_unit(unit_index)?;
+        self.interface
+            .write_register_untyped(Self::DBREAKC_REGS[unit_index], 0)?;
+
+        Ok(())
+    }
+
     fn registers(&self) -> &'static CoreRegisters {
         &XTENSA_CORE_REGSISTERS
     }
@@ -381,4 +484,23 @@ impl<'probe> CoreInterface for Xtensa<'probe> {
         self.interface.leave_ocd_mode()?;
         Ok(())
     }
+
+    fn enable_vector_catch(&mut self, condition: VectorCatchCondition) -> Result<(), Error> {
+        match condition {
+            VectorCatchCondition::CoreReset | VectorCatchCondition::All => self.reset_catch_set(),
+            // The on-chip debug module can only halt the core on resets, not on exceptions.
+            VectorCatchCondition::HardFault | VectorCatchCondition::SecureFault => {
+                Err(Error::NotImplemented("exception vector catch on Xtensa"))
+            }
+        }
+    }
+
+    fn disable_vector_catch(&mut self, condition: VectorCatchCondition) -> Result<(), Error> {
+        match condition {
+            VectorCatchCondition::CoreReset | VectorCatchCondition::All => self.reset_catch_clear(),
+            VectorCatchCondition::HardFault | VectorCatchCondition::SecureFault => {
+                Err(Error::NotImplemented("exception vector catch on Xtensa"))
+            }
+        }
+    }
 }
@@ -1,6 +1,7 @@
 use std::{fmt::Debug, sync::Arc};
 
 use crate::architecture::xtensa::communication_interface::XtensaCommunicationInterface;
+use crate::flashing::ConfigAreaSequence;
 
 /// A interface to operate debug sequences for Xtensa targets.
 ///
@@ -21,6 +22,11 @@ pub trait XtensaDebugSequence: Send + Sync + Debug {
     ) -> Result<Option<usize>, crate::Error> {
         Ok(None)
     }
+
+    /// Return the sequence for writing the configuration areas of the device if it exists
+    fn config_area_sequence(&self) -> Option<Arc<dyn ConfigAreaSequence>> {
+        None
+    }
 }
 
 /// The default sequences that is used for Xtensa chips that do not specify a specific sequence.
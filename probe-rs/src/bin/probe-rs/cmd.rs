@@ -3,17 +3,23 @@ pub mod benchmark;
 pub mod cargo_embed;
 pub mod cargo_flash;
 pub mod chip;
+pub mod config;
 pub mod dap_server;
 pub mod debug;
 pub mod download;
+pub mod dump;
 pub mod erase;
+pub mod gang_download;
 pub mod gdb;
 pub mod info;
 pub mod itm;
+pub mod layout_diff;
 pub mod list;
 pub mod profile;
 pub mod read;
 pub mod reset;
+pub mod rtt;
 pub mod run;
 pub mod trace;
+pub mod verify;
 pub mod write;
@@ -85,7 +85,7 @@ struct TestData {
 
 impl Cmd {
     pub fn run(self, lister: &Lister) -> anyhow::Result<()> {
-        let speed = self.common.speed;
+        let speed = self.common.connect.speed;
         let common_options = self.common.load()?;
         let mut max_speed = self.max_speed;
         let mut speeds = vec![];
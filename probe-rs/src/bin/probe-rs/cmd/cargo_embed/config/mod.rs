@@ -52,6 +52,7 @@ pub struct Flashing {
     pub restore_unwritten_bytes: bool,
     pub flash_layout_output_path: Option<String>,
     pub do_chip_erase: bool,
+    pub preverify: bool,
 }
 
 /// The reset config struct holding all the possible reset options.
@@ -86,12 +87,18 @@ pub struct Rtt {
     /// Connection timeout in ms.
     #[serde(with = "duration_ms")]
     pub timeout: Duration,
+    /// The address of the control block, to select one if several are found in RAM.
+    pub control_block_address: Option<u32>,
     /// Whether to show timestamps in RTTUI
     pub show_timestamps: bool,
+    /// Whether to show the throughput and buffer statistics of the current channel in RTTUI
+    pub show_stats: bool,
     /// Whether to save rtt history buffer on exit to file named history.txt
     pub log_enabled: bool,
     /// Where to save rtt history buffer relative to manifest path.
     pub log_path: PathBuf,
+    /// Where to continuously record the raw data of all up channels, for `probe-rs rtt replay`.
+    pub capture_path: Option<PathBuf>,
 }
 
 mod duration_ms {
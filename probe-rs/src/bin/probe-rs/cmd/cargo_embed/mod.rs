@@ -335,7 +335,9 @@ fn main_try(mut args: Vec<OsString>, offset: UtcOffset) -> Result<()> {
             None
         };
 
-        let rtt_header_address = if let Ok(mut file) = File::open(path) {
+        let rtt_header_address = if let Some(address) = config.rtt.control_block_address {
+            ScanRegion::Exact(address)
+        } else if let Ok(mut file) = File::open(path) {
             if let Some(address) = rttui::app::App::get_rtt_symbol(&mut file) {
                 ScanRegion::Exact(address as u32)
             } else {
@@ -615,6 +617,19 @@ fn flash(
                 PageProgrammed { size, .. } => {
                     program_progress.inc(size as u64);
                 }
+                SectorCompared {
+                    size,
+                    page_size,
+                    unchanged,
+                    ..
+                } => {
+                    if unchanged {
+                        let length = erase_progress.length().unwrap_or(0);
+                        erase_progress.set_length(length.saturating_sub(size));
+                        let length = program_progress.length().unwrap_or(0);
+                        program_progress.set_length(length.saturating_sub(page_size));
+                    }
+                }
                 SectorErased { size, .. } => {
                     erase_progress.inc(size);
                 }
@@ -655,6 +670,7 @@ fn flash(
         options.progress = Some(progress);
         options.keep_unwritten_bytes = config.flashing.restore_unwritten_bytes;
         options.do_chip_erase = config.flashing.do_chip_erase;
+        options.preverify = config.flashing.preverify;
 
         download_file_with_options(session, path, Format::Elf, options)
             .with_context(|| format!("failed to flash {}", path.display()))?;
@@ -666,6 +682,7 @@ fn flash(
         let mut options = DownloadOptions::new();
         options.keep_unwritten_bytes = config.flashing.restore_unwritten_bytes;
         options.do_chip_erase = config.flashing.do_chip_erase;
+        options.preverify = config.flashing.preverify;
 
         download_file_with_options(session, path, Format::Elf, options)
             .with_context(|| format!("failed to flash {}", path.display()))?;
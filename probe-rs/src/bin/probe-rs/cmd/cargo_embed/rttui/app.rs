@@ -24,6 +24,7 @@ use super::{
     super::{config, DefmtInformation},
     channel::ChannelData,
 };
+use crate::util::rtt::capture::CaptureWriter;
 
 use super::{
     channel::{ChannelState, DataFormat},
@@ -41,6 +42,7 @@ pub struct App<'defmt> {
     events: Events,
     history_path: Option<PathBuf>,
     logname: String,
+    show_stats: bool,
 }
 
 fn pull_channel<C: RttChannel>(channels: &mut Vec<C>, n: usize) -> Option<C> {
@@ -112,6 +114,17 @@ impl<'defmt> App<'defmt> {
             }
         }
 
+        if let Some(path) = &config.rtt.capture_path {
+            let capture = CaptureWriter::create(path).with_context(|| {
+                format!("Failed to create the RTT capture file {}", path.display())
+            })?;
+            for tab in tabs.iter_mut() {
+                tab.start_capture(&capture).with_context(|| {
+                    format!("Failed to write to the RTT capture file {}", path.display())
+                })?;
+            }
+        }
+
         // Code farther down relies on tabs being configured and might panic
         // otherwise.
         if tabs.is_empty() {
@@ -152,6 +165,7 @@ impl<'defmt> App<'defmt> {
             events,
             history_path,
             logname,
+            show_stats: config.rtt.show_stats,
         })
     }
 
@@ -177,6 +191,11 @@ impl<'defmt> App<'defmt> {
         let input = self.current_tab().input().to_owned();
         let has_down_channel = self.current_tab().has_down_channel();
         let scroll_offset = self.current_tab().scroll_offset();
+        let stats = self
+            .current_tab()
+            .stats()
+            .filter(|_| self.show_stats)
+            .map(|stats| stats.to_string());
 
         let tabs = &self.tabs;
         let current_tab = self.current_tab;
@@ -185,7 +204,7 @@ impl<'defmt> App<'defmt> {
 
         self.terminal
             .draw(|f| {
-                let chunks = layout_chunks(f, has_down_channel);
+                let chunks = layout_chunks(f, has_down_channel, stats.is_some());
                 render_tabs(f, chunks[0], tabs, current_tab);
 
                 height = chunks[1].height as usize;
@@ -226,10 +245,16 @@ impl<'defmt> App<'defmt> {
                 let messages = List::new(messages).block(Block::default().borders(Borders::NONE));
                 f.render_widget(messages, chunks[1]);
 
+                if let Some(stats) = &stats {
+                    let stats = Paragraph::new(Line::from(vec![Span::raw(stats.clone())]))
+                        .style(Style::default().fg(Color::Black).bg(Color::Gray));
+                    f.render_widget(stats, chunks[2]);
+                }
+
                 if has_down_channel {
                     let input = Paragraph::new(Line::from(vec![Span::raw(input.clone())]))
                         .style(Style::default().fg(Color::Yellow).bg(Color::Blue));
-                    f.render_widget(input, chunks[2]);
+                    f.render_widget(input, chunks[chunks.len() - 1]);
                 }
             })
             .unwrap();
@@ -416,16 +441,16 @@ impl<'defmt> App<'defmt> {
 fn layout_chunks(
     f: &mut ratatui::Frame,
     has_down_channel: bool,
+    has_stats: bool,
 ) -> std::rc::Rc<[ratatui::prelude::Rect]> {
-    let constraints = if has_down_channel {
-        &[
-            Constraint::Length(1),
-            Constraint::Min(1),
-            Constraint::Length(1),
-        ][..]
-    } else {
-        &[Constraint::Length(1), Constraint::Min(1)][..]
-    };
+    // Tabs, messages, and optionally the status line and the input line.
+    let mut constraints = vec![Constraint::Length(1), Constraint::Min(1)];
+    if has_stats {
+        constraints.push(Constraint::Length(1));
+    }
+    if has_down_channel {
+        constraints.push(Constraint::Length(1));
+    }
     Layout::default()
         .direction(Direction::Vertical)
         .margin(0)
@@ -1,12 +1,13 @@
 use std::fmt;
 
 use defmt_decoder::StreamDecoder;
-use probe_rs::rtt::{ChannelMode, DownChannel, UpChannel};
+use probe_rs::rtt::{ChannelMode, ChannelStats, DownChannel, UpChannel};
 use probe_rs::Core;
 use time::UtcOffset;
 use time::{macros::format_description, OffsetDateTime};
 
 use crate::cmd::cargo_embed::DefmtInformation;
+use crate::util::rtt::capture::CaptureWriter;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum DataFormat {
@@ -111,6 +112,7 @@ pub struct ChannelState<'defmt> {
     input: String,
     scroll_offset: usize,
     rtt_buffer: RttBuffer,
+    capture: Option<CaptureWriter>,
 }
 
 impl<'defmt> ChannelState<'defmt> {
@@ -137,13 +139,39 @@ impl<'defmt> ChannelState<'defmt> {
             scroll_offset: 0,
             rtt_buffer: RttBuffer([0u8; 1024]),
             data,
+            capture: None,
         }
     }
 
+    /// Records the data of the up channel to `capture`, starting with the description of the channel.
+    pub fn start_capture(&mut self, capture: &CaptureWriter) -> std::io::Result<()> {
+        let Some(up_channel) = self.up_channel.as_ref() else {
+            return Ok(());
+        };
+
+        let data_format = match self.data {
+            ChannelData::String { .. } => crate::util::rtt::DataFormat::String,
+            ChannelData::Binary { .. } => crate::util::rtt::DataFormat::BinaryLE,
+            ChannelData::Defmt { .. } => crate::util::rtt::DataFormat::Defmt,
+        };
+        let mut capture = capture.try_clone()?;
+        capture.write_channel(up_channel.number(), &self.name, data_format)?;
+        self.capture = Some(capture);
+        Ok(())
+    }
+
     pub fn has_down_channel(&self) -> bool {
         self.down_channel.is_some()
     }
 
+    /// Returns the statistics of the up channel, or of the down channel if there is no up channel.
+    pub fn stats(&self) -> Option<ChannelStats> {
+        self.up_channel
+            .as_ref()
+            .map(|up| up.stats())
+            .or_else(|| self.down_channel.as_ref().map(|down| down.stats()))
+    }
+
     pub fn input(&self) -> &str {
         &self.input
     }
@@ -204,6 +232,14 @@ impl<'defmt> ChannelState<'defmt> {
             return Ok(());
         }
 
+        if let Some(capture) = self.capture.as_mut() {
+            let number = self.up_channel.as_ref().map_or(0, |up| up.number());
+            if let Err(err) = capture.write_data(number, &self.rtt_buffer.0[..count]) {
+                log::error!("\nError writing the RTT capture: {}", err);
+                self.capture = None;
+            }
+        }
+
         match &mut self.data {
             ChannelData::String {
                 data: messages,
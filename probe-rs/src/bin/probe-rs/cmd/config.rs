@@ -0,0 +1,102 @@
+use anyhow::bail;
+use probe_rs::flashing::{read_config_area, write_config_field};
+use probe_rs::Lister;
+
+use crate::util::common_options::ProbeOptions;
+use crate::util::parse_u32;
+
+#[derive(clap::Parser)]
+pub struct Cmd {
+    #[clap(subcommand)]
+    subcommand: Subcommand,
+}
+
+#[derive(clap::Subcommand)]
+enum Subcommand {
+    /// Read the fields of a configuration area, or of all areas if none is given
+    #[clap(name = "read")]
+    Read {
+        #[clap(flatten)]
+        probe_options: ProbeOptions,
+
+        /// The name of the configuration area, e.g. `UICR`.
+        area: Option<String>,
+    },
+    /// Write a field of a configuration area
+    ///
+    /// Fields which are marked as dangerous, e.g. because writing them is irreversible
+    /// or disables the debug access, can only be written with `--allow-dangerous-config`.
+    #[clap(name = "write")]
+    Write {
+        #[clap(flatten)]
+        probe_options: ProbeOptions,
+
+        /// The name of the configuration area, e.g. `UICR`.
+        area: String,
+
+        /// The name of the field, e.g. `APPROTECT`.
+        field: String,
+
+        /// The new value of the field. Hex values are prefixed with `0x`.
+        #[clap(value_parser = parse_u32)]
+        value: u32,
+    },
+}
+
+impl Cmd {
+    pub fn run(self, lister: &Lister) -> anyhow::Result<()> {
+        match self.subcommand {
+            Subcommand::Read {
+                probe_options,
+                area,
+            } => {
+                let (mut session, _probe_options) = probe_options.simple_attach(lister)?;
+
+                let areas = match area {
+                    Some(area) => vec![area],
+                    None => session
+                        .target()
+                        .config_areas
+                        .iter()
+                        .map(|area| area.name.clone())
+                        .collect(),
+                };
+
+                if areas.is_empty() {
+                    bail!(
+                        "Configuration areas are not supported for {}",
+                        session.target().name
+                    );
+                }
+
+                for area in areas {
+                    let values = read_config_area(&mut session, &area)?;
+
+                    println!("{area}:");
+                    for value in values {
+                        let field = &value.field;
+                        println!(
+                            "    {:<16} = {:#x}{}",
+                            field.name,
+                            value.value,
+                            if field.dangerous { " (dangerous)" } else { "" }
+                        );
+                    }
+                }
+            }
+            Subcommand::Write {
+                probe_options,
+                area,
+                field,
+                value,
+            } => {
+                let (mut session, _probe_options) = probe_options.simple_attach(lister)?;
+
+                write_config_field(&mut session, &area, &field, value)?;
+                println!("{area}.{field} = {value:#x}");
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -3,13 +3,15 @@ use super::{
     dap_types,
     repl_commands_helpers::{build_expanded_commands, command_completions},
     request_helpers::{
-        disassemble_target_memory, get_dap_source, get_variable_reference,
-        set_instruction_breakpoint,
+        data_breakpoint_id, disassemble_target_memory, exception_breakpoint_condition,
+        get_dap_source, get_variable_reference, parse_data_breakpoint_id,
+        set_instruction_breakpoint, watchpoint_kind,
     },
 };
 use crate::cmd::dap_server::{
     debug_adapter::protocol::{ProtocolAdapter, ProtocolHelper},
     server::{
+        breakpoint_conditions::BreakpointConditions,
         configuration::ConsoleLog,
         core_data::CoreHandle,
         session_data::{BreakpointType, SourceLocationScope},
@@ -25,8 +27,8 @@ use parse_int::parse;
 use probe_rs::{
     architecture::{arm::ArmError, riscv::communication_interface::RiscvError},
     debug::{
-        ColumnType, DebugRegisters, ObjectRef, SourceLocation, SteppingMode, VariableName,
-        VariableNodeType, VerifiedBreakpoint,
+        ColumnType, DebugRegisters, ObjectRef, SourceLocation, SteppingMode, VariableLocation,
+        VariableName, VariableNodeType, VerifiedBreakpoint,
     },
     Architecture::Riscv,
     CoreStatus, Error, HaltReason, MemoryInterface, RegisterValue,
@@ -429,78 +431,94 @@ impl<P: ProtocolAdapter> DebugAdapter<P> {
                         }
                     }
                 {
-                    // Always search the registers first, because we don't have a VariableCache for them.
-                    if let Some(register_value) = stack_frame
-                        .registers
-                        .get_register_by_name(expression.as_str())
-                        .and_then(|reg| reg.value)
-                    {
-                        response_body.type_ = Some(format!("{}", VariableName::RegistersRoot));
-                        response_body.result = format!("{register_value}");
-                    } else {
-                        // If the expression wasn't pointing to a register, then check if is a local or static variable in our stack_frame
-                        let mut variable: Option<probe_rs::debug::Variable> = None;
-                        let mut variable_cache: Option<&mut probe_rs::debug::VariableCache> = None;
-                        // Search through available caches and stop as soon as the variable is found
-                        #[allow(clippy::manual_flatten)]
-                        for variable_cache_entry in
-                            [
-                                stack_frame.local_variables.as_mut(),
-                                stack_frame.static_variables.as_mut(),
-                                target_core.core_data.core_peripherals.as_mut().map(
-                                    |core_peripherals| &mut core_peripherals.svd_variable_cache,
-                                ),
-                            ]
-                        {
-                            if let Some(search_cache) = variable_cache_entry {
-                                if search_cache.len() == 1 {
-                                    // This is a special case where we have a single variable in the cache, and it is the root of a scope.
-                                    // These variables don't have cached children by default, so we need to resolve them before we proceed.
-                                    // We check for len() == 1, so unwrap() on first_mut() is safe.
-                                    #[allow(clippy::unwrap_used)]
-                                    target_core.core_data.debug_info.cache_deferred_variables(
-                                        search_cache,
-                                        &mut target_core.core,
-                                        &mut search_cache.root_variable(),
-                                        &stack_frame.registers,
-                                        stack_frame.frame_base,
-                                    )?;
-                                }
+                    // The variable id is used instead of the name when the client already knows the variable.
+                    // Peripheral registers are not part of the debug information, so they are searched by name.
+                    let variable_key = expression.parse::<ObjectRef>().ok();
+                    let peripheral_variables = target_core
+                        .core_data
+                        .core_peripherals
+                        .as_mut()
+                        .map(|core_peripherals| &mut core_peripherals.svd_variable_cache);
+                    let mut found_variable = None;
+                    for (search_cache, is_peripheral_cache) in [
+                        (stack_frame.local_variables.as_mut(), false),
+                        (stack_frame.static_variables.as_mut(), false),
+                        (peripheral_variables, true),
+                    ] {
+                        let Some(search_cache) = search_cache else {
+                            continue;
+                        };
+                        let variable = match variable_key {
+                            Some(variable_key) => search_cache.get_variable_by_key(variable_key),
+                            None if is_peripheral_cache => search_cache
+                                .get_variable_by_name(&VariableName::Named(expression.clone())),
+                            None => None,
+                        };
+                        if let Some(mut variable) = variable {
+                            if variable.variable_node_type == VariableNodeType::SvdRegister
+                                || variable.variable_node_type == VariableNodeType::SvdField
+                            {
+                                variable.extract_value(&mut target_core.core, search_cache)
+                            }
+                            found_variable = Some((variable, search_cache));
+                            break;
+                        }
+                    }
 
-                                if let Ok(expression_as_key) = expression.parse::<ObjectRef>() {
-                                    variable = search_cache.get_variable_by_key(expression_as_key);
-                                } else {
-                                    variable = search_cache.get_variable_by_name(
-                                        &VariableName::Named(expression.clone()),
-                                    );
+                    if let Some((variable, variable_cache)) = found_variable {
+                        let (
+                            variables_reference,
+                            named_child_variables_cnt,
+                            indexed_child_variables_cnt,
+                        ) = get_variable_reference(&variable, variable_cache);
+                        response_body.indexed_variables = Some(indexed_child_variables_cnt);
+                        response_body.memory_reference =
+                            Some(format!("{}", variable.memory_location));
+                        response_body.named_variables = Some(named_child_variables_cnt);
+                        response_body.result = variable.get_value(variable_cache);
+                        response_body.type_ = Some(format!("{:?}", variable.type_name));
+                        response_body.variables_reference = variables_reference.into();
+                    } else {
+                        match target_core.core_data.debug_info.evaluate_expression(
+                            &mut target_core.core,
+                            stack_frame,
+                            &expression,
+                        ) {
+                            Ok(evaluated) => {
+                                response_body.result = evaluated.value;
+                                response_body.type_ = Some(evaluated.type_name);
+                                if let VariableLocation::Address(_) = evaluated.memory_location {
+                                    response_body.memory_reference =
+                                        Some(format!("{}", evaluated.memory_location));
                                 }
-                                if let Some(variable) = &mut variable {
-                                    if variable.variable_node_type == VariableNodeType::SvdRegister
-                                        || variable.variable_node_type == VariableNodeType::SvdField
-                                    {
-                                        variable.extract_value(&mut target_core.core, search_cache)
+                                // Variables of the stack frame can be expanded to show their children.
+                                if let Some(variable) = evaluated.variable {
+                                    if let Some(variable_cache) = [
+                                        stack_frame.local_variables.as_mut(),
+                                        stack_frame.static_variables.as_mut(),
+                                    ]
+                                    .into_iter()
+                                    .flatten()
+                                    .find(|variable_cache| {
+                                        variable_cache
+                                            .get_variable_by_key(variable.variable_key())
+                                            .is_some()
+                                    }) {
+                                        let (
+                                            variables_reference,
+                                            named_child_variables_cnt,
+                                            indexed_child_variables_cnt,
+                                        ) = get_variable_reference(&variable, variable_cache);
+                                        response_body.indexed_variables =
+                                            Some(indexed_child_variables_cnt);
+                                        response_body.named_variables =
+                                            Some(named_child_variables_cnt);
+                                        response_body.variables_reference =
+                                            variables_reference.into();
                                     }
-                                    variable_cache = Some(search_cache);
-                                    break;
                                 }
                             }
-                        }
-                        // Check if we found a variable.
-                        if let (Some(variable), Some(variable_cache)) = (variable, variable_cache) {
-                            let (
-                                variables_reference,
-                                named_child_variables_cnt,
-                                indexed_child_variables_cnt,
-                            ) = get_variable_reference(&variable, variable_cache);
-                            response_body.indexed_variables = Some(indexed_child_variables_cnt);
-                            response_body.memory_reference =
-                                Some(format!("{}", variable.memory_location));
-                            response_body.named_variables = Some(named_child_variables_cnt);
-                            response_body.result = variable.get_value(variable_cache);
-                            response_body.type_ = Some(format!("{:?}", variable.type_name));
-                            response_body.variables_reference = variables_reference.into();
-                        } else {
-                            // If we made it to here, no register or variable matched the expression.
+                            Err(error) => response_body.result = error.to_string(),
                         }
                     }
                 }
@@ -687,9 +705,11 @@ impl<P: ProtocolAdapter> DebugAdapter<P> {
                 let saved_breakpoints = std::mem::take(&mut target_core.core_data.breakpoints);
 
                 for breakpoint in saved_breakpoints {
-                    match target_core
-                        .set_breakpoint(breakpoint.address, breakpoint.breakpoint_type.clone())
-                    {
+                    match target_core.set_breakpoint(
+                        breakpoint.address,
+                        breakpoint.breakpoint_type.clone(),
+                        breakpoint.conditions.clone(),
+                    ) {
                         Ok(_) => {}
                         Err(error) => {
                             //This will cause the debugger to show the user an error, but not stop the debugger.
@@ -859,12 +879,23 @@ impl<P: ProtocolAdapter> DebugAdapter<P> {
                         Some(bp.column.unwrap_or(0) as u64 + 1)
                     };
 
-                    match target_core.verify_and_set_breakpoint(
-                        &source_path,
-                        requested_breakpoint_line,
-                        requested_breakpoint_column,
-                        &args.source,
-                    ) {
+                    let conditions = BreakpointConditions::parse(
+                        bp.condition.as_deref(),
+                        bp.hit_condition.as_deref(),
+                        bp.log_message.as_deref(),
+                    );
+
+                    match conditions
+                        .map_err(DebuggerError::Other)
+                        .and_then(|conditions| {
+                            target_core.verify_and_set_breakpoint(
+                                &source_path,
+                                requested_breakpoint_line,
+                                requested_breakpoint_column,
+                                &args.source,
+                                conditions,
+                            )
+                        }) {
                         Ok(VerifiedBreakpoint {
                             address,
                             source_location,
@@ -951,6 +982,302 @@ impl<P: ProtocolAdapter> DebugAdapter<P> {
         self.send_response(request, Ok(Some(instruction_breakpoint_body)))
     }
 
+    pub(crate) fn set_function_breakpoints(
+        &mut self,
+        target_core: &mut CoreHandle,
+        request: &Request,
+    ) -> Result<()> {
+        let arguments: SetFunctionBreakpointsArguments = get_arguments(self, request)?;
+
+        // Always clear existing breakpoints before setting new ones.
+        if let Err(error) = target_core.clear_function_breakpoints() {
+            tracing::warn!("Failed to clear function breakpoints. {}", error);
+        }
+
+        let mut created_breakpoints: Vec<Breakpoint> = Vec::new(); // For returning in the Response
+
+        for requested_breakpoint in arguments.breakpoints {
+            let conditions = BreakpointConditions::parse(
+                requested_breakpoint.condition.as_deref(),
+                requested_breakpoint.hit_condition.as_deref(),
+                None,
+            );
+
+            let breakpoint = match conditions
+                .map_err(DebuggerError::Other)
+                .and_then(|conditions| {
+                    target_core
+                        .verify_and_set_function_breakpoint(&requested_breakpoint.name, conditions)
+                }) {
+                Ok(verified_breakpoints) => {
+                    let addresses = verified_breakpoints
+                        .iter()
+                        .map(|breakpoint| format!("{:#010X}", breakpoint.address))
+                        .collect::<Vec<_>>();
+                    // The client can only show a single location for each breakpoint, so we use the first one.
+                    let first_breakpoint = verified_breakpoints.first();
+                    let source_location =
+                        first_breakpoint.map(|breakpoint| &breakpoint.source_location);
+                    Breakpoint {
+                        column: source_location.and_then(|location| {
+                            location.column.map(|col| match col {
+                                ColumnType::LeftEdge => 0_i64,
+                                ColumnType::Column(c) => c as i64,
+                            })
+                        }),
+                        end_column: None,
+                        end_line: None,
+                        id: None,
+                        line: source_location
+                            .and_then(|location| location.line.map(|line| line as i64)),
+                        message: Some(format!(
+                            "Function breakpoint for {} at memory address(es): {}",
+                            requested_breakpoint.name,
+                            addresses.join(", ")
+                        )),
+                        source: source_location.and_then(get_dap_source),
+                        instruction_reference: first_breakpoint
+                            .map(|breakpoint| format!("{:#010X}", breakpoint.address)),
+                        offset: None,
+                        verified: true,
+                    }
+                }
+                Err(error) => Breakpoint {
+                    column: None,
+                    end_column: None,
+                    end_line: None,
+                    id: None,
+                    line: None,
+                    message: Some(error.to_string()),
+                    source: None,
+                    instruction_reference: None,
+                    offset: None,
+                    verified: false,
+                },
+            };
+            created_breakpoints.push(breakpoint);
+        }
+
+        self.send_response(
+            request,
+            Ok(Some(SetFunctionBreakpointsResponseBody {
+                breakpoints: created_breakpoints,
+            })),
+        )
+    }
+
+    pub(crate) fn set_exception_breakpoints(
+        &mut self,
+        target_core: &mut CoreHandle,
+        request: &Request,
+    ) -> Result<()> {
+        let arguments: SetExceptionBreakpointsArguments = get_arguments(self, request)?;
+
+        let mut conditions = Vec::new();
+        let mut unknown_filters = Vec::new();
+        for filter in &arguments.filters {
+            match exception_breakpoint_condition(filter) {
+                Some(condition) => conditions.push(condition),
+                None => unknown_filters.push(filter),
+            }
+        }
+
+        let mut enable_results = target_core
+            .set_exception_breakpoints(&conditions)
+            .into_iter();
+
+        // The response has a breakpoint for each of the requested filters, in the same order.
+        let mut created_breakpoints: Vec<Breakpoint> = Vec::new();
+        for filter in &arguments.filters {
+            let result = if unknown_filters.contains(&filter) {
+                Err(format!("Unknown exception breakpoint filter: {filter}"))
+            } else {
+                enable_results
+                    .next()
+                    .unwrap_or(Ok(()))
+                    .map_err(|error| format!("Cannot halt on {filter} exceptions: {error}"))
+            };
+            let (verified, message) = match result {
+                Ok(()) => (true, None),
+                Err(message) => {
+                    self.log_to_console(format!("Warning: {message}"));
+                    (false, Some(message))
+                }
+            };
+            created_breakpoints.push(Breakpoint {
+                column: None,
+                end_column: None,
+                end_line: None,
+                id: None,
+                line: None,
+                message,
+                source: None,
+                instruction_reference: None,
+                offset: None,
+                verified,
+            });
+        }
+
+        self.send_response(
+            request,
+            Ok(Some(SetExceptionBreakpointsResponseBody {
+                breakpoints: Some(created_breakpoints),
+            })),
+        )
+    }
+
+    /// Find the memory location and size of the variable that the client wants to set a data breakpoint on.
+    /// The variable is either the child named `name` of the variable container `variables_reference`,
+    /// or an expression which is evaluated in the requested stack frame.
+    pub(crate) fn data_breakpoint_info(
+        &mut self,
+        target_core: &mut CoreHandle,
+        request: &Request,
+    ) -> Result<()> {
+        let arguments: DataBreakpointInfoArguments = get_arguments(self, request)?;
+
+        let variable = if let Some(variables_reference) = arguments.variables_reference {
+            let parent_key: ObjectRef = variables_reference.into();
+            target_core
+                .core_data
+                .stack_frames
+                .iter()
+                .flat_map(|stack_frame| {
+                    [
+                        stack_frame.local_variables.as_ref(),
+                        stack_frame.static_variables.as_ref(),
+                    ]
+                })
+                .flatten()
+                .find_map(|variable_cache| {
+                    variable_cache
+                        .get_children(parent_key)
+                        .ok()?
+                        .into_iter()
+                        .find(|variable| variable.name.to_string() == arguments.name)
+                })
+        } else {
+            let stack_frame = match arguments.frame_id.map(ObjectRef::from) {
+                Some(frame_id) => target_core
+                    .core_data
+                    .stack_frames
+                    .iter_mut()
+                    .find(|stack_frame| stack_frame.id == frame_id),
+                None => target_core.core_data.stack_frames.first_mut(),
+            };
+            stack_frame.and_then(|stack_frame| {
+                target_core
+                    .core_data
+                    .debug_info
+                    .evaluate_expression(&mut target_core.core, stack_frame, &arguments.name)
+                    .ok()?
+                    .variable
+            })
+        };
+
+        // Data breakpoints can only watch variables which are stored in target memory.
+        let watched_memory = variable.as_ref().and_then(|variable| {
+            let address = variable.memory_location.memory_address().ok()?;
+            let len = variable
+                .byte_size
+                .filter(|byte_size| !byte_size.is_zero())?;
+            Some((address, len))
+        });
+
+        let response_body = match watched_memory {
+            Some((address, len)) => DataBreakpointInfoResponseBody {
+                access_types: Some(vec![
+                    DataBreakpointAccessType::Read,
+                    DataBreakpointAccessType::Write,
+                    DataBreakpointAccessType::ReadWrite,
+                ]),
+                can_persist: Some(false),
+                data_id: Some(data_breakpoint_id(address, len)),
+                description: format!("{} ({len} bytes at {address:#010x})", arguments.name),
+            },
+            None => DataBreakpointInfoResponseBody {
+                access_types: None,
+                can_persist: None,
+                data_id: None,
+                description: format!(
+                    "{} is not stored in target memory, or has an unknown size.",
+                    arguments.name
+                ),
+            },
+        };
+
+        self.send_response(request, Ok(Some(response_body)))
+    }
+
+    pub(crate) fn set_data_breakpoints(
+        &mut self,
+        target_core: &mut CoreHandle,
+        request: &Request,
+    ) -> Result<()> {
+        let arguments: SetDataBreakpointsArguments = get_arguments(self, request)?;
+
+        let mut data_breakpoints = Vec::new();
+        let mut invalid_ids = Vec::new();
+        for requested_breakpoint in &arguments.breakpoints {
+            match parse_data_breakpoint_id(&requested_breakpoint.data_id) {
+                Ok((address, len)) => data_breakpoints.push((
+                    address,
+                    len,
+                    watchpoint_kind(requested_breakpoint.access_type.as_ref()),
+                )),
+                Err(error) => invalid_ids.push((&requested_breakpoint.data_id, error)),
+            }
+        }
+
+        let mut set_results = target_core
+            .set_data_breakpoints(&data_breakpoints)
+            .into_iter();
+
+        // The response has a breakpoint for each of the requested data breakpoints, in the same order.
+        let mut created_breakpoints: Vec<Breakpoint> = Vec::new();
+        for requested_breakpoint in &arguments.breakpoints {
+            let result = if let Some((_, error)) = invalid_ids
+                .iter()
+                .find(|(data_id, _)| *data_id == &requested_breakpoint.data_id)
+            {
+                Err(error.to_string())
+            } else {
+                set_results.next().unwrap_or(Ok(())).map_err(|error| {
+                    format!(
+                        "Cannot set data breakpoint on {}: {error}",
+                        requested_breakpoint.data_id
+                    )
+                })
+            };
+            let (verified, message) = match result {
+                Ok(()) => (true, None),
+                Err(message) => {
+                    self.log_to_console(format!("Warning: {message}"));
+                    (false, Some(message))
+                }
+            };
+            created_breakpoints.push(Breakpoint {
+                column: None,
+                end_column: None,
+                end_line: None,
+                id: None,
+                line: None,
+                message,
+                source: None,
+                instruction_reference: None,
+                offset: None,
+                verified,
+            });
+        }
+
+        self.send_response(
+            request,
+            Ok(Some(SetDataBreakpointsResponseBody {
+                breakpoints: created_breakpoints,
+            })),
+        )
+    }
+
     pub(crate) fn threads(
         &mut self,
         target_core: &mut CoreHandle,
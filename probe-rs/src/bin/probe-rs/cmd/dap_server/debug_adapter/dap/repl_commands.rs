@@ -285,12 +285,11 @@ pub(crate) static REPL_COMMANDS: &[ReplCommand<ReplHandler>] = &[
     },
     ReplCommand {
         command: "p",
-        // Stricly speaking, gdb refers to this as an expression, but we only support variables.
-        help_text: "Print known information about variable.",
+        help_text: "Print the value of an expression, e.g. `p my_struct.field[2] + 1`, or all local variables.",
         sub_commands: None,
         args: Some(&[
             ReplCommandArgs::Optional("/f (f=format[n|v])"),
-            ReplCommandArgs::Required("<local variable name>"),
+            ReplCommandArgs::Optional("<expression>"),
         ]),
         handler: |target_core, command_arguments, evaluate_arguments| {
             let input_arguments = command_arguments.split_whitespace();
@@ -298,8 +297,7 @@ pub(crate) static REPL_COMMANDS: &[ReplCommand<ReplHandler>] = &[
                 format_specifier: GdbFormat::Native,
                 ..Default::default()
             };
-            // If no variable name is provided, use the root of the local scope, and print all it's children.
-            let mut variable_name = VariableName::LocalScopeRoot;
+            let mut expression = Vec::new();
 
             for input_argument in input_arguments {
                 if input_argument.starts_with('/') {
@@ -322,11 +320,26 @@ pub(crate) static REPL_COMMANDS: &[ReplCommand<ReplHandler>] = &[
                         ));
                     }
                 } else {
-                    variable_name = VariableName::Named(input_argument.to_string());
+                    expression.push(input_argument);
                 }
             }
 
-            get_local_variable(evaluate_arguments, target_core, variable_name, gdb_nuf)
+            if expression.is_empty() {
+                // If no expression is provided, use the root of the local scope, and print all it's children.
+                get_local_variable(
+                    evaluate_arguments,
+                    target_core,
+                    VariableName::LocalScopeRoot,
+                    gdb_nuf,
+                )
+            } else {
+                print_expression(
+                    evaluate_arguments,
+                    target_core,
+                    &expression.join(" "),
+                    gdb_nuf,
+                )
+            }
         },
     },
     ReplCommand {
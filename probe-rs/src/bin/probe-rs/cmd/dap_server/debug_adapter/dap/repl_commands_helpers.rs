@@ -1,5 +1,5 @@
 use probe_rs::{
-    debug::{ObjectRef, VariableName},
+    debug::{ObjectRef, VariableLocation, VariableName},
     MemoryInterface,
 };
 
@@ -111,6 +111,78 @@ pub(crate) fn get_local_variable(
     Ok(response)
 }
 
+/// Evaluate the `expression` in the context of the selected stack frame, and add the result to the
+/// `response_body.result` for display to the user.
+pub(crate) fn print_expression(
+    evaluate_arguments: &EvaluateArguments,
+    target_core: &mut CoreHandle,
+    expression: &str,
+    gdb_nuf: GdbNuf,
+) -> Result<Response, DebuggerError> {
+    let frame_ref = evaluate_arguments.frame_id.map(ObjectRef::from);
+
+    let stack_frame = match frame_ref {
+        Some(frame_id) => target_core
+            .core_data
+            .stack_frames
+            .iter_mut()
+            .find(|stack_frame| stack_frame.id == frame_id),
+        None => {
+            // Use the current frame_id
+            target_core.core_data.stack_frames.first_mut()
+        }
+    };
+
+    // Make sure we have a valid StackFrame
+    let Some(stack_frame) = stack_frame else {
+        return Err(DebuggerError::UserMessage("No frame selected.".to_string()));
+    };
+
+    let evaluated = target_core
+        .core_data
+        .debug_info
+        .evaluate_expression(&mut target_core.core, stack_frame, expression)
+        .map_err(|error| DebuggerError::UserMessage(format!("{expression}: {error}")))?;
+
+    let mut response = Response {
+        command: "variables".to_string(),
+        success: true,
+        message: None,
+        type_: "response".to_string(),
+        request_seq: 0,
+        seq: 0,
+        body: None,
+    };
+    let mut response_body = EvaluateResponseBody {
+        result: "".to_string(),
+        variables_reference: 0,
+        named_variables: None,
+        indexed_variables: None,
+        memory_reference: None,
+        type_: None,
+        presentation_hint: None,
+    };
+    if gdb_nuf.format_specifier == GdbFormat::DapReference {
+        if let VariableLocation::Address(_) = evaluated.memory_location {
+            response_body.memory_reference = Some(format!("{}", evaluated.memory_location));
+        }
+        response_body.result = format!("{expression} : {} ", evaluated.value);
+        response_body.type_ = Some(evaluated.type_name);
+        // Only variables of the stack frame can be expanded by the client.
+        if let Some(variable) = evaluated.variable {
+            response_body.variables_reference = variable.variable_key().into();
+        }
+    } else {
+        response_body.result = format!(
+            "\n{expression} [{} @ {}]: {} ",
+            evaluated.type_name, evaluated.memory_location, evaluated.value
+        );
+    }
+    response.message = Some(response_body.result.clone());
+    response.body = serde_json::to_value(response_body).ok();
+    Ok(response)
+}
+
 /// Read memory at the specified address (hex), using the [`GdbNuf`] specifiers to determine size and format.
 pub(crate) fn memory_read(
     address: u64,
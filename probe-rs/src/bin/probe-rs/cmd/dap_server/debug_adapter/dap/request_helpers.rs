@@ -1,6 +1,9 @@
 use crate::cmd::dap_server::{
     debug_adapter::dap::dap_types::{DisassembledInstruction, Source},
-    server::{core_data::CoreHandle, session_data::BreakpointType},
+    server::{
+        breakpoint_conditions::BreakpointConditions, core_data::CoreHandle,
+        session_data::BreakpointType,
+    },
     DebuggerError,
 };
 use anyhow::{anyhow, Result};
@@ -11,11 +14,98 @@ use capstone::{
 use num_traits::Zero;
 use probe_rs::{
     debug::{ColumnType, ObjectRef, SourceLocation},
-    CoreType, InstructionSet, MemoryInterface,
+    CoreType, InstructionSet, MemoryInterface, VectorCatchCondition, WatchpointKind,
 };
 use std::{fmt::Write, time::Duration};
 
-use super::dap_types::{Breakpoint, InstructionBreakpoint, MemoryAddress};
+use super::dap_types::{
+    Breakpoint, DataBreakpointAccessType, ExceptionBreakpointsFilter, InstructionBreakpoint,
+    MemoryAddress,
+};
+
+/// The exception breakpoint filters which are offered to the client: the filter id, label, description,
+/// and the [`VectorCatchCondition`] that is enabled when the filter is selected.
+const EXCEPTION_BREAKPOINT_FILTERS: [(&str, &str, &str, VectorCatchCondition); 4] = [
+    (
+        "hardfault",
+        "HardFault",
+        "Halt when the core takes a HardFault, or a fault exception on RISC-V cores.",
+        VectorCatchCondition::HardFault,
+    ),
+    (
+        "reset",
+        "Reset",
+        "Halt when the core is reset.",
+        VectorCatchCondition::CoreReset,
+    ),
+    (
+        "securefault",
+        "SecureFault",
+        "Halt when the core takes a SecureFault. Only available on ARMv8-M cores with the Security Extension.",
+        VectorCatchCondition::SecureFault,
+    ),
+    (
+        "all",
+        "All exceptions",
+        "Halt on all of the above exceptions that are supported by the core.",
+        VectorCatchCondition::All,
+    ),
+];
+
+/// The exception breakpoint filters to report in the [`super::dap_types::Capabilities`] of the debug adapter.
+pub(crate) fn exception_breakpoint_filters() -> Vec<ExceptionBreakpointsFilter> {
+    EXCEPTION_BREAKPOINT_FILTERS
+        .iter()
+        .map(
+            |(filter, label, description, _)| ExceptionBreakpointsFilter {
+                filter: filter.to_string(),
+                label: label.to_string(),
+                description: Some(description.to_string()),
+                default: Some(false),
+                supports_condition: None,
+                condition_description: None,
+            },
+        )
+        .collect()
+}
+
+/// Find the [`VectorCatchCondition`] for the exception breakpoint `filter` selected by the client.
+pub(crate) fn exception_breakpoint_condition(filter: &str) -> Option<VectorCatchCondition> {
+    EXCEPTION_BREAKPOINT_FILTERS
+        .iter()
+        .find(|(filter_id, ..)| *filter_id == filter)
+        .map(|(.., condition)| *condition)
+}
+
+/// Create the `dataId` of a data breakpoint on the `len` bytes starting at `address`,
+/// which the client passes back to us in the `setDataBreakpoints` request.
+pub(crate) fn data_breakpoint_id(address: u64, len: u64) -> String {
+    format!("{address:#010x}/{len}")
+}
+
+/// Parse a `dataId` created by [`data_breakpoint_id`] into the address and length of the watched memory.
+pub(crate) fn parse_data_breakpoint_id(data_id: &str) -> Result<(u64, u64), DebuggerError> {
+    data_id
+        .split_once('/')
+        .and_then(|(address, len)| {
+            let address = u64::from_str_radix(address.strip_prefix("0x")?, 16).ok()?;
+            let len = len.parse().ok()?;
+            Some((address, len))
+        })
+        .ok_or_else(|| {
+            DebuggerError::UserMessage(format!("Invalid data breakpoint id: {data_id:?}"))
+        })
+}
+
+/// Find the [`WatchpointKind`] for the `access_type` of a data breakpoint. If the client doesn't specify
+/// an access type, the data breakpoint triggers on writes.
+pub(crate) fn watchpoint_kind(access_type: Option<&DataBreakpointAccessType>) -> WatchpointKind {
+    match access_type {
+        Some(DataBreakpointAccessType::Read) => WatchpointKind::Read,
+        Some(DataBreakpointAccessType::ReadWrite) => WatchpointKind::Access,
+        Some(DataBreakpointAccessType::Write) | None => WatchpointKind::Write,
+    }
+}
 
 pub(crate) fn disassemble_target_memory(
     target_core: &mut CoreHandle,
@@ -27,7 +117,7 @@ pub(crate) fn disassemble_target_memory(
     let cs = get_capstone(target_core)?;
     let target_instruction_set = target_core.core.instruction_set()?;
     let instruction_offset_as_bytes = match target_instruction_set {
-        InstructionSet::Thumb2 | InstructionSet::RV32C => {
+        InstructionSet::Thumb2 | InstructionSet::RV32C | InstructionSet::RV64C => {
             // Since we cannot guarantee the size of individual instructions, let's assume we will read the 120% of the requested number of 16-bit instructions.
             (instruction_offset
                 * target_core
@@ -37,7 +127,7 @@ pub(crate) fn disassemble_target_memory(
                 / 4
                 * 5
         }
-        InstructionSet::A32 | InstructionSet::A64 | InstructionSet::RV32 => {
+        InstructionSet::A32 | InstructionSet::A64 | InstructionSet::RV32 | InstructionSet::RV64 => {
             instruction_offset
                 * target_core
                     .core
@@ -262,6 +352,19 @@ pub(crate) fn get_capstone(target_core: &mut CoreHandle) -> Result<Capstone, Deb
                 capstone::arch::riscv::ArchExtraMode::RiscVC,
             ))
             .build(),
+        InstructionSet::RV64 => Capstone::new()
+            .riscv()
+            .mode(riscvArchMode::RiscV64)
+            .endian(Endian::Little)
+            .build(),
+        InstructionSet::RV64C => Capstone::new()
+            .riscv()
+            .mode(riscvArchMode::RiscV64)
+            .endian(Endian::Little)
+            .extra_mode(std::iter::once(
+                capstone::arch::riscv::ArchExtraMode::RiscVC,
+            ))
+            .build(),
         InstructionSet::Xtensa => return Err(DebuggerError::Unimplemented),
     }
     .map_err(|err| anyhow!("Error creating capstone: {:?}", err))?;
@@ -398,12 +501,30 @@ pub(crate) fn set_instruction_breakpoint(
         verified: false,
     };
 
+    let conditions = match BreakpointConditions::parse(
+        requested_breakpoint.condition.as_deref(),
+        requested_breakpoint.hit_condition.as_deref(),
+        None,
+    ) {
+        Ok(conditions) => conditions,
+        Err(error) => {
+            breakpoint_response.instruction_reference =
+                Some(requested_breakpoint.instruction_reference);
+            breakpoint_response.message = Some(error.to_string());
+            return breakpoint_response;
+        }
+    };
+
     if let Ok(MemoryAddress(memory_reference)) = requested_breakpoint
         .instruction_reference
         .as_str()
         .try_into()
     {
-        match target_core.set_breakpoint(memory_reference, BreakpointType::InstructionBreakpoint) {
+        match target_core.set_breakpoint(
+            memory_reference,
+            BreakpointType::InstructionBreakpoint,
+            conditions,
+        ) {
             Ok(_) => {
                 breakpoint_response.verified = true;
                 breakpoint_response.instruction_reference =
@@ -1,3 +1,5 @@
+/// The conditions, hit conditions and log messages of breakpoints.
+pub(crate) mod breakpoint_conditions;
 /// All the shared options that control the behaviour of the debugger.
 pub(crate) mod configuration;
 /// The data structures borrowed from the [`session_data::SessionData`], that applies to a specific core.
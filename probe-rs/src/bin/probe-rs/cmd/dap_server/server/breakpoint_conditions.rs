@@ -0,0 +1,237 @@
+use anyhow::{anyhow, bail, Result};
+use probe_rs::debug::Expression;
+use std::str::FromStr;
+
+/// The conditions of a breakpoint, which are checked by the debugger every time the breakpoint is hit.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct BreakpointConditions {
+    /// The expression which has to be true for the core to halt.
+    ///
+    /// It is evaluated with [`probe_rs::debug::DebugInfo::evaluate_expression`] every time the breakpoint is hit.
+    pub(crate) condition: Option<String>,
+    /// The number of hits for which the core halts.
+    pub(crate) hit_condition: Option<HitCondition>,
+    /// The message which is logged instead of halting the core.
+    pub(crate) log_message: Option<String>,
+}
+
+impl BreakpointConditions {
+    /// Parses the `condition`, `hitCondition` and `logMessage` of a breakpoint request.
+    ///
+    /// Empty strings are treated as if the value was not set.
+    pub(crate) fn parse(
+        condition: Option<&str>,
+        hit_condition: Option<&str>,
+        log_message: Option<&str>,
+    ) -> Result<Self> {
+        fn non_empty(value: Option<&str>) -> Option<&str> {
+            value.map(str::trim).filter(|value| !value.is_empty())
+        }
+
+        Ok(Self {
+            condition: non_empty(condition)
+                .map(|condition| {
+                    // Syntax errors are reported when the breakpoint is set, not when it is hit.
+                    condition
+                        .parse::<Expression>()
+                        .map(|_| condition.to_owned())
+                        .map_err(|error| anyhow!("Invalid condition `{condition}`: {error}"))
+                })
+                .transpose()?,
+            hit_condition: non_empty(hit_condition)
+                .map(|hit_condition| {
+                    hit_condition.parse().map_err(|error| {
+                        anyhow!("Invalid hit condition `{hit_condition}`: {error}")
+                    })
+                })
+                .transpose()?,
+            log_message: non_empty(log_message).map(str::to_owned),
+        })
+    }
+}
+
+/// A condition on the number of times a breakpoint was hit.
+///
+/// A plain number, optionally preceded by `==`, halts on exactly that hit, while `%` halts on every multiple of the number.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum HitCondition {
+    Equal(u64),
+    Greater(u64),
+    GreaterOrEqual(u64),
+    Less(u64),
+    LessOrEqual(u64),
+    MultipleOf(u64),
+}
+
+impl HitCondition {
+    /// Returns true if the core should halt on the `hit_count`th hit of the breakpoint.
+    pub(crate) fn is_met(self, hit_count: u64) -> bool {
+        match self {
+            HitCondition::Equal(count) => hit_count == count,
+            HitCondition::Greater(count) => hit_count > count,
+            HitCondition::GreaterOrEqual(count) => hit_count >= count,
+            HitCondition::Less(count) => hit_count < count,
+            HitCondition::LessOrEqual(count) => hit_count <= count,
+            HitCondition::MultipleOf(count) => hit_count.checked_rem(count) == Some(0),
+        }
+    }
+}
+
+impl FromStr for HitCondition {
+    type Err = anyhow::Error;
+
+    fn from_str(hit_condition: &str) -> Result<Self> {
+        let hit_condition = hit_condition.trim();
+
+        // The two character operators have to be checked first.
+        let operator = ["==", ">=", "<=", ">", "<", "%"]
+            .into_iter()
+            .find(|operator| hit_condition.starts_with(operator))
+            .unwrap_or_default();
+
+        let count = hit_condition[operator.len()..]
+            .trim_start()
+            .parse::<u64>()
+            .map_err(|_| anyhow!("Expected a number of hits, optionally preceded by one of `==`, `>`, `>=`, `<`, `<=` or `%`"))?;
+
+        let condition = match operator {
+            ">" => HitCondition::Greater(count),
+            ">=" => HitCondition::GreaterOrEqual(count),
+            "<" => HitCondition::Less(count),
+            "<=" => HitCondition::LessOrEqual(count),
+            "%" => HitCondition::MultipleOf(count),
+            _ => HitCondition::Equal(count),
+        };
+        if condition == HitCondition::MultipleOf(0) {
+            bail!("The number of hits has to be a multiple of a number greater than 0");
+        }
+
+        Ok(condition)
+    }
+}
+
+/// Returns true if the value of an evaluated breakpoint condition is `true`, or a non-zero integer or pointer.
+pub(crate) fn is_true(value: &str) -> Result<bool> {
+    let value = value.trim();
+    let integer = match value.strip_prefix("0x") {
+        Some(hex) => u128::from_str_radix(&hex.replace('_', ""), 16).ok(),
+        None => i128::from_str(value)
+            .ok()
+            .map(|integer| integer.unsigned_abs()),
+    };
+
+    match (value, integer) {
+        ("true", _) => Ok(true),
+        ("false", _) => Ok(false),
+        (_, Some(integer)) => Ok(integer != 0),
+        _ => bail!("Expected a boolean or an integer, but the condition evaluated to `{value}`"),
+    }
+}
+
+/// Replaces the expressions in braces in the log message of a log point with their values.
+///
+/// `{{` and `}}` are replaced with single braces. Expressions which cannot be evaluated are
+/// replaced with the error message.
+pub(crate) fn format_log_message(
+    message: &str,
+    evaluate: &mut dyn FnMut(&str) -> Result<String>,
+) -> String {
+    let mut formatted = String::new();
+    let mut chars = message.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.next_if_eq(&'{').is_some() => formatted.push('{'),
+            '}' if chars.next_if_eq(&'}').is_some() => formatted.push('}'),
+            '{' => {
+                let expression = chars.by_ref().take_while(|c| *c != '}').collect::<String>();
+                match evaluate(expression.trim()) {
+                    Ok(value) => formatted.push_str(&value),
+                    Err(error) => formatted.push_str(&format!("<{error}>")),
+                }
+            }
+            c => formatted.push(c),
+        }
+    }
+
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    fn evaluate(expression: &str) -> Result<String> {
+        match expression {
+            "count" => Ok("7".to_owned()),
+            "ready" => Ok("false".to_owned()),
+            _ => bail!("Unknown variable `{expression}`"),
+        }
+    }
+
+    #[test]
+    fn conditions() {
+        let conditions =
+            BreakpointConditions::parse(Some(" count > 5 && !ready "), None, None).unwrap();
+        assert_eq!(conditions.condition.as_deref(), Some("count > 5 && !ready"));
+
+        assert!(BreakpointConditions::parse(Some("count =="), None, None).is_err());
+        assert!(BreakpointConditions::parse(Some("(count == 1"), None, None).is_err());
+        assert!(BreakpointConditions::parse(Some("count == 1 2"), None, None).is_err());
+    }
+
+    #[test]
+    fn condition_values() {
+        assert!(is_true("true").unwrap());
+        assert!(!is_true("false").unwrap());
+        assert!(is_true("16").unwrap());
+        assert!(is_true("-1").unwrap());
+        assert!(!is_true("0").unwrap());
+        assert!(is_true("0x20000000").unwrap());
+        assert!(!is_true("0x00000000").unwrap());
+        assert!(is_true("\"probe\"").is_err());
+    }
+
+    #[test]
+    fn hit_conditions() {
+        let equal: HitCondition = "3".parse().unwrap();
+        assert!(!equal.is_met(2));
+        assert!(equal.is_met(3));
+        assert!(!equal.is_met(4));
+
+        let at_least: HitCondition = ">= 3".parse().unwrap();
+        assert!(!at_least.is_met(2));
+        assert!(at_least.is_met(5));
+
+        let multiple: HitCondition = "%2".parse().unwrap();
+        assert!(!multiple.is_met(1));
+        assert!(multiple.is_met(4));
+
+        assert_eq!(
+            "== 4".parse::<HitCondition>().unwrap(),
+            HitCondition::Equal(4)
+        );
+        assert!("%0".parse::<HitCondition>().is_err());
+        assert!("often".parse::<HitCondition>().is_err());
+    }
+
+    #[test]
+    fn log_messages() {
+        assert_eq!(
+            format_log_message("count={count}, {{ready}}: {ready}", &mut evaluate),
+            "count=7, {ready}: false"
+        );
+        assert_eq!(
+            format_log_message("{missing}", &mut evaluate),
+            "<Unknown variable `missing`>"
+        );
+    }
+
+    #[test]
+    fn empty_conditions() {
+        let conditions = BreakpointConditions::parse(Some(""), Some(" "), None).unwrap();
+        assert_eq!(conditions, BreakpointConditions::default());
+    }
+}
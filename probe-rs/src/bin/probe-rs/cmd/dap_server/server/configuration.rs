@@ -93,6 +93,22 @@ impl SessionConfig {
                         None
                     }
                 };
+            // Update the `additional_binaries` and validate that the files exist.
+            for additional_binary in &mut target_core_config.additional_binaries {
+                let path = get_absolute_path(self.cwd.clone(), Some(&additional_binary.path))?;
+                if !path.is_file() {
+                    return Err(DebuggerError::Other(anyhow!(
+                        "Invalid additional binary file specified '{:?}'",
+                        path
+                    )));
+                }
+                additional_binary.path = path;
+            }
+            // Update the `semihosting_root`, which is not mandatory either.
+            if let Some(semihosting_root) = &target_core_config.semihosting_root {
+                target_core_config.semihosting_root =
+                    Some(get_absolute_path(self.cwd.clone(), Some(semihosting_root))?);
+            }
         }
 
         Ok(())
@@ -168,6 +184,10 @@ pub struct FlashingConfig {
     #[serde(default)]
     pub(crate) restore_unwritten_bytes: bool,
 
+    /// Skip erasing and programming sectors which already contain the data from ELF
+    #[serde(default)]
+    pub(crate) preverify: bool,
+
     /// [`FormatOptions`] to control the flashing operation, depending on the type of binary ( [`probe_rs::flashing::Format`] ) to be flashed.
     #[serde(default)]
     pub(crate) format_options: FormatOptions,
@@ -184,13 +204,36 @@ pub struct CoreConfig {
     /// Binary to debug as a path. Relative to `cwd`, or fully qualified.
     pub(crate) program_binary: Option<PathBuf>,
 
+    /// Additional binaries with debug information for the code on this core, e.g. a bootloader, or the symbols of the ROM of the chip.
+    #[serde(default)]
+    pub(crate) additional_binaries: Vec<AdditionalBinary>,
+
     /// CMSIS-SVD file for the target. Relative to `cwd`, or fully qualified.
     pub(crate) svd_file: Option<PathBuf>,
 
+    /// The directory the target can access files in through semihosting. Relative to `cwd`, or fully qualified.
+    pub(crate) semihosting_root: Option<PathBuf>,
+
+    /// The arguments passed to the target through semihosting.
+    #[serde(default)]
+    pub(crate) semihosting_args: Vec<String>,
+
     #[serde(flatten)]
     pub(crate) rtt_config: rtt::RttConfig,
 }
 
+/// A binary which is only used for its debug information. It is not flashed to the target.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AdditionalBinary {
+    /// Binary with debug information as a path. Relative to `cwd`, or fully qualified.
+    pub(crate) path: PathBuf,
+
+    /// The offset from the addresses in the binary, to where the code is loaded in target memory. Default is 0
+    #[serde(default)]
+    pub(crate) load_offset: u64,
+}
+
 fn default_console_log() -> Option<ConsoleLog> {
     Some(ConsoleLog::Console)
 }
@@ -1,12 +1,22 @@
-use std::{fs::File, ops::Range};
+use std::{
+    fs::File,
+    io::Write,
+    ops::Range,
+    sync::{Arc, Mutex, PoisonError},
+};
 
-use super::session_data::{self, ActiveBreakpoint, BreakpointType, SourceLocationScope};
+use super::{
+    breakpoint_conditions::{format_log_message, is_true, BreakpointConditions},
+    session_data::{self, ActiveBreakpoint, BreakpointType, SourceLocationScope},
+};
 use crate::cmd::dap_server::{
     debug_adapter::{
         dap::{
             adapter::DebugAdapter,
             core_status::DapStatus,
-            dap_types::{ContinuedEventBody, MessageSeverity, Source, StoppedEventBody},
+            dap_types::{
+                ContinuedEventBody, MessageSeverity, OutputEventBody, Source, StoppedEventBody,
+            },
         },
         protocol::ProtocolAdapter,
     },
@@ -17,9 +27,15 @@ use crate::cmd::dap_server::{
 use crate::util::rtt::{self, ChannelMode, DataFormat, RttActiveTarget};
 use anyhow::{anyhow, Result};
 use probe_rs::{
-    debug::{debug_info::DebugInfo, ColumnType, ObjectRef, VerifiedBreakpoint},
+    debug::{
+        debug_info::DebugInfo, stack_frame::StackFrame, ColumnType, DebugRegisters, ObjectRef,
+        VerifiedBreakpoint,
+    },
+    exception_handler_for_core,
     rtt::{Rtt, ScanRegion},
-    Core, CoreStatus, Error, HaltReason,
+    semihosting::{DefaultSemihostingHandler, Semihosting},
+    BreakpointCause, Core, CoreStatus, Error, HaltReason, SemihostingCommand, VectorCatchCondition,
+    WatchpointKind,
 };
 use time::UtcOffset;
 use typed_path::TypedPathBuf;
@@ -41,7 +57,37 @@ pub struct CoreData {
     pub core_peripherals: Option<SvdCache>,
     pub stack_frames: Vec<probe_rs::debug::stack_frame::StackFrame>,
     pub breakpoints: Vec<session_data::ActiveBreakpoint>,
+    /// The vector catch conditions which are enabled by exception breakpoints.
+    pub exception_breakpoints: Vec<VectorCatchCondition>,
+    /// The addresses of the watchpoints which are set for data breakpoints.
+    pub data_breakpoints: Vec<u64>,
     pub rtt_connection: Option<debug_rtt::RttConnection>,
+    pub semihosting: Semihosting<DefaultSemihostingHandler>,
+    pub semihosting_console: SemihostingConsole,
+}
+
+/// Collects the semihosting console output of the target, until it is sent to the client.
+#[derive(Clone, Default)]
+pub struct SemihostingConsole(Arc<Mutex<Vec<u8>>>);
+
+impl SemihostingConsole {
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl Write for SemihostingConsole {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 /// [CoreHandle] provides handles to various data structures required to debug a single instance of a core. The actual state is stored in [session_data::SessionData].
@@ -74,6 +120,21 @@ impl<'p> CoreHandle<'p> {
     ) -> Result<CoreStatus, Error> {
         if debug_adapter.configuration_is_done() {
             match self.core.status() {
+                Ok(CoreStatus::Halted(HaltReason::Breakpoint(BreakpointCause::Semihosting(
+                    command,
+                )))) if self.handle_semihosting(debug_adapter, command)? => {
+                    // The core was resumed after performing the operation, without the client noticing.
+                    self.core_data.last_known_status = CoreStatus::Running;
+                    Ok(CoreStatus::Running)
+                }
+                Ok(CoreStatus::Halted(HaltReason::Breakpoint(_)))
+                    if !self.core_data.last_known_status.is_halted()
+                        && !self.check_breakpoint_conditions(debug_adapter)? =>
+                {
+                    // The core was resumed, because the conditions of the breakpoint were not met, or it is a log point.
+                    self.core_data.last_known_status = CoreStatus::Running;
+                    Ok(CoreStatus::Running)
+                }
                 Ok(status) => {
                     let has_changed_state = status != self.core_data.last_known_status;
                     if has_changed_state {
@@ -157,6 +218,150 @@ impl<'p> CoreHandle<'p> {
         }
     }
 
+    /// Performs the semihosting I/O operation `command`, forwards the console output to the client,
+    /// and resumes the core.
+    ///
+    /// Returns `false` if `command` is not an I/O operation, and the core remains halted.
+    fn handle_semihosting<P: ProtocolAdapter>(
+        &mut self,
+        debug_adapter: &mut DebugAdapter<P>,
+        command: SemihostingCommand,
+    ) -> Result<bool, Error> {
+        if !self.core_data.semihosting.handle(&mut self.core, command)? {
+            return Ok(false);
+        }
+
+        let output = self.core_data.semihosting_console.take();
+        if !output.is_empty() {
+            debug_adapter.send_event(
+                "output",
+                Some(OutputEventBody {
+                    output: String::from_utf8_lossy(&output).into_owned(),
+                    category: Some("stdout".to_owned()),
+                    variables_reference: None,
+                    source: None,
+                    line: None,
+                    column: None,
+                    data: None,
+                    group: None,
+                }),
+            )?;
+        }
+
+        self.core.run()?;
+        Ok(true)
+    }
+
+    /// Checks the conditions of the breakpoint the core halted on, and logs the message if it is a log point.
+    ///
+    /// Returns `false` if the core was resumed, because the conditions were not met or the breakpoint is a log point.
+    fn check_breakpoint_conditions<P: ProtocolAdapter>(
+        &mut self,
+        debug_adapter: &mut DebugAdapter<P>,
+    ) -> Result<bool, Error> {
+        let pc: u64 = self.core.read_core_reg(self.core.program_counter())?;
+        let Some(index) = self
+            .core_data
+            .breakpoints
+            .iter()
+            .position(|breakpoint| breakpoint.address == pc)
+        else {
+            return Ok(true);
+        };
+        let conditions = self.core_data.breakpoints[index].conditions.clone();
+        if conditions == BreakpointConditions::default() {
+            return Ok(true);
+        }
+
+        // Expressions are evaluated in the frame of the function the breakpoint is in.
+        let mut stack_frame = if conditions.condition.is_some() || conditions.log_message.is_some()
+        {
+            self.current_stack_frame()
+        } else {
+            None
+        };
+        if let Some(condition) = &conditions.condition {
+            let value = evaluate(
+                &self.core_data.debug_info,
+                &mut self.core,
+                stack_frame.as_mut(),
+                condition,
+            );
+            match value.and_then(|value| is_true(&value)) {
+                Ok(true) => {}
+                Ok(false) => {
+                    self.core.run()?;
+                    return Ok(false);
+                }
+                Err(error) => {
+                    // Like native debuggers, we halt when the condition cannot be evaluated.
+                    debug_adapter.show_message(
+                        MessageSeverity::Warning,
+                        format!(
+                            "Failed to evaluate the breakpoint condition at {pc:#010x}: {error}"
+                        ),
+                    );
+                    return Ok(true);
+                }
+            }
+        }
+
+        let breakpoint = &mut self.core_data.breakpoints[index];
+        breakpoint.hit_count += 1;
+        if let Some(hit_condition) = conditions.hit_condition {
+            if !hit_condition.is_met(breakpoint.hit_count) {
+                self.core.run()?;
+                return Ok(false);
+            }
+        }
+
+        if let Some(log_message) = &conditions.log_message {
+            let debug_info = &self.core_data.debug_info;
+            let core = &mut self.core;
+            let output = format_log_message(log_message, &mut |expression| {
+                evaluate(debug_info, core, stack_frame.as_mut(), expression)
+            });
+            debug_adapter.send_event(
+                "output",
+                Some(OutputEventBody {
+                    output: format!("{output}\n"),
+                    category: Some("console".to_owned()),
+                    variables_reference: None,
+                    source: None,
+                    line: None,
+                    column: None,
+                    data: None,
+                    group: None,
+                }),
+            )?;
+
+            self.core.run()?;
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    /// Unwinds the innermost stack frame of the halted core.
+    fn current_stack_frame(&mut self) -> Option<StackFrame> {
+        let initial_registers = DebugRegisters::from_core(&mut self.core);
+        let exception_interface = exception_handler_for_core(self.core.core_type());
+        let instruction_set = self.core.instruction_set().ok();
+
+        match self.core_data.debug_info.unwind(
+            &mut self.core,
+            initial_registers,
+            exception_interface.as_ref(),
+            instruction_set,
+        ) {
+            Ok(stack_frames) => stack_frames.into_iter().next(),
+            Err(error) => {
+                tracing::warn!("Failed to unwind the stack: {error}");
+                None
+            }
+        }
+    }
+
     /// Search available [`probe_rs::debug::StackFrame`]'s for the given `id`
     pub(crate) fn get_stackframe(
         &'p self,
@@ -178,15 +383,19 @@ impl<'p> CoreHandle<'p> {
         timestamp_offset: UtcOffset,
     ) -> Result<()> {
         let mut debugger_rtt_channels: Vec<debug_rtt::DebuggerRttChannel> = vec![];
-        // Attach to RTT by using the RTT control block address from the ELF file. Do not scan the memory for the control block.
-        match File::open(program_binary)
-            .map_err(|error| anyhow!("Error attempting to attach to RTT: {}", error))
-            .and_then(|mut open_file| {
-                RttActiveTarget::get_rtt_symbol(&mut open_file).map_or_else(
-                    || Err(anyhow!("No RTT control block found in ELF file")),
-                    |rtt_header_address| Ok(ScanRegion::Exact(rtt_header_address as u32)),
-                )
-            })
+        // Attach to RTT by using the configured RTT control block address, or the one from the ELF file. Do not scan the memory for the control block.
+        let control_block_address = match rtt_config.control_block_address {
+            Some(address) => Ok(address),
+            None => File::open(program_binary)
+                .map_err(|error| anyhow!("Error attempting to attach to RTT: {}", error))
+                .and_then(|mut open_file| {
+                    RttActiveTarget::get_rtt_symbol(&mut open_file)
+                        .map(|rtt_header_address| rtt_header_address as u32)
+                        .ok_or_else(|| anyhow!("No RTT control block found in ELF file"))
+                }),
+        };
+        match control_block_address
+            .map(ScanRegion::Exact)
             .and_then(|scan_region| {
                 Rtt::attach_region(&mut self.core, target_memory_map, &scan_region)
                     .map_err(|error| anyhow!("Error attempting to attach to RTT: {}", error))
@@ -241,6 +450,7 @@ impl<'p> CoreHandle<'p> {
         &mut self,
         address: u64,
         breakpoint_type: session_data::BreakpointType,
+        conditions: BreakpointConditions,
     ) -> Result<(), DebuggerError> {
         // NOTE: After receiving a DAP [`crate::debug_adapter::dap::dap_types::BreakpointEvent`], VSCode will mistakenly
         // identify a `InstructionBreakpoint` as a `SourceBreakpoint`. This results in breakpoints not being cleared correctly from [`CoreHandle::clear_breakpoints()`].
@@ -250,14 +460,16 @@ impl<'p> CoreHandle<'p> {
         }
 
         self.core
-            .set_hw_breakpoint(address)
+            .set_breakpoint(address)
             .map_err(DebuggerError::ProbeRs)?;
-        // Wait until the set of the hw breakpoint succeeded, before we cache it here ...
+        // Wait until the set of the breakpoint succeeded, before we cache it here ...
         self.core_data
             .breakpoints
             .push(session_data::ActiveBreakpoint {
                 breakpoint_type,
                 address,
+                conditions,
+                hit_count: 0,
             });
         Ok(())
     }
@@ -265,7 +477,7 @@ impl<'p> CoreHandle<'p> {
     /// Clear a single breakpoint from target configuration.
     pub(crate) fn clear_breakpoint(&mut self, address: u64) -> Result<()> {
         self.core
-            .clear_hw_breakpoint(address)
+            .clear_breakpoint(address)
             .map_err(DebuggerError::ProbeRs)?;
         if let Some((breakpoint_position, _)) = self.find_breakpoint_in_cache(address) {
             self.core_data.breakpoints.remove(breakpoint_position);
@@ -301,6 +513,120 @@ impl<'p> CoreHandle<'p> {
         Ok(())
     }
 
+    /// Clear all breakpoints of type [`super::session_data::BreakpointType::FunctionBreakpoint`], because the client
+    /// always replaces the complete set of function breakpoints.
+    pub(crate) fn clear_function_breakpoints(&mut self) -> Result<()> {
+        let function_breakpoints = self
+            .core_data
+            .breakpoints
+            .iter()
+            .filter(|breakpoint| {
+                matches!(
+                    breakpoint.breakpoint_type,
+                    BreakpointType::FunctionBreakpoint { .. }
+                )
+            })
+            .map(|breakpoint| breakpoint.address)
+            .collect::<Vec<u64>>();
+        for breakpoint in function_breakpoints {
+            self.clear_breakpoint(breakpoint)?;
+        }
+        Ok(())
+    }
+
+    /// Set a breakpoint at every instance of the function `function_name`, including the places where it was inlined.
+    /// The Result<> contains the "verified" `address` and `SourceLocation` of each breakpoint that was set.
+    pub(crate) fn verify_and_set_function_breakpoint(
+        &mut self,
+        function_name: &str,
+        conditions: BreakpointConditions,
+    ) -> Result<Vec<VerifiedBreakpoint>, DebuggerError> {
+        let locations = self
+            .core_data
+            .debug_info
+            .get_function_breakpoint_locations(function_name)
+            .map_err(|debug_error| {
+                DebuggerError::Other(anyhow!("Cannot set function breakpoint: {debug_error}"))
+            })?;
+
+        let mut verified_breakpoints = Vec::new();
+        let mut last_error = None;
+        for location in locations {
+            match self.set_breakpoint(
+                location.address,
+                BreakpointType::FunctionBreakpoint {
+                    name: function_name.to_string(),
+                },
+                conditions.clone(),
+            ) {
+                Ok(()) => verified_breakpoints.push(location),
+                Err(error) => {
+                    tracing::warn!(
+                        "Failed to set breakpoint for {} @{:#010x}: {}",
+                        function_name,
+                        location.address,
+                        error
+                    );
+                    last_error = Some(error);
+                }
+            }
+        }
+
+        match last_error {
+            Some(error) if verified_breakpoints.is_empty() => Err(error),
+            _ => Ok(verified_breakpoints),
+        }
+    }
+
+    /// Replace the vector catch conditions of the exception breakpoints with `conditions`.
+    /// Returns the result of enabling each of the `conditions`, in the same order.
+    pub(crate) fn set_exception_breakpoints(
+        &mut self,
+        conditions: &[VectorCatchCondition],
+    ) -> Vec<Result<(), Error>> {
+        for condition in std::mem::take(&mut self.core_data.exception_breakpoints) {
+            if let Err(error) = self.core.disable_vector_catch(condition) {
+                tracing::warn!(
+                    "Failed to disable vector catch for {:?}: {}",
+                    condition,
+                    error
+                );
+            }
+        }
+
+        conditions
+            .iter()
+            .map(|&condition| {
+                self.core.enable_vector_catch(condition)?;
+                self.core_data.exception_breakpoints.push(condition);
+                Ok(())
+            })
+            .collect()
+    }
+
+    /// Replace the watchpoints of the data breakpoints with watchpoints on `data_breakpoints`,
+    /// given as the address and length of the watched memory, and the kind of access to halt on.
+    /// Returns the result of setting each of the watchpoints, in the same order.
+    pub(crate) fn set_data_breakpoints(
+        &mut self,
+        data_breakpoints: &[(u64, u64, WatchpointKind)],
+    ) -> Vec<Result<(), Error>> {
+        for address in std::mem::take(&mut self.core_data.data_breakpoints) {
+            if let Err(error) = self.core.clear_watchpoint(address) {
+                tracing::warn!("Failed to clear watchpoint at {:#010x}: {}", address, error);
+            }
+        }
+
+        data_breakpoints
+            .iter()
+            .map(|&(address, len, kind)| {
+                self.core.set_watchpoint(address, len, kind)?;
+                self.core_data.data_breakpoints.push(address);
+                Ok(())
+            })
+            .collect()
+    }
+
     /// Set a breakpoint at the requested address. If the requested source location is not specific, or
     /// if the requested address is not a valid breakpoint location,
     /// the debugger will attempt to find the closest location to the requested location, and set a breakpoint there.
@@ -311,6 +637,7 @@ impl<'p> CoreHandle<'p> {
         requested_breakpoint_line: u64,
         requested_breakpoint_column: Option<u64>,
         requested_source: &Source,
+        conditions: BreakpointConditions,
     ) -> Result<VerifiedBreakpoint, DebuggerError> {
         let VerifiedBreakpoint {
                  address,
@@ -330,6 +657,7 @@ impl<'p> CoreHandle<'p> {
                 source: requested_source.clone(),
                 location: SourceLocationScope::Specific(source_location.clone()),
             },
+            conditions,
         )?;
         Ok(VerifiedBreakpoint {
             address,
@@ -371,6 +699,7 @@ impl<'p> CoreHandle<'p> {
                                 ColumnType::Column(c) => c,
                             }),
                             &source,
+                            breakpoint.conditions.clone(),
                         )
                     });
 
@@ -381,6 +710,29 @@ impl<'p> CoreHandle<'p> {
                 }
             }
         }
+
+        // Function breakpoints are resolved again by name, because the function may have moved, or been inlined in other places.
+        let mut function_breakpoints: Vec<(String, BreakpointConditions)> = Vec::new();
+        for breakpoint in target_breakpoints {
+            if let BreakpointType::FunctionBreakpoint { name } = breakpoint.breakpoint_type {
+                if !function_breakpoints
+                    .iter()
+                    .any(|(function_name, _)| *function_name == name)
+                {
+                    function_breakpoints.push((name, breakpoint.conditions));
+                }
+            }
+        }
+        self.clear_function_breakpoints()?;
+        for (function_name, conditions) in function_breakpoints {
+            if let Err(breakpoint_error) =
+                self.verify_and_set_function_breakpoint(&function_name, conditions)
+            {
+                return Err(DebuggerError::Other(anyhow!(
+                    "Failed to recompute breakpoint for function {function_name}. Error: {breakpoint_error:?}"
+                )));
+            }
+        }
         Ok(())
     }
 
@@ -423,6 +775,21 @@ impl<'p> CoreHandle<'p> {
     }
 }
 
+/// Evaluates `expression` in `stack_frame` and returns its formatted value.
+fn evaluate(
+    debug_info: &DebugInfo,
+    core: &mut Core,
+    stack_frame: Option<&mut StackFrame>,
+    expression: &str,
+) -> Result<String> {
+    let stack_frame =
+        stack_frame.ok_or_else(|| anyhow!("No stack frame to evaluate `{expression}` in"))?;
+
+    Ok(debug_info
+        .evaluate_expression(core, stack_frame, expression)?
+        .value)
+}
+
 /// Return a Vec of memory ranges that consolidate the adjacent memory ranges of the input ranges.
 /// Note: The concept of "adjacent" is calculated to include a gap of up to specicied number of bytes between ranges.
 /// This serves to consolidate memory ranges that are separated by a small gap, but are still close enough for the purpose of the caller.
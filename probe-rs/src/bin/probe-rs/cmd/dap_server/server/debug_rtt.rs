@@ -26,6 +26,15 @@ impl RttConnection {
             at_least_one_channel_had_data |=
                 debugger_rtt_channel.poll_rtt_data(target_core, debug_adapter, &mut self.target_rtt)
         }
+        // Forward the data which socket clients sent to the target.
+        match self.target_rtt.poll_sockets(target_core) {
+            Ok(had_data) => at_least_one_channel_had_data |= had_data,
+            Err(rtt_error) => {
+                debug_adapter
+                    .show_error_message(&DebuggerError::Other(rtt_error))
+                    .ok();
+            }
+        }
         at_least_one_channel_had_data
     }
 }
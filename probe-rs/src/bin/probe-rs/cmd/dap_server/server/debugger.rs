@@ -12,7 +12,7 @@ use crate::{
                     Capabilities, Event, ExitedEventBody, InitializeRequestArguments,
                     MessageSeverity, Request, RttWindowOpenedArguments, TerminatedEventBody,
                 },
-                request_helpers::halt_core,
+                request_helpers::{exception_breakpoint_filters, halt_core},
             },
             protocol::ProtocolAdapter,
         },
@@ -162,6 +162,9 @@ impl Debugger {
                     | "setBreakpoint"
                     | "setBreakpoints"
                     | "setInstructionBreakpoints"
+                    | "setFunctionBreakpoints"
+                    | "setExceptionBreakpoints"
+                    | "setDataBreakpoints"
                     | "clearBreakpoint"
                     | "stackTrace"
                     | "threads"
@@ -260,6 +263,18 @@ impl Debugger {
                     "setInstructionBreakpoints" => {
                         debug_adapter.set_instruction_breakpoints(&mut target_core, &request)
                     }
+                    "setFunctionBreakpoints" => {
+                        debug_adapter.set_function_breakpoints(&mut target_core, &request)
+                    }
+                    "setExceptionBreakpoints" => {
+                        debug_adapter.set_exception_breakpoints(&mut target_core, &request)
+                    }
+                    "dataBreakpointInfo" => {
+                        debug_adapter.data_breakpoint_info(&mut target_core, &request)
+                    }
+                    "setDataBreakpoints" => {
+                        debug_adapter.set_data_breakpoints(&mut target_core, &request)
+                    }
                     "stackTrace" => debug_adapter.stack_trace(&mut target_core, &request),
                     "scopes" => debug_adapter.scopes(&mut target_core, &request),
                     "disassemble" => debug_adapter.disassemble(&mut target_core, &request),
@@ -599,6 +614,7 @@ impl Debugger {
         let mut download_options = DownloadOptions::default();
         download_options.keep_unwritten_bytes = self.config.flashing_config.restore_unwritten_bytes;
         download_options.do_chip_erase = self.config.flashing_config.full_chip_erase;
+        download_options.preverify = self.config.flashing_config.preverify;
 
         let rc_debug_adapter = Rc::new(RefCell::new(debug_adapter));
         let rc_debug_adapter_clone = rc_debug_adapter.clone();
@@ -667,6 +683,18 @@ impl Debugger {
                             .update_progress(Some(1.0), Some("Reading Old Pages Complete!"), id)
                             .ok();
                     }
+                    probe_rs::flashing::ProgressEvent::SectorCompared {
+                        size,
+                        page_size,
+                        unchanged,
+                        ..
+                    } => {
+                        // Skipped sectors are neither erased nor programmed.
+                        if unchanged {
+                            flash_progress.total_sector_size -= size as usize;
+                            flash_progress.total_page_size -= page_size as usize;
+                        }
+                    }
                     probe_rs::flashing::ProgressEvent::StartedErasing => {
                         debug_adapter
                             .update_progress(Some(0.0), Some("Erasing Sectors ..."), id)
@@ -830,11 +858,13 @@ impl Debugger {
             supports_stepping_granularity: Some(true),
             supports_completions_request: Some(true),
             support_terminate_debuggee: Some(true),
+            supports_conditional_breakpoints: Some(true),
+            supports_hit_conditional_breakpoints: Some(true),
+            supports_log_points: Some(true),
+            supports_function_breakpoints: Some(true),
+            exception_breakpoint_filters: Some(exception_breakpoint_filters()),
+            supports_data_breakpoints: Some(true),
             // supports_value_formatting_options: Some(true),
-            // supports_function_breakpoints: Some(true),
-            // TODO: Use DEMCR register to implement exception breakpoints
-            // supports_exception_options: Some(true),
-            // supports_exception_filter_options: Some (true),
             ..Default::default()
         };
         debug_adapter.send_response(&initialize_request, Ok(Some(capabilities)))?;
@@ -933,6 +963,7 @@ mod test {
                     InitializeRequestArguments, Message, Request, Response, Thread,
                     ThreadsResponseBody,
                 },
+                request_helpers::exception_breakpoint_filters,
             },
             protocol::ProtocolAdapter,
         },
@@ -967,10 +998,16 @@ mod test {
             support_suspend_debuggee: Some(true),
             supports_clipboard_context: Some(true),
             supports_completions_request: Some(true),
+            supports_conditional_breakpoints: Some(true),
             supports_configuration_done_request: Some(true),
             supports_delayed_stack_trace_loading: Some(true),
             supports_disassemble_request: Some(true),
+            supports_hit_conditional_breakpoints: Some(true),
             supports_instruction_breakpoints: Some(true),
+            supports_log_points: Some(true),
+            supports_function_breakpoints: Some(true),
+            exception_breakpoint_filters: Some(exception_breakpoint_filters()),
+            supports_data_breakpoints: Some(true),
             supports_read_memory_request: Some(true),
             supports_write_memory_request: Some(true),
             supports_restart_request: Some(true),
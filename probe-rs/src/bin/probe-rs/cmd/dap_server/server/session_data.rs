@@ -1,6 +1,7 @@
 use super::{
+    breakpoint_conditions::BreakpointConditions,
     configuration::{self, CoreConfig, SessionConfig},
-    core_data::{CoreData, CoreHandle},
+    core_data::{CoreData, CoreHandle, SemihostingConsole},
 };
 use crate::cmd::dap_server::{
     debug_adapter::{
@@ -12,14 +13,16 @@ use anyhow::{anyhow, Result};
 use probe_rs::{
     config::TargetSelector,
     debug::{debug_info::DebugInfo, DebugRegisters, SourceLocation},
-    exception_handler_for_core, CoreStatus, DebugProbeError, Lister, Permissions,
-    ProbeCreationError, Session,
+    exception_handler_for_core,
+    semihosting::{DefaultSemihostingHandler, Semihosting},
+    CoreStatus, DebugProbeError, Lister, Permissions, ProbeCreationError, Session,
 };
 use std::env::set_current_dir;
 use time::UtcOffset;
 
 /// The supported breakpoint types
 #[derive(Clone, Debug, PartialEq)]
+#[allow(clippy::enum_variant_names)]
 pub(crate) enum BreakpointType {
     /// A breakpoint was requested using an instruction address, and usually a result of a user requesting a
     /// breakpoint while in a 'disassembly' view.
@@ -29,6 +32,9 @@ pub(crate) enum BreakpointType {
         source: Source,
         location: SourceLocationScope,
     },
+    /// A breakpoint that was requested using the name of a function. One is set for every instance of the function,
+    /// including the places where it was inlined.
+    FunctionBreakpoint { name: String },
 }
 
 /// Breakpoint requests will either be refer to a specific `SourceLocation`, or unspecified, in which case it will refer to
@@ -44,6 +50,9 @@ pub(crate) enum SourceLocationScope {
 pub struct ActiveBreakpoint {
     pub(crate) breakpoint_type: BreakpointType,
     pub(crate) address: u64,
+    pub(crate) conditions: BreakpointConditions,
+    /// The number of times the breakpoint was hit while its condition was met.
+    pub(crate) hit_count: u64,
 }
 
 /// SessionData is designed to be similar to [probe_rs::Session], in as much that it provides handles to the [CoreHandle] instances for each of the available [probe_rs::Core] involved in the debug session.
@@ -170,6 +179,14 @@ impl SessionData {
         let mut core_data_vec = vec![];
 
         for core_configuration in &valid_core_configs {
+            let semihosting_console = SemihostingConsole::default();
+            let mut semihosting_handler = DefaultSemihostingHandler::new()
+                .with_console(semihosting_console.clone())
+                .with_command_line(semihosting_command_line(core_configuration));
+            if let Some(root) = &core_configuration.semihosting_root {
+                semihosting_handler = semihosting_handler.with_root(root);
+            }
+
             core_data_vec.push(CoreData {
                 core_index: core_configuration.core_index,
                 last_known_status: CoreStatus::Unknown,
@@ -182,7 +199,11 @@ impl SessionData {
                 core_peripherals: None,
                 stack_frames: Vec::<probe_rs::debug::stack_frame::StackFrame>::new(),
                 breakpoints: Vec::<ActiveBreakpoint>::new(),
+                exception_breakpoints: Vec::new(),
+                data_breakpoints: Vec::new(),
                 rtt_connection: None,
+                semihosting: Semihosting::new(semihosting_handler),
+                semihosting_console,
             })
         }
 
@@ -342,7 +363,7 @@ impl SessionData {
 pub(crate) fn debug_info_from_binary(
     core_configuration: &CoreConfig,
 ) -> Result<DebugInfo, DebuggerError> {
-    let debug_info = if let Some(binary_path) = &core_configuration.program_binary {
+    let mut debug_info = if let Some(binary_path) = &core_configuration.program_binary {
         DebugInfo::from_file(binary_path).map_err(|error| DebuggerError::Other(anyhow!(error)))?
     } else {
         return Err(anyhow!(
@@ -351,5 +372,31 @@ pub(crate) fn debug_info_from_binary(
         )
         .into());
     };
+    for additional_binary in &core_configuration.additional_binaries {
+        debug_info
+            .add_file(&additional_binary.path, additional_binary.load_offset)
+            .map_err(|error| {
+                DebuggerError::Other(anyhow!(
+                    "Failed to load debug information from {:?}: {}",
+                    additional_binary.path,
+                    error
+                ))
+            })?;
+    }
     Ok(debug_info)
 }
+
+/// The command line passed to the target through semihosting, starting with the program name.
+fn semihosting_command_line(core_configuration: &CoreConfig) -> String {
+    let program_name = core_configuration
+        .program_binary
+        .as_ref()
+        .and_then(|path| path.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    std::iter::once(program_name)
+        .chain(core_configuration.semihosting_args.iter().cloned())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
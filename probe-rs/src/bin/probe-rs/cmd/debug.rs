@@ -21,7 +21,10 @@ use probe_rs::{
 };
 use rustyline::DefaultEditor;
 
-use crate::{util::common_options::ProbeOptions, CoreOptions};
+use crate::{
+    util::{common_options::ProbeOptions, parse_u64},
+    CoreOptions,
+};
 
 #[derive(clap::Parser)]
 pub struct Cmd {
@@ -34,16 +37,34 @@ pub struct Cmd {
     #[clap(long, value_parser)]
     /// Binary to debug
     exe: Option<PathBuf>,
+
+    #[clap(long = "additional-exe", value_parser = parse_additional_exe, requires = "exe")]
+    /// Additional binary with debug information, e.g. a bootloader, in the form 'PATH[@LOAD_OFFSET]'.
+    /// Can be used multiple times.
+    additional_exes: Vec<(PathBuf, u64)>,
 }
 
 impl Cmd {
     pub fn run(self, lister: &Lister) -> anyhow::Result<()> {
         let (mut session, _probe_options) = self.common.simple_attach(lister)?;
 
-        let di = self
+        let mut di = self
             .exe
             .as_ref()
-            .and_then(|path| DebugInfo::from_file(path).ok());
+            .map(|path| {
+                DebugInfo::from_file(path).map_err(|error| {
+                    anyhow!("Failed to load debug information from {path:?}: {error}")
+                })
+            })
+            .transpose()?;
+
+        if let Some(di) = di.as_mut() {
+            for (path, load_offset) in &self.additional_exes {
+                di.add_file(path, *load_offset).map_err(|error| {
+                    anyhow!("Failed to load debug information from {path:?}: {error}")
+                })?;
+            }
+        }
 
         let cli = DebugCli::new();
 
@@ -198,6 +219,19 @@ impl DebugCli {
                             capstone::arch::riscv::ArchExtraMode::RiscVC,
                         ))
                         .build(),
+                    InstructionSet::RV64 => Capstone::new()
+                        .riscv()
+                        .mode(riscvArchMode::RiscV64)
+                        .endian(Endian::Little)
+                        .build(),
+                    InstructionSet::RV64C => Capstone::new()
+                        .riscv()
+                        .mode(riscvArchMode::RiscV64)
+                        .endian(Endian::Little)
+                        .extra_mode(std::iter::once(
+                            capstone::arch::riscv::ArchExtraMode::RiscVC,
+                        ))
+                        .build(),
                     InstructionSet::Xtensa => Err(capstone::Error::UnsupportedArch),
                 }
                 .map_err(|err| anyhow!("Error creating capstone: {:?}", err))?;
@@ -478,7 +512,7 @@ impl DebugCli {
             function: |cli_data, args| {
                 let address = get_int_argument(args, 0)?;
 
-                cli_data.core.set_hw_breakpoint(address)?;
+                cli_data.core.set_breakpoint(address)?;
 
                 println!("Set new breakpoint at address {address:#08x}");
 
@@ -493,7 +527,7 @@ impl DebugCli {
             function: |cli_data, args| {
                 let address = get_int_argument(args, 0)?;
 
-                cli_data.core.clear_hw_breakpoint(address)?;
+                cli_data.core.clear_breakpoint(address)?;
 
                 Ok(CliState::Continue)
             },
@@ -737,6 +771,51 @@ impl DebugCli {
             },
         });
 
+        cli.add_command(Command {
+            name: "print",
+            help_text:
+                "Evaluate an expression in the current frame, e.g. 'print my_struct.field[2] + 1'",
+
+            function: |cli_data, args| {
+                match cli_data.state {
+                    DebugState::Halted(ref mut halted_state) => {
+                        let Some(current_frame) = halted_state.get_current_frame_mut() else {
+                            println!("StackFrame not found.");
+                            return Ok(CliState::Continue);
+                        };
+
+                        let Some(debug_info) = cli_data.debug_info.as_ref() else {
+                            println!("No debug information available.");
+                            return Ok(CliState::Continue);
+                        };
+
+                        let expression = args.join(" ");
+                        if expression.is_empty() {
+                            println!("Please provide an expression to evaluate.");
+                            return Ok(CliState::Continue);
+                        }
+
+                        match debug_info.evaluate_expression(
+                            &mut cli_data.core,
+                            current_frame,
+                            &expression,
+                        ) {
+                            Ok(evaluated) => {
+                                println!(
+                                    "{expression}: {} = {}",
+                                    evaluated.type_name, evaluated.value
+                                )
+                            }
+                            Err(error) => println!("Failed to evaluate '{expression}': {error}"),
+                        }
+                    }
+                    DebugState::Running => println!("Core must be halted for this command."),
+                }
+
+                Ok(CliState::Continue)
+            },
+        });
+
         cli.add_command(Command {
             name: "up",
             help_text: "Move up a frame",
@@ -1030,3 +1109,31 @@ struct Command {
 
     pub function: fn(&mut CliData, args: &[&str]) -> Result<CliState, CliError>,
 }
+
+/// Parse a path to a binary, optionally followed by `@` and the offset at which the binary is loaded.
+fn parse_additional_exe(input: &str) -> anyhow::Result<(PathBuf, u64)> {
+    match input.rsplit_once('@') {
+        Some((path, load_offset)) => Ok((PathBuf::from(path), parse_u64(load_offset.trim())?)),
+        None => Ok((PathBuf::from(input), 0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::parse_additional_exe;
+
+    #[test]
+    fn parse_additional_exes() {
+        assert_eq!(
+            parse_additional_exe("boot.elf").unwrap(),
+            (PathBuf::from("boot.elf"), 0)
+        );
+        assert_eq!(
+            parse_additional_exe("app.elf@0x10000").unwrap(),
+            (PathBuf::from("app.elf"), 0x10000)
+        );
+        assert!(parse_additional_exe("app.elf@xyz").is_err());
+    }
+}
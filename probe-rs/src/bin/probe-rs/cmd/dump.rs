@@ -0,0 +1,164 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use indicatif::{ProgressBar, ProgressStyle};
+use probe_rs::flashing::{DumpFormat, FlashReader};
+use probe_rs::Lister;
+use probe_rs_target::MemoryRegion;
+
+use crate::util::common_options::ProbeOptions;
+use crate::util::parse_u64;
+use crate::CoreOptions;
+
+#[derive(clap::Parser)]
+pub struct Cmd {
+    #[clap(flatten)]
+    shared: CoreOptions,
+
+    #[clap(flatten)]
+    probe_options: ProbeOptions,
+
+    /// Name of a memory region of the target to read. Can be given multiple times.
+    #[clap(long = "region")]
+    regions: Vec<String>,
+
+    /// An address range to read, e.g. `0x08000000..0x08010000`. Can be given multiple times.
+    #[clap(long = "range", value_parser = parse_range)]
+    ranges: Vec<Range<u64>>,
+
+    /// The format of the output file: `bin`, `hex` or `elf`.
+    ///
+    /// If not given, the format is chosen based on the file extension, defaulting to `bin`.
+    #[clap(long, value_parser = DumpFormat::from_str)]
+    format: Option<DumpFormat>,
+
+    /// The path of the output file.
+    path: PathBuf,
+}
+
+impl Cmd {
+    pub fn run(self, lister: &Lister) -> anyhow::Result<()> {
+        let format = self.format.unwrap_or_else(|| {
+            match self
+                .path
+                .extension()
+                .and_then(|extension| extension.to_str())
+            {
+                Some("hex" | "ihex") => DumpFormat::Hex,
+                Some("elf") => DumpFormat::Elf,
+                _ => DumpFormat::Bin,
+            }
+        });
+
+        let (mut session, _probe_options) = self.probe_options.simple_attach(lister)?;
+
+        let target = session.target();
+        let architecture = target.architecture();
+        let core_name = &target
+            .cores
+            .get(self.shared.core)
+            .with_context(|| format!("The target has no core {}", self.shared.core))?
+            .name;
+
+        let mut reader = FlashReader::new();
+
+        for name in &self.regions {
+            let Some(region) = target.memory_map.iter().find(|region| {
+                region_name(region) == Some(name) && region.cores().contains(core_name)
+            }) else {
+                let available = target
+                    .memory_map
+                    .iter()
+                    .filter_map(region_name)
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                bail!("The target has no memory region '{name}'. Available regions: {available}");
+            };
+            reader.add_region(region);
+        }
+
+        for range in &self.ranges {
+            reader.add_range(range.clone());
+        }
+
+        if self.regions.is_empty() && self.ranges.is_empty() {
+            for region in &target.memory_map {
+                if matches!(region, MemoryRegion::Nvm(_)) && region.cores().contains(core_name) {
+                    reader.add_region(region);
+                }
+            }
+        }
+
+        let progress = ProgressBar::new(0);
+        progress.set_style(
+            ProgressStyle::default_bar()
+                .progress_chars("--")
+                .template("{msg:.green.bold} [{elapsed_precise}] [{wide_bar}] {bytes:>8}/{total_bytes:>8} @ {bytes_per_sec:>10} (eta {eta:3})")
+                .expect("Error in progress bar creation. This is a bug, please report it."),
+        );
+        progress.set_message("Reading");
+
+        let mut core = session.core(self.shared.core)?;
+        let dump = reader.read(&mut core, |done, total| {
+            progress.set_length(total);
+            progress.set_position(done);
+        })?;
+        progress.finish();
+
+        let mut file = BufWriter::new(
+            File::create(&self.path)
+                .with_context(|| format!("Failed to create {}", self.path.display()))?,
+        );
+        dump.write(&mut file, format, architecture)?;
+
+        for segment in &dump.segments {
+            println!(
+                "{:#010x}..{:#010x} {}",
+                segment.address,
+                segment.range().end,
+                segment.name.as_deref().unwrap_or("")
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn region_name(region: &MemoryRegion) -> Option<&String> {
+    match region {
+        MemoryRegion::Ram(region) => region.name.as_ref(),
+        MemoryRegion::Nvm(region) => region.name.as_ref(),
+        MemoryRegion::Generic(region) => region.name.as_ref(),
+    }
+}
+
+fn parse_range(input: &str) -> anyhow::Result<Range<u64>> {
+    let Some((start, end)) = input.split_once("..") else {
+        bail!("Expected a range in the form 'start..end'");
+    };
+
+    let range = parse_u64(start.trim())?..parse_u64(end.trim())?;
+    if range.is_empty() {
+        bail!("The range {input} is empty");
+    }
+
+    Ok(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_range;
+
+    #[test]
+    fn parse_ranges() {
+        assert_eq!(parse_range("0x100..0x200").unwrap(), 0x100..0x200);
+        assert_eq!(parse_range("16..32").unwrap(), 16..32);
+        assert!(parse_range("0x100").is_err());
+        assert!(parse_range("0x200..0x100").is_err());
+    }
+}
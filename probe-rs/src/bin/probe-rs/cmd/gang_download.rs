@@ -0,0 +1,128 @@
+use std::time::Instant;
+
+use anyhow::bail;
+use probe_rs::flashing::{gang_download, DownloadOptions, GangOptions};
+use probe_rs::{DebugProbeSelector, Lister, Permissions};
+
+use crate::util::common_options::{ConnectOptions, ProbeOptions};
+use crate::util::flash::build_loader;
+use crate::FormatOptions;
+
+#[derive(clap::Parser)]
+pub struct Cmd {
+    #[clap(flatten)]
+    connect: ConnectOptions,
+
+    /// A probe connected to a target to program, as 'VID:PID:Serial'. Has to be given once per target.
+    #[arg(long = "probe", required = true, help_heading = "PROBE CONFIGURATION")]
+    probes: Vec<DebugProbeSelector>,
+
+    /// The path to the file to be downloaded to the flash
+    path: String,
+
+    /// Whether to erase the entire chips before downloading
+    #[arg(long)]
+    chip_erase: bool,
+    /// Use this flag to disable double-buffering when downloading flash data.
+    #[arg(long)]
+    disable_double_buffering: bool,
+    /// Enable this flag to restore all bytes erased in the sector erase but not overwritten by any page.
+    #[arg(long)]
+    restore_unwritten: bool,
+    /// Before flashing, compare the contents of each sector with the data to be programmed,
+    /// and skip erasing and programming the sectors which are already up to date.
+    #[arg(long)]
+    preverify: bool,
+    /// After flashing, read back all the flashed data to verify it has been written correctly.
+    #[arg(long)]
+    verify: bool,
+
+    #[clap(flatten)]
+    format_options: FormatOptions,
+}
+
+impl Cmd {
+    pub fn run(self, lister: &Lister) -> anyhow::Result<()> {
+        // The image is loaded with a session on the first target, as some formats
+        // need to inspect the target.
+        let probe_options = ProbeOptions {
+            connect: self.connect.clone(),
+            probe_selector: self.probes.first().cloned(),
+            dry_run: false,
+            allow_dangerous_config: false,
+        };
+        let (mut session, probe_options) = probe_options.simple_attach(lister)?;
+        let loader = build_loader(&mut session, &self.path, self.format_options)?;
+        let target = probe_options.get_target_selector()?;
+        drop(session);
+
+        let mut permissions = Permissions::new();
+        if self.connect.allow_erase_all {
+            permissions = permissions.allow_erase_all();
+        }
+        let options = GangOptions {
+            protocol: self.connect.protocol,
+            speed: self.connect.speed,
+            connect_under_reset: self.connect.connect_under_reset,
+            permissions,
+        };
+
+        let start = Instant::now();
+        let results = gang_download(lister, &self.probes, target, &loader, &options, |_| {
+            let mut options = DownloadOptions::new();
+            options.do_chip_erase = self.chip_erase;
+            options.disable_double_buffering = self.disable_double_buffering;
+            options.keep_unwritten_bytes = self.restore_unwritten;
+            options.preverify = self.preverify;
+            options.verify = self.verify;
+            options
+        });
+
+        println!(
+            "{:<12} {:<24} {:>10}  Result",
+            "Probe", "Serial number", "Duration"
+        );
+        for result in &results {
+            let status = match &result.result {
+                Ok(()) => "Ok".to_string(),
+                Err(error) => error_chain(error),
+            };
+            println!(
+                "{:04x}:{:04x}    {:<24} {:>9.2}s  {status}",
+                result.probe.vendor_id,
+                result.probe.product_id,
+                result.probe.serial_number.as_deref().unwrap_or("-"),
+                result.duration.as_secs_f32()
+            );
+        }
+
+        let failed = results
+            .iter()
+            .filter(|result| result.result.is_err())
+            .count();
+        println!(
+            "Programmed {} of {} targets in {:.2}s",
+            results.len() - failed,
+            results.len(),
+            start.elapsed().as_secs_f32()
+        );
+
+        if failed > 0 {
+            bail!("Programming {failed} of {} targets failed", results.len());
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats an error with all of its sources on a single line.
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        message.push_str(": ");
+        message.push_str(&error.to_string());
+        source = error.source();
+    }
+    message
+}
@@ -0,0 +1,59 @@
+use std::fs::File;
+use std::path::PathBuf;
+
+use anyhow::Context;
+
+use crate::util::flash::{build_offline_loader, write_layout_diffs};
+use crate::FormatOptions;
+
+#[derive(clap::Parser)]
+pub struct Cmd {
+    /// The chip the images are built for
+    #[arg(long, env = "PROBE_RS_CHIP")]
+    chip: String,
+    #[arg(value_name = "chip description file path", long)]
+    chip_description_path: Option<PathBuf>,
+
+    /// The path to the old image
+    old: PathBuf,
+    /// The path to the new image
+    new: PathBuf,
+
+    /// The file to write the map to, as an HTML page if the file name ends with `.html`, and as
+    /// an SVG otherwise. If the images span several flash regions, one file is written per region.
+    #[arg(value_name = "filename", long)]
+    output: PathBuf,
+
+    #[clap(flatten)]
+    format_options: FormatOptions,
+}
+
+impl Cmd {
+    pub fn run(self) -> anyhow::Result<()> {
+        if let Some(path) = &self.chip_description_path {
+            let file =
+                File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+            probe_rs::config::add_target_from_yaml(file)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+        }
+        let target = probe_rs::config::get_target_by_name(&self.chip)?;
+
+        let old = build_offline_loader(&target, &self.old, self.format_options.clone())?;
+        let new = build_offline_loader(&target, &self.new, self.format_options)?;
+        let pairs = old.layout_pairs(&new, &target)?;
+
+        write_layout_diffs(&pairs, new.sections(), &self.output, |diff, path| {
+            println!(
+                "{} bytes changed, see {}",
+                diff.changed_bytes(),
+                path.display()
+            );
+            for section in diff.sections() {
+                println!(
+                    "    {:<24} {:#010x} {:>8} bytes, {:>8} changed",
+                    section.name, section.address, section.size, section.changed_bytes
+                );
+            }
+        })
+    }
+}
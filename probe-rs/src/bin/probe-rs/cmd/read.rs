@@ -3,20 +3,7 @@ use probe_rs::{Lister, MemoryInterface};
 use crate::util::common_options::{ProbeOptions, ReadWriteBitWidth, ReadWriteOptions};
 use crate::CoreOptions;
 
-/// Read from target memory address
-///
-/// e.g. probe-rs read b32 0x400E1490 2
-///      Reads 2 32-bit words from address 0x400E1490
-///
-/// Output is a space separated list of hex values padded to the read word width.
-/// e.g. 2 words
-///     00 00 (8-bit)
-///     00000000 00000000 (32-bit)
-///     0000000000000000 0000000000000000 (64-bit)
-///
-/// NOTE: Only supports RAM addresses
 #[derive(clap::Parser)]
-#[clap(verbatim_doc_comment)]
 pub struct Cmd {
     #[clap(flatten)]
     shared: CoreOptions,
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use time::UtcOffset;
+
+use crate::util::rtt::capture::{CaptureContent, CaptureReader};
+use crate::util::rtt::{DataFormat, DefmtState, LogOutput, RttActiveChannel, RttChannelConfig};
+
+#[derive(clap::Parser)]
+pub struct Cmd {
+    #[clap(subcommand)]
+    subcommand: Subcommand,
+}
+
+#[derive(clap::Subcommand)]
+/// Work with RTT data recorded from a target
+enum Subcommand {
+    /// Decode an RTT capture, recorded with `--rtt-capture`, and print the data of its channels
+    #[clap(name = "replay")]
+    Replay(ReplayOptions),
+}
+
+#[derive(clap::Parser)]
+struct ReplayOptions {
+    /// The capture file to decode
+    capture: PathBuf,
+    /// The ELF file of the program which produced the capture, which is required to decode
+    /// defmt channels
+    #[clap(long)]
+    elf: Option<PathBuf>,
+    /// Only print the data of the up channel with this number
+    #[clap(long)]
+    channel: Option<usize>,
+    /// Suppress filename and line number information from the defmt log
+    #[clap(long)]
+    no_location: bool,
+    /// Prefix each line of string channels with the host time at which it was captured
+    #[clap(long)]
+    show_timestamps: bool,
+    /// The format of defmt log messages, e.g. `{t} {L} {s}`, instead of the default format of
+    /// `probe-rs run`
+    #[clap(long)]
+    log_format: Option<String>,
+    /// How the data is printed. With `json`, every defmt frame and every line of a string channel
+    /// is printed as a JSON object on its own line.
+    #[clap(long, value_enum, default_value_t)]
+    log_output: LogOutput,
+}
+
+impl Cmd {
+    pub fn run(self, timestamp_offset: UtcOffset) -> anyhow::Result<()> {
+        match self.subcommand {
+            Subcommand::Replay(options) => replay(options, timestamp_offset),
+        }
+    }
+}
+
+/// Decode the capture file with the same formatting as `probe-rs run`, and print it to stdout.
+fn replay(options: ReplayOptions, timestamp_offset: UtcOffset) -> anyhow::Result<()> {
+    let file = File::open(&options.capture)
+        .with_context(|| format!("Failed to open {}", options.capture.display()))?;
+    let records = CaptureReader::new(BufReader::new(file))
+        .with_context(|| format!("Failed to read {}", options.capture.display()))?;
+
+    let mut channels = HashMap::new();
+    // The defmt table is only loaded once a defmt channel is found.
+    let mut defmt_state = None;
+    let mut stdout = std::io::stdout().lock();
+
+    for record in records {
+        let record =
+            record.with_context(|| format!("Failed to read {}", options.capture.display()))?;
+        if options
+            .channel
+            .is_some_and(|channel| channel != record.channel)
+        {
+            continue;
+        }
+
+        match record.content {
+            CaptureContent::Channel { name, data_format } => {
+                if data_format == DataFormat::Defmt && defmt_state.is_none() {
+                    let Some(elf) = &options.elf else {
+                        anyhow::bail!(
+                            "Channel {} ({name}) contains defmt data, decoding it requires the ELF file of the program given with `--elf`",
+                            record.channel
+                        );
+                    };
+                    defmt_state = Some(load_defmt_state(elf, &options)?);
+                }
+
+                let channel_config = RttChannelConfig {
+                    channel_number: Some(record.channel),
+                    channel_name: Some(name),
+                    data_format,
+                    show_timestamps: options.show_timestamps,
+                    show_location: !options.no_location,
+                    ..Default::default()
+                };
+                let mut channel =
+                    RttActiveChannel::new(None, None, Some(channel_config), timestamp_offset);
+                channel.log_output = options.log_output;
+                channels.insert(record.channel, channel);
+            }
+            CaptureContent::Data(data) => {
+                // Data without a channel description is shown as a string.
+                let channel = channels.entry(record.channel).or_insert_with(|| {
+                    let channel_config = RttChannelConfig {
+                        channel_number: Some(record.channel),
+                        ..Default::default()
+                    };
+                    let mut channel =
+                        RttActiveChannel::new(None, None, Some(channel_config), timestamp_offset);
+                    channel.log_output = options.log_output;
+                    channel
+                });
+                let formatted_data = channel.format_data(
+                    &data,
+                    record.timestamp,
+                    defmt_state.as_ref().and_then(Option::as_ref),
+                )?;
+                stdout.write_all(formatted_data.as_bytes())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn load_defmt_state(
+    elf_path: &Path,
+    options: &ReplayOptions,
+) -> anyhow::Result<Option<DefmtState>> {
+    let elf = std::fs::read(elf_path)
+        .with_context(|| format!("Failed to read {}", elf_path.display()))?;
+
+    DefmtState::try_from_bytes(&elf, !options.no_location, options.log_format.as_deref())
+        .with_context(|| format!("Failed to load the defmt table from {}", elf_path.display()))
+}
@@ -1,12 +1,13 @@
 use std::io::Write;
 use std::ops::Range;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Result};
 use probe_rs::debug::{DebugInfo, DebugRegisters};
+use probe_rs::semihosting::{DefaultSemihostingHandler, Semihosting};
 use probe_rs::{
     exception_handler_for_core, BreakpointCause, Core, CoreInterface, Error, HaltReason, Lister,
     SemihostingCommand, VectorCatchCondition,
@@ -17,10 +18,12 @@ use time::UtcOffset;
 
 use crate::util::common_options::{BinaryDownloadOptions, ProbeOptions};
 use crate::util::flash::{build_loader, run_flash_download};
-use crate::util::rtt::{self, RttConfig};
+use crate::util::parse_u32;
+use crate::util::rtt::{self, LogOutput, RttChannelConfig, RttConfig};
 use crate::FormatOptions;
 
 const RTT_RETRIES: usize = 10;
+const RTT_STATS_INTERVAL: Duration = Duration::from_secs(1);
 
 #[derive(clap::Parser)]
 pub struct Cmd {
@@ -41,6 +44,24 @@ pub struct Cmd {
     #[clap(long)]
     pub(crate) chip_erase: bool,
 
+    /// Load the program into RAM and start it from there, without touching the flash.
+    ///
+    /// All loadable segments of the program have to be located in RAM.
+    #[clap(
+        long,
+        conflicts_with_all = [
+            "chip_erase",
+            "verify",
+            "preverify",
+            "flash_layout_output_path",
+            "layout_diff_output_path",
+            "journal",
+            "journal_file",
+            "resume",
+        ]
+    )]
+    pub(crate) load_to_ram: bool,
+
     /// Suppress filename and line number information from the rtt log
     #[clap(long)]
     pub(crate) no_location: bool,
@@ -51,6 +72,11 @@ pub struct Cmd {
     #[clap(long)]
     pub(crate) log_format: Option<String>,
 
+    /// How the RTT data is printed. With `json`, every defmt frame and every line of a string
+    /// channel is printed as a JSON object on its own line.
+    #[clap(long, value_enum, default_value_t)]
+    pub(crate) log_output: LogOutput,
+
     /// Enable reset vector catch if its supported on the target.
     #[arg(long)]
     pub catch_reset: bool,
@@ -58,9 +84,48 @@ pub struct Cmd {
     #[arg(long)]
     pub catch_hardfault: bool,
 
-    /// Scan the memory to find the RTT control block
-    #[clap(long)]
+    /// Deprecated and ignored: if the ELF file has no `_SEGGER_RTT` symbol, all RAM is scanned for
+    /// the RTT control block by default.
+    #[clap(long, hide = true)]
     pub(crate) rtt_scan_memory: bool,
+
+    /// Limit the scan for the RTT control block to the RTT scan regions of the target, if it defines any.
+    ///
+    /// If the ELF file has no `_SEGGER_RTT` symbol, all RAM is scanned by default.
+    #[clap(long)]
+    pub(crate) rtt_scan_target_regions: bool,
+
+    /// The address of the RTT control block, to select one if several are found in memory.
+    #[clap(long, value_parser = parse_u32)]
+    pub(crate) rtt_address: Option<u32>,
+
+    /// Serve the raw data of an RTT channel on a TCP or Unix socket, in the form 'CHANNEL=ADDRESS'.
+    ///
+    /// The channel is given by its number or name, and the address as 'tcp:HOST:PORT' or 'unix:PATH',
+    /// e.g. '0=tcp:127.0.0.1:19021'. Data which clients send is written to the down channel with the
+    /// same number. Can be used multiple times.
+    #[clap(long = "rtt-socket", value_parser = rtt::parse_rtt_socket)]
+    pub(crate) rtt_sockets: Vec<RttChannelConfig>,
+
+    /// Record the raw data of all RTT up channels, with the host time at which it was read, to a capture file.
+    ///
+    /// The capture can be decoded later with `probe-rs rtt replay`.
+    #[clap(long)]
+    pub(crate) rtt_capture: Option<PathBuf>,
+
+    /// Print the throughput, buffer fill level and estimated dropped bytes of each RTT channel every second.
+    #[clap(long = "stats")]
+    pub(crate) rtt_stats: bool,
+
+    /// The directory the target can access files in through semihosting.
+    ///
+    /// Without it, semihosting file operations fail, but console output still works.
+    #[clap(long)]
+    pub(crate) semihosting_root: Option<PathBuf>,
+
+    /// The arguments passed to the target through semihosting
+    #[clap(last = true)]
+    pub(crate) semihosting_args: Vec<String>,
 }
 
 impl Cmd {
@@ -73,7 +138,10 @@ impl Cmd {
         let (mut session, probe_options) = self.probe_options.simple_attach(lister)?;
         let path = Path::new(&self.path);
 
-        if run_download {
+        if run_download && self.load_to_ram {
+            let loader = build_loader(&mut session, path, self.format_options)?;
+            loader.commit_to_ram(&mut session, 0)?;
+        } else if run_download {
             let loader = build_loader(&mut session, path, self.format_options)?;
             run_flash_download(
                 &mut session,
@@ -90,7 +158,10 @@ impl Cmd {
         }
 
         let memory_map = session.target().memory_map.clone();
-        let rtt_scan_regions = match self.rtt_scan_memory {
+        if self.rtt_scan_memory {
+            tracing::warn!("`--rtt-scan-memory` is deprecated and has no effect, all RAM is scanned for the RTT control block by default.");
+        }
+        let rtt_scan_regions = match self.rtt_scan_target_regions {
             true => session.target().rtt_scan_regions.clone(),
             false => Vec::new(),
         };
@@ -113,15 +184,55 @@ impl Cmd {
         }
         core.run()?;
 
+        let mut semihosting_handler = DefaultSemihostingHandler::new().with_command_line(
+            std::iter::once(self.path.as_str())
+                .chain(self.semihosting_args.iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+        if let Some(root) = self.semihosting_root {
+            semihosting_handler = semihosting_handler.with_root(root);
+        }
+        // With JSON output, stdout only contains the JSON records of the RTT channels.
+        if self.log_output == LogOutput::Json {
+            semihosting_handler = semihosting_handler.with_console(std::io::stderr());
+        }
+
+        let mut rtt_config = rtt::RttConfig {
+            capture_path: self.rtt_capture,
+            log_output: self.log_output,
+            control_block_address: self.rtt_address,
+            ..Default::default()
+        };
+        rtt_config.channels.push(rtt::RttChannelConfig {
+            channel_number: Some(0),
+            show_location: !self.no_location,
+            ..Default::default()
+        });
+        for socket_config in self.rtt_sockets {
+            match rtt_config.channels.iter_mut().find(|channel_config| {
+                socket_config.channel_number.is_some()
+                    && channel_config.channel_number == socket_config.channel_number
+            }) {
+                Some(channel_config) => channel_config.socket = socket_config.socket,
+                None => rtt_config.channels.push(rtt::RttChannelConfig {
+                    show_location: !self.no_location,
+                    ..socket_config
+                }),
+            }
+        }
+
         run_loop(
             &mut core,
+            &mut Semihosting::new(semihosting_handler),
             &memory_map,
             &rtt_scan_regions,
             path,
             timestamp_offset,
             self.always_print_stacktrace,
-            self.no_location,
+            rtt_config,
             self.log_format.as_deref(),
+            self.rtt_stats,
         )?;
 
         Ok(())
@@ -135,21 +246,17 @@ impl Cmd {
 #[allow(clippy::too_many_arguments)]
 fn run_loop(
     core: &mut Core<'_>,
+    semihosting: &mut Semihosting<DefaultSemihostingHandler>,
     memory_map: &[MemoryRegion],
     rtt_scan_regions: &[Range<u64>],
     path: &Path,
     timestamp_offset: UtcOffset,
     always_print_stacktrace: bool,
-    no_location: bool,
+    rtt_config: RttConfig,
     log_format: Option<&str>,
+    rtt_stats: bool,
 ) -> Result<(), anyhow::Error> {
-    let mut rtt_config = rtt::RttConfig::default();
-    rtt_config.channels.push(rtt::RttChannelConfig {
-        channel_number: Some(0),
-        show_location: !no_location,
-        ..Default::default()
-    });
-
+    let json_output = rtt_config.log_output == LogOutput::Json;
     let mut rtta = attach_to_rtt(
         core,
         memory_map,
@@ -165,7 +272,10 @@ fn run_loop(
 
     let mut stdout = std::io::stdout();
     let mut halt_reason = None;
+    let mut last_stats = Instant::now();
     while !exit.load(Ordering::Relaxed) && halt_reason.is_none() {
+        let mut had_semihosting = false;
+
         // check for halt first, poll rtt after.
         // this is important so we do one last poll after halt, so we flush all messages
         // the core printed before halting, such as a panic message.
@@ -176,6 +286,12 @@ fn run_loop(
                 tracing::error!("Target wanted to run semihosting operation {:#x}, but probe-rs does not support this operation yet. Continuing...", operation);
                 core.run()?;
             }
+            probe_rs::CoreStatus::Halted(HaltReason::Breakpoint(BreakpointCause::Semihosting(
+                command,
+            ))) if semihosting.handle(core, command)? => {
+                core.run()?;
+                had_semihosting = true;
+            }
             probe_rs::CoreStatus::Halted(r) => halt_reason = Some(r),
             probe_rs::CoreStatus::Running
             | probe_rs::CoreStatus::LockedUp
@@ -187,12 +303,21 @@ fn run_loop(
 
         let had_rtt_data = poll_rtt(&mut rtta, core, &mut stdout)?;
 
+        if rtt_stats && last_stats.elapsed() >= RTT_STATS_INTERVAL {
+            if let Some(rtta) = &rtta {
+                print_rtt_stats(rtta);
+            }
+            last_stats = Instant::now();
+        }
+
         // Poll RTT with a frequency of 10 Hz if we do not receive any new data.
         // Once we receive new data, we bump the frequency to 1kHz.
         //
         // If the polling frequency is too high, the USB connection to the probe
         // can become unstable. Hence we only pull as little as necessary.
-        if had_rtt_data {
+        // Semihosting operations are usually issued in quick succession, so
+        // they are polled for at the higher frequency as well.
+        if had_rtt_data || had_semihosting {
             std::thread::sleep(Duration::from_millis(1));
         } else {
             std::thread::sleep(Duration::from_millis(100));
@@ -219,7 +344,12 @@ fn run_loop(
     };
 
     if always_print_stacktrace || result.is_err() {
-        print_stacktrace(core, path)?;
+        // Keep stdout free of anything but the JSON records.
+        if json_output {
+            print_stacktrace(core, path, &mut std::io::stderr())?;
+        } else {
+            print_stacktrace(core, path, &mut std::io::stdout())?;
+        }
     }
 
     signal_hook::low_level::unregister(sig_id);
@@ -228,8 +358,12 @@ fn run_loop(
     result
 }
 
-/// Prints the stacktrace of the current execution state.
-fn print_stacktrace(core: &mut impl CoreInterface, path: &Path) -> Result<(), anyhow::Error> {
+/// Prints the stacktrace of the current execution state to `out`.
+fn print_stacktrace(
+    core: &mut impl CoreInterface,
+    path: &Path,
+    out: &mut dyn Write,
+) -> Result<(), anyhow::Error> {
     let Some(debug_info) = DebugInfo::from_file(path).ok() else {
         log::error!("No debug info found.");
         return Ok(());
@@ -246,48 +380,48 @@ fn print_stacktrace(core: &mut impl CoreInterface, path: &Path) -> Result<(), an
         )
         .unwrap();
     for (i, frame) in stack_frames.iter().enumerate() {
-        print!("Frame {}: {} @ {}", i, frame.function_name, frame.pc);
+        write!(out, "Frame {}: {} @ {}", i, frame.function_name, frame.pc)?;
 
         if frame.is_inlined {
-            print!(" inline");
+            write!(out, " inline")?;
         }
-        println!();
+        writeln!(out)?;
 
         if let Some(location) = &frame.source_location {
             if location.directory.is_some() || location.file.is_some() {
-                print!("       ");
+                write!(out, "       ")?;
 
                 if let Some(dir) = &location.directory {
-                    print!("{}", dir.to_path().display());
+                    write!(out, "{}", dir.to_path().display())?;
                 }
 
                 if let Some(file) = &location.file {
-                    print!("/{file}");
+                    write!(out, "/{file}")?;
 
                     if let Some(line) = location.line {
-                        print!(":{line}");
+                        write!(out, ":{line}")?;
 
                         if let Some(col) = location.column {
                             match col {
                                 probe_rs::debug::ColumnType::LeftEdge => {
-                                    print!(":1")
+                                    write!(out, ":1")?;
                                 }
                                 probe_rs::debug::ColumnType::Column(c) => {
-                                    print!(":{c}")
+                                    write!(out, ":{c}")?;
                                 }
                             }
                         }
                     }
                 }
 
-                println!();
+                writeln!(out)?;
             }
         }
     }
     Ok(())
 }
 
-/// Poll RTT and print the received buffer.
+/// Poll RTT and print the received buffer, and forward the data of the RTT sockets.
 fn poll_rtt(
     rtta: &mut Option<rtt::RttActiveTarget>,
     core: &mut Core<'_>,
@@ -301,10 +435,33 @@ fn poll_rtt(
             }
             stdout.write_all(data.as_bytes())?;
         }
+        had_data |= rtta.poll_sockets(core)?;
     };
     Ok(had_data)
 }
 
+/// Print the statistics of all RTT channels to stderr, so they do not mix with the RTT output.
+fn print_rtt_stats(rtta: &rtt::RttActiveTarget) {
+    for channel in &rtta.active_channels {
+        if let Some(up_channel) = &channel.up_channel {
+            eprintln!(
+                "RTT up channel {} ({}): {}",
+                up_channel.number(),
+                channel.channel_name,
+                up_channel.stats()
+            );
+        }
+        if let Some(down_channel) = &channel.down_channel {
+            eprintln!(
+                "RTT down channel {} ({}): {}",
+                down_channel.number(),
+                channel.channel_name,
+                down_channel.stats()
+            );
+        }
+    }
+}
+
 /// Attach to the RTT buffers.
 fn attach_to_rtt(
     core: &mut Core<'_>,
@@ -315,6 +472,7 @@ fn attach_to_rtt(
     timestamp_offset: UtcOffset,
     log_format: Option<&str>,
 ) -> Option<rtt::RttActiveTarget> {
+    let mut last_error = None;
     for _ in 0..RTT_RETRIES {
         match rtt::attach_to_rtt(
             core,
@@ -328,10 +486,14 @@ fn attach_to_rtt(
             Ok(target_rtt) => return Some(target_rtt),
             Err(error) => {
                 log::debug!("{:?} RTT attach error", error);
+                last_error = Some(error);
             }
         }
         std::thread::sleep(std::time::Duration::from_millis(100));
     }
-    log::error!("Failed to attach to RTT continuing...");
+    match last_error {
+        Some(error) => log::error!("Failed to attach to RTT continuing... ({error})"),
+        None => log::error!("Failed to attach to RTT continuing..."),
+    }
     None
 }
@@ -0,0 +1,84 @@
+use anyhow::bail;
+use probe_rs::Lister;
+use serde::Serialize;
+
+use crate::util::common_options::ProbeOptions;
+use crate::util::flash::build_loader;
+use crate::FormatOptions;
+
+#[derive(clap::Parser)]
+pub struct Cmd {
+    #[clap(flatten)]
+    probe_options: ProbeOptions,
+
+    /// The path to the file to be compared with the target memory
+    path: String,
+
+    /// Print the result as JSON
+    #[clap(long)]
+    json: bool,
+
+    #[clap(flatten)]
+    format_options: FormatOptions,
+}
+
+#[derive(Serialize)]
+struct VerifyReport<'a> {
+    path: &'a str,
+    matches: bool,
+    mismatches: Vec<Mismatch>,
+}
+
+#[derive(Serialize)]
+struct Mismatch {
+    start: u64,
+    end: u64,
+    size: u64,
+}
+
+impl Cmd {
+    pub fn run(self, lister: &Lister) -> anyhow::Result<()> {
+        let (mut session, _probe_options) = self.probe_options.simple_attach(lister)?;
+
+        let loader = build_loader(&mut session, &self.path, self.format_options)?;
+        let mismatches = loader.verify(&mut session)?;
+
+        if self.json {
+            let report = VerifyReport {
+                path: &self.path,
+                matches: mismatches.is_empty(),
+                mismatches: mismatches
+                    .iter()
+                    .map(|range| Mismatch {
+                        start: range.start,
+                        end: range.end,
+                        size: range.end - range.start,
+                    })
+                    .collect(),
+            };
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else if mismatches.is_empty() {
+            println!("The target memory matches {}", self.path);
+        } else {
+            println!("The target memory differs from {} in:", self.path);
+            for range in &mismatches {
+                println!(
+                    "    {:#010x}..{:#010x} ({} bytes)",
+                    range.start,
+                    range.end,
+                    range.end - range.start
+                );
+            }
+        }
+
+        if !mismatches.is_empty() {
+            let size: u64 = mismatches.iter().map(|range| range.end - range.start).sum();
+            bail!(
+                "Verification failed: {size} bytes in {} ranges differ",
+                mismatches.len()
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -66,6 +66,41 @@ enum Subcommand {
     Debug(cmd::debug::Cmd),
     /// Download memory to attached target
     Download(cmd::download::Cmd),
+    /// Read memory regions from the target and store them in a file
+    ///
+    /// e.g. probe-rs dump --chip nRF52840_xxAA firmware.hex
+    ///      Reads all non-volatile memory of the target into an Intel HEX file.
+    ///
+    /// e.g. probe-rs dump --chip nRF52840_xxAA --range 0x20000000..0x20001000 ram.bin
+    ///      Reads the first 4 KiB of RAM into a binary file.
+    ///
+    /// If neither regions nor ranges are given, all non-volatile memory regions of the core are read.
+    #[clap(verbatim_doc_comment)]
+    Dump(cmd::dump::Cmd),
+    /// Compare a firmware image with the contents of the target memory
+    ///
+    /// Nothing is erased or programmed. All address ranges whose contents differ
+    /// from the image are reported, and the command fails if there is any.
+    Verify(cmd::verify::Cmd),
+    /// Flash one image to the targets of several probes at once
+    ///
+    /// The image is loaded once, after which all targets are programmed in parallel.
+    ///
+    /// e.g. probe-rs gang-download --chip nRF52840_xxAA --probe 1366:1015:000683 --probe 1366:1015:000684 firmware.elf
+    #[clap(verbatim_doc_comment)]
+    GangDownload(cmd::gang_download::Cmd),
+    /// Compare the flash layouts of two images, without connecting to a target
+    ///
+    /// Writes a map of the sectors which are unchanged, modified, added or erased when going from
+    /// the old to the new image, e.g. to spot linker script changes which cause a full reflash.
+    ///
+    /// e.g. probe-rs layout-diff --chip nRF52840_xxAA --output diff.html old.elf new.elf
+    #[clap(verbatim_doc_comment)]
+    LayoutDiff(cmd::layout_diff::Cmd),
+    /// Read and write device configuration areas like option bytes, UICR or eFuses
+    ///
+    /// So far, only the UICR of the nRF52 series is supported.
+    Config(cmd::config::Cmd),
     /// Erase all nonvolatile memory of attached target
     Erase(cmd::erase::Cmd),
     /// Flash and run an ELF program
@@ -80,13 +115,36 @@ enum Subcommand {
     /// Configure and monitor ITM trace packets from the target.
     #[clap(name = "itm")]
     Itm(cmd::itm::Cmd),
+    /// Inspect internal registry of supported chips
     Chip(cmd::chip::Cmd),
     /// Measure the throughput of the selected debug probe
     Benchmark(cmd::benchmark::Cmd),
     /// Profile on-target runtime performance of target ELF program
     Profile(cmd::profile::ProfileCmd),
+    /// Read from target memory address
+    ///
+    /// e.g. probe-rs read b32 0x400E1490 2
+    ///      Reads 2 32-bit words from address 0x400E1490
+    ///
+    /// Output is a space separated list of hex values padded to the read word width.
+    /// e.g. 2 words
+    ///     00 00 (8-bit)
+    ///     00000000 00000000 (32-bit)
+    ///     0000000000000000 0000000000000000 (64-bit)
+    ///
+    /// NOTE: Only supports RAM addresses
+    #[clap(verbatim_doc_comment)]
     Read(cmd::read::Cmd),
+    /// Write to target memory address
+    ///
+    /// e.g. probe-rs write b32 0x400E1490 0xDEADBEEF 0xCAFEF00D
+    ///      Writes 0xDEADBEEF to address 0x400E1490 and 0xCAFEF00D to address 0x400E1494
+    ///
+    /// NOTE: Only supports RAM addresses
+    #[clap(verbatim_doc_comment)]
     Write(cmd::write::Cmd),
+    /// Work with RTT data recorded from a target
+    Rtt(cmd::rtt::Cmd),
 }
 
 /// Shared options for core selection, shared between commands
@@ -167,6 +225,9 @@ impl FormatOptions {
                 })
             }
             Format::Uf2 => Format::Uf2,
+            Format::Srec => Format::Srec,
+            Format::TiTxt => Format::TiTxt,
+            Format::Manifest => Format::Manifest,
         })
     }
 }
@@ -336,6 +397,11 @@ fn main() -> Result<()> {
         Subcommand::Reset(cmd) => cmd.run(&lister),
         Subcommand::Debug(cmd) => cmd.run(&lister),
         Subcommand::Download(cmd) => cmd.run(&lister),
+        Subcommand::Dump(cmd) => cmd.run(&lister),
+        Subcommand::Verify(cmd) => cmd.run(&lister),
+        Subcommand::GangDownload(cmd) => cmd.run(&lister),
+        Subcommand::LayoutDiff(cmd) => cmd.run(),
+        Subcommand::Config(cmd) => cmd.run(&lister),
         Subcommand::Run(cmd) => cmd.run(&lister, true, utc_offset),
         Subcommand::Attach(cmd) => cmd.run(&lister, utc_offset),
         Subcommand::Erase(cmd) => cmd.run(&lister),
@@ -346,6 +412,7 @@ fn main() -> Result<()> {
         Subcommand::Profile(cmd) => cmd.run(&lister),
         Subcommand::Read(cmd) => cmd.run(&lister),
         Subcommand::Write(cmd) => cmd.run(&lister),
+        Subcommand::Rtt(cmd) => cmd.run(utc_offset),
     };
 
     if let Some(ref log_path) = log_path {
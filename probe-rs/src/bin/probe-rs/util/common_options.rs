@@ -33,7 +33,7 @@
 //! ```
 use super::ArtifactError;
 
-use std::{fs::File, path::Path, path::PathBuf};
+use std::{fs::File, path::Path, path::PathBuf, sync::OnceLock};
 
 use crate::util::parse_u64;
 use clap;
@@ -92,9 +92,29 @@ pub struct BinaryDownloadOptions {
     /// Requests the flash builder to output the layout into the given file in SVG format.
     #[arg(value_name = "filename", long = "flash-layout")]
     pub flash_layout_output_path: Option<String>,
+    /// Before flashing, read the flash and write a map of the sectors which the download changes
+    /// into the given file, as an HTML page if the file name ends with `.html`, and as an SVG otherwise.
+    #[arg(value_name = "filename", long = "show-layout-diff")]
+    pub layout_diff_output_path: Option<PathBuf>,
+    /// Before flashing, compare the contents of each sector with the data to be programmed,
+    /// and skip erasing and programming the sectors which are already up to date.
+    #[arg(long)]
+    pub preverify: bool,
     /// After flashing, read back all the flashed data to verify it has been written correctly.
     #[arg(long)]
     pub verify: bool,
+    /// Record the erased and programmed sectors in a journal, so an interrupted download can be
+    /// resumed with `--resume`. The journal is kept in the temporary directory, separately for
+    /// every image path, chip and probe.
+    #[arg(long)]
+    pub journal: bool,
+    /// Record the journal in the given file instead of the temporary directory. Implies `--journal`.
+    #[arg(value_name = "filename", long)]
+    pub journal_file: Option<PathBuf>,
+    /// Resume an interrupted download of the same image from its journal. The sectors recorded as
+    /// programmed in the journal are verified and skipped. Implies `--journal`.
+    #[arg(long)]
+    pub resume: bool,
 }
 
 /// Supported bit-widths for read/write commands (not every device may support each width).
@@ -120,9 +140,11 @@ pub struct ReadWriteOptions {
     pub address: u64,
 }
 
-/// Common options and logic when interfacing with a [Probe].
-#[derive(clap::Parser, Debug)]
-pub struct ProbeOptions {
+/// The chip and how to connect to it, independent of the probe used.
+///
+/// Part of [ProbeOptions], and used on its own by commands connecting through several probes.
+#[derive(clap::Parser, Debug, Clone)]
+pub struct ConnectOptions {
     #[arg(long, env = "PROBE_RS_CHIP")]
     pub chip: Option<String>,
     #[arg(value_name = "chip description file path", long)]
@@ -131,13 +153,6 @@ pub struct ProbeOptions {
     /// Protocol used to connect to chip. Possible options: [swd, jtag]
     #[arg(long, help_heading = "PROBE CONFIGURATION")]
     pub protocol: Option<WireProtocol>,
-
-    /// Use this flag to select a specific probe in the list.
-    ///
-    /// Use '--probe VID:PID' or '--probe VID:PID:Serial' if you have more than one
-    /// probe with the same VID:PID.",
-    #[arg(long = "probe", help_heading = "PROBE CONFIGURATION")]
-    pub probe_selector: Option<DebugProbeSelector>,
     /// The protocol speed in kHz.
     #[arg(long, help_heading = "PROBE CONFIGURATION")]
     pub speed: Option<u32>,
@@ -145,14 +160,32 @@ pub struct ProbeOptions {
     /// the chip.
     #[arg(long)]
     pub connect_under_reset: bool,
-    #[arg(long)]
-    pub dry_run: bool,
     /// Use this flag to allow all memory, including security keys and 3rd party
     /// firmware, to be erased even when it has read-only protection.
     #[arg(long)]
     pub allow_erase_all: bool,
 }
 
+/// Common options and logic when interfacing with a [Probe].
+#[derive(clap::Parser, Debug)]
+pub struct ProbeOptions {
+    #[clap(flatten)]
+    pub connect: ConnectOptions,
+
+    /// Use this flag to select a specific probe in the list.
+    ///
+    /// Use '--probe VID:PID' or '--probe VID:PID:Serial' if you have more than one
+    /// probe with the same VID:PID.",
+    #[arg(long = "probe", help_heading = "PROBE CONFIGURATION")]
+    pub probe_selector: Option<DebugProbeSelector>,
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Use this flag to allow writing configuration fields which are marked as
+    /// dangerous, like read-out protection bits or one-time programmable fuses.
+    #[arg(long)]
+    pub allow_dangerous_config: bool,
+}
+
 impl ProbeOptions {
     pub fn load(self) -> Result<LoadedProbeOptions, OperationError> {
         LoadedProbeOptions::new(self)
@@ -176,13 +209,20 @@ impl ProbeOptions {
 
 /// Common options and logic when interfacing with a [Probe] which already did all pre operation preparation.
 #[derive(Debug)]
-pub struct LoadedProbeOptions(ProbeOptions);
+pub struct LoadedProbeOptions {
+    options: ProbeOptions,
+    /// The serial number of the probe which was selected because it was the only one connected.
+    selected_serial_number: OnceLock<String>,
+}
 
 impl LoadedProbeOptions {
     /// Performs necessary init calls such as loading all chip descriptions
     /// and returns a newtype that ensures initialization.
     pub(crate) fn new(probe_options: ProbeOptions) -> Result<Self, OperationError> {
-        let options = Self(probe_options);
+        let options = Self {
+            options: probe_options,
+            selected_serial_number: OnceLock::new(),
+        };
         // Load the target description, if given in the cli parameters.
         options.maybe_load_chip_desc()?;
         Ok(options)
@@ -193,7 +233,7 @@ impl LoadedProbeOptions {
     ///
     /// Note: should be called before [FlashOptions::early_exit] and any other functions in [ProbeOptions].
     fn maybe_load_chip_desc(&self) -> Result<(), OperationError> {
-        if let Some(ref cdp) = self.0.chip_description_path {
+        if let Some(ref cdp) = self.options.connect.chip_description_path {
             let file = File::open(Path::new(cdp)).map_err(|error| {
                 OperationError::ChipDescriptionNotFound {
                     source: error,
@@ -213,7 +253,7 @@ impl LoadedProbeOptions {
 
     /// Resolves a resultant target selector from passed [ProbeOptions].
     pub fn get_target_selector(&self) -> Result<TargetSelector, OperationError> {
-        let target = if let Some(chip_name) = &self.0.chip {
+        let target = if let Some(chip_name) = &self.options.connect.chip {
             let target = probe_rs::config::get_target_by_name(chip_name).map_err(|error| {
                 OperationError::ChipNotFound {
                     source: error,
@@ -231,12 +271,12 @@ impl LoadedProbeOptions {
 
     /// Attaches to specified probe and configures it.
     pub fn attach_probe(&self, lister: &Lister) -> Result<Probe, OperationError> {
-        let mut probe = if self.0.dry_run {
+        let mut probe = if self.options.dry_run {
             Probe::from_specific_probe(Box::new(FakeProbe::new()))
         } else {
             // If we got a probe selector as an argument, open the probe
             // matching the selector if possible.
-            let probe = match &self.0.probe_selector {
+            let probe = match &self.options.probe_selector {
                 Some(selector) => lister.open(selector),
                 None => {
                     // Only automatically select a probe if there is
@@ -249,6 +289,9 @@ impl LoadedProbeOptions {
                     let Some(info) = list.first() else {
                         return Err(OperationError::NoProbesFound);
                     };
+                    if let Some(serial_number) = &info.serial_number {
+                        let _ = self.selected_serial_number.set(serial_number.clone());
+                    }
 
                     lister.open(info)
                 }
@@ -257,7 +300,7 @@ impl LoadedProbeOptions {
             probe.map_err(OperationError::FailedToOpenProbe)?
         };
 
-        if let Some(protocol) = self.0.protocol {
+        if let Some(protocol) = self.options.connect.protocol {
             // Select protocol and speed
             probe.select_protocol(protocol).map_err(|error| {
                 OperationError::FailedToSelectProtocol {
@@ -267,7 +310,7 @@ impl LoadedProbeOptions {
             })?;
         }
 
-        if let Some(speed) = self.0.speed {
+        if let Some(speed) = self.options.connect.speed {
             let _actual_speed = probe.set_speed(speed).map_err(|error| {
                 OperationError::FailedToSelectProtocolSpeed {
                     source: error,
@@ -278,7 +321,7 @@ impl LoadedProbeOptions {
             // Warn the user if they specified a speed the debug probe does not support
             // and a fitting speed was automatically selected.
             let protocol_speed = probe.speed_khz();
-            if let Some(speed) = self.0.speed {
+            if let Some(speed) = self.options.connect.speed {
                 if protocol_speed < speed {
                     log::warn!(
                         "Unable to use specified speed of {} kHz, actual speed used is {} kHz",
@@ -302,43 +345,56 @@ impl LoadedProbeOptions {
         target: TargetSelector,
     ) -> Result<Session, OperationError> {
         let mut permissions = Permissions::new();
-        if self.0.allow_erase_all {
+        if self.options.connect.allow_erase_all {
             permissions = permissions.allow_erase_all();
         }
+        if self.options.allow_dangerous_config {
+            permissions = permissions.allow_dangerous_config();
+        }
 
-        let session = if self.0.connect_under_reset {
+        let session = if self.options.connect.connect_under_reset {
             probe.attach_under_reset(target, permissions)
         } else {
             probe.attach(target, permissions)
         }
         .map_err(|error| OperationError::AttachingFailed {
             source: error,
-            connect_under_reset: self.0.connect_under_reset,
+            connect_under_reset: self.options.connect.connect_under_reset,
         })?;
 
         Ok(session)
     }
 
     pub(crate) fn protocol(&self) -> Option<WireProtocol> {
-        self.0.protocol
+        self.options.connect.protocol
     }
 
     pub(crate) fn connect_under_reset(&self) -> bool {
-        self.0.connect_under_reset
+        self.options.connect.connect_under_reset
     }
 
     pub(crate) fn dry_run(&self) -> bool {
-        self.0.dry_run
+        self.options.dry_run
     }
 
     pub(crate) fn chip(&self) -> Option<String> {
-        self.0.chip.clone()
+        self.options.connect.chip.clone()
+    }
+
+    /// Returns the serial number of the probe, if it was given with `--probe` or the probe was
+    /// selected automatically.
+    pub(crate) fn probe_serial_number(&self) -> Option<&str> {
+        self.options
+            .probe_selector
+            .as_ref()
+            .and_then(|selector| selector.serial_number.as_deref())
+            .or_else(|| self.selected_serial_number.get().map(String::as_str))
     }
 }
 
 impl AsRef<ProbeOptions> for LoadedProbeOptions {
     fn as_ref(&self) -> &ProbeOptions {
-        &self.0
+        &self.options
     }
 }
 
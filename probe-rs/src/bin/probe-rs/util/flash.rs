@@ -3,17 +3,23 @@ use crate::FormatOptions;
 use super::common_options::{BinaryDownloadOptions, LoadedProbeOptions, OperationError};
 use super::logging;
 
+use std::cell::Cell;
 use std::fs::File;
 use std::time::Duration;
-use std::{path::Path, sync::Arc, time::Instant};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
 
 use colored::Colorize;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use probe_rs::{
     flashing::{
-        DownloadOptions, FileDownloadError, FlashLoader, FlashProgress, Format, ProgressEvent,
+        DownloadOptions, FileDownloadError, FlashDiffVisualizer, FlashLayout, FlashLoader,
+        FlashProgress, Format, ImageSection, ProgressEvent,
     },
-    Session,
+    Session, Target,
 };
 
 use anyhow::Context;
@@ -43,7 +49,21 @@ pub fn run_flash_download(
     options.dry_run = probe_options.dry_run();
     options.do_chip_erase = do_chip_erase;
     options.disable_double_buffering = download_options.disable_double_buffering;
+    options.preverify = download_options.preverify;
     options.verify = download_options.verify;
+    if download_options.journal
+        || download_options.journal_file.is_some()
+        || download_options.resume
+    {
+        options.journal = Some(download_options.journal_file.clone().unwrap_or_else(|| {
+            default_journal_path(
+                path,
+                &session.target().name,
+                probe_options.probe_serial_number(),
+            )
+        }));
+    }
+    options.resume = download_options.resume;
 
     if !download_options.disable_progressbars {
         // Create progress bars.
@@ -78,6 +98,7 @@ pub fn run_flash_download(
 
         // Register callback to update the progress.
         let flash_layout_output_path = download_options.flash_layout_output_path.clone();
+        let skipped_sectors = Cell::new(0);
         let progress = FlashProgress::new(move |event| match event {
             ProgressEvent::Initialized { flash_layout } => {
                 if let Some(fp) = fill_progress.as_ref() {
@@ -88,6 +109,10 @@ pub fn run_flash_download(
                 let total_sector_size: u64 = flash_layout.sectors().iter().map(|s| s.size()).sum();
                 erase_progress.set_length(total_sector_size);
 
+                let total_page_size: u64 =
+                    flash_layout.pages().iter().map(|s| s.size() as u64).sum();
+                program_progress.set_length(total_page_size);
+
                 let visualizer = flash_layout.visualize();
                 flash_layout_output_path
                     .as_ref()
@@ -108,6 +133,27 @@ pub fn run_flash_download(
             ProgressEvent::PageProgrammed { size, .. } => {
                 program_progress.inc(size as u64);
             }
+            ProgressEvent::SectorCompared {
+                size,
+                page_size,
+                unchanged,
+                ..
+            } => {
+                if unchanged {
+                    // Skipped sectors are neither erased nor programmed, so they no longer count
+                    // towards the totals.
+                    let length = erase_progress.length().unwrap_or(0);
+                    erase_progress.set_length(length.saturating_sub(size));
+                    let length = program_progress.length().unwrap_or(0);
+                    program_progress.set_length(length.saturating_sub(page_size));
+
+                    skipped_sectors.set(skipped_sectors.get() + 1);
+                    erase_progress.set_message(format!(
+                        "      Erasing ({} unchanged sectors skipped)",
+                        skipped_sectors.get()
+                    ));
+                }
+            }
             ProgressEvent::SectorErased { size, .. } => {
                 erase_progress.inc(size);
             }
@@ -145,6 +191,16 @@ pub fn run_flash_download(
         options.progress = Some(progress);
     }
 
+    if let Some(diff_path) = &download_options.layout_diff_output_path {
+        if let Err(error) = write_layout_diff(session, &loader, diff_path) {
+            log::warn!(
+                "Failed to write the flash layout diff to {}: {:?}",
+                diff_path.display(),
+                error
+            );
+        }
+    }
+
     loader
         .commit(session, options)
         .map_err(|error| OperationError::FlashingFailed {
@@ -165,6 +221,129 @@ pub fn run_flash_download(
     Ok(())
 }
 
+/// Writes a map of the differences between the current contents of the flash and the image of
+/// `loader` to `path`, as an HTML page if its extension is `html`, and as an SVG otherwise.
+fn write_layout_diff(
+    session: &mut Session,
+    loader: &FlashLoader,
+    path: &Path,
+) -> anyhow::Result<()> {
+    let layouts = loader.layouts(session.target())?;
+    let mut core = session.core(0)?;
+
+    let mut pairs = Vec::with_capacity(layouts.len());
+    for layout in layouts {
+        pairs.push((layout.read_back(&mut core)?, layout));
+    }
+
+    write_layout_diffs(&pairs, loader.sections(), path, |diff, path| {
+        log::info!(
+            "{} bytes of the flash will be changed, see {}",
+            diff.changed_bytes(),
+            path.display()
+        );
+        for section in diff.sections() {
+            log::info!(
+                "    {}: {} of {} bytes changed",
+                section.name,
+                section.changed_bytes,
+                section.size
+            );
+        }
+    })
+}
+
+/// Writes maps of the differences between the old and new layouts of each flash region in
+/// `pairs` to `path`, as HTML pages if its extension is `html`, and as SVGs otherwise.
+///
+/// The changed bytes of the given `sections` of the image are accounted separately. `report`
+/// is called with each diff and the path it was written to.
+pub fn write_layout_diffs(
+    pairs: &[(FlashLayout, FlashLayout)],
+    sections: &[ImageSection],
+    path: &Path,
+    mut report: impl FnMut(&FlashDiffVisualizer, &Path),
+) -> anyhow::Result<()> {
+    for (old, new) in pairs {
+        let diff = old.visualize_diff(new).with_sections(sections);
+
+        let path = if pairs.len() > 1 {
+            let address = new
+                .sectors()
+                .first()
+                .or(old.sectors().first())
+                .map_or(0, |sector| sector.address());
+            region_layout_diff_path(path, address)
+        } else {
+            path.to_path_buf()
+        };
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "html")
+        {
+            diff.write_html(&path)?;
+        } else {
+            diff.write_svg(&path)?;
+        }
+
+        report(&diff, &path);
+    }
+
+    Ok(())
+}
+
+/// Returns the path of the layout diff of the flash region at `address`, if the image is
+/// programmed into multiple regions.
+fn region_layout_diff_path(path: &Path, address: u64) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+    let mut name = format!("{stem}-{address:08x}");
+    if let Some(extension) = path.extension() {
+        name = format!("{name}.{}", extension.to_string_lossy());
+    }
+    path.with_file_name(name)
+}
+
+/// Returns the default path of the flash journal for downloading the image at `path` to `chip`
+/// with the probe with the given serial number.
+///
+/// Interrupted downloads of the same file name to different boards or from different
+/// directories must not share a journal, so it is named after a hash of all of them.
+/// The hash must not change between builds of probe-rs, so a download can be resumed
+/// with a newer version.
+fn default_journal_path(path: &Path, chip: &str, probe_serial_number: Option<&str>) -> PathBuf {
+    let full_path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let full_path = full_path.to_string_lossy();
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for part in [Some(full_path.as_ref()), Some(chip), probe_serial_number]
+        .into_iter()
+        .flatten()
+    {
+        // Terminate every part, so e.g. the chip and the serial number cannot run into each other.
+        hash = fnv1a(hash, part.as_bytes());
+        hash = fnv1a(hash, &[0]);
+    }
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    std::env::temp_dir().join(format!("probe-rs-{name}-{hash:016x}.journal"))
+}
+
+/// The initial value of a 64 bit FNV-1a hash.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Continues the 64 bit FNV-1a `hash` with `bytes`.
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
 /// Builds a new flash loader for the given target and path. This
 /// will check the path for validity and check what pages have to be
 /// flashed etc.
@@ -177,7 +356,7 @@ pub fn build_loader(
     let mut loader = session.target().flash_loader();
 
     // Add data from the BIN.
-    let mut file = match File::open(path) {
+    let mut file = match File::open(path.as_ref()) {
         Ok(file) => file,
         Err(e) => return Err(FileDownloadError::IO(e)).context("Failed to open binary file."),
     };
@@ -189,7 +368,108 @@ pub fn build_loader(
         Format::Hex => loader.load_hex_data(&mut file),
         Format::Idf(options) => loader.load_idf_data(session, &mut file, options),
         Format::Uf2 => loader.load_uf2_data(&mut file),
+        Format::Srec => loader.load_srec_data(&mut file),
+        Format::TiTxt => loader.load_titxt_data(&mut file),
+        Format::Manifest => loader.load_manifest(session, path.as_ref()),
     }?;
 
     Ok(loader)
 }
+
+/// Loads the image at `path` for `target` without connecting to it.
+///
+/// Unlike [build_loader], this does not support the formats which need a connection to the
+/// target to be loaded.
+pub fn build_offline_loader(
+    target: &Target,
+    path: impl AsRef<Path>,
+    format_options: FormatOptions,
+) -> anyhow::Result<FlashLoader> {
+    let mut loader = target.flash_loader();
+
+    let mut file = match File::open(path.as_ref()) {
+        Ok(file) => file,
+        Err(e) => return Err(FileDownloadError::IO(e)).context("Failed to open binary file."),
+    };
+
+    let format = format_options.into_format(target)?;
+    match format {
+        Format::Bin(options) => loader.load_bin_data(&mut file, options),
+        Format::Elf => loader.load_elf_data(&mut file),
+        Format::Hex => loader.load_hex_data(&mut file),
+        Format::Uf2 => loader.load_uf2_data(&mut file),
+        Format::Srec => loader.load_srec_data(&mut file),
+        Format::TiTxt => loader.load_titxt_data(&mut file),
+        Format::Idf(_) => {
+            anyhow::bail!("ESP-IDF images can only be loaded when connected to the target")
+        }
+        Format::Manifest => {
+            anyhow::bail!("Manifests can only be loaded when connected to the target")
+        }
+    }?;
+
+    Ok(loader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_journal_path, fnv1a, region_layout_diff_path, FNV_OFFSET_BASIS};
+    use std::path::Path;
+
+    #[test]
+    fn layout_diff_path_of_regions() {
+        assert_eq!(
+            region_layout_diff_path(Path::new("out/diff.html"), 0x1000_0000),
+            Path::new("out/diff-10000000.html")
+        );
+        assert_eq!(
+            region_layout_diff_path(Path::new("diff"), 0),
+            Path::new("diff-00000000")
+        );
+    }
+
+    #[test]
+    fn fnv1a_hashes() {
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b"foobar"), 0x8594_4171_f739_67e8);
+        assert_eq!(
+            fnv1a(fnv1a(FNV_OFFSET_BASIS, b"foo"), b"bar"),
+            fnv1a(FNV_OFFSET_BASIS, b"foobar")
+        );
+    }
+
+    #[test]
+    fn journal_path_is_keyed_by_path_chip_and_probe() {
+        let path = Path::new("/firmware/app.elf");
+        let journal = default_journal_path(path, "nRF52840_xxAA", Some("000683"));
+
+        assert_eq!(
+            journal,
+            default_journal_path(path, "nRF52840_xxAA", Some("000683"))
+        );
+        assert!(journal
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("probe-rs-app.elf-"));
+
+        assert_ne!(
+            journal,
+            default_journal_path(Path::new("/other/app.elf"), "nRF52840_xxAA", Some("000683"))
+        );
+        assert_ne!(
+            journal,
+            default_journal_path(path, "nRF52833_xxAA", Some("000683"))
+        );
+        assert_ne!(
+            journal,
+            default_journal_path(path, "nRF52840_xxAA", Some("000684"))
+        );
+        assert_ne!(journal, default_journal_path(path, "nRF52840_xxAA", None));
+        assert_ne!(
+            default_journal_path(path, "nRF52840_xxAA", Some("")),
+            default_journal_path(path, "nRF52840_xxAA", None)
+        );
+    }
+}
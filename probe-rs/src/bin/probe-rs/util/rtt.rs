@@ -1,9 +1,13 @@
+pub mod capture;
+
 use crate::*;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
+use capture::CaptureWriter;
 use defmt_decoder::DecodeError;
 use num_traits::Zero;
 pub use probe_rs::rtt::ChannelMode;
-use probe_rs::rtt::{DownChannel, Rtt, ScanRegion, UpChannel};
+pub use probe_rs::rtt::RttSocketAddress;
+use probe_rs::rtt::{DownChannel, Rtt, RttSocket, ScanRegion, UpChannel};
 use probe_rs::Core;
 use probe_rs_target::MemoryRegion;
 use serde::Deserialize;
@@ -28,23 +32,21 @@ pub fn attach_to_rtt(
     log_format: Option<&str>,
 ) -> Result<RttActiveTarget, anyhow::Error> {
     log::info!("Initializing RTT");
-    let rtt_header_address = if let Ok(mut file) = File::open(elf_file) {
-        if let Some(address) = RttActiveTarget::get_rtt_symbol(&mut file) {
-            ScanRegion::Exact(address as u32)
-        } else {
-            ScanRegion::Ranges(scan_regions.to_vec())
-        }
+    // Use the address of the control block if it is configured or known from the ELF file, and
+    // scan the memory for it otherwise.
+    let rtt_header_address = if let Some(address) = rtt_config.control_block_address {
+        ScanRegion::Exact(address)
+    } else if let Some(address) = File::open(elf_file)
+        .ok()
+        .and_then(|mut file| RttActiveTarget::get_rtt_symbol(&mut file))
+    {
+        ScanRegion::Exact(address as u32)
+    } else if scan_regions.is_empty() {
+        ScanRegion::Ram
     } else {
         ScanRegion::Ranges(scan_regions.to_vec())
     };
 
-    if let ScanRegion::Ranges(rngs) = &rtt_header_address {
-        if rngs.is_empty() {
-            // We have no regions to scan so we cannot initialize RTT.
-            return Err(anyhow!("ELF file has no RTT block symbol, and this target does not support automatic scanning"));
-        }
-    }
-
     match Rtt::attach_region(core, memory_map, &rtt_header_address) {
         Ok(rtt) => {
             log::info!("RTT initialized.");
@@ -89,6 +91,45 @@ impl FromStr for DataFormat {
     }
 }
 
+/// How the data of the RTT channels is printed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum LogOutput {
+    /// Formatted text
+    #[default]
+    Text,
+    /// One JSON object per line, e.g. for log aggregation.
+    ///
+    /// Defmt frames become `{channel, timestamp, level, module, file, line, message}` records,
+    /// string channels `{channel, text}` records for each line, and binary channels `{channel, data}` records.
+    Json,
+}
+
+/// A defmt frame, printed with `LogOutput::Json`.
+#[derive(Serialize)]
+struct DefmtJsonRecord<'a> {
+    channel: usize,
+    timestamp: Option<String>,
+    level: Option<&'static str>,
+    module: Option<&'a str>,
+    file: Option<String>,
+    line: Option<u32>,
+    message: String,
+}
+
+/// A line of a string channel, printed with `LogOutput::Json`.
+#[derive(Serialize)]
+struct TextJsonRecord<'a> {
+    channel: usize,
+    text: &'a str,
+}
+
+/// The data of a binary channel, printed with `LogOutput::Json`.
+#[derive(Serialize)]
+struct BinaryJsonRecord<'a> {
+    channel: usize,
+    data: &'a [u8],
+}
+
 /// The initial configuration for RTT (Real Time Transfer). This configuration is complimented with the additional information specified for each of the channels in `RttChannel`.
 #[derive(clap::Parser, Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RttConfig {
@@ -99,6 +140,18 @@ pub struct RttConfig {
     #[structopt(skip)]
     #[serde(default = "default_channel_formats", rename = "rttChannelFormats")]
     pub channels: Vec<RttChannelConfig>,
+    /// Record the raw data of all up channels to this capture file, for `probe-rs rtt replay`.
+    #[structopt(skip)]
+    #[serde(default, rename = "rttCapturePath")]
+    pub capture_path: Option<PathBuf>,
+    /// The address of the control block, which selects one if there are several in memory.
+    #[structopt(skip)]
+    #[serde(default, rename = "rttControlBlockAddress")]
+    pub control_block_address: Option<u32>,
+    /// How the data of the channels is printed.
+    #[structopt(skip)]
+    #[serde(skip)]
+    pub log_output: LogOutput,
 }
 
 /// The User specified configuration for each active RTT Channel. The configuration is passed via a
@@ -119,6 +172,40 @@ pub struct RttChannelConfig {
     #[serde(default = "default_include_location")]
     // Control the inclusion of source location information for DataFormat::Defmt.
     pub show_location: bool,
+    #[structopt(skip)]
+    #[serde(default)]
+    // Serve the raw data of the channel on a TCP or Unix socket, e.g. `tcp:127.0.0.1:19021`.
+    pub socket: Option<RttSocketAddress>,
+}
+
+impl RttChannelConfig {
+    /// Check if this configuration applies to the channel with `number` and `name`.
+    fn matches(&self, number: usize, name: Option<&str>) -> bool {
+        self.channel_number == Some(number)
+            || (self.channel_name.is_some() && self.channel_name.as_deref() == name)
+    }
+}
+
+/// Parse the socket for an RTT channel from the command line, in the form `CHANNEL=ADDRESS`,
+/// where `CHANNEL` is the number or the name of the channel, e.g. `0=tcp:127.0.0.1:19021`.
+pub fn parse_rtt_socket(input: &str) -> Result<RttChannelConfig> {
+    let Some((channel, address)) = input.split_once('=') else {
+        return Err(anyhow!(
+            "Expected an RTT socket in the form 'CHANNEL=ADDRESS', e.g. '0=tcp:127.0.0.1:19021'"
+        ));
+    };
+
+    let (channel_number, channel_name) = match channel.parse::<usize>() {
+        Ok(number) => (Some(number), None),
+        Err(_) => (None, Some(channel.to_string())),
+    };
+
+    Ok(RttChannelConfig {
+        channel_number,
+        channel_name,
+        socket: Some(address.parse().map_err(|error: String| anyhow!(error))?),
+        ..Default::default()
+    })
 }
 
 /// This is the primary interface through which RTT channel data is read and written. Every actual
@@ -129,11 +216,21 @@ pub struct RttActiveChannel {
     pub down_channel: Option<DownChannel>,
     pub channel_name: String,
     pub data_format: DataFormat,
+    /// How the data of the channel is printed.
+    pub log_output: LogOutput,
+    /// The number of the channel, or the configured number if there is no channel on the target.
+    channel_number: usize,
     /// Data that will be written to the down_channel (host to target)
     _input_data: String,
     rtt_buffer: RttBuffer,
     show_timestamps: bool,
     show_location: bool,
+    /// The socket on which the raw data of the channel is served.
+    socket: Option<RttSocket>,
+    /// The capture file to which the raw data of the channel is recorded.
+    capture: Option<CaptureWriter>,
+    /// The start of a line of a string channel which has not been terminated yet, for JSON output.
+    partial_line: Vec<u8>,
 
     /// UTC offset used for creating timestamps
     ///
@@ -199,15 +296,26 @@ impl RttActiveChannel {
                     full_config.channel_number.unwrap_or(0)
                 )
             });
+        let channel_number = up_channel
+            .as_ref()
+            .map(|up| up.number())
+            .or_else(|| down_channel.as_ref().map(|down| down.number()))
+            .or(full_config.channel_number)
+            .unwrap_or(0);
         Self {
             up_channel,
             down_channel,
             channel_name: name,
             data_format,
+            log_output: LogOutput::Text,
+            channel_number,
             _input_data: String::new(),
             rtt_buffer: RttBuffer::new(buffer_size),
             show_timestamps: full_config.show_timestamps,
             show_location,
+            socket: None,
+            capture: None,
+            partial_line: Vec::new(),
             timestamp_offset,
         }
     }
@@ -255,26 +363,75 @@ impl RttActiveChannel {
     ) -> Result<Option<(String, String)>, anyhow::Error> {
         self.poll_rtt(core)
             .map(|bytes_read| {
+                let data = self.rtt_buffer.0[..bytes_read].to_vec();
+                if let Some(socket) = self.socket.as_mut() {
+                    socket.send(&data);
+                }
+                let number = self.number().unwrap_or(0);
+                if let Some(capture) = self.capture.as_mut() {
+                    if let Err(error) = capture.write_data(number, &data) {
+                        log::error!(
+                            "Failed to write to the RTT capture, stopping the capture of {}: {}",
+                            self.channel_name,
+                            error
+                        );
+                        self.capture = None;
+                    }
+                }
                 Ok((
-                    self.number().unwrap_or(0).to_string(), // If the Channel doesn't have a number, then send the output to channel 0
-                    {
-                        let mut formatted_data = String::new();
-                        match self.data_format {
-                            DataFormat::String => self.get_string(bytes_read, &mut formatted_data),
-                            DataFormat::BinaryLE => {
-                                self.get_binary_le(bytes_read, &mut formatted_data)
-                            }
-                            DataFormat::Defmt => {
-                                self.get_defmt(bytes_read, &mut formatted_data, defmt_state)?
-                            }
-                        };
-                        formatted_data
-                    },
+                    number.to_string(), // If the Channel doesn't have a number, then send the output to channel 0
+                    self.format_data(&data, OffsetDateTime::now_utc(), defmt_state)?,
                 ))
             })
             .transpose()
     }
 
+    /// Formats `data` of the channel according to its data format. `timestamp` is the time at which
+    /// the data was read, which is shown for `DataFormat::String` if timestamps are enabled.
+    pub fn format_data(
+        &mut self,
+        data: &[u8],
+        timestamp: OffsetDateTime,
+        defmt_state: Option<&DefmtState>,
+    ) -> Result<String, anyhow::Error> {
+        let mut formatted_data = String::new();
+        match self.data_format {
+            DataFormat::String => self.get_string(data, timestamp, &mut formatted_data),
+            DataFormat::BinaryLE => self.get_binary_le(data, &mut formatted_data),
+            DataFormat::Defmt => self.get_defmt(data, &mut formatted_data, defmt_state)?,
+        };
+        Ok(formatted_data)
+    }
+
+    /// Records the data of the up channel to `capture`, starting with the description of the channel.
+    fn start_capture(&mut self, capture: &CaptureWriter) -> Result<()> {
+        let Some(number) = self.number() else {
+            return Ok(());
+        };
+
+        let mut capture = capture.try_clone()?;
+        capture.write_channel(number, &self.channel_name, self.data_format)?;
+        self.capture = Some(capture);
+        Ok(())
+    }
+
+    /// Serves the socket of the channel, if it has one, and writes the data which its clients sent to the down channel.
+    /// Returns `true` if any data was written to the target.
+    pub fn poll_socket(&mut self, core: &mut Core) -> Result<bool, anyhow::Error> {
+        let Some(socket) = self.socket.as_mut() else {
+            return Ok(false);
+        };
+
+        match self.down_channel.as_ref() {
+            Some(down_channel) => Ok(socket.write_input(core, down_channel)? > 0),
+            None => {
+                // There is nowhere to send the input of the clients to.
+                socket.take_input();
+                Ok(false)
+            }
+        }
+    }
+
     pub fn _push_rtt(&mut self, core: &mut Core) {
         if let Some(down_channel) = self.down_channel.as_mut() {
             self._input_data += "\n";
@@ -285,14 +442,19 @@ impl RttActiveChannel {
         }
     }
 
-    fn get_string(&self, bytes_read: usize, formatted_data: &mut String) {
-        let incoming = String::from_utf8_lossy(&self.rtt_buffer.0[..bytes_read]).to_string();
+    fn get_string(&mut self, data: &[u8], timestamp: OffsetDateTime, formatted_data: &mut String) {
+        if self.log_output == LogOutput::Json {
+            self.get_string_json(data, formatted_data);
+            return;
+        }
+
+        let incoming = String::from_utf8_lossy(data).to_string();
         for line in incoming.split_terminator('\n') {
             if self.show_timestamps {
                 write!(
                     formatted_data,
                     "{} :",
-                    OffsetDateTime::now_utc().to_offset(self.timestamp_offset)
+                    timestamp.to_offset(self.timestamp_offset)
                 )
                 .expect("Writing to String cannot fail");
             }
@@ -300,8 +462,39 @@ impl RttActiveChannel {
         }
     }
 
-    fn get_binary_le(&self, bytes_read: usize, formatted_data: &mut String) {
-        for element in &self.rtt_buffer.0[..bytes_read] {
+    /// Writes a record for every complete line. A line can be split across reads, so the
+    /// unterminated rest of `data` is kept until its newline arrives.
+    fn get_string_json(&mut self, data: &[u8], formatted_data: &mut String) {
+        self.partial_line.extend_from_slice(data);
+        let Some(end) = self.partial_line.iter().rposition(|&byte| byte == b'\n') else {
+            return;
+        };
+
+        let lines = self.partial_line.drain(..=end).collect::<Vec<_>>();
+        for line in String::from_utf8_lossy(&lines).split_terminator('\n') {
+            let record = TextJsonRecord {
+                channel: self.channel_number,
+                text: line,
+            };
+            self.write_json(formatted_data, &record);
+        }
+    }
+
+    fn write_json(&self, formatted_data: &mut String, record: &impl Serialize) {
+        let json = serde_json::to_string(record).expect("Serializing a record cannot fail");
+        writeln!(formatted_data, "{json}").expect("Writing to String cannot fail");
+    }
+
+    fn get_binary_le(&self, data: &[u8], formatted_data: &mut String) {
+        if self.log_output == LogOutput::Json {
+            let record = BinaryJsonRecord {
+                channel: self.channel_number,
+                data,
+            };
+            self.write_json(formatted_data, &record);
+            return;
+        }
+        for element in data {
             // Width of 4 allows 0xFF to be printed.
             write!(formatted_data, "{element:#04x}").expect("Writing to String cannot fail");
         }
@@ -309,7 +502,7 @@ impl RttActiveChannel {
 
     fn get_defmt(
         &self,
-        bytes_read: usize,
+        data: &[u8],
         formatted_data: &mut String,
         defmt_state: Option<&DefmtState>,
     ) -> anyhow::Result<()> {
@@ -320,11 +513,32 @@ impl RttActiveChannel {
                 formatter,
             }) => {
                 let mut stream_decoder = table.new_stream_decoder();
-                stream_decoder.received(&self.rtt_buffer.0[..bytes_read]);
+                stream_decoder.received(data);
                 loop {
                     match stream_decoder.decode() {
                         Ok(frame) => {
                             let loc = locs.as_ref().and_then(|locs| locs.get(&frame.index()));
+                            if self.log_output == LogOutput::Json {
+                                let record = DefmtJsonRecord {
+                                    channel: self.channel_number,
+                                    timestamp: frame
+                                        .display_timestamp()
+                                        .map(|timestamp| timestamp.to_string()),
+                                    level: frame.level().map(|level| level.as_str()),
+                                    module: loc.map(|loc| loc.module.as_str()),
+                                    file: loc.map(|loc| {
+                                        loc.file
+                                            .strip_prefix(std::env::current_dir().unwrap())
+                                            .unwrap_or(&loc.file)
+                                            .display()
+                                            .to_string()
+                                    }),
+                                    line: loc.and_then(|loc| loc.line.try_into().ok()),
+                                    message: frame.display_message().to_string(),
+                                };
+                                self.write_json(formatted_data, &record);
+                                continue;
+                            }
                             let (file, line, module) = if let Some(loc) = loc {
                                 let relpath = loc
                                     .file
@@ -389,6 +603,58 @@ pub struct DefmtState {
     formatter: defmt_decoder::log::Formatter,
 }
 
+impl DefmtState {
+    /// Load the defmt table and locations from `elf`. `show_location` and `log_format` select
+    /// how the decoded messages are formatted.
+    ///
+    /// Returns `None` if `elf` contains no defmt table.
+    pub fn try_from_bytes(
+        elf: &[u8],
+        show_location: bool,
+        log_format: Option<&str>,
+    ) -> Result<Option<Self>> {
+        if let Some(table) = defmt_decoder::Table::parse(elf)? {
+            let has_timestamp = table.has_timestamp();
+
+            // Format options:
+            // 1. Custom format
+            // 2. Default with timestamp with location
+            // 3. Default with timestamp without location
+            // 4. Default without timestamp with location
+            // 5. Default without timestamp without location
+            let format = log_format.unwrap_or(match (show_location, has_timestamp) {
+                (true, true) => "{t} {L} {s}\n└─ {m} @ {F}:{l}",
+                (true, false) => "{L} {s}\n└─ {m} @ {F}:{l}",
+                (false, true) => "{t} {L} {s}",
+                (false, false) => "{L} {s}",
+            });
+            let formatter = defmt_decoder::log::Formatter::new(format);
+
+            let locs = {
+                let locs = table.get_locations(elf)?;
+
+                if !table.is_empty() && locs.is_empty() {
+                    log::warn!("Insufficient DWARF info; compile your program with `debug = 2` to enable location info.");
+                    None
+                } else if table.indices().all(|idx| locs.contains_key(&(idx as u64))) {
+                    Some(locs)
+                } else {
+                    log::warn!("Location info is incomplete; it will be omitted from the output.");
+                    None
+                }
+            };
+            Ok(Some(Self {
+                table,
+                locs,
+                formatter,
+            }))
+        } else {
+            log::warn!("No `Table` definition in DWARF info; compile your program with `debug = 2` to enable location info.");
+            Ok(None)
+        }
+    }
+}
+
 impl RttActiveTarget {
     /// RttActiveTarget collects references to all the `RttActiveChannel`s, for latter polling/pushing of data.
     pub fn new(
@@ -400,36 +666,57 @@ impl RttActiveTarget {
     ) -> Result<Self> {
         let mut active_channels = Vec::new();
         // For each channel configured in the RTT Control Block (`Rtt`), check if there are additional user configuration in a `RttChannelConfig`. If not, apply defaults.
+        // A down channel with the same number as an up channel is paired with it, so that they can share a socket.
         let up_channels = rtt.up_channels().drain();
-        let down_channels = rtt.down_channels().drain();
-        for channel in up_channels {
-            let number = channel.number();
-            let channel_config = rtt_config
-                .channels
-                .clone()
+        let mut down_channels = rtt.down_channels().drain().collect::<Vec<_>>();
+        let channels = up_channels
+            .map(|up_channel| {
+                let down_channel = down_channels
+                    .iter()
+                    .position(|down_channel| down_channel.number() == up_channel.number())
+                    .map(|index| down_channels.remove(index));
+                (Some(up_channel), down_channel)
+            })
+            .collect::<Vec<_>>();
+        let channels = channels.into_iter().chain(
+            down_channels
                 .into_iter()
-                .find(|channel| channel.channel_number == Some(number));
-            active_channels.push(RttActiveChannel::new(
-                Some(channel),
-                None,
-                channel_config,
-                timestamp_offset,
-            ));
-        }
-
-        for channel in down_channels {
-            let number = channel.number();
+                .map(|down_channel| (None, Some(down_channel))),
+        );
+
+        for (up_channel, down_channel) in channels {
+            let (number, name) = match (&up_channel, &down_channel) {
+                (Some(channel), _) => (channel.number(), channel.name()),
+                (None, Some(channel)) => (channel.number(), channel.name()),
+                (None, None) => continue,
+            };
             let channel_config = rtt_config
                 .channels
-                .clone()
-                .into_iter()
-                .find(|channel| channel.channel_number == Some(number));
-            active_channels.push(RttActiveChannel::new(
-                None,
-                Some(channel),
-                channel_config,
-                timestamp_offset,
-            ));
+                .iter()
+                .find(|channel| channel.matches(number, name))
+                .cloned();
+            let socket = rtt_config
+                .channels
+                .iter()
+                .filter(|channel| channel.matches(number, name))
+                .find_map(|channel| channel.socket.as_ref())
+                .map(|address| {
+                    RttSocket::bind(address).map_err(|error| {
+                        anyhow!(
+                            "Failed to serve RTT channel {} on {}: {}",
+                            number,
+                            address,
+                            error
+                        )
+                    })
+                })
+                .transpose()?;
+
+            let mut active_channel =
+                RttActiveChannel::new(up_channel, down_channel, channel_config, timestamp_offset);
+            active_channel.socket = socket;
+            active_channel.log_output = rtt_config.log_output;
+            active_channels.push(active_channel);
         }
 
         // It doesn't make sense to pretend RTT is active, if there are no active channels
@@ -455,51 +742,22 @@ impl RttActiveTarget {
                 .expect("`active_channels` is not empty")
                 .show_location;
 
-            if let Some(table) = defmt_decoder::Table::parse(&elf)? {
-                let has_timestamp = table.has_timestamp();
-
-                // Format options:
-                // 1. Custom format
-                // 2. Default with timestamp with location
-                // 3. Default with timestamp without location
-                // 4. Default without timestamp with location
-                // 5. Default without timestamp without location
-                let format = log_format.unwrap_or(match (show_location, has_timestamp) {
-                    (true, true) => "{t} {L} {s}\n└─ {m} @ {F}:{l}",
-                    (true, false) => "{L} {s}\n└─ {m} @ {F}:{l}",
-                    (false, true) => "{t} {L} {s}",
-                    (false, false) => "{L} {s}",
-                });
-                let formatter = defmt_decoder::log::Formatter::new(format);
-
-                let locs = {
-                    let locs = table.get_locations(&elf)?;
-
-                    if !table.is_empty() && locs.is_empty() {
-                        log::warn!("Insufficient DWARF info; compile your program with `debug = 2` to enable location info.");
-                        None
-                    } else if table.indices().all(|idx| locs.contains_key(&(idx as u64))) {
-                        Some(locs)
-                    } else {
-                        log::warn!(
-                            "Location info is incomplete; it will be omitted from the output."
-                        );
-                        None
-                    }
-                };
-                Some(DefmtState {
-                    table,
-                    locs,
-                    formatter,
-                })
-            } else {
-                log::warn!("No `Table` definition in DWARF info; compile your program with `debug = 2` to enable location info.");
-                None
-            }
+            DefmtState::try_from_bytes(&elf, show_location, log_format)?
         } else {
             None
         };
 
+        if let Some(path) = &rtt_config.capture_path {
+            let capture = CaptureWriter::create(path).with_context(|| {
+                format!("Failed to create the RTT capture file {}", path.display())
+            })?;
+            for channel in active_channels.iter_mut() {
+                channel.start_capture(&capture).with_context(|| {
+                    format!("Failed to write to the RTT capture file {}", path.display())
+                })?;
+            }
+        }
+
         Ok(Self {
             active_channels,
             defmt_state,
@@ -540,6 +798,16 @@ impl RttActiveTarget {
         Ok(data)
     }
 
+    /// Serves the sockets of all channels, and writes the data which their clients sent to the target.
+    /// Returns `true` if any data was written to the target.
+    pub fn poll_sockets(&mut self, core: &mut Core) -> Result<bool, anyhow::Error> {
+        let mut had_data = false;
+        for channel in self.active_channels.iter_mut() {
+            had_data |= channel.poll_socket(core)?;
+        }
+        Ok(had_data)
+    }
+
     // pub fn push_rtt(&mut self) {
     //     self.tabs[self.current_tab].push_rtt();
     // }
@@ -562,3 +830,68 @@ impl fmt::Debug for RttBuffer {
         self.0.fmt(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rtt_sockets() {
+        let config = parse_rtt_socket("0=tcp:127.0.0.1:19021").unwrap();
+        assert_eq!(config.channel_number, Some(0));
+        assert_eq!(
+            config.socket,
+            Some(RttSocketAddress::Tcp(([127, 0, 0, 1], 19021).into()))
+        );
+
+        let config = parse_rtt_socket("Terminal=unix:/tmp/rtt").unwrap();
+        assert_eq!(config.channel_name.as_deref(), Some("Terminal"));
+        assert!(config.matches(2, Some("Terminal")));
+        assert!(!config.matches(2, None));
+
+        assert!(parse_rtt_socket("0").is_err());
+        assert!(parse_rtt_socket("0=19021").is_err());
+    }
+
+    #[test]
+    fn format_string_channel_as_json() {
+        let channel_config = RttChannelConfig {
+            channel_number: Some(1),
+            ..Default::default()
+        };
+        let mut channel = RttActiveChannel::new(None, None, Some(channel_config), UtcOffset::UTC);
+        channel.log_output = LogOutput::Json;
+
+        let formatted_data = channel
+            .format_data(b"Hello\n\"host\"\n", OffsetDateTime::UNIX_EPOCH, None)
+            .unwrap();
+        assert_eq!(
+            formatted_data,
+            "{\"channel\":1,\"text\":\"Hello\"}\n{\"channel\":1,\"text\":\"\\\"host\\\"\"}\n"
+        );
+    }
+
+    #[test]
+    fn json_lines_split_across_reads() {
+        let channel_config = RttChannelConfig {
+            channel_number: Some(0),
+            ..Default::default()
+        };
+        let mut channel = RttActiveChannel::new(None, None, Some(channel_config), UtcOffset::UTC);
+        channel.log_output = LogOutput::Json;
+
+        let mut format = |data: &[u8]| {
+            channel
+                .format_data(data, OffsetDateTime::UNIX_EPOCH, None)
+                .unwrap()
+        };
+        assert_eq!(format(b"Hel"), "");
+        assert_eq!(format(b"lo\nWor"), "{\"channel\":0,\"text\":\"Hello\"}\n");
+        // A character which is split across reads is kept intact.
+        assert_eq!(format(b"ld \xC3"), "");
+        assert_eq!(
+            format(b"\xA4\n\n"),
+            "{\"channel\":0,\"text\":\"World \u{e4}\"}\n{\"channel\":0,\"text\":\"\"}\n"
+        );
+    }
+}
@@ -0,0 +1,323 @@
+//! Capture files, which record the raw data of RTT channels so that it can be decoded later.
+//!
+//! A capture file starts with [`MAGIC`], followed by a sequence of records. Every record has a header,
+//! with all integers in little endian, followed by its payload:
+//!
+//! | Field       | Type  | Description                                                   |
+//! |-------------|-------|---------------------------------------------------------------|
+//! | `kind`      | `u8`  | `0` for the description of a channel, `1` for channel data    |
+//! | `timestamp` | `u64` | Host time at which the record was written, in ns since the Unix epoch |
+//! | `channel`   | `u32` | Number of the up channel                                      |
+//! | `length`    | `u32` | Length of the payload in bytes                                |
+//!
+//! The description of a channel is written before its first data, and its payload is the data format
+//! of the channel (`0` = String, `1` = BinaryLE, `2` = Defmt), followed by the name of the channel in UTF-8.
+//! The payload of channel data are the bytes as they were read from the target. Payloads are at most
+//! [`MAX_RECORD_LENGTH`] bytes long, larger reads are split into several records.
+
+use super::DataFormat;
+use std::fs::File;
+use std::io::{self, ErrorKind, Read, Write};
+use std::path::Path;
+use time::OffsetDateTime;
+
+/// The first bytes of every capture file, which include the version of the format.
+pub const MAGIC: &[u8; 8] = b"PRSRTT\x00\x01";
+
+const KIND_CHANNEL: u8 = 0;
+const KIND_DATA: u8 = 1;
+const HEADER_SIZE: usize = 17;
+
+/// The maximum length of the payload of a record, so a corrupt length does not make the reader
+/// allocate gigabytes.
+pub const MAX_RECORD_LENGTH: usize = 1024 * 1024;
+
+/// The content of a record in a capture file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaptureContent {
+    /// The description of a channel, which is recorded before its data.
+    Channel {
+        name: String,
+        data_format: DataFormat,
+    },
+    /// Data which was read from a channel.
+    Data(Vec<u8>),
+}
+
+/// A record in a capture file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureRecord {
+    /// The host time at which the record was written.
+    pub timestamp: OffsetDateTime,
+    /// The number of the up channel which the record belongs to.
+    pub channel: usize,
+    pub content: CaptureContent,
+}
+
+/// Writes records to a capture file.
+///
+/// Every record is written with a single write, so the writer can be cloned for each channel,
+/// and all clones append to the same file.
+#[derive(Debug)]
+pub struct CaptureWriter {
+    file: File,
+}
+
+impl CaptureWriter {
+    /// Create the capture file at `path`, replacing an existing file.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let mut file = File::create(path)?;
+        file.write_all(MAGIC)?;
+        Ok(Self { file })
+    }
+
+    /// Creates a writer which appends to the same file.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self {
+            file: self.file.try_clone()?,
+        })
+    }
+
+    /// Record the description of `channel`.
+    pub fn write_channel(
+        &mut self,
+        channel: usize,
+        name: &str,
+        data_format: DataFormat,
+    ) -> io::Result<()> {
+        let format = match data_format {
+            DataFormat::String => 0,
+            DataFormat::BinaryLE => 1,
+            DataFormat::Defmt => 2,
+        };
+        let mut payload = vec![format];
+        payload.extend_from_slice(name.as_bytes());
+        self.write_record(KIND_CHANNEL, channel, &payload)
+    }
+
+    /// Record `data`, which was read from `channel`.
+    pub fn write_data(&mut self, channel: usize, data: &[u8]) -> io::Result<()> {
+        for chunk in data.chunks(MAX_RECORD_LENGTH) {
+            self.write_record(KIND_DATA, channel, chunk)?;
+        }
+        Ok(())
+    }
+
+    fn write_record(&mut self, kind: u8, channel: usize, payload: &[u8]) -> io::Result<()> {
+        let timestamp = OffsetDateTime::now_utc().unix_timestamp_nanos() as u64;
+        let channel = u32::try_from(channel)
+            .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "Channel number is too large"))?;
+        if payload.len() > MAX_RECORD_LENGTH {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "Record is too large",
+            ));
+        }
+        let length = payload.len() as u32;
+
+        let mut record = Vec::with_capacity(HEADER_SIZE + payload.len());
+        record.push(kind);
+        record.extend_from_slice(&timestamp.to_le_bytes());
+        record.extend_from_slice(&channel.to_le_bytes());
+        record.extend_from_slice(&length.to_le_bytes());
+        record.extend_from_slice(payload);
+        self.file.write_all(&record)
+    }
+}
+
+/// Reads the records of a capture file.
+///
+/// A record which was cut off at the end of the file, e.g. because the capturing process was killed,
+/// ends the iteration like the end of the file.
+pub struct CaptureReader<R> {
+    reader: R,
+}
+
+impl<R: Read> CaptureReader<R> {
+    /// Starts reading a capture file, and checks that it is one.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0u8; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "Not an RTT capture file, or a capture from an unsupported version",
+            ));
+        }
+
+        Ok(Self { reader })
+    }
+
+    fn read_record(&mut self) -> io::Result<Option<CaptureRecord>> {
+        let mut header = [0u8; HEADER_SIZE];
+        let mut read = 0;
+        while read < header.len() {
+            match self.reader.read(&mut header[read..]) {
+                Ok(0) if read == 0 => return Ok(None),
+                Ok(0) => {
+                    log::warn!("The last record of the RTT capture is incomplete.");
+                    return Ok(None);
+                }
+                Ok(count) => read += count,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            }
+        }
+
+        let kind = header[0];
+        let timestamp = u64::from_le_bytes(header[1..9].try_into().unwrap());
+        let channel = u32::from_le_bytes(header[9..13].try_into().unwrap()) as usize;
+        let length = u32::from_le_bytes(header[13..17].try_into().unwrap()) as usize;
+        if length > MAX_RECORD_LENGTH {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Record of {length} bytes is longer than the maximum of {MAX_RECORD_LENGTH} bytes, the capture is corrupt"),
+            ));
+        }
+
+        let mut payload = vec![0u8; length];
+        match self.reader.read_exact(&mut payload) {
+            Ok(()) => {}
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => {
+                log::warn!("The last record of the RTT capture is incomplete.");
+                return Ok(None);
+            }
+            Err(error) => return Err(error),
+        }
+
+        let timestamp = OffsetDateTime::from_unix_timestamp_nanos(timestamp as i128)
+            .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?;
+        let content = match kind {
+            KIND_CHANNEL => {
+                let data_format = match payload.first() {
+                    Some(0) => DataFormat::String,
+                    Some(1) => DataFormat::BinaryLE,
+                    Some(2) => DataFormat::Defmt,
+                    _ => {
+                        return Err(io::Error::new(
+                            ErrorKind::InvalidData,
+                            format!("Invalid data format for RTT channel {channel}"),
+                        ))
+                    }
+                };
+                CaptureContent::Channel {
+                    name: String::from_utf8_lossy(&payload[1..]).into_owned(),
+                    data_format,
+                }
+            }
+            KIND_DATA => CaptureContent::Data(payload),
+            kind => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Unknown kind of record: {kind}"),
+                ))
+            }
+        };
+
+        Ok(Some(CaptureRecord {
+            timestamp,
+            channel,
+            content,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = io::Result<CaptureRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_and_read_capture() {
+        let path = std::env::temp_dir().join(format!("rtt-capture-{}.bin", std::process::id()));
+
+        let mut writer = CaptureWriter::create(&path).unwrap();
+        let mut clone = writer.try_clone().unwrap();
+        writer.write_channel(0, "defmt", DataFormat::Defmt).unwrap();
+        clone
+            .write_channel(1, "Terminal", DataFormat::String)
+            .unwrap();
+        writer.write_data(0, &[1, 2, 3]).unwrap();
+        clone.write_data(1, b"Hello").unwrap();
+        drop((writer, clone));
+
+        // Simulate a capture which was cut off in the middle of a record.
+        let mut bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        bytes.extend_from_slice(&[KIND_DATA, 0, 0]);
+
+        let records = CaptureReader::new(bytes.as_slice())
+            .unwrap()
+            .map(|record| {
+                let record = record.unwrap();
+                (record.channel, record.content)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            records,
+            [
+                (
+                    0,
+                    CaptureContent::Channel {
+                        name: "defmt".to_string(),
+                        data_format: DataFormat::Defmt
+                    }
+                ),
+                (
+                    1,
+                    CaptureContent::Channel {
+                        name: "Terminal".to_string(),
+                        data_format: DataFormat::String
+                    }
+                ),
+                (0, CaptureContent::Data(vec![1, 2, 3])),
+                (1, CaptureContent::Data(b"Hello".to_vec())),
+            ]
+        );
+
+        assert!(CaptureReader::new(&b"not a capture"[..]).is_err());
+    }
+
+    #[test]
+    fn long_data_is_split_into_records() {
+        let path =
+            std::env::temp_dir().join(format!("rtt-capture-split-{}.bin", std::process::id()));
+
+        let data = vec![0x55; MAX_RECORD_LENGTH + 1];
+        let mut writer = CaptureWriter::create(&path).unwrap();
+        writer.write_data(0, &data).unwrap();
+        drop(writer);
+
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let lengths = CaptureReader::new(bytes.as_slice())
+            .unwrap()
+            .map(|record| match record.unwrap().content {
+                CaptureContent::Data(data) => data.len(),
+                content => panic!("Unexpected record {content:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(lengths, [MAX_RECORD_LENGTH, 1]);
+    }
+
+    #[test]
+    fn corrupt_record_length_is_an_error() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(KIND_DATA);
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+
+        let mut records = CaptureReader::new(bytes.as_slice()).unwrap();
+        let error = records.next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -56,6 +56,7 @@ fn add_generic_targets(vec: &mut Vec<ChipFamily>) {
             ],
 
             flash_algorithms: vec![],
+            config_areas: vec![],
             source: TargetDescriptionSource::Generic,
         },
         ChipFamily {
@@ -65,6 +66,7 @@ fn add_generic_targets(vec: &mut Vec<ChipFamily>) {
             pack_file_release: None,
             variants: vec![Chip::generic_arm("Cortex-M3", CoreType::Armv7m)],
             flash_algorithms: vec![],
+            config_areas: vec![],
             source: TargetDescriptionSource::Generic,
         },
         ChipFamily {
@@ -77,6 +79,7 @@ fn add_generic_targets(vec: &mut Vec<ChipFamily>) {
                 Chip::generic_arm("Cortex-M7", CoreType::Armv7em),
             ],
             flash_algorithms: vec![],
+            config_areas: vec![],
             source: TargetDescriptionSource::Generic,
         },
         ChipFamily {
@@ -91,6 +94,7 @@ fn add_generic_targets(vec: &mut Vec<ChipFamily>) {
                 Chip::generic_arm("Cortex-M55", CoreType::Armv8m),
             ],
             flash_algorithms: vec![],
+            config_areas: vec![],
             source: TargetDescriptionSource::Generic,
         },
         ChipFamily {
@@ -105,15 +109,20 @@ fn add_generic_targets(vec: &mut Vec<ChipFamily>) {
                 cores: vec![Core {
                     name: "core".to_owned(),
                     core_type: CoreType::Riscv,
-                    core_access_options: CoreAccessOptions::Riscv(RiscvCoreAccessOptions {}),
+                    core_access_options: CoreAccessOptions::Riscv(RiscvCoreAccessOptions {
+                        hart_id: None,
+                        halt_group: false,
+                    }),
                 }],
                 memory_map: vec![],
                 flash_algorithms: vec![],
                 rtt_scan_ranges: None,
                 scan_chain: Some(vec![]),
                 default_binary_format: Some(BinaryFormat::Raw),
+                config_areas: vec![],
             }],
             flash_algorithms: vec![],
+            config_areas: vec![],
             source: TargetDescriptionSource::Generic,
         },
     ]);
@@ -516,6 +525,34 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn variants_inherit_the_config_areas_of_their_family() {
+        let registry = Registry::from_builtin_families();
+
+        let fields = |name| {
+            let target = registry.get_target_by_name(name).unwrap();
+            assert_eq!(target.config_areas.len(), 1);
+            target.config_areas[0]
+                .fields
+                .iter()
+                .map(|field| field.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        // The variants add their own fields to the ones of the family.
+        assert_eq!(
+            fields("nRF52805_xxAA"),
+            ["NRFFW0", "PSELRESET0", "APPROTECT"]
+        );
+        assert_eq!(
+            fields("nRF52840_xxAA"),
+            ["NRFFW0", "PSELRESET0", "APPROTECT", "NFCPINS", "REGOUT0"]
+        );
+
+        let target = registry.get_target_by_name("nrf51822_Xxaa").unwrap();
+        assert!(target.config_areas.is_empty());
+    }
+
     #[test]
     fn add_targets_with_and_without_scanchain() -> TestResult {
         let file = File::open("tests/scan_chain_test.yaml")?;
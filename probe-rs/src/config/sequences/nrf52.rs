@@ -1,6 +1,9 @@
 //! Sequences for Nrf52 devices
 
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use probe_rs_target::ConfigArea;
 
 use crate::architecture::arm::{
     ap::MemoryAp,
@@ -9,7 +12,9 @@ use crate::architecture::arm::{
     sequences::{ArmDebugSequence, ArmDebugSequenceError},
     ApAddress, ArmError, ArmProbeInterface, DpAddress,
 };
+use crate::flashing::ConfigAreaSequence;
 use crate::session::MissingPermissions;
+use crate::MemoryInterface;
 
 /// An error when operating a core ROM table component occurred.
 #[derive(thiserror::Error, Debug)]
@@ -28,6 +33,13 @@ const ERASEALL: u8 = 0x04;
 const ERASEALLSTATUS: u8 = 0x08;
 const APPROTECTSTATUS: u8 = 0x0C;
 
+/// The base address of the NVMC peripheral
+const NVMC: u64 = 0x4001_E000;
+const NVMC_READY: u64 = NVMC + 0x400;
+const NVMC_CONFIG: u64 = NVMC + 0x504;
+const NVMC_CONFIG_REN: u32 = 0;
+const NVMC_CONFIG_WEN: u32 = 1;
+
 /// Marker struct indicating initialization sequencing for nRF52 family parts.
 #[derive(Debug)]
 pub struct Nrf52 {}
@@ -127,6 +139,10 @@ impl ArmDebugSequence for Nrf52 {
         Err(ArmError::ReAttachRequired)
     }
 
+    fn config_area_sequence(&self) -> Option<Arc<dyn ConfigAreaSequence>> {
+        Some(Self::create())
+    }
+
     fn trace_start(
         &self,
         interface: &mut dyn ArmProbeInterface,
@@ -170,6 +186,49 @@ impl ArmDebugSequence for Nrf52 {
     }
 }
 
+impl Nrf52 {
+    fn wait_for_nvmc_ready(memory: &mut dyn MemoryInterface) -> Result<(), crate::Error> {
+        let start = Instant::now();
+        while memory.read_word_32(NVMC_READY)? & 1 == 0 {
+            if start.elapsed() > Duration::from_millis(100) {
+                return Err(crate::Error::Timeout);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ConfigAreaSequence for Nrf52 {
+    /// Writes a word of the UICR through the NVMC.
+    ///
+    /// Bits can only be cleared, setting a bit requires erasing the whole UICR.
+    fn write_config_word(
+        &self,
+        memory: &mut dyn MemoryInterface,
+        area: &ConfigArea,
+        address: u64,
+        value: u32,
+    ) -> Result<(), crate::Error> {
+        let current = memory.read_word_32(address)?;
+        if value & !current != 0 {
+            return Err(ArmError::from(ArmDebugSequenceError::custom(format!(
+                "Writing {value:#010x} to {address:#010x} requires erasing {}",
+                area.name
+            )))
+            .into());
+        }
+
+        memory.write_word_32(NVMC_CONFIG, NVMC_CONFIG_WEN)?;
+        Self::wait_for_nvmc_ready(memory)?;
+
+        memory.write_word_32(address, value)?;
+        let result = Self::wait_for_nvmc_ready(memory);
+
+        memory.write_word_32(NVMC_CONFIG, NVMC_CONFIG_REN)?;
+        result
+    }
+}
+
 impl From<ComponentError> for ArmError {
     fn from(value: ComponentError) -> ArmError {
         ArmError::DebugSequence(ArmDebugSequenceError::custom(value))
@@ -30,7 +30,7 @@ use crate::architecture::{
     xtensa::sequences::{DefaultXtensaSequence, XtensaDebugSequence},
 };
 use crate::flashing::FlashLoader;
-use probe_rs_target::{Architecture, BinaryFormat, ChipFamily, MemoryRange};
+use probe_rs_target::{Architecture, BinaryFormat, ChipFamily, ConfigArea, MemoryRange};
 use std::sync::Arc;
 
 /// This describes a complete target with a fixed chip model and variant.
@@ -61,6 +61,23 @@ pub struct Target {
     pub scan_chain: Option<Vec<ScanChainElement>>,
     /// The default executable format for the target.
     pub default_format: BinaryFormat,
+    /// The configuration areas of the target, such as option bytes, UICR or eFuses.
+    pub config_areas: Vec<ConfigArea>,
+}
+
+/// Returns the configuration areas of a chip, which consist of the ones of its family,
+/// extended by the fields of the chip's areas with the same name, and the chip's other areas.
+fn merge_config_areas(family_areas: &[ConfigArea], chip_areas: &[ConfigArea]) -> Vec<ConfigArea> {
+    let mut areas = family_areas.to_vec();
+
+    for chip_area in chip_areas {
+        match areas.iter_mut().find(|area| area.name == chip_area.name) {
+            Some(area) => area.fields.extend(chip_area.fields.iter().cloned()),
+            None => areas.push(chip_area.clone()),
+        }
+    }
+
+    areas
 }
 
 impl std::fmt::Debug for Target {
@@ -234,6 +251,7 @@ impl Target {
             rtt_scan_regions,
             scan_chain: chip.scan_chain.clone(),
             default_format: chip.default_binary_format.clone().unwrap_or_default(),
+            config_areas: merge_config_areas(&family.config_areas, &chip.config_areas),
         })
     }
 
@@ -21,7 +21,8 @@ use crate::{
 use anyhow::anyhow;
 pub use probe_rs_target::{Architecture, CoreAccessOptions};
 use probe_rs_target::{
-    ArmCoreAccessOptions, MemoryRange, RiscvCoreAccessOptions, XtensaCoreAccessOptions,
+    ArmCoreAccessOptions, MemoryRange, MemoryRegion, RiscvCoreAccessOptions,
+    XtensaCoreAccessOptions,
 };
 use scroll::Pread;
 use std::{
@@ -38,11 +39,13 @@ pub mod core_state;
 pub mod core_status;
 pub mod memory_mapped_registers;
 pub mod registers;
+mod software_breakpoints;
 
 pub use core_state::*;
 pub use core_status::*;
 pub use memory_mapped_registers::MemoryMappedRegister;
 pub use registers::*;
+pub(crate) use software_breakpoints::SoftwareBreakpoints;
 
 /// An struct for storing the current state of a core.
 #[derive(Debug, Clone)]
@@ -119,6 +122,63 @@ pub trait CoreInterface: MemoryInterface {
     /// Clears the breakpoint configured in unit `unit_index`.
     fn clear_hw_breakpoint(&mut self, unit_index: usize) -> Result<(), error::Error>;
 
+    /// Returns the number of available data watchpoint units of the core.
+    ///
+    /// On some architectures, watchpoints and hardware breakpoints are set using the
+    /// same units, in which case every unit in use by a breakpoint can not be used by
+    /// a watchpoint, and vice versa.
+    fn available_watchpoint_units(&mut self) -> Result<u32, error::Error> {
+        Err(Error::NotImplemented("watchpoints"))
+    }
+
+    /// Reads the watchpoints configured in the watchpoint units of the core.
+    /// A value of None in any position of the Vector indicates that the unit is unset/available.
+    fn watchpoints(&mut self) -> Result<Vec<Option<Watchpoint>>, error::Error> {
+        Err(Error::NotImplemented("watchpoints"))
+    }
+
+    /// Returns true if the watchpoint unit `unit_index` is in use by a hardware breakpoint.
+    ///
+    /// This can only be the case on architectures where watchpoints and hardware breakpoints
+    /// share the same units.
+    fn watchpoint_unit_in_use_by_breakpoint(
+        &mut self,
+        _unit_index: usize,
+    ) -> Result<bool, error::Error> {
+        Ok(false)
+    }
+
+    /// Returns true if the breakpoint unit `unit_index` is in use by a watchpoint.
+    ///
+    /// This can only be the case on architectures where watchpoints and hardware breakpoints
+    /// share the same units.
+    fn breakpoint_unit_in_use_by_watchpoint(
+        &mut self,
+        _unit_index: usize,
+    ) -> Result<bool, error::Error> {
+        Ok(false)
+    }
+
+    /// Sets a watchpoint on the `len` bytes starting at `address`, using unit `unit_index`.
+    /// The core halts as soon as the watched memory is accessed as described by `kind`.
+    ///
+    /// Returns [`Error::UnsupportedWatchpoint`] if the unit can not watch the given range,
+    /// e.g. because it is not aligned or too large.
+    fn set_watchpoint(
+        &mut self,
+        _unit_index: usize,
+        _address: u64,
+        _len: u64,
+        _kind: WatchpointKind,
+    ) -> Result<(), error::Error> {
+        Err(Error::NotImplemented("watchpoints"))
+    }
+
+    /// Clears the watchpoint configured in unit `unit_index`.
+    fn clear_watchpoint(&mut self, _unit_index: usize) -> Result<(), error::Error> {
+        Err(Error::NotImplemented("watchpoints"))
+    }
+
     /// Returns a list of all the registers of this core.
     fn registers(&self) -> &'static registers::CoreRegisters;
 
@@ -143,6 +203,17 @@ pub trait CoreInterface: MemoryInterface {
         Ok(())
     }
 
+    /// Make the core fetch the `len` instructions bytes at `address` from memory again, after
+    /// they were changed by the debugger, e.g. to set a software breakpoint.
+    fn invalidate_instruction_cache(
+        &mut self,
+        _address: u64,
+        _len: u64,
+    ) -> Result<(), error::Error> {
+        // Cores without an instruction cache have nothing to do.
+        Ok(())
+    }
+
     /// Get the `Architecture` of the Core.
     fn architecture(&self) -> Architecture;
 
@@ -621,6 +692,7 @@ pub fn exception_handler_for_core(core_type: CoreType) -> Box<dyn ExceptionInter
 /// to allow potential other shareholders of the session struct to grab a core handle too.
 pub struct Core<'probe> {
     inner: Box<dyn CoreInterface + 'probe>,
+    software_breakpoints: &'probe mut SoftwareBreakpoints,
 }
 
 impl<'probe> Core<'probe> {
@@ -630,9 +702,13 @@ impl<'probe> Core<'probe> {
     }
 
     /// Create a new [`Core`].
-    pub(crate) fn new(core: impl CoreInterface + 'probe) -> Core<'probe> {
+    pub(crate) fn new(
+        software_breakpoints: &'probe mut SoftwareBreakpoints,
+        core: impl CoreInterface + 'probe,
+    ) -> Core<'probe> {
         Self {
             inner: Box::new(core),
+            software_breakpoints,
         }
     }
 
@@ -645,7 +721,20 @@ impl<'probe> Core<'probe> {
     ) -> CombinedCoreState {
         let specific_state = SpecificCoreState::from_core_type(core_type);
 
-        match options {
+        // Software breakpoints can only be set in the RAM accessible by the core.
+        let core_name = &target.cores[id].name;
+        let ram = target
+            .memory_map
+            .iter()
+            .filter_map(|region| match region {
+                MemoryRegion::Ram(region) if region.cores.contains(core_name) => {
+                    Some(region.range.clone())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut combined_state = match options {
             CoreAccessOptions::Arm(options) => {
                 let DebugSequence::Arm(sequence) = target.debug_sequence.clone() else {
                     panic!(
@@ -677,7 +766,10 @@ impl<'probe> Core<'probe> {
                     specific_state,
                 }
             }
-        }
+        };
+
+        combined_state.core_state.software_breakpoints = SoftwareBreakpoints::new(ram);
+        combined_state
     }
 
     /// Returns the ID of this core.
@@ -708,6 +800,7 @@ impl<'probe> Core<'probe> {
     /// Continue to execute instructions.
     #[tracing::instrument(skip(self))]
     pub fn run(&mut self) -> Result<(), error::Error> {
+        self.step_over_sw_breakpoint()?;
         self.inner.run()
     }
 
@@ -732,7 +825,10 @@ impl<'probe> Core<'probe> {
     /// Steps one instruction and then enters halted state again.
     #[tracing::instrument(skip(self))]
     pub fn step(&mut self) -> Result<CoreInformation, error::Error> {
-        self.inner.step()
+        match self.step_over_sw_breakpoint()? {
+            Some(core_information) => Ok(core_information),
+            None => self.inner.step(),
+        }
     }
 
     /// Returns the current status of the core.
@@ -835,12 +931,13 @@ impl<'probe> Core<'probe> {
 
     /// Find the index of the next available HW breakpoint comparator.
     fn find_free_breakpoint_comparator_index(&mut self) -> Result<usize, error::Error> {
-        let mut next_available_hw_breakpoint = 0;
-        for breakpoint in self.inner.hw_breakpoints()? {
-            if breakpoint.is_none() {
-                return Ok(next_available_hw_breakpoint);
-            } else {
-                next_available_hw_breakpoint += 1;
+        for (unit_index, breakpoint) in self.inner.hw_breakpoints()?.into_iter().enumerate() {
+            if breakpoint.is_none()
+                && !self
+                    .inner
+                    .breakpoint_unit_in_use_by_watchpoint(unit_index)?
+            {
+                return Ok(unit_index);
             }
         }
         Err(error::Error::Other(anyhow!(
@@ -925,6 +1022,261 @@ impl<'probe> Core<'probe> {
         Ok(())
     }
 
+    /// Set a software breakpoint
+    ///
+    /// This function replaces the instruction at `address` with a breakpoint instruction,
+    /// after saving the original instruction so it can be restored later.
+    ///
+    /// Software breakpoints can only be set in RAM accessible by the core. The core executes the
+    /// original instruction when it is resumed or stepped on a software breakpoint.
+    #[tracing::instrument(skip(self))]
+    pub fn set_sw_breakpoint(&mut self, address: u64) -> Result<(), error::Error> {
+        if self.software_breakpoints.original(address).is_some() {
+            return Ok(());
+        }
+
+        let mut first_bytes = [0; 2];
+        if self.software_breakpoints.is_in_ram(address, 2) {
+            self.inner.read_8(address, &mut first_bytes)?;
+        }
+        let instruction_set = self.inner.instruction_set()?;
+        let instruction = software_breakpoints::breakpoint_instruction(
+            self.inner.core_type(),
+            instruction_set,
+            first_bytes,
+        );
+
+        if !self
+            .software_breakpoints
+            .is_in_ram(address, instruction.len() as u64)
+        {
+            return Err(error::Error::Other(anyhow!(
+                "Software breakpoints can only be set in RAM, but {:#010x} is not",
+                address
+            )));
+        }
+
+        if self.software_breakpoints.is_empty() {
+            self.inner.debug_on_sw_breakpoint(true)?;
+        }
+
+        let mut original = vec![0; instruction.len()];
+        self.inner.read_8(address, &mut original)?;
+
+        tracing::debug!(
+            "Setting SW breakpoint at {:#010x}, replacing {:02x?}",
+            address,
+            original
+        );
+
+        self.inner.write_8(address, instruction)?;
+        self.inner.flush()?;
+        self.inner
+            .invalidate_instruction_cache(address, instruction.len() as u64)?;
+        self.software_breakpoints.insert(address, original);
+
+        Ok(())
+    }
+
+    /// Clear a software breakpoint
+    ///
+    /// This function restores the original instruction at `address`, if there is a software breakpoint at that address.
+    #[tracing::instrument(skip(self))]
+    pub fn clear_sw_breakpoint(&mut self, address: u64) -> Result<(), error::Error> {
+        let Some(original) = self.software_breakpoints.remove(address) else {
+            return Err(error::Error::Other(anyhow!(
+                "No software breakpoint found at address {:#010x}",
+                address
+            )));
+        };
+
+        tracing::debug!("Clearing SW breakpoint at {:#010x}", address);
+
+        self.inner.write_8(address, &original)?;
+        self.inner.flush()?;
+        self.inner
+            .invalidate_instruction_cache(address, original.len() as u64)?;
+
+        Ok(())
+    }
+
+    /// Returns the addresses of all software breakpoints set on the core.
+    pub fn sw_breakpoints(&self) -> Vec<u64> {
+        self.software_breakpoints.addresses()
+    }
+
+    /// Clear all software breakpoints
+    ///
+    /// This function restores the original instructions of all software breakpoints set by probe-rs.
+    /// Also used as a helper function in [`Session::drop`](crate::session::Session).
+    #[tracing::instrument(skip(self))]
+    pub fn clear_all_sw_breakpoints(&mut self) -> Result<(), error::Error> {
+        for address in self.software_breakpoints.addresses() {
+            self.clear_sw_breakpoint(address)?;
+        }
+        Ok(())
+    }
+
+    /// Set a breakpoint
+    ///
+    /// Breakpoints in RAM are set as software breakpoints, all others use a hardware breakpoint unit.
+    pub fn set_breakpoint(&mut self, address: u64) -> Result<(), error::Error> {
+        if self.software_breakpoints.is_in_ram(address, 2) {
+            self.set_sw_breakpoint(address)
+        } else {
+            self.set_hw_breakpoint(address)
+        }
+    }
+
+    /// Clear a breakpoint set by [`Core::set_breakpoint`].
+    pub fn clear_breakpoint(&mut self, address: u64) -> Result<(), error::Error> {
+        if self.software_breakpoints.original(address).is_some() {
+            self.clear_sw_breakpoint(address)
+        } else {
+            self.clear_hw_breakpoint(address)
+        }
+    }
+
+    /// If the core is halted on a software breakpoint, executes the original instruction
+    /// and puts the breakpoint back in place.
+    ///
+    /// Returns the core information after the step, or `None` if the core was not halted on a software breakpoint.
+    fn step_over_sw_breakpoint(&mut self) -> Result<Option<CoreInformation>, error::Error> {
+        if self.software_breakpoints.is_empty() || !self.inner.core_halted()? {
+            return Ok(None);
+        }
+
+        let pc_id = self.inner.program_counter().id();
+        let pc_value = self.inner.read_core_reg(pc_id)?;
+        let pc: u64 = pc_value.try_into()?;
+        let Some(original) = self.software_breakpoints.original(pc).map(<[u8]>::to_vec) else {
+            return Ok(None);
+        };
+
+        tracing::debug!("Stepping over SW breakpoint at {:#010x}", pc);
+
+        let mut instruction = vec![0; original.len()];
+        self.inner.read_8(pc, &mut instruction)?;
+        self.inner.write_8(pc, &original)?;
+        self.inner.flush()?;
+        self.inner
+            .invalidate_instruction_cache(pc, original.len() as u64)?;
+
+        // Writing the program counter keeps the core from skipping the original instruction
+        // as if it was the breakpoint instruction.
+        self.inner.write_core_reg(pc_id, pc_value)?;
+        let result = self.inner.step();
+
+        self.inner.write_8(pc, &instruction)?;
+        self.inner.flush()?;
+        self.inner
+            .invalidate_instruction_cache(pc, instruction.len() as u64)?;
+
+        result.map(Some)
+    }
+
+    /// Returns the number of available data watchpoint units of the core.
+    pub fn available_watchpoint_units(&mut self) -> Result<u32, error::Error> {
+        self.inner.available_watchpoint_units()
+    }
+
+    /// Reads the watchpoints configured on the core.
+    ///
+    /// A value of None in any position of the Vector indicates that the unit is unset/available.
+    pub fn watchpoints(&mut self) -> Result<Vec<Option<Watchpoint>>, error::Error> {
+        self.inner.watchpoints()
+    }
+
+    /// Set a data watchpoint
+    ///
+    /// This function will try to set a watchpoint on the `len` bytes starting at `address`,
+    /// which halts the core as soon as they are accessed as described by `kind`.
+    /// A watchpoint which is already set at `address` is replaced.
+    ///
+    /// The amount of watchpoints which are supported, as well as the supported
+    /// alignment and length of the watched ranges, is chip specific.
+    #[tracing::instrument(skip(self))]
+    pub fn set_watchpoint(
+        &mut self,
+        address: u64,
+        len: u64,
+        kind: WatchpointKind,
+    ) -> Result<(), error::Error> {
+        let unit_index = self
+            .watchpoint_unit(address)?
+            .ok_or_else(|| error::Error::Other(anyhow!("No available watchpoint units")))?;
+
+        tracing::debug!(
+            "Trying to set watchpoint #{} on {} bytes at {:#010x}",
+            unit_index,
+            len,
+            address
+        );
+
+        self.inner.set_watchpoint(unit_index, address, len, kind)
+    }
+
+    /// Returns true if a watchpoint can be set at `address`, because a watchpoint unit is free,
+    /// or because there is a watchpoint at `address` already, which would be replaced.
+    pub fn can_set_watchpoint(&mut self, address: u64) -> Result<bool, error::Error> {
+        Ok(self.watchpoint_unit(address)?.is_some())
+    }
+
+    /// Returns the unit of the watchpoint at `address` if there is one, else the first free unit.
+    fn watchpoint_unit(&mut self, address: u64) -> Result<Option<usize>, error::Error> {
+        let watchpoints = self.inner.watchpoints()?;
+
+        if let Some(unit_index) = watchpoints
+            .iter()
+            .position(|wp| matches!(wp, Some(wp) if wp.address == address))
+        {
+            return Ok(Some(unit_index));
+        }
+
+        for (unit_index, watchpoint) in watchpoints.into_iter().enumerate() {
+            if watchpoint.is_none()
+                && !self
+                    .inner
+                    .watchpoint_unit_in_use_by_breakpoint(unit_index)?
+            {
+                return Ok(Some(unit_index));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Clear a data watchpoint
+    ///
+    /// This function will try to clear the watchpoint at `address` if there exists a watchpoint at that address.
+    #[tracing::instrument(skip(self))]
+    pub fn clear_watchpoint(&mut self, address: u64) -> Result<(), error::Error> {
+        let unit_index = self
+            .inner
+            .watchpoints()?
+            .iter()
+            .position(|wp| matches!(wp, Some(wp) if wp.address == address))
+            .ok_or_else(|| {
+                error::Error::Other(anyhow!("No watchpoint found at address {:#010x}", address))
+            })?;
+
+        self.inner.clear_watchpoint(unit_index)
+    }
+
+    /// Clear all data watchpoints
+    ///
+    /// This function will clear all watchpoints which are configured on the target,
+    /// regardless if they are set by probe-rs.
+    #[tracing::instrument(skip(self))]
+    pub fn clear_all_watchpoints(&mut self) -> Result<(), error::Error> {
+        for (unit_index, watchpoint) in self.inner.watchpoints()?.into_iter().enumerate() {
+            if watchpoint.is_some() {
+                self.inner.clear_watchpoint(unit_index)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Returns the architecture of the core.
     pub fn architecture(&self) -> Architecture {
         self.inner.architecture()
@@ -1082,6 +1434,42 @@ impl<'probe> CoreInterface for Core<'probe> {
         self.clear_all_hw_breakpoints()
     }
 
+    fn available_watchpoint_units(&mut self) -> Result<u32, error::Error> {
+        self.available_watchpoint_units()
+    }
+
+    fn watchpoints(&mut self) -> Result<Vec<Option<Watchpoint>>, error::Error> {
+        self.watchpoints()
+    }
+
+    fn watchpoint_unit_in_use_by_breakpoint(
+        &mut self,
+        unit_index: usize,
+    ) -> Result<bool, error::Error> {
+        self.inner.watchpoint_unit_in_use_by_breakpoint(unit_index)
+    }
+
+    fn breakpoint_unit_in_use_by_watchpoint(
+        &mut self,
+        unit_index: usize,
+    ) -> Result<bool, error::Error> {
+        self.inner.breakpoint_unit_in_use_by_watchpoint(unit_index)
+    }
+
+    fn set_watchpoint(
+        &mut self,
+        _unit_index: usize,
+        address: u64,
+        len: u64,
+        kind: WatchpointKind,
+    ) -> Result<(), error::Error> {
+        self.set_watchpoint(address, len, kind)
+    }
+
+    fn clear_watchpoint(&mut self, unit_index: usize) -> Result<(), error::Error> {
+        self.inner.clear_watchpoint(unit_index)
+    }
+
     fn registers(&self) -> &'static registers::CoreRegisters {
         self.registers()
     }
@@ -1165,3 +1553,95 @@ impl std::fmt::Debug for ResolvedCoreOptions {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::MockCore;
+
+    #[test]
+    fn breakpoints_and_watchpoints_share_units() {
+        let mut software_breakpoints = SoftwareBreakpoints::default();
+        let mut core = Core::new(
+            &mut software_breakpoints,
+            MockCore::new(0x2000_0000, 0x100, 2),
+        );
+
+        // The watchpoint takes the last unit, so the breakpoint gets the first one.
+        core.set_watchpoint(0x2000_0040, 4, WatchpointKind::Write)
+            .unwrap();
+        core.set_hw_breakpoint(0x0800_0000).unwrap();
+
+        // All units are in use now.
+        assert!(core.set_hw_breakpoint(0x0800_0010).is_err());
+        assert!(!core.can_set_watchpoint(0x2000_0080).unwrap());
+        assert!(core
+            .set_watchpoint(0x2000_0080, 4, WatchpointKind::Read)
+            .is_err());
+
+        // Clearing the watchpoint frees its unit for a breakpoint, without touching the other one.
+        core.clear_watchpoint(0x2000_0040).unwrap();
+        core.set_hw_breakpoint(0x0800_0010).unwrap();
+        assert_eq!(
+            core.inner.hw_breakpoints().unwrap(),
+            [Some(0x0800_0000), Some(0x0800_0010)]
+        );
+
+        // Likewise, clearing a breakpoint frees its unit for a watchpoint.
+        core.clear_hw_breakpoint(0x0800_0000).unwrap();
+        core.set_watchpoint(0x2000_0080, 4, WatchpointKind::Read)
+            .unwrap();
+        assert_eq!(
+            core.watchpoints().unwrap(),
+            [
+                None,
+                Some(Watchpoint {
+                    address: 0x2000_0080,
+                    len: 4,
+                    kind: WatchpointKind::Read,
+                })
+            ]
+        );
+    }
+
+    #[test]
+    fn step_over_sw_breakpoint() {
+        let mut software_breakpoints = SoftwareBreakpoints::new(vec![Range {
+            start: 0x2000_0000,
+            end: 0x2000_0100,
+        }]);
+        let mut mock = MockCore::new(0x2000_0000, 0x100, 2);
+        // addi a0, a0, 1; addi a0, a0, 1
+        mock.ram[0x10..0x18].copy_from_slice(&[0x13, 0x05, 0x15, 0x00, 0x13, 0x05, 0x15, 0x00]);
+        mock.pc = 0x2000_0010;
+        let invalidated = mock.invalidated.clone();
+        let mut core = Core::new(&mut software_breakpoints, mock);
+
+        // Breakpoints in RAM are software breakpoints.
+        core.set_breakpoint(0x2000_0010).unwrap();
+        assert_eq!(core.sw_breakpoints(), [0x2000_0010]);
+        assert_eq!(*invalidated.borrow(), [(0x2000_0010, 4)]);
+        assert_eq!(core.inner.hw_breakpoints().unwrap(), [None, None]);
+        let mut instruction = [0; 4];
+        core.read_8(0x2000_0010, &mut instruction).unwrap();
+        assert_eq!(instruction, [0x73, 0x00, 0x10, 0x00]);
+
+        // Stepping executes the original instruction, and puts the breakpoint back afterwards.
+        let info = core.step().unwrap();
+        assert_eq!(info.pc, 0x2000_0014);
+        core.read_8(0x2000_0010, &mut instruction).unwrap();
+        assert_eq!(instruction, [0x73, 0x00, 0x10, 0x00]);
+        // The core has to fetch the instruction again after each change.
+        assert_eq!(invalidated.borrow().len(), 3);
+
+        // Without a breakpoint at the program counter, the core is stepped as usual.
+        let info = core.step().unwrap();
+        assert_eq!(info.pc, 0x2000_0018);
+
+        core.clear_breakpoint(0x2000_0010).unwrap();
+        core.read_8(0x2000_0010, &mut instruction).unwrap();
+        assert_eq!(instruction, [0x13, 0x05, 0x15, 0x00]);
+        assert!(core.sw_breakpoints().is_empty());
+        assert_eq!(invalidated.borrow().last(), Some(&(0x2000_0010, 4)));
+    }
+}
@@ -11,7 +11,7 @@ use crate::{
     Core, CoreType, Error,
 };
 
-use super::ResolvedCoreOptions;
+use super::{software_breakpoints::SoftwareBreakpoints, ResolvedCoreOptions};
 
 #[derive(Debug)]
 pub(crate) struct CombinedCoreState {
@@ -45,34 +45,40 @@ impl CombinedCoreState {
                 ))
             }
         };
+        let software_breakpoints = &mut self.core_state.software_breakpoints;
 
         Ok(match &mut self.specific_state {
             SpecificCoreState::Armv6m(s) => Core::new(
+                software_breakpoints,
                 crate::architecture::arm::armv6m::Armv6m::new(memory, s, debug_sequence, self.id)?,
             ),
-            SpecificCoreState::Armv7a(s) => {
-                Core::new(crate::architecture::arm::armv7a::Armv7a::new(
+            SpecificCoreState::Armv7a(s) => Core::new(
+                software_breakpoints,
+                crate::architecture::arm::armv7a::Armv7a::new(
                     memory,
                     s,
                     options.debug_base.expect("base_address not specified"),
                     debug_sequence,
                     self.id,
-                )?)
-            }
+                )?,
+            ),
             SpecificCoreState::Armv7m(s) | SpecificCoreState::Armv7em(s) => Core::new(
+                software_breakpoints,
                 crate::architecture::arm::armv7m::Armv7m::new(memory, s, debug_sequence, self.id)?,
             ),
-            SpecificCoreState::Armv8a(s) => {
-                Core::new(crate::architecture::arm::armv8a::Armv8a::new(
+            SpecificCoreState::Armv8a(s) => Core::new(
+                software_breakpoints,
+                crate::architecture::arm::armv8a::Armv8a::new(
                     memory,
                     s,
                     options.debug_base.expect("base_address not specified"),
                     options.cti_base.expect("cti_address not specified"),
                     debug_sequence,
                     self.id,
-                )?)
-            }
+                )?,
+            ),
             SpecificCoreState::Armv8m(s) => Core::new(
+                software_breakpoints,
                 crate::architecture::arm::armv8m::Armv8m::new(memory, s, debug_sequence, self.id)?,
             ),
             _ => {
@@ -129,10 +135,20 @@ impl CombinedCoreState {
         &'probe mut self,
         interface: &'probe mut RiscvCommunicationInterface,
     ) -> Result<Core<'probe>, Error> {
+        let ResolvedCoreOptions::Riscv { options } = &self.core_state.core_access_options else {
+            return Err(Error::UnableToOpenProbe(
+                "Core architecture and Probe mismatch.",
+            ));
+        };
+        interface.select_hart(options.hart_id.unwrap_or(self.id as u32))?;
+
+        let software_breakpoints = &mut self.core_state.software_breakpoints;
+
         Ok(match &mut self.specific_state {
-            SpecificCoreState::Riscv(s) => Core::new(crate::architecture::riscv::Riscv32::new(
-                interface, s, self.id,
-            )),
+            SpecificCoreState::Riscv(s) => Core::new(
+                software_breakpoints,
+                crate::architecture::riscv::Riscv32::new(interface, s, self.id),
+            ),
             _ => {
                 return Err(Error::UnableToOpenProbe(
                     "Core architecture and Probe mismatch.",
@@ -145,10 +161,13 @@ impl CombinedCoreState {
         &'probe mut self,
         interface: &'probe mut XtensaCommunicationInterface,
     ) -> Result<Core<'probe>, Error> {
+        let software_breakpoints = &mut self.core_state.software_breakpoints;
+
         Ok(match &mut self.specific_state {
-            SpecificCoreState::Xtensa(s) => Core::new(crate::architecture::xtensa::Xtensa::new(
-                interface, s, self.id,
-            )),
+            SpecificCoreState::Xtensa(s) => Core::new(
+                software_breakpoints,
+                crate::architecture::xtensa::Xtensa::new(interface, s, self.id),
+            ),
             _ => {
                 return Err(Error::UnableToOpenProbe(
                     "Core architecture and Probe mismatch.",
@@ -172,6 +191,9 @@ impl CombinedCoreState {
 pub struct CoreState {
     /// Information needed to access the core
     core_access_options: ResolvedCoreOptions,
+
+    /// The software breakpoints set on the core, which have to outlive the [`Core`] handle.
+    pub(crate) software_breakpoints: SoftwareBreakpoints,
 }
 
 impl CoreState {
@@ -179,6 +201,7 @@ impl CoreState {
     pub fn new(core_access_options: ResolvedCoreOptions) -> Self {
         Self {
             core_access_options,
+            software_breakpoints: SoftwareBreakpoints::default(),
         }
     }
 
@@ -37,6 +37,57 @@ pub enum SemihostingCommand {
         /// Some architecture-specific or application specific exit code
         code: u64,
     },
+    /// SYS_OPEN: The target wants to open a file or the console.
+    Open {
+        /// The address of the argument block of the operation.
+        parameter: u32,
+    },
+    /// SYS_CLOSE: The target wants to close a file.
+    Close {
+        /// The address of the argument block of the operation.
+        parameter: u32,
+    },
+    /// SYS_WRITEC: The target wants to write a character to the console.
+    WriteC {
+        /// The address of the character.
+        parameter: u32,
+    },
+    /// SYS_WRITE0: The target wants to write a null-terminated string to the console.
+    Write0 {
+        /// The address of the string.
+        parameter: u32,
+    },
+    /// SYS_WRITE: The target wants to write to a file.
+    Write {
+        /// The address of the argument block of the operation.
+        parameter: u32,
+    },
+    /// SYS_READ: The target wants to read from a file.
+    Read {
+        /// The address of the argument block of the operation.
+        parameter: u32,
+    },
+    /// SYS_SEEK: The target wants to move the position in a file.
+    Seek {
+        /// The address of the argument block of the operation.
+        parameter: u32,
+    },
+    /// SYS_FLEN: The target wants to know the length of a file.
+    FileLen {
+        /// The address of the argument block of the operation.
+        parameter: u32,
+    },
+    /// SYS_CLOCK: The target wants to know its execution time in centiseconds.
+    Clock,
+    /// SYS_TIME: The target wants to know the number of seconds since the Unix epoch.
+    Time,
+    /// SYS_ERRNO: The target wants to know the error of the last failed operation.
+    Errno,
+    /// SYS_GET_CMDLINE: The target wants to know its command line.
+    GetCommandLine {
+        /// The address of the argument block of the operation.
+        parameter: u32,
+    },
     /// The target indicated that it would like to run a semihosting operation which we don't support yet
     Unknown {
         /// The semihosting operation requested
@@ -103,3 +154,25 @@ pub enum VectorCatchCondition {
     /// We encountered any exception.
     All,
 }
+
+/// The kind of data access which triggers a watchpoint.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum WatchpointKind {
+    /// Halt when the watched memory is read.
+    Read,
+    /// Halt when the watched memory is written.
+    Write,
+    /// Halt when the watched memory is read or written.
+    Access,
+}
+
+/// A data watchpoint configured in a watchpoint unit of a core.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Watchpoint {
+    /// The address of the first watched byte.
+    pub address: u64,
+    /// The number of watched bytes.
+    pub len: u64,
+    /// The kind of access which triggers the watchpoint.
+    pub kind: WatchpointKind,
+}
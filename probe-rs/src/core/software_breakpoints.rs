@@ -0,0 +1,157 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use crate::{CoreType, InstructionSet};
+
+/// The software breakpoints of a core.
+///
+/// A software breakpoint replaces the instruction at its address with a breakpoint instruction.
+/// The original instruction is kept here, so it can be restored when the breakpoint is cleared,
+/// or temporarily when the core has to execute it.
+#[derive(Debug, Default)]
+pub(crate) struct SoftwareBreakpoints {
+    /// The RAM ranges accessible by the core, which are the only places breakpoint
+    /// instructions are written to.
+    ram: Vec<Range<u64>>,
+    /// The original instructions which were replaced by breakpoint instructions, by address.
+    breakpoints: BTreeMap<u64, Vec<u8>>,
+}
+
+impl SoftwareBreakpoints {
+    pub(crate) fn new(ram: Vec<Range<u64>>) -> Self {
+        Self {
+            ram,
+            breakpoints: BTreeMap::new(),
+        }
+    }
+
+    /// Returns true if a breakpoint instruction of `len` bytes can be written to `address`.
+    pub(crate) fn is_in_ram(&self, address: u64, len: u64) -> bool {
+        self.ram
+            .iter()
+            .any(|range| range.start <= address && address.saturating_add(len) <= range.end)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.breakpoints.is_empty()
+    }
+
+    pub(crate) fn addresses(&self) -> Vec<u64> {
+        self.breakpoints.keys().copied().collect()
+    }
+
+    /// Returns the original instruction replaced by the breakpoint at `address`.
+    pub(crate) fn original(&self, address: u64) -> Option<&[u8]> {
+        self.breakpoints.get(&address).map(Vec::as_slice)
+    }
+
+    pub(crate) fn insert(&mut self, address: u64, original: Vec<u8>) {
+        self.breakpoints.insert(address, original);
+    }
+
+    pub(crate) fn remove(&mut self, address: u64) -> Option<Vec<u8>> {
+        self.breakpoints.remove(&address)
+    }
+}
+
+/// Returns the breakpoint instruction which replaces the instruction starting with `first_bytes`.
+///
+/// The length of the returned instruction is the number of bytes which have to be saved.
+pub(crate) fn breakpoint_instruction(
+    core_type: CoreType,
+    instruction_set: InstructionSet,
+    first_bytes: [u8; 2],
+) -> &'static [u8] {
+    // ARMv8-A only enters debug state on HLT, as BKPT and BRK are handled by the self-hosted debugger.
+    let armv8a = core_type == CoreType::Armv8a;
+
+    match instruction_set {
+        // BKPT #0 / HLT #0
+        InstructionSet::Thumb2 if armv8a => &[0x80, 0xBA],
+        InstructionSet::Thumb2 => &[0x00, 0xBE],
+        // BKPT #0 / HLT #0
+        InstructionSet::A32 if armv8a => &[0x70, 0x00, 0x00, 0xE1],
+        InstructionSet::A32 => &[0x70, 0x00, 0x20, 0xE1],
+        // HLT #0
+        InstructionSet::A64 => &[0x00, 0x00, 0x40, 0xD4],
+        // Compressed instructions are the ones whose lowest two bits are not set.
+        InstructionSet::RV32
+        | InstructionSet::RV32C
+        | InstructionSet::RV64
+        | InstructionSet::RV64C => {
+            if first_bytes[0] & 0b11 != 0b11 {
+                // C.EBREAK
+                &[0x02, 0x90]
+            } else {
+                // EBREAK
+                &[0x73, 0x00, 0x10, 0x00]
+            }
+        }
+        // Narrow instructions have an op0 of 8 or higher.
+        InstructionSet::Xtensa => {
+            if first_bytes[0] & 0x0F >= 8 {
+                // BREAK.N 1
+                &[0x2D, 0xF1]
+            } else {
+                // BREAK 1, 15
+                &[0xF0, 0x41, 0x00]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ram_ranges() {
+        let breakpoints =
+            SoftwareBreakpoints::new(vec![0x1000_0000..0x1000_1000, 0x2000_0000..0x2000_1000]);
+
+        assert!(breakpoints.is_in_ram(0x1000_0000, 4));
+        assert!(breakpoints.is_in_ram(0x2000_0000, 2));
+        assert!(breakpoints.is_in_ram(0x2000_0FFE, 2));
+        assert!(!breakpoints.is_in_ram(0x2000_0FFE, 4));
+        assert!(!breakpoints.is_in_ram(0x0800_0000, 2));
+    }
+
+    #[test]
+    fn riscv_breakpoint_instructions() {
+        // c.addi a0, 1
+        assert_eq!(
+            breakpoint_instruction(CoreType::Riscv, InstructionSet::RV32C, [0x05, 0x05]),
+            &[0x02, 0x90]
+        );
+        // addi a0, a0, 1
+        assert_eq!(
+            breakpoint_instruction(CoreType::Riscv, InstructionSet::RV32C, [0x13, 0x05]),
+            &[0x73, 0x00, 0x10, 0x00]
+        );
+    }
+
+    #[test]
+    fn xtensa_breakpoint_instructions() {
+        // mov.n a2, a3
+        assert_eq!(
+            breakpoint_instruction(CoreType::Xtensa, InstructionSet::Xtensa, [0x2D, 0x03]),
+            &[0x2D, 0xF1]
+        );
+        // l32i a2, a1, 0
+        assert_eq!(
+            breakpoint_instruction(CoreType::Xtensa, InstructionSet::Xtensa, [0x22, 0x21]),
+            &[0xF0, 0x41, 0x00]
+        );
+    }
+
+    #[test]
+    fn arm_breakpoint_instructions() {
+        assert_eq!(
+            breakpoint_instruction(CoreType::Armv7em, InstructionSet::Thumb2, [0, 0]),
+            &[0x00, 0xBE]
+        );
+        assert_eq!(
+            breakpoint_instruction(CoreType::Armv8a, InstructionSet::A64, [0, 0]),
+            &[0x00, 0x00, 0x40, 0xD4]
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use super::ObjectRef;
 use super::{
     function_die::FunctionDie, get_object_reference, unit_info::UnitInfo, variable::*, DebugError,
-    DebugRegisters, SourceLocation, StackFrame, VariableCache,
+    DebugRegisters, EvaluatedExpression, Expression, SourceLocation, StackFrame, VariableCache,
 };
 use crate::core::UnwindRule;
 use crate::debug::source_statement::SourceStatement;
@@ -43,7 +43,20 @@ pub struct VerifiedBreakpoint {
 }
 
 /// Debug information which is parsed from DWARF debugging information.
+///
+/// The debug information can be loaded from several object files, e.g. a bootloader and an application.
+/// Addresses are looked up in the object file which contains the code at that address.
 pub struct DebugInfo {
+    /// The debug information of each of the object files. There is always at least one.
+    pub(crate) objects: Vec<ObjectDebugInfo>,
+}
+
+/// The debug information of one of the object files in [`DebugInfo`].
+///
+/// Code addresses are used as they appear in the object file, unless noted otherwise.
+/// Register values and memory contents are addresses in target memory,
+/// where the object file is loaded `address_offset` bytes after the addresses it is linked at.
+pub(crate) struct ObjectDebugInfo {
     pub(crate) dwarf: gimli::Dwarf<DwarfReader>,
     pub(crate) frame_section: gimli::DebugFrame<DwarfReader>,
     pub(crate) locations_section: gimli::LocationLists<DwarfReader>,
@@ -51,6 +64,9 @@ pub struct DebugInfo {
     pub(crate) debug_line_section: gimli::DebugLine<DwarfReader>,
 
     pub(crate) unit_infos: Vec<UnitInfo>,
+
+    /// The offset which is added to the addresses in the object file, to get the addresses in target memory.
+    pub(crate) address_offset: u64,
 }
 
 impl DebugInfo {
@@ -63,50 +79,63 @@ impl DebugInfo {
 
     /// Parse debug information directly from a buffer containing an ELF file.
     pub fn from_raw(data: &[u8]) -> Result<Self, DebugError> {
-        let object = object::File::parse(data)?;
-
-        // Load a section and return as `Cow<[u8]>`.
-        let load_section = |id: gimli::SectionId| -> Result<DwarfReader, gimli::Error> {
-            let data = object
-                .section_by_name(id.name())
-                .and_then(|section| section.uncompressed_data().ok())
-                .unwrap_or_else(|| borrow::Cow::Borrowed(&[][..]));
+        Ok(DebugInfo {
+            objects: vec![ObjectDebugInfo::from_raw(data, 0)?],
+        })
+    }
 
-            Ok(gimli::read::EndianRcSlice::new(
-                Rc::from(&*data),
-                gimli::LittleEndian,
-            ))
-        };
+    /// Add the debug information of another ELF file, e.g. the application when the first file is
+    /// the bootloader, or the symbols of the ROM of the chip.
+    ///
+    /// The code of the ELF file is loaded `address_offset` bytes after the addresses it is linked at.
+    pub fn add_file<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        address_offset: u64,
+    ) -> Result<(), DebugError> {
+        let data = std::fs::read(path)?;
 
-        // Load all of the sections.
-        let dwarf_cow = gimli::Dwarf::load(&load_section)?;
+        self.add_raw(&data, address_offset)
+    }
 
-        use gimli::Section;
-        let frame_section = gimli::DebugFrame::load(load_section)?;
-        let address_section = gimli::DebugAddr::load(load_section)?;
-        let debug_loc = gimli::DebugLoc::load(load_section)?;
-        let debug_loc_lists = gimli::DebugLocLists::load(load_section)?;
-        let locations_section = gimli::LocationLists::new(debug_loc, debug_loc_lists);
-        let debug_line_section = gimli::DebugLine::load(load_section)?;
+    /// Add the debug information of another ELF file, from a buffer containing the file.
+    ///
+    /// See [`DebugInfo::add_file`] for details.
+    pub fn add_raw(&mut self, data: &[u8], address_offset: u64) -> Result<(), DebugError> {
+        self.objects
+            .push(ObjectDebugInfo::from_raw(data, address_offset)?);
+        Ok(())
+    }
 
-        let mut unit_infos = Vec::new();
+    /// Find the object file which contains the code at `address` in target memory.
+    ///
+    /// If none of the object files contains the address, the first object file is used.
+    pub(crate) fn object_for_address(&self, address: u64) -> &ObjectDebugInfo {
+        // There is always at least one object file.
+        let first_object = &self.objects[0];
+        if self.objects.len() == 1 {
+            return first_object;
+        }
 
-        let mut iter = dwarf_cow.units();
+        self.objects
+            .iter()
+            .find(|object| {
+                address >= object.address_offset
+                    && object.contains_address(object.object_address(address))
+            })
+            .unwrap_or(first_object)
+    }
 
-        while let Ok(Some(header)) = iter.next() {
-            if let Ok(unit) = dwarf_cow.unit(header) {
-                unit_infos.push(UnitInfo::new(unit));
-            };
+    /// Find the object file which contains the code of the stack frame with `registers`.
+    pub(crate) fn object_for_registers(&self, registers: &DebugRegisters) -> &ObjectDebugInfo {
+        match registers
+            .get_program_counter()
+            .and_then(|register| register.value)
+            .and_then(|value| value.try_into().ok())
+        {
+            Some(program_counter) => self.object_for_address(program_counter),
+            None => &self.objects[0],
         }
-
-        Ok(DebugInfo {
-            dwarf: dwarf_cow,
-            frame_section,
-            locations_section,
-            address_section,
-            debug_line_section,
-            unit_infos,
-        })
     }
 
     /// Get the name of the function at the given address.
@@ -116,849 +145,1091 @@ impl DebugInfo {
     /// ## Inlined functions
     /// Multiple nested inline functions could exist at the given address.
     /// This function will currently return the innermost function in that case.
-    // TODO: This function takes a memory interface. This seems odd, but gimly sometimes needs to read memory to resolve.
-    // Maybe this can be factored out if we can be sure that memory is never read for this usecase.
-    // Until we have more tests we cannot be sure tho and it should stay like this.
     pub fn function_name(
         &self,
         address: u64,
         find_inlined: bool,
     ) -> Result<Option<String>, DebugError> {
-        for unit_info in &self.unit_infos {
-            let mut functions = unit_info.get_function_dies(self, address, find_inlined)?;
+        let object = self.object_for_address(address);
+        object.function_name(object.object_address(address), find_inlined)
+    }
 
-            // Use the last functions from the list, this is the function which most closely
-            // corresponds to the PC in case of multiple inlined functions.
-            if let Some(die_cursor_state) = functions.pop() {
-                let function_name = die_cursor_state.function_name(self);
+    /// Try get the [`SourceLocation`] for a given address.
+    pub fn get_source_location(&self, address: u64) -> Option<SourceLocation> {
+        let object = self.object_for_address(address);
+        object
+            .get_source_location(object.object_address(address))
+            .map(|location| object.target_source_location(location))
+    }
 
-                if function_name.is_some() {
-                    return Ok(function_name);
-                }
-            }
-        }
+    /// This effects the on-demand expansion of lazy/deferred load of all the 'child' `Variable`s for a given 'parent'.
+    pub fn cache_deferred_variables(
+        &self,
+        cache: &mut VariableCache,
+        memory: &mut dyn MemoryInterface,
+        parent_variable: &mut Variable,
+        stack_frame_registers: &DebugRegisters,
+        frame_base: Option<u64>,
+    ) -> Result<(), DebugError> {
+        self.object_for_registers(stack_frame_registers)
+            .cache_deferred_variables(
+                cache,
+                memory,
+                parent_variable,
+                stack_frame_registers,
+                frame_base,
+            )
+    }
 
-        Ok(None)
+    /// Evaluates an expression like `a.b[3]`, `*p` or `*(u32*)0x2000_0000` in the context of `stack_frame`.
+    ///
+    /// See [`Expression`] for the supported syntax.
+    pub fn evaluate_expression(
+        &self,
+        memory: &mut dyn MemoryInterface,
+        stack_frame: &mut StackFrame,
+        expression: &str,
+    ) -> Result<EvaluatedExpression, DebugError> {
+        expression
+            .parse::<Expression>()?
+            .evaluate(self, memory, stack_frame)
     }
 
-    /// Try get the [`SourceLocation`] for a given address.
-    pub fn get_source_location(&self, address: u64) -> Option<SourceLocation> {
-        for unit_info in &self.unit_infos {
-            let unit = &unit_info.unit;
+    /// Returns a populated (resolved) [`StackFrame`] struct, using the object file which contains `address`.
+    pub(crate) fn get_stackframe_info(
+        &self,
+        memory: &mut impl MemoryInterface,
+        address: u64,
+        unwind_registers: &registers::DebugRegisters,
+    ) -> Result<Vec<StackFrame>, DebugError> {
+        self.object_for_address(address)
+            .get_stackframe_info(memory, address, unwind_registers)
+    }
 
-            let mut ranges = match self.dwarf.unit_ranges(unit) {
-                Ok(ranges) => ranges,
-                Err(error) => {
-                    tracing::warn!(
-                        "No valid source code ranges found for address {}: {:?}",
-                        address,
-                        error
+    /// Performs the logical unwind of the stack and returns a `Vec<StackFrame>`
+    /// - The first 'StackFrame' represents the frame at the current PC (program counter), and ...
+    /// - Each subsequent `StackFrame` represents the **previous or calling** `StackFrame` in the call stack.
+    /// - The majority of the work happens in the `'unwind: while` loop, where each iteration will create a `StackFrame` where possible, and update the `unwind_registers` to prepare for the next iteration.
+    ///
+    /// The unwind loop will continue until we meet one of the following conditions:
+    /// - We can no longer unwind a valid PC value to be used for the next frame.
+    /// - We encounter a LR register value of 0x0 or 0xFFFFFFFF(Arm 'Reset' value for that register).
+    /// - We can not intelligently calculate a valid LR register value from the other registers, or the gimli::RegisterRule result is a value of 0x0. Note: [DWARF](https://dwarfstd.org) 6.4.4 - CIE defines the return register address used in the `gimli::RegisterRule` tables for unwind operations. Theoretically, if we encounter a function that has `Undefined` `gimli::RegisterRule` for the return register address, it means we have reached the bottom of the stack OR the function is a 'no return' type of function. I have found actual examples (e.g. local functions) where we get `Undefined` for register rule when we cannot apply this logic. Example 1: local functions in main.rs will have LR rule as `Undefined`. Example 2: main()-> ! that is called from a trampoline will have a valid LR rule.
+    /// - Similarly, certain error conditions encountered in `StackFrameIterator` will also break out of the unwind loop.
+    /// Note: In addition to populating the `StackFrame`s, this function will also populate the `DebugInfo::VariableCache` with `Variable`s for available Registers as well as static and function variables.
+    /// TODO: Separate logic for stackframe creation and cache population
+    pub fn unwind(
+        &self,
+        core: &mut impl MemoryInterface,
+        initial_registers: DebugRegisters,
+        exception_handler: &dyn ExceptionInterface,
+        instruction_set: Option<InstructionSet>,
+    ) -> Result<Vec<StackFrame>, crate::Error> {
+        self.unwind_impl(initial_registers, core, exception_handler, instruction_set)
+    }
+
+    pub(crate) fn unwind_impl(
+        &self,
+        initial_registers: registers::DebugRegisters,
+        memory: &mut impl MemoryInterface,
+        exception_handler: &dyn ExceptionInterface,
+        instruction_set: Option<InstructionSet>,
+    ) -> Result<Vec<StackFrame>, crate::Error> {
+        let mut stack_frames = Vec::<StackFrame>::new();
+
+        let mut unwind_context: Box<UnwindContext<DwarfReader>> =
+            Box::new(gimli::UnwindContext::new());
+
+        let mut unwind_registers = initial_registers;
+
+        // Unwind [StackFrame]'s for as long as we can unwind a valid PC value.
+        'unwind: while let Some(frame_pc_register_value) = unwind_registers
+            .get_program_counter()
+            .and_then(|pc| pc.value)
+        {
+            // PART 0: The first step is to determine the exception context for the current PC.
+            // - If we are at an exception hanlder frame, we need to overwrite the unwind registers with the exception context.
+            // - If for some reason we cannot determine the exception context, we silently continue with the rest of the unwind.
+            // At worst, the unwind will be able to unwind the stack to the frame of the most recent exception handler.
+            let exception_info = match exception_handler
+                .exception_details(memory, &unwind_registers)
+            {
+                Ok(Some(exception_info)) => {
+                    tracing::trace!(
+                        "UNWIND: Found exception context: {}",
+                        exception_info.description
                     );
-                    continue;
+                    Some(exception_info)
+                }
+                Ok(None) => {
+                    tracing::trace!(
+                        "UNWIND: No exception context found. Stack unwind will continue."
+                    );
+                    None
+                }
+                Err(e) => {
+                    tracing::warn!("UNWIND: Error while checking for exception context. The stack trace will not include the calling frames. : {}", e);
+                    None
                 }
             };
 
-            while let Ok(Some(range)) = ranges.next() {
-                if !(range.begin <= address && address < range.end) {
-                    continue;
-                }
-                // Get the function name.
+            // PART 1: Construct the `StackFrame` for the current pc.
+            let frame_pc = frame_pc_register_value
+                .try_into()
+                .map_err(|error| crate::Error::Register(format!("Cannot convert register value for program counter to a 64-bit integer value: {:?}", error)))?;
+            tracing::trace!(
+                "UNWIND: Will generate `StackFrame` for function at address (PC) {:#}",
+                frame_pc_register_value
+            );
 
-                let ilnp = unit.line_program.as_ref()?.clone();
+            // PART 1-a: Prepare the `StackFrame` that holds the current frame information.
 
-                let (program, sequences) = match ilnp.sequences() {
-                    Ok(value) => value,
-                    Err(error) => {
-                        tracing::warn!(
-                            "No valid source code ranges found for address {}: {:?}",
-                            address,
-                            error
+            let mut cached_stack_frames =
+                match self.get_stackframe_info(memory, frame_pc, &unwind_registers) {
+                    Ok(cached_stack_frames) => cached_stack_frames,
+                    Err(e) => {
+                        tracing::error!(
+                            "UNWIND: Unable to complete `StackFrame` information: {}",
+                            e
                         );
-                        continue;
+                        // There is no point in continuing with the unwind, so let's get out of here.
+                        break;
                     }
                 };
 
-                // Normalize the address.
-                let mut target_seq = None;
+            while cached_stack_frames.len() > 1 {
+                // If we encountered INLINED functions (all `StackFrames`s in this Vec, except for the last one, which is the containing NON-INLINED function), these are simply added to the list of stack_frames we return.
+                #[allow(clippy::unwrap_used)]
+                let inlined_frame = cached_stack_frames.pop().unwrap(); // unwrap is safe while .len() > 1
+                tracing::trace!(
+                    "UNWIND: Found inlined function - name={}, pc={}",
+                    inlined_frame.function_name,
+                    inlined_frame.pc
+                );
+                stack_frames.push(inlined_frame);
+            }
 
-                for seq in sequences {
-                    if seq.start <= address && address < seq.end {
-                        target_seq = Some(seq);
-                        break;
-                    }
-                }
+            let mut only_exception = false;
 
-                let Some(target_seq) = target_seq.as_ref() else {
-                    continue;
-                };
+            let mut return_frame = match cached_stack_frames.pop() {
+                Some(frame) => frame,
+                None => {
+                    if let Some(exception_info) = &exception_info {
+                        only_exception = true;
+                        let address = frame_pc;
 
-                let mut previous_row: Option<gimli::LineRow> = None;
+                        let previous_regs = unwind_registers.clone();
 
-                let mut rows = program.resume_from(target_seq);
+                        StackFrame {
+                            id: get_object_reference(),
+                            function_name: exception_info.description.clone(),
+                            source_location: None,
+                            registers: previous_regs,
+                            pc: match unwind_registers.get_address_size_bytes() {
+                                4 => RegisterValue::U32(address as u32),
+                                8 => RegisterValue::U64(address),
+                                _ => RegisterValue::from(address),
+                            },
+                            frame_base: None,
+                            is_inlined: false,
+                            static_variables: None,
+                            local_variables: None,
+                        }
+                    } else {
+                        let address = frame_pc;
 
-                while let Ok(Some((header, row))) = rows.next_row() {
-                    match row.address().cmp(&address) {
-                        Ordering::Greater => {
-                            // The address is after the current row, so we use the previous row data.
-                            //
-                            // (If we don't do this, you get the artificial effect where the debugger
-                            // steps to the top of the file when it is steppping out of a function.)
-                            if let Some(previous_row) = previous_row {
-                                if let Some(file_entry) = previous_row.file(header) {
-                                    if let Some((file, directory)) =
-                                        self.find_file_and_directory(unit, header, file_entry)
-                                    {
-                                        tracing::debug!("{} - {:?}", address, previous_row.isa());
-                                        return Some(SourceLocation {
-                                            line: previous_row.line().map(NonZeroU64::get),
-                                            column: Some(previous_row.column().into()),
-                                            file,
-                                            directory,
-                                            low_pc: Some(target_seq.start as u32),
-                                            high_pc: Some(target_seq.end as u32),
-                                        });
-                                    }
-                                }
-                            }
-                        }
-                        Ordering::Less => {}
-                        Ordering::Equal => {
-                            if let Some(file_entry) = row.file(header) {
-                                if let Some((file, directory)) =
-                                    self.find_file_and_directory(unit, header, file_entry)
-                                {
-                                    tracing::debug!("{} - {:?}", address, row.isa());
+                        // When reporting the address, we format it as a hex string, with the width matching
+                        // the configured size of the datatype used in the `RegisterValue` address.
+                        let unknown_function = format!(
+                            "<unknown function @ {:#0width$x}>",
+                            address,
+                            width = (unwind_registers.get_address_size_bytes() * 2 + 2)
+                        );
 
-                                    return Some(SourceLocation {
-                                        line: row.line().map(NonZeroU64::get),
-                                        column: Some(row.column().into()),
-                                        file,
-                                        directory,
-                                        low_pc: Some(target_seq.start as u32),
-                                        high_pc: Some(target_seq.end as u32),
-                                    });
-                                }
-                            }
+                        StackFrame {
+                            id: get_object_reference(),
+                            function_name: unknown_function,
+                            source_location: self.get_source_location(address),
+                            registers: unwind_registers.clone(),
+                            pc: match unwind_registers.get_address_size_bytes() {
+                                4 => RegisterValue::U32(address as u32),
+                                8 => RegisterValue::U64(address),
+                                _ => RegisterValue::from(address),
+                            },
+                            frame_base: None,
+                            is_inlined: false,
+                            static_variables: None,
+                            local_variables: None,
                         }
                     }
-                    previous_row = Some(*row);
                 }
-            }
-        }
-        None
-    }
-
-    /// We do not actually resolve the children of `[VariableName::StaticScope]` automatically, and only create the necessary header in the `VariableCache`.
-    /// This allows us to resolve the `[VariableName::StaticScope]` on demand/lazily, when a user requests it from the debug client.
-    /// This saves a lot of overhead when a user only wants to see the `[VariableName::LocalScope]` or `[VariableName::Registers]` while stepping through code (the most common use cases)
-    pub(crate) fn create_static_scope_cache(
-        &self,
-        unit_info: &UnitInfo,
-    ) -> Result<VariableCache, gimli::Error> {
-        // Only process statics for this unit header.
-        let abbrevs = &unit_info.unit.abbreviations;
-        // Navigate the current unit from the header down.
-        let mut header_tree = unit_info.unit.header.entries_tree(abbrevs, None)?;
-        let unit_node = header_tree.root()?;
-
-        Ok(VariableCache::new_dwarf_cache(
-            unit_info.unit.header.offset(),
-            unit_node.entry().offset(),
-            VariableName::StaticScopeRoot,
-        ))
-    }
-
-    /// Creates the unpopulated cache for `function` variables
-    pub(crate) fn create_function_scope_cache(
-        &self,
-        die_cursor_state: &FunctionDie,
-        unit_info: &UnitInfo,
-    ) -> Result<VariableCache, DebugError> {
-        let abbrevs = &unit_info.unit.abbreviations;
-        let mut tree = unit_info
-            .unit
-            .header
-            .entries_tree(abbrevs, Some(die_cursor_state.function_die.offset()))?;
-        let function_node = tree.root()?;
-
-        let function_variable_cache = VariableCache::new_dwarf_cache(
-            unit_info.unit.header.offset(),
-            function_node.entry().offset(),
-            VariableName::LocalScopeRoot,
-        );
-
-        Ok(function_variable_cache)
-    }
-
-    /// This effects the on-demand expansion of lazy/deferred load of all the 'child' `Variable`s for a given 'parent'.
-    pub fn cache_deferred_variables(
-        &self,
-        cache: &mut VariableCache,
-        memory: &mut dyn MemoryInterface,
-        parent_variable: &mut Variable,
-        stack_frame_registers: &DebugRegisters,
-        frame_base: Option<u64>,
-    ) -> Result<(), DebugError> {
-        if !parent_variable.is_valid() {
-            // Do nothing. The parent_variable.get_value() will already report back the debug_error value.
-            return Ok(());
-        }
-
-        // Only attempt this part if we have not yet resolved the referenced children.
-        if cache.has_children(parent_variable)? {
-            return Ok(());
-        }
+            };
 
-        let Some(header_offset) = parent_variable.unit_header_offset else {
-            return Ok(());
-        };
+            // Part 1-b: Check LR values to determine if we can continue unwinding.
+            let Some(check_return_address) = unwind_registers.get_return_address() else {
+                // If the debug info rules result in a None return address, we cannot continue unwinding.
+                stack_frames.push(return_frame);
+                tracing::trace!("UNWIND: Stack unwind complete - LR register value is 'None.");
+                break;
+            };
 
-        let unit_header = self.dwarf.debug_info.header_from_offset(header_offset)?;
-        let unit_info = UnitInfo {
-            unit: gimli::Unit::new(&self.dwarf, unit_header)?,
-        };
+            if check_return_address.is_max_value() || check_return_address.is_zero() {
+                // When we encounter the starting (after reset) return address, we've reached the bottom of the stack, so no more unwinding after this.
+                stack_frames.push(return_frame);
+                tracing::trace!(
+                    "UNWIND: Stack unwind complete - Reached the 'Reset' value of the LR register."
+                );
+                break;
+            }
 
-        match parent_variable.variable_node_type {
-            VariableNodeType::ReferenceOffset(reference_offset) => {
-                // Reference to a type, or an node.entry() to another type or a type modifier which will point to another type.
-                let mut type_tree = unit_info
-                    .unit
-                    .header
-                    .entries_tree(&unit_info.unit.abbreviations, Some(reference_offset))?;
-                let referenced_node = type_tree.root()?;
-                let mut referenced_variable = cache.create_variable(
-                    parent_variable.variable_key,
-                    unit_info.unit.header.offset().as_debug_info_offset(),
-                    Some(referenced_node.entry().offset()),
-                )?;
+            // Part 1-c: If the target current frame is an exception handler, we need to update the `unwind_registers` to match the frame that invoked the exception handler.
+            if let Some(exception_info) = exception_info {
+                tracing::trace!(
+                    "UNWIND: Stack unwind reached an exception handler {}",
+                    exception_info.description
+                );
 
-                referenced_variable.name = match &parent_variable.name {
-                    VariableName::Named(name) if name.starts_with("Some ") => VariableName::Named(name.replacen('&', "*", 1)) ,
-                    VariableName::Named(name) => VariableName::Named(format!("*{name}")),
-                    other => VariableName::Named(format!("Error: Unable to generate name, parent variable does not have a name but is special variable {other:?}")),
-                };
+                tracing::trace!(
+                    "UNWIND: Stack unwind will attempt to unwind the frame that invoked {}.",
+                    exception_info.description
+                );
 
-                referenced_variable = unit_info.extract_type(
-                    self,
-                    referenced_node,
-                    parent_variable,
-                    referenced_variable,
-                    memory,
-                    stack_frame_registers,
-                    frame_base,
-                    cache,
-                )?;
+                // Now that we've optionally updated the `unwind_registers` to match the exception handler, we can continue.
+                if only_exception {
+                    // If we are at an exception handler frame, we need to overwrite the unwind registers.
+                    // This will allow us to continue unwinding from the exception handler frame.
+                    unwind_registers = exception_info.calling_frame_registers;
 
-                if referenced_variable.type_name == VariableType::Base("()".to_owned()) {
-                    // Only use this, if it is NOT a unit datatype.
-                    cache.remove_cache_entry(referenced_variable.variable_key)?;
+                    stack_frames.push(return_frame);
+                    continue;
                 }
             }
-            VariableNodeType::TypeOffset(type_offset) => {
-                // Find the parent node
-                let mut type_tree = unit_info
-                    .unit
-                    .header
-                    .entries_tree(&unit_info.unit.abbreviations, Some(type_offset))?;
-                let parent_node = type_tree.root()?;
-
-                // For process_tree we need to create a temporary parent that will later be eliminated with VariableCache::adopt_grand_children
-                // TODO: Investigate if UnitInfo::process_tree can be modified to use `&mut parent_variable`, then we would not need this temporary variable.
-                let mut temporary_variable = parent_variable.clone();
-                temporary_variable.variable_key = ObjectRef::Invalid;
-                temporary_variable.parent_key = parent_variable.variable_key;
-                cache.add_variable(parent_variable.variable_key, &mut temporary_variable)?;
-
-                temporary_variable = unit_info.process_tree(
-                    self,
-                    parent_node,
-                    temporary_variable,
-                    memory,
-                    stack_frame_registers,
-                    frame_base,
-                    cache,
-                )?;
 
-                cache.adopt_grand_children(parent_variable, &temporary_variable)?;
-            }
-            VariableNodeType::DirectLookup => {
-                // Find the parent node
-                let mut type_tree = unit_info.unit.header.entries_tree(
-                    &unit_info.unit.abbreviations,
-                    parent_variable.variable_unit_offset,
-                )?;
+            // PART 2: Setup the registers for the next iteration (a.k.a. unwind previous frame, a.k.a. "callee", in the call stack).
+            tracing::trace!(
+                "UNWIND - Preparing `StackFrameIterator` to unwind NON-INLINED function {:?} at {:?}",
+                return_frame.function_name,
+                return_frame.source_location
+            );
+            // PART 2-a: get the `gimli::FrameDescriptorEntry` for this address and then the unwind info associated with this row.
+            let object = self.object_for_address(frame_pc);
+            let unwind_info = match get_unwind_info(
+                &mut unwind_context,
+                &object.frame_section,
+                object.object_address(frame_pc),
+            ) {
+                Ok(unwind_info) => unwind_info,
+                Err(error) => {
+                    // We cannot do stack unwinding if we do not have debug info. However, there is one case where we can continue. When the following conditions are met:
+                    // 1. The current frame is the first frame in the stack, AND ...
+                    // 2. The frame registers have a valid return address/LR value.
+                    // If both these conditions are met, we can push the 'unknown function' to the list of stack frames, and use the LR value to calculate the PC for the calling frame.
+                    // The current logic will then use that PC to get the next frame's unwind info, and if that exists, will be able to continue unwinding.
+                    // If the calling frame has no debug info, then the unwindindg will end with that frame.
+                    if stack_frames.is_empty() {
+                        let callee_frame_registers = unwind_registers.clone();
+                        let mut unwound_return_address: Option<RegisterValue> =
+                            callee_frame_registers
+                                .get_return_address()
+                                .and_then(|lr| lr.value);
 
-                // For process_tree we need to create a temporary parent that will later be eliminated with VariableCache::adopt_grand_children
-                // TODO: Investigate if UnitInfo::process_tree can be modified to use `&mut parent_variable`, then we would not need this temporary variable.
-                let mut temporary_variable = parent_variable.clone();
-                temporary_variable.variable_key = ObjectRef::Invalid;
-                temporary_variable.parent_key = parent_variable.variable_key;
-                cache.add_variable(parent_variable.variable_key, &mut temporary_variable)?;
+                        if let Some(calling_pc) = unwind_registers.get_program_counter_mut() {
+                            if let ControlFlow::Break(error) = unwind_register(
+                                calling_pc,
+                                &callee_frame_registers,
+                                None,
+                                None,
+                                &mut unwound_return_address,
+                                memory,
+                                instruction_set,
+                            ) {
+                                // This is not fatal, but we cannot continue unwinding beyond the current frame.
+                                tracing::error!("{:?}", &error);
+                                return_frame.function_name =
+                                    format!("{} : ERROR : {error}", &return_frame.function_name);
+                                stack_frames.push(return_frame);
+                                break 'unwind;
+                            } else {
+                                // The unwind registers were updated with the calling frame's PC, so we can continue unwinding.
+                                stack_frames.push(return_frame);
+                                continue 'unwind;
+                            };
+                        } else {
+                            stack_frames.push(return_frame);
+                            continue 'unwind;
+                        }
+                    } else {
+                        stack_frames.push(return_frame);
+                        tracing::trace!("UNWIND: Stack unwind complete. No available debug info for program counter {}: {}", frame_pc, error);
+                        break;
+                    }
+                }
+            };
 
-                let parent_node = type_tree.root()?;
+            // Because we will be updating the `unwind_registers` with previous frame unwind info, we need to keep a copy of the current frame's registers that can be used to resolve [DWARF](https://dwarfstd.org) expressions.
+            let callee_frame_registers = unwind_registers.clone();
+            // PART 2-b: Determine the CFA (canonical frame address) to use for this unwind row.
+            let unwind_cfa = match unwind_info.cfa() {
+                gimli::CfaRule::RegisterAndOffset { register, offset } => {
+                    let reg_val = unwind_registers
+                        .get_register_by_dwarf_id(register.0)
+                        .and_then(|register| register.value);
+                    match reg_val {
+                        Some(reg_val) => {
+                            if reg_val.is_zero() {
+                                // If we encounter this rule for CFA, it implies the scenario depends on a FP/frame pointer to continue successfully.
+                                // Therefore, if reg_val is zero (i.e. FP is zero), then we do not have enough information to determine the CFA by rule.
+                                stack_frames.push(return_frame);
+                                tracing::trace!("UNWIND: Stack unwind complete - The FP register value unwound to a value of zero.");
+                                break;
+                            }
+                            let unwind_cfa = add_to_address(
+                                reg_val.try_into()?,
+                                *offset,
+                                unwind_registers.get_address_size_bytes(),
+                            );
+                            tracing::trace!(
+                                "UNWIND - CFA : {:#010x}\tRule: {:?}",
+                                unwind_cfa,
+                                unwind_info.cfa()
+                            );
+                            Some(unwind_cfa)
+                        }
+                        None => {
+                            tracing::error!("UNWIND: `StackFrameIterator` unable to determine the unwind CFA: Missing value of register {}",register.0);
+                            stack_frames.push(return_frame);
+                            break;
+                        }
+                    }
+                }
+                gimli::CfaRule::Expression(_) => unimplemented!(),
+            };
 
-                temporary_variable = unit_info.process_tree(
-                    self,
-                    parent_node,
-                    temporary_variable,
+            // PART 2-c: Unwind registers for the "previous/calling" frame.
+            // We sometimes need to keep a copy of the LR value to calculate the PC. For both ARM, and RISC-V, The LR will be unwound before the PC, so we can reference it safely.
+            let mut unwound_return_address: Option<RegisterValue> = None;
+            for debug_register in unwind_registers.0.iter_mut() {
+                if let ControlFlow::Break(error) = unwind_register(
+                    debug_register,
+                    &callee_frame_registers,
+                    Some(unwind_info),
+                    unwind_cfa,
+                    &mut unwound_return_address,
                     memory,
-                    stack_frame_registers,
-                    frame_base,
-                    cache,
-                )?;
-
-                cache.adopt_grand_children(parent_variable, &temporary_variable)?;
+                    instruction_set,
+                ) {
+                    tracing::error!("{:?}", &error);
+                    return_frame.function_name =
+                        format!("{} : ERROR: {error}", &return_frame.function_name);
+                    stack_frames.push(return_frame);
+                    break 'unwind;
+                };
             }
-            _ => {
-                // Do nothing. These have already been recursed to their maximum.
+
+            stack_frames.push(return_frame);
+
+            // Check if we unwound over an exception handler
+            if let Some(value) = unwind_registers.get_program_counter().and_then(|s| s.value) {
+                let value: u32 = value.try_into().unwrap();
+
+                if (value >> 28) & 0xf == 0xf {
+                    let ra = unwind_registers
+                        .get_register_mut_by_role(&RegisterRole::ReturnAddress)
+                        .unwrap();
+                    ra.value = Some(RegisterValue::U32(value));
+
+                    // Now, how do we handle this.
+                    if let Some(details) =
+                        exception_handler.exception_details(memory, &unwind_registers)?
+                    {
+                        unwind_registers = details.calling_frame_registers;
+                        let address = frame_pc;
+
+                        let exception_frame = StackFrame {
+                            id: get_object_reference(),
+                            function_name: details.description.clone(),
+                            source_location: None,
+                            registers: unwind_registers.clone(),
+                            pc: match unwind_registers.get_address_size_bytes() {
+                                4 => RegisterValue::U32(address as u32),
+                                8 => RegisterValue::U64(address),
+                                _ => RegisterValue::from(address),
+                            },
+                            frame_base: None,
+                            is_inlined: false,
+                            static_variables: None,
+                            local_variables: None,
+                        };
+
+                        stack_frames.push(exception_frame);
+                    }
+                }
             }
         }
-        Ok(())
+
+        Ok(stack_frames)
     }
 
-    /// Returns a populated (resolved) [`StackFrame`] struct.
-    /// This function will also populate the `DebugInfo::VariableCache` with in scope `Variable`s for each `StackFrame`, while taking into account the appropriate strategy for lazy-loading of variables.
-    pub(crate) fn get_stackframe_info(
+    /// Find the program counter where a breakpoint should be set,
+    /// given a source file, a line and optionally a column.
+    pub fn get_breakpoint_location(
         &self,
-        memory: &mut impl MemoryInterface,
-        address: u64,
-        unwind_registers: &registers::DebugRegisters,
-    ) -> Result<Vec<StackFrame>, DebugError> {
-        // When reporting the address, we format it as a hex string, with the width matching
-        // the configured size of the datatype used in the `RegisterValue` address.
-        let unknown_function = format!(
-            "<unknown function @ {:#0width$x}>",
-            address,
-            width = (unwind_registers.get_address_size_bytes() * 2 + 2)
+        path: &TypedPathBuf,
+        line: u64,
+        column: Option<u64>,
+    ) -> Result<VerifiedBreakpoint, DebugError> {
+        tracing::debug!(
+            "Looking for breakpoint location for {}:{}:{}",
+            path.to_path().display(),
+            line,
+            column
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| "-".to_owned())
         );
 
-        let mut frames = Vec::new();
+        for object in &self.objects {
+            if let Some(location) = object.get_breakpoint_location(path, line, column)? {
+                return Ok(object.target_breakpoint(location));
+            }
+        }
 
-        for unit_info in &self.unit_infos {
-            let functions = unit_info.get_function_dies(self, address, true)?;
+        let p = path.to_path();
 
-            if functions.is_empty() {
-                continue;
-            }
+        Err(DebugError::Other(anyhow::anyhow!(
+            "No valid breakpoint information found for file: {}, line: {:?}, column: {:?}",
+            p.display(),
+            line,
+            column
+        )))
+    }
 
-            // The first function is the non-inlined function, and the rest are inlined functions.
-            // The frame base only exists for the non-inlined function, so we can reuse it for all the inlined functions.
-            let frame_base = functions[0].frame_base(self, memory, unwind_registers)?;
+    /// Find the program counters where breakpoints should be set, to halt at the start of
+    /// every instance of a function, including the places where it was inlined.
+    ///
+    /// The `function_name` is matched against the name of the function, its demangled path,
+    /// e.g. `my_crate::module::function`, or the trailing segments of that path.
+    pub fn get_function_breakpoint_locations(
+        &self,
+        function_name: &str,
+    ) -> Result<Vec<VerifiedBreakpoint>, DebugError> {
+        tracing::debug!(
+            "Looking for breakpoint locations for function {}",
+            function_name
+        );
 
-            // Handle all functions which contain further inlined functions. For
-            // these functions, the location is the call site of the inlined function.
-            for (index, function_die) in functions[0..functions.len() - 1].iter().enumerate() {
-                let function_name = function_die
-                    .function_name(self)
-                    .unwrap_or_else(|| unknown_function.clone());
+        let mut breakpoints = Vec::new();
+        for object in &self.objects {
+            breakpoints.extend(
+                object
+                    .get_function_breakpoint_locations(function_name)?
+                    .into_iter()
+                    .map(|breakpoint| object.target_breakpoint(breakpoint)),
+            );
+        }
 
-                tracing::debug!("UNWIND: Function name: {}", function_name);
+        if breakpoints.is_empty() {
+            Err(DebugError::Other(anyhow::anyhow!(
+                "No function named {:?} found in the debug information",
+                function_name
+            )))
+        } else {
+            Ok(breakpoints)
+        }
+    }
+}
 
-                let next_function = &functions[index + 1];
+impl ObjectDebugInfo {
+    /// Parse debug information directly from a buffer containing an ELF file,
+    /// which is loaded `address_offset` bytes after the addresses it is linked at.
+    fn from_raw(data: &[u8], address_offset: u64) -> Result<Self, DebugError> {
+        let object = object::File::parse(data)?;
 
-                assert!(next_function.is_inline());
+        // Load a section and return as `Cow<[u8]>`.
+        let load_section = |id: gimli::SectionId| -> Result<DwarfReader, gimli::Error> {
+            let data = object
+                .section_by_name(id.name())
+                .and_then(|section| section.uncompressed_data().ok())
+                .unwrap_or_else(|| borrow::Cow::Borrowed(&[][..]));
 
-                // Calculate the call site for this function, so that we can use it later to create an additional 'callee' `StackFrame` from that PC.
-                let address_size = unit_info.unit.header.address_size() as u64;
+            Ok(gimli::read::EndianRcSlice::new(
+                Rc::from(&*data),
+                gimli::LittleEndian,
+            ))
+        };
 
-                if next_function.low_pc > address_size && next_function.low_pc < u32::MAX.into() {
-                    // The first instruction of the inlined function is used as the call site
-                    let inlined_call_site = RegisterValue::from(next_function.low_pc);
+        // Load all of the sections.
+        let dwarf_cow = gimli::Dwarf::load(&load_section)?;
 
-                    tracing::debug!(
-                        "UNWIND: Callsite for inlined function {:?}",
-                        next_function.function_name(self)
-                    );
+        use gimli::Section;
+        let frame_section = gimli::DebugFrame::load(load_section)?;
+        let address_section = gimli::DebugAddr::load(load_section)?;
+        let debug_loc = gimli::DebugLoc::load(load_section)?;
+        let debug_loc_lists = gimli::DebugLocLists::load(load_section)?;
+        let locations_section = gimli::LocationLists::new(debug_loc, debug_loc_lists);
+        let debug_line_section = gimli::DebugLine::load(load_section)?;
 
-                    let inlined_caller_source_location = next_function.inline_call_location(self);
+        let mut unit_infos = Vec::new();
 
-                    tracing::debug!("UNWIND: Call site: {:?}", inlined_caller_source_location);
+        let mut iter = dwarf_cow.units();
 
-                    // Now that we have the function_name and function_source_location, we can create the appropriate variable caches for this stack frame.
-                    // Resolve the statics that belong to the compilation unit that this function is in.
-                    let static_variables = self.create_static_scope_cache(unit_info).map_or_else(
-                        |error| {
-                            tracing::error!(
-                                "Could not resolve static variables. {}. Continuing...",
-                                error
-                            );
-                            None
-                        },
-                        Some,
-                    );
+        while let Ok(Some(header)) = iter.next() {
+            if let Ok(unit) = dwarf_cow.unit(header) {
+                unit_infos.push(UnitInfo::new(unit));
+            };
+        }
 
-                    // Next, resolve and cache the function variables.
-                    let local_variables = self
-                        .create_function_scope_cache(function_die, unit_info)
-                        .map_or_else(
-                            |error| {
-                                tracing::error!(
-                                    "Could not resolve function variables. {}. Continuing...",
-                                    error
-                                );
-                                None
-                            },
-                            Some,
-                        );
-
-                    frames.push(StackFrame {
-                        id: get_object_reference(),
-                        function_name,
-                        source_location: inlined_caller_source_location,
-                        registers: unwind_registers.clone(),
-                        pc: inlined_call_site,
-                        frame_base,
-                        is_inlined: function_die.is_inline(),
-                        static_variables,
-                        local_variables,
-                    });
-                } else {
-                    tracing::warn!(
-                        "UNWIND: Unknown call site for inlined function {}.",
-                        function_name
-                    );
-                }
-            }
-
-            // Handle last function, which contains no further inlined functions
-            //UNWRAP: Checked at beginning of loop, functions must contain at least one value
-            #[allow(clippy::unwrap_used)]
-            let last_function = functions.last().unwrap();
+        Ok(ObjectDebugInfo {
+            dwarf: dwarf_cow,
+            frame_section,
+            locations_section,
+            address_section,
+            debug_line_section,
+            unit_infos,
+            address_offset,
+        })
+    }
 
-            let function_name = last_function
-                .function_name(self)
-                .unwrap_or_else(|| unknown_function.clone());
+    /// Converts an address in target memory to the corresponding address in the object file.
+    pub(crate) fn object_address(&self, address: u64) -> u64 {
+        address.wrapping_sub(self.address_offset)
+    }
 
-            let function_location = self.get_source_location(address);
+    /// Converts an address in the object file to the corresponding address in target memory.
+    pub(crate) fn target_address(&self, address: u64) -> u64 {
+        address.wrapping_add(self.address_offset)
+    }
 
-            // Now that we have the function_name and function_source_location, we can create the appropriate variable caches for this stack frame.
-            // Resolve the statics that belong to the compilation unit that this function is in.
-            let static_variables = self.create_static_scope_cache(unit_info).map_or_else(
-                |error| {
-                    tracing::error!(
-                        "Could not resolve static variables. {}. Continuing...",
-                        error
-                    );
-                    None
-                },
-                Some,
-            );
+    /// Converts the code addresses of a [`SourceLocation`] in the object file to addresses in target memory.
+    pub(crate) fn target_source_location(&self, location: SourceLocation) -> SourceLocation {
+        SourceLocation {
+            low_pc: location
+                .low_pc
+                .map(|low_pc| self.target_address(low_pc.into()) as u32),
+            high_pc: location
+                .high_pc
+                .map(|high_pc| self.target_address(high_pc.into()) as u32),
+            ..location
+        }
+    }
 
-            // Next, resolve and cache the function variables.
-            let local_variables = self
-                .create_function_scope_cache(last_function, unit_info)
-                .map_or_else(
-                    |error| {
-                        tracing::error!(
-                            "Could not resolve function variables. {}. Continuing...",
-                            error
-                        );
-                        None
-                    },
-                    Some,
-                );
+    /// Converts the address of a [`VerifiedBreakpoint`] in the object file to the address in target memory.
+    fn target_breakpoint(&self, breakpoint: VerifiedBreakpoint) -> VerifiedBreakpoint {
+        VerifiedBreakpoint {
+            address: self.target_address(breakpoint.address),
+            source_location: self.target_source_location(breakpoint.source_location),
+        }
+    }
 
-            frames.push(StackFrame {
-                id: get_object_reference(),
-                function_name,
-                source_location: function_location,
-                registers: unwind_registers.clone(),
-                pc: match unwind_registers.get_address_size_bytes() {
-                    4 => RegisterValue::U32(address as u32),
-                    8 => RegisterValue::U64(address),
-                    _ => RegisterValue::from(address),
-                },
-                frame_base,
-                is_inlined: last_function.is_inline(),
-                static_variables,
-                local_variables,
-            });
+    /// Checks if the object file contains debug or unwind information for the code at `address`.
+    fn contains_address(&self, address: u64) -> bool {
+        let in_unit = self.unit_infos.iter().any(|unit_info| {
+            let Ok(mut ranges) = self.dwarf.unit_ranges(&unit_info.unit) else {
+                return false;
+            };
+            while let Ok(Some(range)) = ranges.next() {
+                if range.begin <= address && address < range.end {
+                    return true;
+                }
+            }
+            false
+        });
 
-            break;
-        }
-        Ok(frames)
+        in_unit
+            || self
+                .frame_section
+                .fde_for_address(
+                    &BaseAddresses::default(),
+                    address,
+                    gimli::DebugFrame::cie_from_offset,
+                )
+                .is_ok()
     }
 
-    /// Performs the logical unwind of the stack and returns a `Vec<StackFrame>`
-    /// - The first 'StackFrame' represents the frame at the current PC (program counter), and ...
-    /// - Each subsequent `StackFrame` represents the **previous or calling** `StackFrame` in the call stack.
-    /// - The majority of the work happens in the `'unwind: while` loop, where each iteration will create a `StackFrame` where possible, and update the `unwind_registers` to prepare for the next iteration.
+    /// Get the name of the function at the given address.
     ///
-    /// The unwind loop will continue until we meet one of the following conditions:
-    /// - We can no longer unwind a valid PC value to be used for the next frame.
-    /// - We encounter a LR register value of 0x0 or 0xFFFFFFFF(Arm 'Reset' value for that register).
-    /// - We can not intelligently calculate a valid LR register value from the other registers, or the gimli::RegisterRule result is a value of 0x0. Note: [DWARF](https://dwarfstd.org) 6.4.4 - CIE defines the return register address used in the `gimli::RegisterRule` tables for unwind operations. Theoretically, if we encounter a function that has `Undefined` `gimli::RegisterRule` for the return register address, it means we have reached the bottom of the stack OR the function is a 'no return' type of function. I have found actual examples (e.g. local functions) where we get `Undefined` for register rule when we cannot apply this logic. Example 1: local functions in main.rs will have LR rule as `Undefined`. Example 2: main()-> ! that is called from a trampoline will have a valid LR rule.
-    /// - Similarly, certain error conditions encountered in `StackFrameIterator` will also break out of the unwind loop.
-    /// Note: In addition to populating the `StackFrame`s, this function will also populate the `DebugInfo::VariableCache` with `Variable`s for available Registers as well as static and function variables.
-    /// TODO: Separate logic for stackframe creation and cache population
-    pub fn unwind(
+    /// If no function is found, `None` will be returned.
+    ///
+    /// ## Inlined functions
+    /// Multiple nested inline functions could exist at the given address.
+    /// This function will currently return the innermost function in that case.
+    // TODO: This function takes a memory interface. This seems odd, but gimly sometimes needs to read memory to resolve.
+    // Maybe this can be factored out if we can be sure that memory is never read for this usecase.
+    // Until we have more tests we cannot be sure tho and it should stay like this.
+    pub(crate) fn function_name(
         &self,
-        core: &mut impl MemoryInterface,
-        initial_registers: DebugRegisters,
-        exception_handler: &dyn ExceptionInterface,
-        instruction_set: Option<InstructionSet>,
-    ) -> Result<Vec<StackFrame>, crate::Error> {
-        self.unwind_impl(initial_registers, core, exception_handler, instruction_set)
-    }
+        address: u64,
+        find_inlined: bool,
+    ) -> Result<Option<String>, DebugError> {
+        for unit_info in &self.unit_infos {
+            let mut functions = unit_info.get_function_dies(self, address, find_inlined)?;
 
-    pub(crate) fn unwind_impl(
-        &self,
-        initial_registers: registers::DebugRegisters,
-        memory: &mut impl MemoryInterface,
-        exception_handler: &dyn ExceptionInterface,
-        instruction_set: Option<InstructionSet>,
-    ) -> Result<Vec<StackFrame>, crate::Error> {
-        let mut stack_frames = Vec::<StackFrame>::new();
+            // Use the last functions from the list, this is the function which most closely
+            // corresponds to the PC in case of multiple inlined functions.
+            if let Some(die_cursor_state) = functions.pop() {
+                let function_name = die_cursor_state.function_name(self);
 
-        let mut unwind_context: Box<UnwindContext<DwarfReader>> =
-            Box::new(gimli::UnwindContext::new());
+                if function_name.is_some() {
+                    return Ok(function_name);
+                }
+            }
+        }
 
-        let mut unwind_registers = initial_registers;
+        Ok(None)
+    }
 
-        // Unwind [StackFrame]'s for as long as we can unwind a valid PC value.
-        'unwind: while let Some(frame_pc_register_value) = unwind_registers
-            .get_program_counter()
-            .and_then(|pc| pc.value)
-        {
-            // PART 0: The first step is to determine the exception context for the current PC.
-            // - If we are at an exception hanlder frame, we need to overwrite the unwind registers with the exception context.
-            // - If for some reason we cannot determine the exception context, we silently continue with the rest of the unwind.
-            // At worst, the unwind will be able to unwind the stack to the frame of the most recent exception handler.
-            let exception_info = match exception_handler
-                .exception_details(memory, &unwind_registers)
-            {
-                Ok(Some(exception_info)) => {
-                    tracing::trace!(
-                        "UNWIND: Found exception context: {}",
-                        exception_info.description
-                    );
-                    Some(exception_info)
-                }
-                Ok(None) => {
-                    tracing::trace!(
-                        "UNWIND: No exception context found. Stack unwind will continue."
+    /// Try get the [`SourceLocation`] for a given address.
+    pub(crate) fn get_source_location(&self, address: u64) -> Option<SourceLocation> {
+        for unit_info in &self.unit_infos {
+            let unit = &unit_info.unit;
+
+            let mut ranges = match self.dwarf.unit_ranges(unit) {
+                Ok(ranges) => ranges,
+                Err(error) => {
+                    tracing::warn!(
+                        "No valid source code ranges found for address {}: {:?}",
+                        address,
+                        error
                     );
-                    None
-                }
-                Err(e) => {
-                    tracing::warn!("UNWIND: Error while checking for exception context. The stack trace will not include the calling frames. : {}", e);
-                    None
+                    continue;
                 }
             };
 
-            // PART 1: Construct the `StackFrame` for the current pc.
-            let frame_pc = frame_pc_register_value
-                .try_into()
-                .map_err(|error| crate::Error::Register(format!("Cannot convert register value for program counter to a 64-bit integer value: {:?}", error)))?;
-            tracing::trace!(
-                "UNWIND: Will generate `StackFrame` for function at address (PC) {:#}",
-                frame_pc_register_value
-            );
+            while let Ok(Some(range)) = ranges.next() {
+                if !(range.begin <= address && address < range.end) {
+                    continue;
+                }
+                // Get the function name.
 
-            // PART 1-a: Prepare the `StackFrame` that holds the current frame information.
+                let ilnp = unit.line_program.as_ref()?.clone();
 
-            let mut cached_stack_frames =
-                match self.get_stackframe_info(memory, frame_pc, &unwind_registers) {
-                    Ok(cached_stack_frames) => cached_stack_frames,
-                    Err(e) => {
-                        tracing::error!(
-                            "UNWIND: Unable to complete `StackFrame` information: {}",
-                            e
+                let (program, sequences) = match ilnp.sequences() {
+                    Ok(value) => value,
+                    Err(error) => {
+                        tracing::warn!(
+                            "No valid source code ranges found for address {}: {:?}",
+                            address,
+                            error
                         );
-                        // There is no point in continuing with the unwind, so let's get out of here.
+                        continue;
+                    }
+                };
+
+                // Normalize the address.
+                let mut target_seq = None;
+
+                for seq in sequences {
+                    if seq.start <= address && address < seq.end {
+                        target_seq = Some(seq);
                         break;
                     }
+                }
+
+                let Some(target_seq) = target_seq.as_ref() else {
+                    continue;
                 };
 
-            while cached_stack_frames.len() > 1 {
-                // If we encountered INLINED functions (all `StackFrames`s in this Vec, except for the last one, which is the containing NON-INLINED function), these are simply added to the list of stack_frames we return.
-                #[allow(clippy::unwrap_used)]
-                let inlined_frame = cached_stack_frames.pop().unwrap(); // unwrap is safe while .len() > 1
-                tracing::trace!(
-                    "UNWIND: Found inlined function - name={}, pc={}",
-                    inlined_frame.function_name,
-                    inlined_frame.pc
-                );
-                stack_frames.push(inlined_frame);
-            }
+                let mut previous_row: Option<gimli::LineRow> = None;
 
-            let mut only_exception = false;
+                let mut rows = program.resume_from(target_seq);
 
-            let mut return_frame = match cached_stack_frames.pop() {
-                Some(frame) => frame,
-                None => {
-                    if let Some(exception_info) = &exception_info {
-                        only_exception = true;
-                        let address = frame_pc;
-
-                        let previous_regs = unwind_registers.clone();
-
-                        StackFrame {
-                            id: get_object_reference(),
-                            function_name: exception_info.description.clone(),
-                            source_location: None,
-                            registers: previous_regs,
-                            pc: match unwind_registers.get_address_size_bytes() {
-                                4 => RegisterValue::U32(address as u32),
-                                8 => RegisterValue::U64(address),
-                                _ => RegisterValue::from(address),
-                            },
-                            frame_base: None,
-                            is_inlined: false,
-                            static_variables: None,
-                            local_variables: None,
+                while let Ok(Some((header, row))) = rows.next_row() {
+                    match row.address().cmp(&address) {
+                        Ordering::Greater => {
+                            // The address is after the current row, so we use the previous row data.
+                            //
+                            // (If we don't do this, you get the artificial effect where the debugger
+                            // steps to the top of the file when it is steppping out of a function.)
+                            if let Some(previous_row) = previous_row {
+                                if let Some(file_entry) = previous_row.file(header) {
+                                    if let Some((file, directory)) =
+                                        self.find_file_and_directory(unit, header, file_entry)
+                                    {
+                                        tracing::debug!("{} - {:?}", address, previous_row.isa());
+                                        return Some(SourceLocation {
+                                            line: previous_row.line().map(NonZeroU64::get),
+                                            column: Some(previous_row.column().into()),
+                                            file,
+                                            directory,
+                                            low_pc: Some(target_seq.start as u32),
+                                            high_pc: Some(target_seq.end as u32),
+                                        });
+                                    }
+                                }
+                            }
                         }
-                    } else {
-                        let address = frame_pc;
-
-                        // When reporting the address, we format it as a hex string, with the width matching
-                        // the configured size of the datatype used in the `RegisterValue` address.
-                        let unknown_function = format!(
-                            "<unknown function @ {:#0width$x}>",
-                            address,
-                            width = (unwind_registers.get_address_size_bytes() * 2 + 2)
-                        );
+                        Ordering::Less => {}
+                        Ordering::Equal => {
+                            if let Some(file_entry) = row.file(header) {
+                                if let Some((file, directory)) =
+                                    self.find_file_and_directory(unit, header, file_entry)
+                                {
+                                    tracing::debug!("{} - {:?}", address, row.isa());
 
-                        StackFrame {
-                            id: get_object_reference(),
-                            function_name: unknown_function,
-                            source_location: self.get_source_location(address),
-                            registers: unwind_registers.clone(),
-                            pc: match unwind_registers.get_address_size_bytes() {
-                                4 => RegisterValue::U32(address as u32),
-                                8 => RegisterValue::U64(address),
-                                _ => RegisterValue::from(address),
-                            },
-                            frame_base: None,
-                            is_inlined: false,
-                            static_variables: None,
-                            local_variables: None,
+                                    return Some(SourceLocation {
+                                        line: row.line().map(NonZeroU64::get),
+                                        column: Some(row.column().into()),
+                                        file,
+                                        directory,
+                                        low_pc: Some(target_seq.start as u32),
+                                        high_pc: Some(target_seq.end as u32),
+                                    });
+                                }
+                            }
                         }
                     }
+                    previous_row = Some(*row);
                 }
-            };
+            }
+        }
+        None
+    }
 
-            // Part 1-b: Check LR values to determine if we can continue unwinding.
-            let Some(check_return_address) = unwind_registers.get_return_address() else {
-                // If the debug info rules result in a None return address, we cannot continue unwinding.
-                stack_frames.push(return_frame);
-                tracing::trace!("UNWIND: Stack unwind complete - LR register value is 'None.");
-                break;
-            };
+    /// We do not actually resolve the children of `[VariableName::StaticScope]` automatically, and only create the necessary header in the `VariableCache`.
+    /// This allows us to resolve the `[VariableName::StaticScope]` on demand/lazily, when a user requests it from the debug client.
+    /// This saves a lot of overhead when a user only wants to see the `[VariableName::LocalScope]` or `[VariableName::Registers]` while stepping through code (the most common use cases)
+    pub(crate) fn create_static_scope_cache(
+        &self,
+        unit_info: &UnitInfo,
+    ) -> Result<VariableCache, gimli::Error> {
+        // Only process statics for this unit header.
+        let abbrevs = &unit_info.unit.abbreviations;
+        // Navigate the current unit from the header down.
+        let mut header_tree = unit_info.unit.header.entries_tree(abbrevs, None)?;
+        let unit_node = header_tree.root()?;
 
-            if check_return_address.is_max_value() || check_return_address.is_zero() {
-                // When we encounter the starting (after reset) return address, we've reached the bottom of the stack, so no more unwinding after this.
-                stack_frames.push(return_frame);
-                tracing::trace!(
-                    "UNWIND: Stack unwind complete - Reached the 'Reset' value of the LR register."
-                );
-                break;
-            }
+        Ok(VariableCache::new_dwarf_cache(
+            unit_info.unit.header.offset(),
+            unit_node.entry().offset(),
+            VariableName::StaticScopeRoot,
+        ))
+    }
 
-            // Part 1-c: If the target current frame is an exception handler, we need to update the `unwind_registers` to match the frame that invoked the exception handler.
-            if let Some(exception_info) = exception_info {
-                tracing::trace!(
-                    "UNWIND: Stack unwind reached an exception handler {}",
-                    exception_info.description
-                );
+    /// Creates the unpopulated cache for `function` variables
+    pub(crate) fn create_function_scope_cache(
+        &self,
+        die_cursor_state: &FunctionDie,
+        unit_info: &UnitInfo,
+    ) -> Result<VariableCache, DebugError> {
+        let abbrevs = &unit_info.unit.abbreviations;
+        let mut tree = unit_info
+            .unit
+            .header
+            .entries_tree(abbrevs, Some(die_cursor_state.function_die.offset()))?;
+        let function_node = tree.root()?;
 
-                tracing::trace!(
-                    "UNWIND: Stack unwind will attempt to unwind the frame that invoked {}.",
-                    exception_info.description
-                );
+        let function_variable_cache = VariableCache::new_dwarf_cache(
+            unit_info.unit.header.offset(),
+            function_node.entry().offset(),
+            VariableName::LocalScopeRoot,
+        );
 
-                // Now that we've optionally updated the `unwind_registers` to match the exception handler, we can continue.
-                if only_exception {
-                    // If we are at an exception handler frame, we need to overwrite the unwind registers.
-                    // This will allow us to continue unwinding from the exception handler frame.
-                    unwind_registers = exception_info.calling_frame_registers;
+        Ok(function_variable_cache)
+    }
 
-                    stack_frames.push(return_frame);
-                    continue;
+    /// This effects the on-demand expansion of lazy/deferred load of all the 'child' `Variable`s for a given 'parent'.
+    pub(crate) fn cache_deferred_variables(
+        &self,
+        cache: &mut VariableCache,
+        memory: &mut dyn MemoryInterface,
+        parent_variable: &mut Variable,
+        stack_frame_registers: &DebugRegisters,
+        frame_base: Option<u64>,
+    ) -> Result<(), DebugError> {
+        if !parent_variable.is_valid() {
+            // Do nothing. The parent_variable.get_value() will already report back the debug_error value.
+            return Ok(());
+        }
+
+        // Only attempt this part if we have not yet resolved the referenced children.
+        if cache.has_children(parent_variable)? {
+            return Ok(());
+        }
+
+        let Some(header_offset) = parent_variable.unit_header_offset else {
+            return Ok(());
+        };
+
+        let unit_header = self.dwarf.debug_info.header_from_offset(header_offset)?;
+        let unit_info = UnitInfo {
+            unit: gimli::Unit::new(&self.dwarf, unit_header)?,
+        };
+
+        match parent_variable.variable_node_type {
+            VariableNodeType::ReferenceOffset(reference_offset) => {
+                // Reference to a type, or an node.entry() to another type or a type modifier which will point to another type.
+                let mut type_tree = unit_info
+                    .unit
+                    .header
+                    .entries_tree(&unit_info.unit.abbreviations, Some(reference_offset))?;
+                let referenced_node = type_tree.root()?;
+                let mut referenced_variable = cache.create_variable(
+                    parent_variable.variable_key,
+                    unit_info.unit.header.offset().as_debug_info_offset(),
+                    Some(referenced_node.entry().offset()),
+                )?;
+
+                referenced_variable.name = match &parent_variable.name {
+                    VariableName::Named(name) if name.starts_with("Some ") => VariableName::Named(name.replacen('&', "*", 1)) ,
+                    VariableName::Named(name) => VariableName::Named(format!("*{name}")),
+                    other => VariableName::Named(format!("Error: Unable to generate name, parent variable does not have a name but is special variable {other:?}")),
+                };
+
+                referenced_variable = unit_info.extract_type(
+                    self,
+                    referenced_node,
+                    parent_variable,
+                    referenced_variable,
+                    memory,
+                    stack_frame_registers,
+                    frame_base,
+                    cache,
+                )?;
+
+                if referenced_variable.type_name == VariableType::Base("()".to_owned()) {
+                    // Only use this, if it is NOT a unit datatype.
+                    cache.remove_cache_entry(referenced_variable.variable_key)?;
                 }
             }
+            VariableNodeType::TypeOffset(type_offset) => {
+                // Find the parent node
+                let mut type_tree = unit_info
+                    .unit
+                    .header
+                    .entries_tree(&unit_info.unit.abbreviations, Some(type_offset))?;
+                let parent_node = type_tree.root()?;
 
-            // PART 2: Setup the registers for the next iteration (a.k.a. unwind previous frame, a.k.a. "callee", in the call stack).
-            tracing::trace!(
-                "UNWIND - Preparing `StackFrameIterator` to unwind NON-INLINED function {:?} at {:?}",
-                return_frame.function_name,
-                return_frame.source_location
-            );
-            // PART 2-a: get the `gimli::FrameDescriptorEntry` for this address and then the unwind info associated with this row.
-            let unwind_info = match get_unwind_info(
-                &mut unwind_context,
-                &self.frame_section,
-                frame_pc,
-            ) {
-                Ok(unwind_info) => unwind_info,
-                Err(error) => {
-                    // We cannot do stack unwinding if we do not have debug info. However, there is one case where we can continue. When the following conditions are met:
-                    // 1. The current frame is the first frame in the stack, AND ...
-                    // 2. The frame registers have a valid return address/LR value.
-                    // If both these conditions are met, we can push the 'unknown function' to the list of stack frames, and use the LR value to calculate the PC for the calling frame.
-                    // The current logic will then use that PC to get the next frame's unwind info, and if that exists, will be able to continue unwinding.
-                    // If the calling frame has no debug info, then the unwindindg will end with that frame.
-                    if stack_frames.is_empty() {
-                        let callee_frame_registers = unwind_registers.clone();
-                        let mut unwound_return_address: Option<RegisterValue> =
-                            callee_frame_registers
-                                .get_return_address()
-                                .and_then(|lr| lr.value);
+                // For process_tree we need to create a temporary parent that will later be eliminated with VariableCache::adopt_grand_children
+                // TODO: Investigate if UnitInfo::process_tree can be modified to use `&mut parent_variable`, then we would not need this temporary variable.
+                let mut temporary_variable = parent_variable.clone();
+                temporary_variable.variable_key = ObjectRef::Invalid;
+                temporary_variable.parent_key = parent_variable.variable_key;
+                cache.add_variable(parent_variable.variable_key, &mut temporary_variable)?;
 
-                        if let Some(calling_pc) = unwind_registers.get_program_counter_mut() {
-                            if let ControlFlow::Break(error) = unwind_register(
-                                calling_pc,
-                                &callee_frame_registers,
-                                None,
-                                None,
-                                &mut unwound_return_address,
-                                memory,
-                                instruction_set,
-                            ) {
-                                // This is not fatal, but we cannot continue unwinding beyond the current frame.
-                                tracing::error!("{:?}", &error);
-                                return_frame.function_name =
-                                    format!("{} : ERROR : {error}", &return_frame.function_name);
-                                stack_frames.push(return_frame);
-                                break 'unwind;
-                            } else {
-                                // The unwind registers were updated with the calling frame's PC, so we can continue unwinding.
-                                stack_frames.push(return_frame);
-                                continue 'unwind;
-                            };
-                        } else {
-                            stack_frames.push(return_frame);
-                            continue 'unwind;
-                        }
-                    } else {
-                        stack_frames.push(return_frame);
-                        tracing::trace!("UNWIND: Stack unwind complete. No available debug info for program counter {}: {}", frame_pc, error);
-                        break;
-                    }
-                }
-            };
+                temporary_variable = unit_info.process_tree(
+                    self,
+                    parent_node,
+                    temporary_variable,
+                    memory,
+                    stack_frame_registers,
+                    frame_base,
+                    cache,
+                )?;
 
-            // Because we will be updating the `unwind_registers` with previous frame unwind info, we need to keep a copy of the current frame's registers that can be used to resolve [DWARF](https://dwarfstd.org) expressions.
-            let callee_frame_registers = unwind_registers.clone();
-            // PART 2-b: Determine the CFA (canonical frame address) to use for this unwind row.
-            let unwind_cfa = match unwind_info.cfa() {
-                gimli::CfaRule::RegisterAndOffset { register, offset } => {
-                    let reg_val = unwind_registers
-                        .get_register_by_dwarf_id(register.0)
-                        .and_then(|register| register.value);
-                    match reg_val {
-                        Some(reg_val) => {
-                            if reg_val.is_zero() {
-                                // If we encounter this rule for CFA, it implies the scenario depends on a FP/frame pointer to continue successfully.
-                                // Therefore, if reg_val is zero (i.e. FP is zero), then we do not have enough information to determine the CFA by rule.
-                                stack_frames.push(return_frame);
-                                tracing::trace!("UNWIND: Stack unwind complete - The FP register value unwound to a value of zero.");
-                                break;
-                            }
-                            let unwind_cfa = add_to_address(
-                                reg_val.try_into()?,
-                                *offset,
-                                unwind_registers.get_address_size_bytes(),
-                            );
-                            tracing::trace!(
-                                "UNWIND - CFA : {:#010x}\tRule: {:?}",
-                                unwind_cfa,
-                                unwind_info.cfa()
-                            );
-                            Some(unwind_cfa)
-                        }
-                        None => {
-                            tracing::error!("UNWIND: `StackFrameIterator` unable to determine the unwind CFA: Missing value of register {}",register.0);
-                            stack_frames.push(return_frame);
-                            break;
-                        }
-                    }
-                }
-                gimli::CfaRule::Expression(_) => unimplemented!(),
-            };
+                cache.adopt_grand_children(parent_variable, &temporary_variable)?;
+            }
+            VariableNodeType::DirectLookup => {
+                // Find the parent node
+                let mut type_tree = unit_info.unit.header.entries_tree(
+                    &unit_info.unit.abbreviations,
+                    parent_variable.variable_unit_offset,
+                )?;
+
+                // For process_tree we need to create a temporary parent that will later be eliminated with VariableCache::adopt_grand_children
+                // TODO: Investigate if UnitInfo::process_tree can be modified to use `&mut parent_variable`, then we would not need this temporary variable.
+                let mut temporary_variable = parent_variable.clone();
+                temporary_variable.variable_key = ObjectRef::Invalid;
+                temporary_variable.parent_key = parent_variable.variable_key;
+                cache.add_variable(parent_variable.variable_key, &mut temporary_variable)?;
+
+                let parent_node = type_tree.root()?;
+
+                temporary_variable = unit_info.process_tree(
+                    self,
+                    parent_node,
+                    temporary_variable,
+                    memory,
+                    stack_frame_registers,
+                    frame_base,
+                    cache,
+                )?;
+
+                cache.adopt_grand_children(parent_variable, &temporary_variable)?;
+            }
+            _ => {
+                // Do nothing. These have already been recursed to their maximum.
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a populated (resolved) [`StackFrame`] struct, for the code at `address` in target memory.
+    /// This function will also populate the `DebugInfo::VariableCache` with in scope `Variable`s for each `StackFrame`, while taking into account the appropriate strategy for lazy-loading of variables.
+    pub(crate) fn get_stackframe_info(
+        &self,
+        memory: &mut impl MemoryInterface,
+        address: u64,
+        unwind_registers: &registers::DebugRegisters,
+    ) -> Result<Vec<StackFrame>, DebugError> {
+        // When reporting the address, we format it as a hex string, with the width matching
+        // the configured size of the datatype used in the `RegisterValue` address.
+        let unknown_function = format!(
+            "<unknown function @ {:#0width$x}>",
+            address,
+            width = (unwind_registers.get_address_size_bytes() * 2 + 2)
+        );
 
-            // PART 2-c: Unwind registers for the "previous/calling" frame.
-            // We sometimes need to keep a copy of the LR value to calculate the PC. For both ARM, and RISC-V, The LR will be unwound before the PC, so we can reference it safely.
-            let mut unwound_return_address: Option<RegisterValue> = None;
-            for debug_register in unwind_registers.0.iter_mut() {
-                if let ControlFlow::Break(error) = unwind_register(
-                    debug_register,
-                    &callee_frame_registers,
-                    Some(unwind_info),
-                    unwind_cfa,
-                    &mut unwound_return_address,
-                    memory,
-                    instruction_set,
-                ) {
-                    tracing::error!("{:?}", &error);
-                    return_frame.function_name =
-                        format!("{} : ERROR: {error}", &return_frame.function_name);
-                    stack_frames.push(return_frame);
-                    break 'unwind;
-                };
+        let object_address = self.object_address(address);
+
+        let mut frames = Vec::new();
+
+        for unit_info in &self.unit_infos {
+            let functions = unit_info.get_function_dies(self, object_address, true)?;
+
+            if functions.is_empty() {
+                continue;
             }
 
-            stack_frames.push(return_frame);
+            // The first function is the non-inlined function, and the rest are inlined functions.
+            // The frame base only exists for the non-inlined function, so we can reuse it for all the inlined functions.
+            let frame_base = functions[0].frame_base(self, memory, unwind_registers)?;
 
-            // Check if we unwound over an exception handler
-            if let Some(value) = unwind_registers.get_program_counter().and_then(|s| s.value) {
-                let value: u32 = value.try_into().unwrap();
+            // Handle all functions which contain further inlined functions. For
+            // these functions, the location is the call site of the inlined function.
+            for (index, function_die) in functions[0..functions.len() - 1].iter().enumerate() {
+                let function_name = function_die
+                    .function_name(self)
+                    .unwrap_or_else(|| unknown_function.clone());
 
-                if (value >> 28) & 0xf == 0xf {
-                    let ra = unwind_registers
-                        .get_register_mut_by_role(&RegisterRole::ReturnAddress)
-                        .unwrap();
-                    ra.value = Some(RegisterValue::U32(value));
+                tracing::debug!("UNWIND: Function name: {}", function_name);
 
-                    // Now, how do we handle this.
-                    if let Some(details) =
-                        exception_handler.exception_details(memory, &unwind_registers)?
-                    {
-                        unwind_registers = details.calling_frame_registers;
-                        let address = frame_pc;
+                let next_function = &functions[index + 1];
 
-                        let exception_frame = StackFrame {
-                            id: get_object_reference(),
-                            function_name: details.description.clone(),
-                            source_location: None,
-                            registers: unwind_registers.clone(),
-                            pc: match unwind_registers.get_address_size_bytes() {
-                                4 => RegisterValue::U32(address as u32),
-                                8 => RegisterValue::U64(address),
-                                _ => RegisterValue::from(address),
+                assert!(next_function.is_inline());
+
+                // Calculate the call site for this function, so that we can use it later to create an additional 'callee' `StackFrame` from that PC.
+                let address_size = unit_info.unit.header.address_size() as u64;
+
+                if next_function.low_pc > address_size && next_function.low_pc < u32::MAX.into() {
+                    // The first instruction of the inlined function is used as the call site
+                    let inlined_call_site =
+                        RegisterValue::from(self.target_address(next_function.low_pc));
+
+                    tracing::debug!(
+                        "UNWIND: Callsite for inlined function {:?}",
+                        next_function.function_name(self)
+                    );
+
+                    let inlined_caller_source_location = next_function
+                        .inline_call_location(self)
+                        .map(|location| self.target_source_location(location));
+
+                    tracing::debug!("UNWIND: Call site: {:?}", inlined_caller_source_location);
+
+                    // Now that we have the function_name and function_source_location, we can create the appropriate variable caches for this stack frame.
+                    // Resolve the statics that belong to the compilation unit that this function is in.
+                    let static_variables = self.create_static_scope_cache(unit_info).map_or_else(
+                        |error| {
+                            tracing::error!(
+                                "Could not resolve static variables. {}. Continuing...",
+                                error
+                            );
+                            None
+                        },
+                        Some,
+                    );
+
+                    // Next, resolve and cache the function variables.
+                    let local_variables = self
+                        .create_function_scope_cache(function_die, unit_info)
+                        .map_or_else(
+                            |error| {
+                                tracing::error!(
+                                    "Could not resolve function variables. {}. Continuing...",
+                                    error
+                                );
+                                None
                             },
-                            frame_base: None,
-                            is_inlined: false,
-                            static_variables: None,
-                            local_variables: None,
-                        };
+                            Some,
+                        );
 
-                        stack_frames.push(exception_frame);
-                    }
+                    frames.push(StackFrame {
+                        id: get_object_reference(),
+                        function_name,
+                        source_location: inlined_caller_source_location,
+                        registers: unwind_registers.clone(),
+                        pc: inlined_call_site,
+                        frame_base,
+                        is_inlined: function_die.is_inline(),
+                        static_variables,
+                        local_variables,
+                    });
+                } else {
+                    tracing::warn!(
+                        "UNWIND: Unknown call site for inlined function {}.",
+                        function_name
+                    );
                 }
             }
-        }
 
-        Ok(stack_frames)
+            // Handle last function, which contains no further inlined functions
+            //UNWRAP: Checked at beginning of loop, functions must contain at least one value
+            #[allow(clippy::unwrap_used)]
+            let last_function = functions.last().unwrap();
+
+            let function_name = last_function
+                .function_name(self)
+                .unwrap_or_else(|| unknown_function.clone());
+
+            let function_location = self
+                .get_source_location(object_address)
+                .map(|location| self.target_source_location(location));
+
+            // Now that we have the function_name and function_source_location, we can create the appropriate variable caches for this stack frame.
+            // Resolve the statics that belong to the compilation unit that this function is in.
+            let static_variables = self.create_static_scope_cache(unit_info).map_or_else(
+                |error| {
+                    tracing::error!(
+                        "Could not resolve static variables. {}. Continuing...",
+                        error
+                    );
+                    None
+                },
+                Some,
+            );
+
+            // Next, resolve and cache the function variables.
+            let local_variables = self
+                .create_function_scope_cache(last_function, unit_info)
+                .map_or_else(
+                    |error| {
+                        tracing::error!(
+                            "Could not resolve function variables. {}. Continuing...",
+                            error
+                        );
+                        None
+                    },
+                    Some,
+                );
+
+            frames.push(StackFrame {
+                id: get_object_reference(),
+                function_name,
+                source_location: function_location,
+                registers: unwind_registers.clone(),
+                pc: match unwind_registers.get_address_size_bytes() {
+                    4 => RegisterValue::U32(address as u32),
+                    8 => RegisterValue::U64(address),
+                    _ => RegisterValue::from(address),
+                },
+                frame_base,
+                is_inlined: last_function.is_inline(),
+                static_variables,
+                local_variables,
+            });
+
+            break;
+        }
+        Ok(frames)
     }
 
-    /// Find the program counter where a breakpoint should be set,
+    /// Find the program counter in the object file where a breakpoint should be set,
     /// given a source file, a line and optionally a column.
-    pub fn get_breakpoint_location(
+    fn get_breakpoint_location(
         &self,
         path: &TypedPathBuf,
         line: u64,
         column: Option<u64>,
-    ) -> Result<VerifiedBreakpoint, DebugError> {
-        tracing::debug!(
-            "Looking for breakpoint location for {}:{}:{}",
-            path.to_path().display(),
-            line,
-            column
-                .map(|c| c.to_string())
-                .unwrap_or_else(|| "-".to_owned())
-        );
-
+    ) -> Result<Option<VerifiedBreakpoint>, DebugError> {
         for unit_header in &self.unit_infos {
             let Some(ref line_program) = &unit_header.unit.line_program else {
                 continue;
@@ -967,18 +1238,11 @@ impl DebugInfo {
             if let Some(location) =
                 self.get_breakpoint_location_in_unit(unit_header, line_program, path, line, column)?
             {
-                return Ok(location);
+                return Ok(Some(location));
             };
         }
 
-        let p = path.to_path();
-
-        Err(DebugError::Other(anyhow::anyhow!(
-            "No valid breakpoint information found for file: {}, line: {:?}, column: {:?}",
-            p.display(),
-            line,
-            column
-        )))
+        Ok(None)
     }
 
     fn get_breakpoint_location_in_unit(
@@ -1088,6 +1352,136 @@ impl DebugInfo {
         Ok(None)
     }
 
+    /// Find the program counters in the object file where breakpoints should be set,
+    /// to halt at the start of every instance of a function.
+    fn get_function_breakpoint_locations(
+        &self,
+        function_name: &str,
+    ) -> Result<Vec<VerifiedBreakpoint>, DebugError> {
+        let mut breakpoints: Vec<VerifiedBreakpoint> = Vec::new();
+        for unit_info in &self.unit_infos {
+            let mut entries = unit_info.unit.entries();
+            while let Some((_, entry)) = entries.next_dfs()? {
+                if !matches!(
+                    entry.tag(),
+                    gimli::DW_TAG_subprogram | gimli::DW_TAG_inlined_subroutine
+                ) || !self.is_function_named(unit_info, entry, function_name)
+                {
+                    continue;
+                }
+
+                // Declarations and abstract instances of inlined functions have no code.
+                let mut die_ranges = Vec::new();
+                let mut ranges = self.dwarf.die_ranges(&unit_info.unit, entry)?;
+                while let Some(range) = ranges.next()? {
+                    die_ranges.push(range);
+                }
+                let Some(entry_range) = die_ranges.into_iter().min_by_key(|range| range.begin)
+                else {
+                    continue;
+                };
+
+                let address = if entry.tag() == gimli::DW_TAG_subprogram {
+                    // Halt after the prologue, so that the arguments of the function can be inspected.
+                    self.prologue_end(unit_info, entry_range)
+                        .unwrap_or(entry_range.begin)
+                } else {
+                    entry_range.begin
+                };
+
+                if breakpoints
+                    .iter()
+                    .any(|breakpoint| breakpoint.address == address)
+                {
+                    continue;
+                }
+                breakpoints.push(VerifiedBreakpoint {
+                    address,
+                    source_location: self.get_source_location(address).unwrap_or_default(),
+                });
+            }
+        }
+
+        Ok(breakpoints)
+    }
+
+    /// Check if the function DIE `entry`, or the declaration it refers to, has the requested name.
+    fn is_function_named(
+        &self,
+        unit_info: &UnitInfo,
+        entry: &gimli::DebuggingInformationEntry<GimliReader, usize>,
+        function_name: &str,
+    ) -> bool {
+        let mut current_entry = Some((unit_info, entry.offset()));
+
+        // Inlined and out-of-line instances refer to the declaration for their names,
+        // which can in turn refer to a specification. The declaration can be in a different unit.
+        for _ in 0..3 {
+            let Some((unit_info, offset)) = current_entry.take() else {
+                break;
+            };
+            let unit = &unit_info.unit;
+            let Ok(entry) = unit.entry(offset) else {
+                break;
+            };
+
+            let attribute_string = |attribute| {
+                let value = entry.attr_value(attribute).ok()??;
+                let name = self.dwarf.attr_string(unit, value).ok()?;
+                from_utf8(&name).ok().map(str::to_string)
+            };
+
+            if attribute_string(gimli::DW_AT_name).as_deref() == Some(function_name) {
+                return true;
+            }
+
+            if let Some(linkage_name) = attribute_string(gimli::DW_AT_linkage_name)
+                .or_else(|| attribute_string(gimli::DW_AT_MIPS_linkage_name))
+            {
+                // The alternate format omits the hash at the end of Rust symbol names.
+                let path = format!("{:#}", rustc_demangle::demangle(&linkage_name));
+                if path == function_name
+                    || path
+                        .strip_suffix(function_name)
+                        .is_some_and(|prefix| prefix.ends_with("::"))
+                {
+                    return true;
+                }
+            }
+
+            current_entry = [gimli::DW_AT_abstract_origin, gimli::DW_AT_specification]
+                .into_iter()
+                .find_map(|attribute| match entry.attr_value(attribute) {
+                    Ok(Some(gimli::AttributeValue::UnitRef(offset))) => Some((unit_info, offset)),
+                    Ok(Some(gimli::AttributeValue::DebugInfoRef(offset))) => {
+                        self.unit_infos.iter().find_map(|unit_info| {
+                            offset
+                                .to_unit_offset(&unit_info.unit.header)
+                                .map(|offset| (unit_info, offset))
+                        })
+                    }
+                    _ => None,
+                });
+        }
+
+        false
+    }
+
+    /// Find the address at the end of the prologue of the function in `range`,
+    /// as marked by the line program of the unit.
+    fn prologue_end(&self, unit_info: &UnitInfo, range: gimli::Range) -> Option<u64> {
+        let line_program = unit_info.unit.line_program.clone()?;
+        let mut rows = line_program.rows();
+
+        while let Ok(Some((_, row))) = rows.next_row() {
+            if row.prologue_end() && (range.begin..range.end).contains(&row.address()) {
+                return Some(row.address());
+            }
+        }
+
+        None
+    }
+
     /// Get the path for an entry in a line program header, using the compilation unit's directory and file entries.
     // TODO: Determine if it is necessary to navigate the include directories to find the file absolute path for C files.
     pub(crate) fn get_path(
@@ -1,5 +1,5 @@
 use super::{
-    debug_info::DebugInfo,
+    debug_info::{DebugInfo, ObjectDebugInfo},
     source_statement::SourceStatements,
     {DebugError, SourceLocation},
 };
@@ -173,6 +173,9 @@ impl SteppingMode {
     /// NOTE about errors returned: Sometimes the target program_counter is at a location where the debug_info program row data does not contain valid statements
     /// for halt points, and we will return a `DebugError::NoValidHaltLocation`. In this case, we recommend the consumer of this API step the core to the next instruction
     /// and try again, with a reasonable retry limit. All other error kinds are should be treated as non recoverable errors.
+    ///
+    /// NOTE about addresses: The program counter, return address and returned halt address are addresses in target memory.
+    /// The debug information is searched with the corresponding addresses in the object file that contains the program counter.
     pub(crate) fn get_halt_location(
         &self,
         core: &mut impl CoreInterface,
@@ -180,16 +183,19 @@ impl SteppingMode {
         program_counter: u64,
         return_address: Option<u64>,
     ) -> Result<(Option<u64>, Option<SourceLocation>), DebugError> {
-        let program_unit = get_compile_unit_info(debug_info, program_counter)?;
+        let object = debug_info.object_for_address(program_counter);
+        let object_program_counter = object.object_address(program_counter);
+        let program_unit = get_compile_unit_info(object, program_counter)?;
         match self {
             SteppingMode::BreakPoint => {
                 // Find the first_breakpoint_address
                 for source_statement in
-                    SourceStatements::new(debug_info, program_unit, program_counter)?.statements
+                    SourceStatements::new(object, program_unit, object_program_counter)?.statements
                 {
                     if let Some(halt_address) =
-                        source_statement.get_first_halt_address(program_counter)
+                        source_statement.get_first_halt_address(object_program_counter)
                     {
+                        let halt_address = object.target_address(halt_address);
                         tracing::debug!(
                             "Found first breakpoint {:#010x} for address: {:#010x}",
                             halt_address,
@@ -206,7 +212,7 @@ impl SteppingMode {
                                     .header()
                                     .file(source_statement.file_index)
                                     .and_then(|file_entry| {
-                                        debug_info
+                                        object
                                             .find_file_and_directory(
                                                 &program_unit.unit,
                                                 line_program.header(),
@@ -226,7 +232,11 @@ impl SteppingMode {
                                             })
                                     })
                             });
-                        return Ok((first_breakpoint_address, first_breakpoint_source_location));
+                        return Ok((
+                            first_breakpoint_address,
+                            first_breakpoint_source_location
+                                .map(|location| object.target_source_location(location)),
+                        ));
                     }
                 }
             }
@@ -238,13 +248,13 @@ impl SteppingMode {
                 //    -- If there is one, it means the step over target is in the current sequence, so we get the get_first_halt_address() for this next statement.
                 //    -- Otherwise the step over target is the same as the step out target.
                 let source_statements =
-                    SourceStatements::new(debug_info, program_unit, program_counter)?.statements;
+                    SourceStatements::new(object, program_unit, object_program_counter)?.statements;
                 let mut source_statements_iter = source_statements.iter();
                 if let Some((target_address, target_location)) = source_statements_iter
                     .find(|source_statement| {
                         source_statement
                             .instruction_range
-                            .contains(&program_counter)
+                            .contains(&object_program_counter)
                     })
                     .and_then(|_| {
                         if source_statements.len() == 1 {
@@ -253,7 +263,12 @@ impl SteppingMode {
                         } else {
                             source_statements_iter.next().and_then(|next_line| {
                                 SteppingMode::BreakPoint
-                                    .get_halt_location(core, debug_info, next_line.low_pc(), None)
+                                    .get_halt_location(
+                                        core,
+                                        debug_info,
+                                        object.target_address(next_line.low_pc()),
+                                        None,
+                                    )
                                     .ok()
                             })
                         }
@@ -279,22 +294,25 @@ impl SteppingMode {
                 // TODO: In theory, we could disassemble the instructions in this statement's address range, and find branching instructions, then we would not need to single step the core past the original haltpoint.
 
                 let source_statements =
-                    SourceStatements::new(debug_info, program_unit, program_counter)?.statements;
+                    SourceStatements::new(object, program_unit, object_program_counter)?.statements;
                 let mut source_statements_iter = source_statements.iter();
                 if let Some(current_source_statement) =
                     source_statements_iter.find(|source_statement| {
                         source_statement
                             .instruction_range
-                            .contains(&program_counter)
+                            .contains(&object_program_counter)
                     })
                 {
-                    let inclusive_range = current_source_statement.instruction_range.start
-                        ..=current_source_statement.instruction_range.end;
+                    let statement_end =
+                        object.target_address(current_source_statement.instruction_range.end);
+                    let inclusive_range = object
+                        .target_address(current_source_statement.instruction_range.start)
+                        ..=statement_end;
                     let (core_status, new_pc) = step_to_address(inclusive_range, core)?;
-                    if new_pc == current_source_statement.instruction_range.end {
+                    if new_pc == statement_end {
                         // We have halted at the address after the current statement, so we can conclude there was no branching calls in this sequence.
                         tracing::debug!("Stepping into next statement, but no branching calls found. Stepped to next available statement.");
-                    } else if new_pc < current_source_statement.instruction_range.end
+                    } else if new_pc < statement_end
                         && matches!(core_status, CoreStatus::Halted(HaltReason::Breakpoint(_)))
                     {
                         // We have halted at a PC that is within the current statement, so there must be another breakpoint.
@@ -315,13 +333,13 @@ impl SteppingMode {
             }
             SteppingMode::OutOfStatement => {
                 if let Ok(function_dies) =
-                    program_unit.get_function_dies(debug_info, program_counter, true)
+                    program_unit.get_function_dies(object, object_program_counter, true)
                 {
                     // We want the first qualifying (PC is in range) function from the back of this list, to access the 'innermost' functions first.
                     if let Some(function) = function_dies.iter().next_back() {
                         tracing::trace!(
                             "Step Out target: Evaluating function {:?}, low_pc={:?}, high_pc={:?}",
-                            function.function_name(debug_info),
+                            function.function_name(object),
                             function.low_pc,
                             function.high_pc
                         );
@@ -329,15 +347,18 @@ impl SteppingMode {
                         if function.attribute(gimli::DW_AT_noreturn).is_some() {
                             return Err(DebugError::Other(anyhow::anyhow!(
                                 "Function {:?} is marked as `noreturn`. Cannot step out of this function.",
-                                function.function_name(debug_info)
+                                function.function_name(object)
                             )));
-                        } else if function.low_pc <= program_counter
-                            && function.high_pc > program_counter
+                        } else if function.low_pc <= object_program_counter
+                            && function.high_pc > object_program_counter
                         {
                             if function.is_inline() {
                                 // Step_out_address for inlined functions, is the first available breakpoint address after the last statement in the inline function.
-                                let (_, next_instruction_address) =
-                                    run_to_address(program_counter, function.high_pc, core)?;
+                                let (_, next_instruction_address) = run_to_address(
+                                    program_counter,
+                                    object.target_address(function.high_pc),
+                                    core,
+                                )?;
                                 return SteppingMode::BreakPoint.get_halt_location(
                                     core,
                                     debug_info,
@@ -479,16 +500,19 @@ fn step_to_address(
     ))
 }
 
-/// Find the compile unit at the current address.
+/// Find the compile unit in the object file at the current address in target memory.
 fn get_compile_unit_info(
-    debug_info: &DebugInfo,
+    debug_info: &ObjectDebugInfo,
     program_counter: u64,
 ) -> Result<&super::unit_info::UnitInfo, DebugError> {
+    let object_program_counter = debug_info.object_address(program_counter);
     for header in &debug_info.unit_infos {
         match debug_info.dwarf.unit_ranges(&header.unit) {
             Ok(mut ranges) => {
                 while let Ok(Some(range)) = ranges.next() {
-                    if (range.begin <= program_counter) && (range.end > program_counter) {
+                    if (range.begin <= object_program_counter)
+                        && (range.end > object_program_counter)
+                    {
                         return Ok(header);
                     }
                 }
@@ -0,0 +1,1814 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use gimli::{DebugInfoOffset, UnitOffset};
+
+use super::{
+    debug_info::ObjectDebugInfo, extract_byte_size, extract_name, unit_info::UnitInfo, DebugError,
+    DebugInfo, DebugRegister, StackFrame, Variable, VariableCache, VariableLocation, VariableName,
+    VariableType,
+};
+use crate::MemoryInterface;
+
+/// The type name used for integer literals, which take the type of the other operand.
+const INTEGER_LITERAL: &str = "{integer}";
+/// The type name used for floating point literals.
+const FLOAT_LITERAL: &str = "{float}";
+
+/// An expression, which can be evaluated in the context of a [`StackFrame`].
+///
+/// Expressions are parsed from strings like `a.b[3]`, `*p`, `$pc + 4` or `*(u32*)0x2000_0000`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    /// An integer literal, e.g. `42` or `0x2000_0000`.
+    Integer(i128),
+    /// A floating point literal, e.g. `1.5`.
+    Float(f64),
+    /// A boolean literal, `true` or `false`.
+    Bool(bool),
+    /// A variable which is in scope of the stack frame.
+    Variable(String),
+    /// A register of the stack frame, e.g. `$pc`.
+    Register(String),
+    /// A member of a struct, enum or tuple, e.g. `a.b` or `a.0`.
+    Member(Box<Expression>, String),
+    /// An element of an array, a slice or the memory a pointer points to, e.g. `a[3]`.
+    Index(Box<Expression>, Box<Expression>),
+    /// The value a pointer points to, e.g. `*p`.
+    Deref(Box<Expression>),
+    /// The address of a variable, e.g. `&a`.
+    AddressOf(Box<Expression>),
+    /// A value converted to another type, e.g. `(u8)a` or `(u32*)0x2000_0000`.
+    Cast(TypeName, Box<Expression>),
+    /// An operation on a single value, e.g. `-a`.
+    Unary(UnaryOperator, Box<Expression>),
+    /// An operation on two values, e.g. `a + b`.
+    Binary(BinaryOperator, Box<Expression>, Box<Expression>),
+}
+
+/// The name of the type a value is cast to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeName {
+    /// The name of a primitive type like `u32`, or of a type from the debug information.
+    pub name: String,
+    /// The number of pointers to the named type, e.g. 1 for `u32*`.
+    pub pointer_depth: usize,
+}
+
+impl TypeName {
+    /// The type this type points to.
+    fn pointee(&self) -> TypeName {
+        TypeName {
+            name: self.name.clone(),
+            pointer_depth: self.pointer_depth.saturating_sub(1),
+        }
+    }
+}
+
+impl fmt::Display for TypeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.name, "*".repeat(self.pointer_depth))
+    }
+}
+
+/// An operator of a [`Expression::Unary`] expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOperator {
+    /// `-`
+    Negate,
+    /// `!`, which is a logical not for booleans and a bitwise not for integers.
+    Not,
+    /// `~`
+    BitNot,
+}
+
+impl fmt::Display for UnaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnaryOperator::Negate => "-",
+            UnaryOperator::Not => "!",
+            UnaryOperator::BitNot => "~",
+        }
+        .fmt(f)
+    }
+}
+
+/// An operator of a [`Expression::Binary`] expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOperator {
+    /// `+`
+    Add,
+    /// `-`
+    Subtract,
+    /// `*`
+    Multiply,
+    /// `/`
+    Divide,
+    /// `%`
+    Remainder,
+    /// `&`
+    BitAnd,
+    /// `|`
+    BitOr,
+    /// `^`
+    BitXor,
+    /// `<<`
+    ShiftLeft,
+    /// `>>`
+    ShiftRight,
+    /// `==`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `<`
+    Less,
+    /// `<=`
+    LessOrEqual,
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterOrEqual,
+    /// `&&`
+    And,
+    /// `||`
+    Or,
+}
+
+impl BinaryOperator {
+    /// The operators, with their symbol and precedence. Operators with a higher precedence bind tighter.
+    const ALL: [(BinaryOperator, &'static str, u8); 18] = [
+        (BinaryOperator::Or, "||", 1),
+        (BinaryOperator::And, "&&", 2),
+        (BinaryOperator::BitOr, "|", 3),
+        (BinaryOperator::BitXor, "^", 4),
+        (BinaryOperator::BitAnd, "&", 5),
+        (BinaryOperator::Equal, "==", 6),
+        (BinaryOperator::NotEqual, "!=", 6),
+        (BinaryOperator::Less, "<", 7),
+        (BinaryOperator::LessOrEqual, "<=", 7),
+        (BinaryOperator::Greater, ">", 7),
+        (BinaryOperator::GreaterOrEqual, ">=", 7),
+        (BinaryOperator::ShiftLeft, "<<", 8),
+        (BinaryOperator::ShiftRight, ">>", 8),
+        (BinaryOperator::Add, "+", 9),
+        (BinaryOperator::Subtract, "-", 9),
+        (BinaryOperator::Multiply, "*", 10),
+        (BinaryOperator::Divide, "/", 10),
+        (BinaryOperator::Remainder, "%", 10),
+    ];
+
+    fn from_symbol(symbol: &str) -> Option<(BinaryOperator, u8)> {
+        Self::ALL
+            .iter()
+            .find(|(_, operator_symbol, _)| *operator_symbol == symbol)
+            .map(|(operator, _, precedence)| (*operator, *precedence))
+    }
+
+    /// Returns the result of this operator if it is a comparison, or `None` otherwise.
+    fn compare(self, ordering: Option<Ordering>) -> Option<bool> {
+        let Some(ordering) = ordering else {
+            // Values which cannot be ordered, like NaN, are only unequal.
+            return self
+                .compare(Some(Ordering::Less))
+                .map(|_| self == Self::NotEqual);
+        };
+
+        Some(match self {
+            BinaryOperator::Equal => ordering.is_eq(),
+            BinaryOperator::NotEqual => ordering.is_ne(),
+            BinaryOperator::Less => ordering.is_lt(),
+            BinaryOperator::LessOrEqual => ordering.is_le(),
+            BinaryOperator::Greater => ordering.is_gt(),
+            BinaryOperator::GreaterOrEqual => ordering.is_ge(),
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for BinaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Self::ALL
+            .iter()
+            .find(|(operator, _, _)| operator == self)
+            .map_or("?", |(_, symbol, _)| symbol)
+            .fmt(f)
+    }
+}
+
+impl Expression {
+    /// Evaluates the expression in the context of `stack_frame`.
+    ///
+    /// Variables are looked up in the local and static scopes of the stack frame, and the children
+    /// of deferred variables are cached in those scopes as the expression requires them.
+    pub fn evaluate(
+        &self,
+        debug_info: &DebugInfo,
+        memory: &mut dyn MemoryInterface,
+        stack_frame: &mut StackFrame,
+    ) -> Result<EvaluatedExpression, DebugError> {
+        let mut evaluator = Evaluator {
+            debug_info: debug_info.object_for_registers(&stack_frame.registers),
+            memory,
+            stack_frame,
+            evaluated_variables: VariableCache::new_expression_cache(),
+        };
+
+        let operand = evaluator.evaluate(self)?;
+        evaluator.finish(operand)
+    }
+}
+
+impl FromStr for Expression {
+    type Err = DebugError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(expression)?,
+            position: 0,
+        };
+
+        let parsed = parser.parse_binary(0)?;
+        match parser.next() {
+            None => Ok(parsed),
+            Some(token) => Err(invalid(format!("Unexpected `{token}` in expression"))),
+        }
+    }
+}
+
+/// The result of evaluating an [`Expression`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvaluatedExpression {
+    /// The value, formatted for display.
+    pub value: String,
+    /// The name of the type of the value.
+    pub type_name: String,
+    /// Where the value was read from.
+    pub memory_location: VariableLocation,
+    /// The variable of the stack frame the expression refers to, if any.
+    ///
+    /// Its children can be found in the local or static [`VariableCache`] of the stack frame.
+    pub variable: Option<Variable>,
+}
+
+fn invalid(message: String) -> DebugError {
+    DebugError::InvalidExpression { message }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Integer(i128),
+    Float(f64),
+    Identifier(String),
+    Register(String),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Integer(value) => value.fmt(f),
+            Token::Float(value) => value.fmt(f),
+            Token::Identifier(name) => name.fmt(f),
+            Token::Register(name) => write!(f, "${name}"),
+            Token::Symbol(symbol) => symbol.fmt(f),
+        }
+    }
+}
+
+/// The symbols of the expression language. Longer symbols come first, so they are matched before their prefixes.
+const SYMBOLS: [&str; 25] = [
+    "||", "&&", "==", "!=", "<=", ">=", "<<", ">>", "(", ")", "[", "]", ".", "*", "&", "+", "-",
+    "/", "%", "!", "~", "^", "|", "<", ">",
+];
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, DebugError> {
+    let mut tokens = Vec::new();
+    let mut rest = expression.trim_start();
+
+    while let Some(first) = rest.chars().next() {
+        let length = if first.is_ascii_digit() {
+            // Tuple members like `a.0.1` are integers, not floating point numbers.
+            let length = if tokens.last() == Some(&Token::Symbol(".")) {
+                rest.find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len())
+            } else {
+                number_length(rest)
+            };
+            tokens.push(parse_number(&rest[..length])?);
+            length
+        } else if first == '$' {
+            let length = 1 + identifier_length(&rest[1..]);
+            if length == 1 {
+                return Err(invalid("Expected a register name after `$`".to_string()));
+            }
+            tokens.push(Token::Register(rest[1..length].to_string()));
+            length
+        } else if first.is_alphabetic() || first == '_' {
+            let length = identifier_length(rest);
+            tokens.push(Token::Identifier(rest[..length].to_string()));
+            length
+        } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+            tokens.push(Token::Symbol(symbol));
+            symbol.len()
+        } else {
+            return Err(invalid(format!(
+                "Unexpected character `{first}` in expression"
+            )));
+        };
+
+        rest = rest[length..].trim_start();
+    }
+
+    Ok(tokens)
+}
+
+/// The length of the identifier at the start of `input`, which may be a path like `module::NAME`.
+fn identifier_length(input: &str) -> usize {
+    let mut length = 0;
+    loop {
+        let rest = &input[length..];
+        match rest.chars().next() {
+            Some(c) if c.is_alphanumeric() || c == '_' => length += c.len_utf8(),
+            Some(':') if length > 0 && rest.starts_with("::") => length += 2,
+            _ => break length,
+        }
+    }
+}
+
+/// The length of the number literal at the start of `input`.
+fn number_length(input: &str) -> usize {
+    let bytes = input.as_bytes();
+    let is_decimal = !matches!(bytes, [b'0', b'x' | b'X' | b'b' | b'B' | b'o' | b'O', ..]);
+
+    let mut length = 0;
+    let mut has_fraction = false;
+    while let Some(&byte) = bytes.get(length) {
+        let starts_fraction = byte == b'.'
+            && is_decimal
+            && !has_fraction
+            && bytes.get(length + 1).is_some_and(u8::is_ascii_digit);
+
+        if byte.is_ascii_alphanumeric() || byte == b'_' {
+            length += 1;
+        } else if starts_fraction {
+            has_fraction = true;
+            length += 1;
+        } else {
+            break;
+        }
+    }
+    length
+}
+
+fn parse_number(literal: &str) -> Result<Token, DebugError> {
+    let digits = literal.replace('_', "");
+    let (radix, digits) = match digits.get(..2) {
+        Some("0x" | "0X") => (16, &digits[2..]),
+        Some("0b" | "0B") => (2, &digits[2..]),
+        Some("0o" | "0O") => (8, &digits[2..]),
+        _ => (10, digits.as_str()),
+    };
+
+    if let Ok(value) = i128::from_str_radix(digits, radix) {
+        Ok(Token::Integer(value))
+    } else if let (10, Ok(value)) = (radix, digits.parse::<f64>()) {
+        Ok(Token::Float(value))
+    } else {
+        Err(invalid(format!("Invalid number `{literal}`")))
+    }
+}
+
+/// The primitive types, which can be used without debug information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Primitive {
+    Unsigned(usize),
+    Signed(usize),
+    Float(usize),
+    Bool,
+    Char,
+}
+
+impl Primitive {
+    fn from_name(name: &str, address_size: usize) -> Option<Self> {
+        Some(match name {
+            "u8" => Primitive::Unsigned(1),
+            "u16" => Primitive::Unsigned(2),
+            "u32" => Primitive::Unsigned(4),
+            "u64" => Primitive::Unsigned(8),
+            "u128" => Primitive::Unsigned(16),
+            "usize" => Primitive::Unsigned(address_size),
+            "i8" => Primitive::Signed(1),
+            "i16" => Primitive::Signed(2),
+            "i32" => Primitive::Signed(4),
+            "i64" => Primitive::Signed(8),
+            "i128" => Primitive::Signed(16),
+            "isize" => Primitive::Signed(address_size),
+            "f32" => Primitive::Float(4),
+            "f64" => Primitive::Float(8),
+            "bool" => Primitive::Bool,
+            "char" => Primitive::Char,
+            _ => return None,
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Primitive::Unsigned(size) | Primitive::Signed(size) | Primitive::Float(size) => size,
+            Primitive::Bool => 1,
+            Primitive::Char => 4,
+        }
+    }
+
+    /// Truncates `value` to the range of this type.
+    fn wrap(self, value: i128) -> i128 {
+        let bits = self.size() as u32 * 8;
+        match self {
+            Primitive::Unsigned(_) | Primitive::Char if bits < 128 => value & ((1 << bits) - 1),
+            Primitive::Signed(_) if bits < 128 => (value << (128 - bits)) >> (128 - bits),
+            _ => value,
+        }
+    }
+
+    /// Decodes a little endian value of this type.
+    fn decode(self, bytes: &[u8], type_name: &str) -> Scalar {
+        match self {
+            Primitive::Float(4) => Scalar::Float {
+                value: f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]).into(),
+                type_name: type_name.to_string(),
+            },
+            Primitive::Float(_) => {
+                let mut buffer = [0; 8];
+                buffer.copy_from_slice(&bytes[..8]);
+                Scalar::Float {
+                    value: f64::from_le_bytes(buffer),
+                    type_name: type_name.to_string(),
+                }
+            }
+            Primitive::Bool => Scalar::Bool(bytes[0] != 0),
+            _ => {
+                let mut buffer = [0; 16];
+                buffer[..bytes.len()].copy_from_slice(bytes);
+                Scalar::Integer {
+                    value: self.wrap(i128::from_le_bytes(buffer)),
+                    type_name: type_name.to_string(),
+                }
+            }
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.position + offset)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        let found = matches!(self.peek(0), Some(Token::Symbol(found)) if *found == symbol);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), DebugError> {
+        if self.eat(symbol) {
+            Ok(())
+        } else {
+            Err(invalid(match self.peek(0) {
+                Some(token) => format!("Expected `{symbol}`, found `{token}`"),
+                None => format!("Expected `{symbol}` at the end of the expression"),
+            }))
+        }
+    }
+
+    /// Parses binary operations, whose operators have at least `min_precedence`.
+    fn parse_binary(&mut self, min_precedence: u8) -> Result<Expression, DebugError> {
+        let mut left = self.parse_unary()?;
+
+        while let Some(Token::Symbol(symbol)) = self.peek(0) {
+            let Some((operator, precedence)) = BinaryOperator::from_symbol(symbol) else {
+                break;
+            };
+            if precedence < min_precedence {
+                break;
+            }
+            self.position += 1;
+
+            let right = self.parse_binary(precedence + 1)?;
+            left = Expression::Binary(operator, Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, DebugError> {
+        if let Some(type_name) = self.parse_cast() {
+            return Ok(Expression::Cast(type_name, Box::new(self.parse_unary()?)));
+        }
+
+        let Some(Token::Symbol(symbol)) = self.peek(0) else {
+            return self.parse_postfix();
+        };
+        let build: fn(Box<Expression>) -> Expression = match *symbol {
+            "-" => |operand| Expression::Unary(UnaryOperator::Negate, operand),
+            "!" => |operand| Expression::Unary(UnaryOperator::Not, operand),
+            "~" => |operand| Expression::Unary(UnaryOperator::BitNot, operand),
+            "*" => Expression::Deref,
+            "&" => Expression::AddressOf,
+            _ => return self.parse_postfix(),
+        };
+        self.position += 1;
+
+        Ok(build(Box::new(self.parse_unary()?)))
+    }
+
+    /// Parses a cast like `(u32*)`, if the next tokens are one.
+    ///
+    /// A name in parentheses is a cast if it is a pointer or primitive type, or if it is followed by a value.
+    fn parse_cast(&mut self) -> Option<TypeName> {
+        let (Some(Token::Symbol("(")), Some(Token::Identifier(name))) =
+            (self.peek(0), self.peek(1))
+        else {
+            return None;
+        };
+
+        let pointer_depth = (2..)
+            .take_while(|offset| self.peek(*offset) == Some(&Token::Symbol("*")))
+            .count();
+        if self.peek(2 + pointer_depth) != Some(&Token::Symbol(")")) {
+            return None;
+        }
+
+        let is_cast = pointer_depth > 0
+            || Primitive::from_name(name, 0).is_some()
+            || matches!(
+                self.peek(3 + pointer_depth),
+                Some(
+                    Token::Integer(_)
+                        | Token::Float(_)
+                        | Token::Identifier(_)
+                        | Token::Register(_)
+                        | Token::Symbol("(")
+                )
+            );
+        if !is_cast {
+            return None;
+        }
+
+        let type_name = TypeName {
+            name: name.clone(),
+            pointer_depth,
+        };
+        self.position += 3 + pointer_depth;
+        Some(type_name)
+    }
+
+    fn parse_postfix(&mut self) -> Result<Expression, DebugError> {
+        let mut expression = self.parse_primary()?;
+
+        loop {
+            if self.eat(".") {
+                let member = match self.next() {
+                    Some(Token::Identifier(name)) => name,
+                    // Tuple members are named `__0`, `__1`, ... in the debug information.
+                    Some(Token::Integer(index)) => format!("__{index}"),
+                    _ => return Err(invalid("Expected a member name after `.`".to_string())),
+                };
+                expression = Expression::Member(Box::new(expression), member);
+            } else if self.eat("[") {
+                let index = self.parse_binary(0)?;
+                self.expect("]")?;
+                expression = Expression::Index(Box::new(expression), Box::new(index));
+            } else {
+                return Ok(expression);
+            }
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expression, DebugError> {
+        match self.next() {
+            Some(Token::Integer(value)) => Ok(Expression::Integer(value)),
+            Some(Token::Float(value)) => Ok(Expression::Float(value)),
+            Some(Token::Identifier(name)) => Ok(match name.as_str() {
+                "true" => Expression::Bool(true),
+                "false" => Expression::Bool(false),
+                _ => Expression::Variable(name),
+            }),
+            Some(Token::Register(name)) => Ok(Expression::Register(name)),
+            Some(Token::Symbol("(")) => {
+                let expression = self.parse_binary(0)?;
+                self.expect(")")?;
+                Ok(expression)
+            }
+            Some(token) => Err(invalid(format!("Unexpected `{token}` in expression"))),
+            None => Err(invalid("Unexpected end of expression".to_string())),
+        }
+    }
+}
+
+/// The variable cache an evaluated variable is stored in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Local,
+    Static,
+    /// Variables which are created while evaluating an expression, e.g. by dereferencing a cast address.
+    Evaluated,
+}
+
+/// A value while evaluating an expression.
+// Operands are short lived, so boxing the variable would not save anything.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+enum Operand {
+    Scalar(Scalar),
+    Variable { variable: Variable, scope: Scope },
+}
+
+/// A value which is not backed by a variable.
+#[derive(Debug, Clone, PartialEq)]
+enum Scalar {
+    Integer { value: i128, type_name: String },
+    Float { value: f64, type_name: String },
+    Bool(bool),
+    Pointer { address: u64, target: TypeName },
+}
+
+impl Scalar {
+    fn type_name(&self) -> String {
+        match self {
+            Scalar::Integer { type_name, .. } | Scalar::Float { type_name, .. } => {
+                type_name.clone()
+            }
+            Scalar::Bool(_) => "bool".to_string(),
+            Scalar::Pointer { target, .. } => format!("{target}*"),
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Scalar::Integer { value, .. } => Some(*value as f64),
+            Scalar::Float { value, .. } => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn is_true(&self) -> bool {
+        match self {
+            Scalar::Integer { value, .. } => *value != 0,
+            Scalar::Float { value, .. } => *value != 0.0,
+            Scalar::Bool(value) => *value,
+            Scalar::Pointer { address, .. } => *address != 0,
+        }
+    }
+}
+
+impl fmt::Display for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scalar::Integer { value, type_name } if type_name == "char" => {
+                match u32::try_from(*value).ok().and_then(char::from_u32) {
+                    Some(character) => write!(f, "{character:?}"),
+                    None => value.fmt(f),
+                }
+            }
+            Scalar::Integer { value, .. } => value.fmt(f),
+            Scalar::Float { value, .. } => value.fmt(f),
+            Scalar::Bool(value) => value.fmt(f),
+            Scalar::Pointer { address, .. } => write!(f, "{address:#010x}"),
+        }
+    }
+}
+
+/// A type from the debug information.
+struct DwarfType {
+    header_offset: DebugInfoOffset,
+    type_offset: UnitOffset,
+    byte_size: Option<u64>,
+}
+
+struct Evaluator<'a> {
+    /// The debug information of the object file which contains the code of the stack frame.
+    debug_info: &'a ObjectDebugInfo,
+    memory: &'a mut dyn MemoryInterface,
+    stack_frame: &'a mut StackFrame,
+    evaluated_variables: VariableCache,
+}
+
+impl Evaluator<'_> {
+    fn evaluate(&mut self, expression: &Expression) -> Result<Operand, DebugError> {
+        let scalar = match expression {
+            Expression::Integer(value) => Scalar::Integer {
+                value: *value,
+                type_name: INTEGER_LITERAL.to_string(),
+            },
+            Expression::Float(value) => Scalar::Float {
+                value: *value,
+                type_name: FLOAT_LITERAL.to_string(),
+            },
+            Expression::Bool(value) => Scalar::Bool(*value),
+            Expression::Variable(name) => return self.variable(name),
+            Expression::Register(name) => self.register(name)?,
+            Expression::Member(base, member) => {
+                let base = self.evaluate(base)?;
+                return self.member(base, member);
+            }
+            Expression::Index(base, index) => {
+                let base = self.evaluate(base)?;
+                let index = self.evaluate(index)?;
+                let index = self.integer(index)?;
+                return self.index(base, index);
+            }
+            Expression::Deref(operand) => {
+                let operand = self.evaluate(operand)?;
+                return self.deref(operand);
+            }
+            Expression::AddressOf(operand) => match self.evaluate(operand)? {
+                Operand::Variable { variable, .. } => Scalar::Pointer {
+                    address: variable.memory_location.memory_address()?,
+                    target: TypeName {
+                        name: variable.type_name.to_string(),
+                        pointer_depth: 0,
+                    },
+                },
+                Operand::Scalar(_) => {
+                    return Err(invalid("Only variables have an address".to_string()))
+                }
+            },
+            Expression::Cast(type_name, operand) => {
+                let operand = self.evaluate(operand)?;
+                return self.cast(type_name, operand);
+            }
+            Expression::Unary(operator, operand) => {
+                let operand = self.evaluate(operand)?;
+                self.unary(*operator, operand)?
+            }
+            Expression::Binary(operator, left, right) => self.binary(*operator, left, right)?,
+        };
+
+        Ok(Operand::Scalar(scalar))
+    }
+
+    fn finish(&mut self, operand: Operand) -> Result<EvaluatedExpression, DebugError> {
+        match operand {
+            Operand::Variable {
+                mut variable,
+                scope,
+            } => {
+                // The members of complex variables are part of their value, but only cached on demand.
+                self.cache_children(&mut variable, scope)?;
+                let cache = self.cache(scope)?;
+
+                Ok(EvaluatedExpression {
+                    value: variable.get_value(cache),
+                    type_name: variable.type_name.to_string(),
+                    memory_location: variable.memory_location.clone(),
+                    variable: (scope != Scope::Evaluated).then_some(variable),
+                })
+            }
+            Operand::Scalar(scalar) => Ok(EvaluatedExpression {
+                value: scalar.to_string(),
+                type_name: scalar.type_name(),
+                memory_location: VariableLocation::Value,
+                variable: None,
+            }),
+        }
+    }
+
+    fn address_size(&self) -> usize {
+        match self.stack_frame.registers.get_address_size_bytes() {
+            0 => 4,
+            address_size => address_size,
+        }
+    }
+
+    fn cache(&self, scope: Scope) -> Result<&VariableCache, DebugError> {
+        match scope {
+            Scope::Local => self.stack_frame.local_variables.as_ref(),
+            Scope::Static => self.stack_frame.static_variables.as_ref(),
+            Scope::Evaluated => Some(&self.evaluated_variables),
+        }
+        .ok_or_else(|| invalid("No variables are available for this stack frame".to_string()))
+    }
+
+    /// Resolves the deferred children of `variable`, if they were not cached yet.
+    fn cache_children(&mut self, variable: &mut Variable, scope: Scope) -> Result<(), DebugError> {
+        let cache = match scope {
+            Scope::Local => self.stack_frame.local_variables.as_mut(),
+            Scope::Static => self.stack_frame.static_variables.as_mut(),
+            Scope::Evaluated => Some(&mut self.evaluated_variables),
+        }
+        .ok_or_else(|| invalid("No variables are available for this stack frame".to_string()))?;
+
+        self.debug_info.cache_deferred_variables(
+            cache,
+            self.memory,
+            variable,
+            &self.stack_frame.registers,
+            self.stack_frame.frame_base,
+        )
+    }
+
+    fn children(
+        &mut self,
+        variable: &mut Variable,
+        scope: Scope,
+    ) -> Result<Vec<Variable>, DebugError> {
+        self.cache_children(variable, scope)?;
+        Ok(self.cache(scope)?.get_children(variable.variable_key)?)
+    }
+
+    fn variable(&mut self, name: &str) -> Result<Operand, DebugError> {
+        let (namespace, name) = match name.rsplit_once("::") {
+            Some((namespace, name)) => (Some(namespace), name),
+            None => (None, name),
+        };
+
+        for scope in [Scope::Local, Scope::Static] {
+            let Ok(root) = self.cache(scope).map(VariableCache::root_variable) else {
+                continue;
+            };
+            if let Some(variable) = self.find_variable(root, scope, namespace, name)? {
+                return Ok(Operand::Variable { variable, scope });
+            }
+        }
+
+        // Registers can also be used without the `$` prefix, as long as no variable has the same name.
+        match self.stack_frame.registers.get_register_by_name(name) {
+            Some(register) if namespace.is_none() => {
+                Ok(Operand::Scalar(register_value(&register)?))
+            }
+            _ => Err(invalid(format!("Unknown variable `{name}`"))),
+        }
+    }
+
+    /// Searches the children of `parent` for the variable `name`, including the children of nested namespaces.
+    fn find_variable(
+        &mut self,
+        mut parent: Variable,
+        scope: Scope,
+        namespace: Option<&str>,
+        name: &str,
+    ) -> Result<Option<Variable>, DebugError> {
+        let children = self.children(&mut parent, scope)?;
+
+        let in_namespace = match (namespace, &parent.name) {
+            (None, _) => true,
+            (Some(namespace), VariableName::Namespace(parent_namespace)) => {
+                namespace == parent_namespace
+            }
+            _ => false,
+        };
+        let variable_name = VariableName::Named(name.to_string());
+        if let Some(variable) = children
+            .iter()
+            .find(|child| in_namespace && child.name == variable_name)
+        {
+            return Ok(Some(variable.clone()));
+        }
+
+        for child in children {
+            if matches!(child.name, VariableName::Namespace(_)) {
+                if let Some(variable) = self.find_variable(child, scope, namespace, name)? {
+                    return Ok(Some(variable));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn register(&self, name: &str) -> Result<Scalar, DebugError> {
+        let registers = &self.stack_frame.registers;
+        let register = match name {
+            "pc" => registers.get_program_counter().cloned(),
+            "sp" => registers.get_stack_pointer().cloned(),
+            "fp" => registers.get_frame_pointer().cloned(),
+            "lr" | "ra" => registers.get_return_address().cloned(),
+            _ => None,
+        }
+        .or_else(|| registers.get_register_by_name(name))
+        .or_else(|| registers.get_register_by_name(&name.to_uppercase()))
+        .or_else(|| registers.get_register_by_name(&name.to_lowercase()))
+        .ok_or_else(|| invalid(format!("Unknown register `${name}`")))?;
+
+        register_value(&register)
+    }
+
+    fn member(&mut self, base: Operand, member: &str) -> Result<Operand, DebugError> {
+        let Operand::Variable {
+            mut variable,
+            scope,
+        } = base
+        else {
+            return Err(invalid(format!(
+                "Only variables have members, `{member}` cannot be accessed"
+            )));
+        };
+
+        let member_name = VariableName::Named(member.to_string());
+        if let Some(child) = self
+            .children(&mut variable, scope)?
+            .into_iter()
+            .find(|child| child.name == member_name)
+        {
+            return Ok(Operand::Variable {
+                variable: child,
+                scope,
+            });
+        }
+
+        // Like in Rust, the members of the value a pointer points to can be accessed directly.
+        if matches!(variable.type_name, VariableType::Pointer(_)) {
+            let pointee = self.deref(Operand::Variable { variable, scope })?;
+            return self.member(pointee, member);
+        }
+
+        Err(invalid(format!(
+            "`{}` has no member `{member}`",
+            variable.name
+        )))
+    }
+
+    fn index(&mut self, base: Operand, index: i128) -> Result<Operand, DebugError> {
+        match base {
+            Operand::Variable { variable, scope } if variable.type_name.is_array() => {
+                let name = variable.name.clone();
+                self.member(Operand::Variable { variable, scope }, &format!("__{index}"))
+                    .map_err(|_| invalid(format!("Index {index} is out of bounds of `{name}`")))
+            }
+            Operand::Variable { variable, scope }
+                if matches!(variable.type_name, VariableType::Pointer(_)) =>
+            {
+                match self.deref(Operand::Variable { variable, scope })? {
+                    Operand::Variable { variable, .. } => self.offset_variable(&variable, index),
+                    Operand::Scalar(_) => {
+                        unreachable!("Pointer variables dereference to variables")
+                    }
+                }
+            }
+            Operand::Variable { variable, scope } => {
+                // Slices and strings are pointers with a length.
+                let base = Operand::Variable {
+                    variable: variable.clone(),
+                    scope,
+                };
+                let (Ok(data_pointer), Ok(length)) = (
+                    self.member(base.clone(), "data_ptr"),
+                    self.member(base, "length"),
+                ) else {
+                    return Err(invalid(format!(
+                        "`{}` is not an array, a slice or a pointer, and cannot be indexed",
+                        variable.name
+                    )));
+                };
+
+                let length = self.integer(length)?;
+                if !(0..length).contains(&index) {
+                    return Err(invalid(format!(
+                        "Index {index} is out of bounds of `{}`, which has a length of {length}",
+                        variable.name
+                    )));
+                }
+                self.index(data_pointer, index)
+            }
+            Operand::Scalar(Scalar::Pointer { address, target }) => {
+                let address = self.offset(address, &target, index)?;
+                self.read(address, &target)
+            }
+            Operand::Scalar(_) => Err(invalid(
+                "Only arrays, slices and pointers can be indexed".to_string(),
+            )),
+        }
+    }
+
+    /// Creates a variable of the same type as `variable`, `index` elements after it in memory.
+    fn offset_variable(&mut self, variable: &Variable, index: i128) -> Result<Operand, DebugError> {
+        let (Some(header_offset), Some(type_offset), Some(byte_size)) = (
+            variable.unit_header_offset,
+            variable.variable_unit_offset,
+            variable.byte_size,
+        ) else {
+            return Err(invalid(format!(
+                "The size of `{}` is unknown, and it cannot be indexed",
+                variable.name
+            )));
+        };
+
+        let address = variable.memory_location.memory_address()?;
+        let address = i128::from(byte_size)
+            .checked_mul(index)
+            .and_then(|offset| offset.checked_add(i128::from(address)))
+            .and_then(|address| u64::try_from(address).ok())
+            .ok_or_else(|| invalid(format!("Index {index} is out of the address space")))?;
+
+        self.variable_at(
+            header_offset,
+            type_offset,
+            address,
+            format!("{}[{index}]", variable.name),
+        )
+    }
+
+    fn deref(&mut self, operand: Operand) -> Result<Operand, DebugError> {
+        match operand {
+            Operand::Variable {
+                mut variable,
+                scope,
+            } if matches!(variable.type_name, VariableType::Pointer(_)) => {
+                let pointee = self
+                    .children(&mut variable, scope)?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| {
+                        invalid(format!("`{}` cannot be dereferenced", variable.name))
+                    })?;
+                Ok(Operand::Variable {
+                    variable: pointee,
+                    scope,
+                })
+            }
+            Operand::Variable { variable, .. } => Err(invalid(format!(
+                "`{}` is not a pointer, and cannot be dereferenced",
+                variable.name
+            ))),
+            Operand::Scalar(Scalar::Pointer { address, target }) => self.read(address, &target),
+            Operand::Scalar(Scalar::Integer { value, .. }) => Err(invalid(format!(
+                "Only pointers can be dereferenced. Cast the address to a pointer first, e.g. `*(u32*){value:#x}`"
+            ))),
+            Operand::Scalar(_) => Err(invalid("Only pointers can be dereferenced".to_string())),
+        }
+    }
+
+    fn cast(&mut self, type_name: &TypeName, operand: Operand) -> Result<Operand, DebugError> {
+        if type_name.pointer_depth > 0 {
+            let address = self.integer(operand)?;
+            let address = u64::try_from(address)
+                .map_err(|_| invalid(format!("{address} is not a valid address")))?;
+            return Ok(Operand::Scalar(Scalar::Pointer {
+                address,
+                target: type_name.pointee(),
+            }));
+        }
+
+        let name = type_name.name.clone();
+        let scalar = match Primitive::from_name(&name, self.address_size()) {
+            Some(Primitive::Float(size)) => {
+                let value = self.float(operand)?;
+                Scalar::Float {
+                    value: if size == 4 {
+                        value as f32 as f64
+                    } else {
+                        value
+                    },
+                    type_name: name,
+                }
+            }
+            Some(Primitive::Bool) => Scalar::Bool(self.scalar(operand)?.is_true()),
+            Some(primitive) => Scalar::Integer {
+                value: primitive.wrap(self.integer(operand)?),
+                type_name: name,
+            },
+            None => {
+                // Values in memory can be reinterpreted as any type from the debug information.
+                let Operand::Variable { variable, .. } = operand else {
+                    return Err(invalid(format!(
+                        "Only variables can be cast to `{name}`. Use a pointer to read it from memory, e.g. `*({name}*)address`"
+                    )));
+                };
+                let address = variable.memory_location.memory_address()?;
+                let dwarf_type = self.find_type(&name)?;
+                return self.variable_at(
+                    dwarf_type.header_offset,
+                    dwarf_type.type_offset,
+                    address,
+                    format!("({name}){}", variable.name),
+                );
+            }
+        };
+
+        Ok(Operand::Scalar(scalar))
+    }
+
+    fn unary(&mut self, operator: UnaryOperator, operand: Operand) -> Result<Scalar, DebugError> {
+        let scalar = self.scalar(operand)?;
+        let type_name = scalar.type_name();
+
+        match (operator, scalar) {
+            (UnaryOperator::Negate, Scalar::Integer { value, type_name }) => {
+                let value = value
+                    .checked_neg()
+                    .ok_or_else(|| invalid(format!("Negating {value} overflows")))?;
+                Ok(Scalar::Integer {
+                    value: self.wrap(&type_name, value),
+                    type_name,
+                })
+            }
+            (UnaryOperator::Negate, Scalar::Float { value, type_name }) => Ok(Scalar::Float {
+                value: -value,
+                type_name,
+            }),
+            (UnaryOperator::Not, Scalar::Bool(value)) => Ok(Scalar::Bool(!value)),
+            (UnaryOperator::Not | UnaryOperator::BitNot, Scalar::Integer { value, type_name }) => {
+                Ok(Scalar::Integer {
+                    value: self.wrap(&type_name, !value),
+                    type_name,
+                })
+            }
+            _ => Err(invalid(format!(
+                "`{operator}` cannot be applied to a value of type `{type_name}`"
+            ))),
+        }
+    }
+
+    fn binary(
+        &mut self,
+        operator: BinaryOperator,
+        left: &Expression,
+        right: &Expression,
+    ) -> Result<Scalar, DebugError> {
+        let left = self.evaluate(left)?;
+        let left = self.scalar(left)?;
+
+        // The right side of logical operators is only evaluated when it is needed.
+        match operator {
+            BinaryOperator::And if !left.is_true() => return Ok(Scalar::Bool(false)),
+            BinaryOperator::Or if left.is_true() => return Ok(Scalar::Bool(true)),
+            BinaryOperator::And | BinaryOperator::Or => {
+                let right = self.evaluate(right)?;
+                return Ok(Scalar::Bool(self.scalar(right)?.is_true()));
+            }
+            _ => {}
+        }
+
+        let right = self.evaluate(right)?;
+        let right = self.scalar(right)?;
+
+        match (operator, left, right) {
+            (
+                BinaryOperator::Add | BinaryOperator::Subtract,
+                Scalar::Pointer { address, target },
+                Scalar::Integer { value, .. },
+            ) => {
+                let index = if operator == BinaryOperator::Subtract {
+                    value.saturating_neg()
+                } else {
+                    value
+                };
+                Ok(Scalar::Pointer {
+                    address: self.offset(address, &target, index)?,
+                    target,
+                })
+            }
+            (
+                BinaryOperator::Add,
+                Scalar::Integer { value, .. },
+                Scalar::Pointer { address, target },
+            ) => Ok(Scalar::Pointer {
+                address: self.offset(address, &target, value)?,
+                target,
+            }),
+            (
+                BinaryOperator::Subtract,
+                Scalar::Pointer { address, target },
+                Scalar::Pointer {
+                    address: other_address,
+                    ..
+                },
+            ) => {
+                let size = self.size_of(&target)?;
+                let value = (i128::from(address) - i128::from(other_address))
+                    .checked_div(i128::from(size))
+                    .ok_or_else(|| invalid(format!("`{target}` has a size of zero")))?;
+                Ok(Scalar::Integer {
+                    value,
+                    type_name: "isize".to_string(),
+                })
+            }
+            (operator, left, right) => self.apply(operator, left, right),
+        }
+    }
+
+    /// Applies an arithmetic, bitwise or comparison operator to two scalars.
+    fn apply(
+        &self,
+        operator: BinaryOperator,
+        left: Scalar,
+        right: Scalar,
+    ) -> Result<Scalar, DebugError> {
+        let mismatch = || {
+            invalid(format!(
+                "`{operator}` cannot be applied to values of type `{}` and `{}`",
+                left.type_name(),
+                right.type_name()
+            ))
+        };
+
+        // Pointers are compared by their address.
+        let as_integer = |scalar: &Scalar| match scalar {
+            Scalar::Pointer { address, .. } => Scalar::Integer {
+                value: i128::from(*address),
+                type_name: "usize".to_string(),
+            },
+            other => other.clone(),
+        };
+
+        match (as_integer(&left), as_integer(&right)) {
+            (Scalar::Bool(left_value), Scalar::Bool(right_value)) => {
+                let value = match operator {
+                    BinaryOperator::BitAnd => left_value & right_value,
+                    BinaryOperator::BitOr => left_value | right_value,
+                    BinaryOperator::BitXor => left_value ^ right_value,
+                    _ => operator
+                        .compare(Some(left_value.cmp(&right_value)))
+                        .ok_or_else(mismatch)?,
+                };
+                Ok(Scalar::Bool(value))
+            }
+            (
+                Scalar::Integer {
+                    value: left_value,
+                    type_name: left_type,
+                },
+                Scalar::Integer {
+                    value: right_value,
+                    type_name: right_type,
+                },
+            ) => {
+                if let Some(result) = operator.compare(Some(left_value.cmp(&right_value))) {
+                    return Ok(Scalar::Bool(result));
+                }
+
+                let shift = u32::try_from(right_value).ok();
+                let value = match operator {
+                    BinaryOperator::Add => left_value.checked_add(right_value),
+                    BinaryOperator::Subtract => left_value.checked_sub(right_value),
+                    BinaryOperator::Multiply => left_value.checked_mul(right_value),
+                    BinaryOperator::Divide => left_value.checked_div(right_value),
+                    BinaryOperator::Remainder => left_value.checked_rem(right_value),
+                    BinaryOperator::BitAnd => Some(left_value & right_value),
+                    BinaryOperator::BitOr => Some(left_value | right_value),
+                    BinaryOperator::BitXor => Some(left_value ^ right_value),
+                    BinaryOperator::ShiftLeft => {
+                        shift.and_then(|shift| left_value.checked_shl(shift))
+                    }
+                    BinaryOperator::ShiftRight => {
+                        shift.and_then(|shift| left_value.checked_shr(shift))
+                    }
+                    _ => return Err(mismatch()),
+                }
+                .ok_or_else(|| {
+                    invalid(format!(
+                        "`{left_value} {operator} {right_value}` overflows or divides by zero"
+                    ))
+                })?;
+
+                // Literals take the type of the other operand.
+                let type_name = if left_type == INTEGER_LITERAL {
+                    right_type
+                } else {
+                    left_type
+                };
+                Ok(Scalar::Integer {
+                    value: self.wrap(&type_name, value),
+                    type_name,
+                })
+            }
+            (left_scalar, right_scalar) => {
+                let (Some(left_value), Some(right_value)) =
+                    (left_scalar.as_f64(), right_scalar.as_f64())
+                else {
+                    return Err(mismatch());
+                };
+
+                if let Some(result) = operator.compare(left_value.partial_cmp(&right_value)) {
+                    return Ok(Scalar::Bool(result));
+                }
+
+                let value = match operator {
+                    BinaryOperator::Add => left_value + right_value,
+                    BinaryOperator::Subtract => left_value - right_value,
+                    BinaryOperator::Multiply => left_value * right_value,
+                    BinaryOperator::Divide => left_value / right_value,
+                    BinaryOperator::Remainder => left_value % right_value,
+                    _ => return Err(mismatch()),
+                };
+                let type_name = [left_scalar, right_scalar]
+                    .into_iter()
+                    .find_map(|scalar| match scalar {
+                        Scalar::Float { type_name, .. } if type_name != FLOAT_LITERAL => {
+                            Some(type_name)
+                        }
+                        _ => None,
+                    })
+                    .unwrap_or_else(|| FLOAT_LITERAL.to_string());
+                Ok(Scalar::Float { value, type_name })
+            }
+        }
+    }
+
+    /// Truncates `value` to the range of the primitive type `type_name`, if it is one.
+    fn wrap(&self, type_name: &str, value: i128) -> i128 {
+        match Primitive::from_name(type_name, self.address_size()) {
+            Some(primitive) => primitive.wrap(value),
+            None => value,
+        }
+    }
+
+    /// Converts a variable to the scalar value it holds.
+    fn scalar(&mut self, operand: Operand) -> Result<Scalar, DebugError> {
+        let (mut variable, scope) = match operand {
+            Operand::Scalar(scalar) => return Ok(scalar),
+            Operand::Variable { variable, scope } => (variable, scope),
+        };
+
+        match variable.type_name.clone() {
+            VariableType::Base(type_name) => {
+                let value = variable.get_value(self.cache(scope)?);
+                self.parse_base_value(&type_name, &value).ok_or_else(|| {
+                    invalid(format!(
+                        "The value `{value}` of `{}` cannot be used in an expression",
+                        variable.name
+                    ))
+                })
+            }
+            VariableType::Pointer(_) => {
+                let pointee = self
+                    .children(&mut variable, scope)?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| {
+                        invalid(format!(
+                            "The address `{}` points to is unknown",
+                            variable.name
+                        ))
+                    })?;
+                Ok(Scalar::Pointer {
+                    address: pointee.memory_location.memory_address()?,
+                    target: TypeName {
+                        name: pointee.type_name.to_string(),
+                        pointer_depth: 0,
+                    },
+                })
+            }
+            other => Err(invalid(format!(
+                "`{}` of type `{other}` cannot be used as a value",
+                variable.name
+            ))),
+        }
+    }
+
+    fn parse_base_value(&self, type_name: &str, value: &str) -> Option<Scalar> {
+        let type_name = type_name.to_string();
+        match Primitive::from_name(&type_name, self.address_size())? {
+            Primitive::Bool => value.parse().ok().map(Scalar::Bool),
+            Primitive::Char => {
+                let mut characters = value.chars();
+                match (characters.next(), characters.next()) {
+                    (Some(character), None) => Some(Scalar::Integer {
+                        value: i128::from(u32::from(character)),
+                        type_name,
+                    }),
+                    _ => None,
+                }
+            }
+            Primitive::Float(_) => value
+                .parse()
+                .ok()
+                .map(|value| Scalar::Float { value, type_name }),
+            Primitive::Unsigned(_) | Primitive::Signed(_) => value
+                .parse()
+                .ok()
+                .map(|value| Scalar::Integer { value, type_name }),
+        }
+    }
+
+    fn integer(&mut self, operand: Operand) -> Result<i128, DebugError> {
+        match self.scalar(operand)? {
+            Scalar::Integer { value, .. } => Ok(value),
+            Scalar::Float { value, .. } => Ok(value as i128),
+            Scalar::Bool(value) => Ok(i128::from(value)),
+            Scalar::Pointer { address, .. } => Ok(i128::from(address)),
+        }
+    }
+
+    fn float(&mut self, operand: Operand) -> Result<f64, DebugError> {
+        let scalar = self.scalar(operand)?;
+        scalar.as_f64().ok_or_else(|| {
+            invalid(format!(
+                "A value of type `{}` cannot be converted to a floating point number",
+                scalar.type_name()
+            ))
+        })
+    }
+
+    /// The address `index` elements of type `target` after `address`.
+    fn offset(&self, address: u64, target: &TypeName, index: i128) -> Result<u64, DebugError> {
+        let size = self.size_of(target)?;
+        i128::from(size)
+            .checked_mul(index)
+            .and_then(|offset| offset.checked_add(i128::from(address)))
+            .and_then(|address| u64::try_from(address).ok())
+            .ok_or_else(|| invalid(format!("Index {index} is out of the address space")))
+    }
+
+    fn size_of(&self, type_name: &TypeName) -> Result<u64, DebugError> {
+        if type_name.pointer_depth > 0 {
+            return Ok(self.address_size() as u64);
+        }
+        if let Some(primitive) = Primitive::from_name(&type_name.name, self.address_size()) {
+            return Ok(primitive.size() as u64);
+        }
+
+        self.find_type(&type_name.name)?
+            .byte_size
+            .ok_or_else(|| invalid(format!("The size of `{type_name}` is unknown")))
+    }
+
+    /// Reads a value of type `type_name` from memory.
+    fn read(&mut self, address: u64, type_name: &TypeName) -> Result<Operand, DebugError> {
+        let address_size = self.address_size();
+
+        if type_name.pointer_depth > 0 {
+            let mut bytes = [0; 8];
+            self.memory.read(address, &mut bytes[..address_size])?;
+            return Ok(Operand::Scalar(Scalar::Pointer {
+                address: u64::from_le_bytes(bytes),
+                target: type_name.pointee(),
+            }));
+        }
+
+        if let Some(primitive) = Primitive::from_name(&type_name.name, address_size) {
+            let mut bytes = vec![0; primitive.size()];
+            self.memory.read(address, &mut bytes)?;
+            return Ok(Operand::Scalar(primitive.decode(&bytes, &type_name.name)));
+        }
+
+        let dwarf_type = self.find_type(&type_name.name)?;
+        self.variable_at(
+            dwarf_type.header_offset,
+            dwarf_type.type_offset,
+            address,
+            format!("*({type_name}*){address:#010x}"),
+        )
+    }
+
+    /// Finds a named base, struct, union or enum type in the debug information.
+    fn find_type(&self, name: &str) -> Result<DwarfType, DebugError> {
+        // Types are named without their namespace in the debug information.
+        let type_name = name.rsplit("::").next().unwrap_or(name);
+
+        for unit_info in &self.debug_info.unit_infos {
+            let Some(header_offset) = unit_info.unit.header.offset().as_debug_info_offset() else {
+                continue;
+            };
+
+            let mut entries = unit_info.unit.entries();
+            while let Ok(Some((_, entry))) = entries.next_dfs() {
+                if !matches!(
+                    entry.tag(),
+                    gimli::DW_TAG_base_type
+                        | gimli::DW_TAG_structure_type
+                        | gimli::DW_TAG_union_type
+                        | gimli::DW_TAG_enumeration_type
+                ) || matches!(
+                    entry.attr_value(gimli::DW_AT_declaration),
+                    Ok(Some(gimli::AttributeValue::Flag(true)))
+                ) {
+                    continue;
+                }
+
+                let Ok(Some(entry_name)) = entry.attr_value(gimli::DW_AT_name) else {
+                    continue;
+                };
+                if extract_name(self.debug_info, entry_name) == type_name {
+                    return Ok(DwarfType {
+                        header_offset,
+                        type_offset: entry.offset(),
+                        byte_size: extract_byte_size(entry),
+                    });
+                }
+            }
+        }
+
+        Err(invalid(format!("Unknown type `{name}`")))
+    }
+
+    /// Creates a variable of the type at `type_offset`, which is stored at `address`.
+    fn variable_at(
+        &mut self,
+        header_offset: DebugInfoOffset,
+        type_offset: UnitOffset,
+        address: u64,
+        name: String,
+    ) -> Result<Operand, DebugError> {
+        let unit_header = self
+            .debug_info
+            .dwarf
+            .debug_info
+            .header_from_offset(header_offset)?;
+        let unit_info = UnitInfo::new(gimli::Unit::new(&self.debug_info.dwarf, unit_header)?);
+        let mut type_tree = unit_info
+            .unit
+            .header
+            .entries_tree(&unit_info.unit.abbreviations, Some(type_offset))?;
+        let type_node = type_tree.root()?;
+
+        let parent = self.evaluated_variables.root_variable();
+        let mut variable = self.evaluated_variables.create_variable(
+            parent.variable_key,
+            Some(header_offset),
+            Some(type_offset),
+        )?;
+        variable.name = VariableName::Named(name);
+        variable.memory_location = VariableLocation::Address(address);
+
+        let variable = unit_info.extract_type(
+            self.debug_info,
+            type_node,
+            &parent,
+            variable,
+            self.memory,
+            &self.stack_frame.registers,
+            self.stack_frame.frame_base,
+            &mut self.evaluated_variables,
+        )?;
+
+        Ok(Operand::Variable {
+            variable,
+            scope: Scope::Evaluated,
+        })
+    }
+}
+
+fn register_value(register: &DebugRegister) -> Result<Scalar, DebugError> {
+    let value = register.value.ok_or_else(|| {
+        invalid(format!(
+            "The value of register `{}` is not available",
+            register.get_register_name()
+        ))
+    })?;
+    let value: u64 = value.try_into()?;
+
+    Ok(Scalar::Integer {
+        value: i128::from(value),
+        type_name: format!("u{}", register.core_register.size_in_bits()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::{core::exception_handler_for_core, CoreDump};
+
+    fn parse(expression: &str) -> Expression {
+        expression.parse().unwrap()
+    }
+
+    fn variable(name: &str) -> Box<Expression> {
+        Box::new(Expression::Variable(name.to_string()))
+    }
+
+    fn integer(value: i128) -> Box<Expression> {
+        Box::new(Expression::Integer(value))
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            parse("a + b * 2 == 7 && !c"),
+            Expression::Binary(
+                BinaryOperator::And,
+                Box::new(Expression::Binary(
+                    BinaryOperator::Equal,
+                    Box::new(Expression::Binary(
+                        BinaryOperator::Add,
+                        variable("a"),
+                        Box::new(Expression::Binary(
+                            BinaryOperator::Multiply,
+                            variable("b"),
+                            integer(2)
+                        ))
+                    )),
+                    integer(7)
+                )),
+                Box::new(Expression::Unary(UnaryOperator::Not, variable("c")))
+            )
+        );
+        assert_eq!(
+            parse("(a - 1) - 2"),
+            Expression::Binary(
+                BinaryOperator::Subtract,
+                Box::new(Expression::Binary(
+                    BinaryOperator::Subtract,
+                    variable("a"),
+                    integer(1)
+                )),
+                integer(2)
+            )
+        );
+    }
+
+    #[test]
+    fn members_and_indices() {
+        assert_eq!(
+            parse("*a.b[3].0"),
+            Expression::Deref(Box::new(Expression::Member(
+                Box::new(Expression::Index(
+                    Box::new(Expression::Member(variable("a"), "b".to_string())),
+                    integer(3)
+                )),
+                "__0".to_string()
+            )))
+        );
+        assert_eq!(
+            parse("a.0.1"),
+            Expression::Member(
+                Box::new(Expression::Member(variable("a"), "__0".to_string())),
+                "__1".to_string()
+            )
+        );
+        assert_eq!(
+            parse("module::NAME"),
+            Expression::Variable("module::NAME".to_string())
+        );
+    }
+
+    #[test]
+    fn casts() {
+        assert_eq!(
+            parse("*(u32*)0x2000_0000"),
+            Expression::Deref(Box::new(Expression::Cast(
+                TypeName {
+                    name: "u32".to_string(),
+                    pointer_depth: 1
+                },
+                integer(0x2000_0000)
+            )))
+        );
+        assert_eq!(
+            parse("(Point)p"),
+            Expression::Cast(
+                TypeName {
+                    name: "Point".to_string(),
+                    pointer_depth: 0
+                },
+                variable("p")
+            )
+        );
+        // A variable in parentheses is not a cast.
+        assert_eq!(
+            parse("(a) - 1"),
+            Expression::Binary(BinaryOperator::Subtract, variable("a"), integer(1))
+        );
+    }
+
+    #[test]
+    fn literals_and_registers() {
+        assert_eq!(parse("0b101"), Expression::Integer(5));
+        assert_eq!(parse("1_000"), Expression::Integer(1000));
+        assert_eq!(parse("2.5"), Expression::Float(2.5));
+        assert_eq!(parse("true"), Expression::Bool(true));
+        assert_eq!(
+            parse("$pc + 4"),
+            Expression::Binary(
+                BinaryOperator::Add,
+                Box::new(Expression::Register("pc".to_string())),
+                integer(4)
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_expressions() {
+        for expression in ["", "a +", "a[1", "(u32*", "a..b", "1 # 2", "$", "0xZZ"] {
+            assert!(
+                expression.parse::<Expression>().is_err(),
+                "{expression:?} should not parse"
+            );
+        }
+    }
+
+    fn evaluate(expression: &str) -> Result<EvaluatedExpression, DebugError> {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("tests/debug-unwind-tests");
+
+        let debug_info = DebugInfo::from_file(path.join("RP2040.elf")).unwrap();
+        let mut adapter = CoreDump::load(&path.join("RP2040.coredump")).unwrap();
+
+        let initial_registers = adapter.debug_registers();
+        let exception_handler = exception_handler_for_core(adapter.core_type());
+        let instruction_set = adapter.instruction_set();
+        let mut stack_frames = debug_info
+            .unwind(
+                &mut adapter,
+                initial_registers,
+                exception_handler.as_ref(),
+                Some(instruction_set),
+            )
+            .unwrap();
+
+        debug_info.evaluate_expression(&mut adapter, &mut stack_frames[0], expression)
+    }
+
+    fn value(expression: &str) -> (String, String) {
+        let evaluated = evaluate(expression).unwrap();
+        (evaluated.value, evaluated.type_name)
+    }
+
+    #[test]
+    fn evaluate_variables() {
+        assert_eq!(value("U32"), ("32".to_string(), "u32".to_string()));
+        assert_eq!(
+            value("common_testing_code::I64"),
+            ("-64".to_string(), "i64".to_string())
+        );
+        assert_eq!(
+            value("REGULAR_STRUCT.Case1.1.x"),
+            ("24".to_string(), "i64".to_string())
+        );
+        assert_eq!(
+            value("*GLOBAL_STATIC.data_ptr"),
+            ("65".to_string(), "u8".to_string())
+        );
+        assert_eq!(value("GLOBAL_STATIC[1]").0, "32");
+
+        let evaluated = evaluate("REGULAR_STRUCT.Case1.1").unwrap();
+        assert_eq!(
+            evaluated.memory_location,
+            VariableLocation::Address(0x20000058)
+        );
+        assert!(evaluated.variable.is_some());
+    }
+
+    #[test]
+    fn evaluate_arithmetic() {
+        assert_eq!(value("U32 * 2 + 1"), ("65".to_string(), "u32".to_string()));
+        assert_eq!(
+            value("(u8)(U32 * 10)"),
+            ("64".to_string(), "u8".to_string())
+        );
+        assert_eq!(value("I8 - 100"), ("-32".to_string(), "i8".to_string()));
+        assert_eq!(value("F32 * 2"), ("5".to_string(), "f32".to_string()));
+        assert_eq!(
+            value("U16 > 10 && !B"),
+            ("true".to_string(), "bool".to_string())
+        );
+        assert_eq!(value("(char)97"), ("'a'".to_string(), "char".to_string()));
+        assert_eq!(value("1 << 4 | 1").0, "17");
+    }
+
+    #[test]
+    fn evaluate_memory() {
+        let (address, _) = value("(usize)GLOBAL_STATIC.data_ptr");
+        assert_eq!(
+            value(&format!("*(u8*){address}")),
+            ("65".to_string(), "u8".to_string())
+        );
+        assert_eq!(value(&format!("((u8*){address})[1]")).0, "32");
+        assert_eq!(
+            value("&REGULAR_STRUCT.Case1.1.x"),
+            ("0x20000058".to_string(), "i64*".to_string())
+        );
+        assert_eq!(
+            value("(*(ComplexStruct*)0x20000058).y"),
+            ("25".to_string(), "i32".to_string())
+        );
+    }
+
+    #[test]
+    fn evaluate_registers() {
+        let (value, type_name) = value("$pc");
+        assert_eq!(type_name, "u32");
+        assert_eq!(value, 0x1000810a_i128.to_string());
+    }
+
+    #[test]
+    fn evaluation_errors() {
+        for expression in [
+            "UNKNOWN",
+            "U32.member",
+            "*U32",
+            "U32 / 0",
+            "$unknown",
+            "*(UnknownType*)0x20000000",
+            "GLOBAL_STATIC[100]",
+        ] {
+            assert!(
+                evaluate(expression).is_err(),
+                "{expression:?} should not evaluate"
+            );
+        }
+    }
+}
@@ -70,7 +70,10 @@ impl<'debugunit, 'abbrev, 'unit: 'debugunit, 'unit_info> FunctionDie<'abbrev, 'u
     }
 
     /// Returns the function name described by the die.
-    pub(crate) fn function_name(&self, debug_info: &super::DebugInfo) -> Option<String> {
+    pub(crate) fn function_name(
+        &self,
+        debug_info: &super::debug_info::ObjectDebugInfo,
+    ) -> Option<String> {
         let Some(fn_name_attr) = self.attribute(gimli::DW_AT_name) else {
             tracing::debug!("DW_AT_name attribute not found, unable to retrieve function name");
             return None;
@@ -96,7 +99,7 @@ impl<'debugunit, 'abbrev, 'unit: 'debugunit, 'unit_info> FunctionDie<'abbrev, 'u
     /// this function returns `None`.
     pub(crate) fn inline_call_location(
         &self,
-        debug_info: &super::DebugInfo,
+        debug_info: &super::debug_info::ObjectDebugInfo,
     ) -> Option<SourceLocation> {
         if !self.is_inline() {
             return None;
@@ -178,7 +181,7 @@ impl<'debugunit, 'abbrev, 'unit: 'debugunit, 'unit_info> FunctionDie<'abbrev, 'u
     /// Try to retrieve the frame base for this function
     pub fn frame_base(
         &self,
-        debug_info: &super::DebugInfo,
+        debug_info: &super::debug_info::ObjectDebugInfo,
         memory: &mut impl MemoryInterface,
         stackframe_registers: &DebugRegisters,
     ) -> Result<Option<u64>, DebugError> {
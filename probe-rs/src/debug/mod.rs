@@ -9,6 +9,8 @@
 pub mod debug_info;
 /// Stepping through a program during debug, at various granularities.
 pub mod debug_step;
+/// Expressions which are evaluated against the variables, registers and memory of a stack frame.
+pub mod expression;
 /// References to the DIE (debug information entry) of functions.
 pub mod function_die;
 /// Target Register definitions, expanded from [`crate::core::registers::CoreRegister`] to include unwind specific information.
@@ -25,7 +27,12 @@ pub mod variable;
 pub mod variable_cache;
 
 pub use self::{
-    debug_info::*, debug_step::SteppingMode, registers::*, stack_frame::StackFrame, variable::*,
+    debug_info::*,
+    debug_step::SteppingMode,
+    expression::{EvaluatedExpression, Expression},
+    registers::*,
+    stack_frame::StackFrame,
+    variable::*,
     variable_cache::VariableCache,
 };
 use crate::{core::Core, MemoryInterface};
@@ -86,6 +93,12 @@ pub enum DebugError {
         /// A message that can be displayed to the user to help them understand the reason for the incomplete results.
         message: String,
     },
+    /// An expression could not be parsed, or could not be evaluated in the context of a stack frame.
+    #[error("{message}")]
+    InvalidExpression {
+        /// A message that explains to the user what is wrong with the expression.
+        message: String,
+    },
     /// Some other error occurred.
     #[error(transparent)]
     Other(#[from] anyhow::Error),
@@ -229,7 +242,7 @@ impl SourceLocation {
 
 /// If file information is available, it returns `Some(directory:PathBuf, file_name:String)`, otherwise `None`.
 fn extract_file(
-    debug_info: &DebugInfo,
+    debug_info: &ObjectDebugInfo,
     unit: &gimli::Unit<GimliReader>,
     attribute_value: gimli::AttributeValue<GimliReader>,
 ) -> Option<(TypedPathBuf, String)> {
@@ -294,7 +307,7 @@ fn extract_line(attribute_value: gimli::AttributeValue<GimliReader>) -> Option<u
 }
 
 fn extract_name(
-    debug_info: &DebugInfo,
+    debug_info: &ObjectDebugInfo,
     attribute_value: gimli::AttributeValue<GimliReader>,
 ) -> String {
     match attribute_value {
@@ -1,4 +1,4 @@
-use super::{unit_info::UnitInfo, DebugError, DebugInfo};
+use super::{debug_info::ObjectDebugInfo, unit_info::UnitInfo, DebugError};
 use gimli::{ColumnType, LineSequence};
 use std::{
     fmt::{Debug, Formatter},
@@ -26,7 +26,7 @@ impl SourceStatements {
     /// Extract all the source statements from the `program_unit`, starting at the `program_counter`.
     /// Note:: In the interest of efficiency, for the case of SteppingMode::Breakpoint, this method will return as soon as it finds a valid halt point, and the result will only include the source statements between program_counter and the first valid haltpoint (inclusive).
     pub(crate) fn new(
-        debug_info: &DebugInfo,
+        debug_info: &ObjectDebugInfo,
         program_unit: &UnitInfo,
         program_counter: u64,
     ) -> Result<Self, DebugError> {
@@ -229,7 +229,7 @@ impl From<&gimli::LineRow> for SourceStatement {
 #[allow(clippy::type_complexity)]
 /// Resolve the relevant program row data for the given program counter.
 fn get_program_info_at_pc(
-    debug_info: &DebugInfo,
+    debug_info: &ObjectDebugInfo,
     program_unit: &UnitInfo,
     program_counter: u64,
 ) -> Result<
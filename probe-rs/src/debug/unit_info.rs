@@ -3,7 +3,7 @@ use super::{
     function_die::FunctionDie, registers, variable::*, DebugError, DebugRegisters, EndianReader,
     SourceLocation, VariableCache,
 };
-use crate::{core::RegisterValue, Error, MemoryInterface};
+use crate::{Error, MemoryInterface};
 use gimli::{AttributeValue::Language, EvaluationResult, Location, UnitOffset};
 use num_traits::Zero;
 
@@ -45,7 +45,7 @@ impl UnitInfo {
     /// If `find_inlined` is `true`, then the result will contain a  [`Vec<FunctionDie>`], where the innermost (deepest in the stack) function die is the last entry in the Vec.
     pub(crate) fn get_function_dies(
         &self,
-        debug_info: &super::DebugInfo,
+        debug_info: &ObjectDebugInfo,
         address: u64,
         find_inlined: bool,
     ) -> Result<Vec<FunctionDie>, DebugError> {
@@ -104,7 +104,7 @@ impl UnitInfo {
     /// given address.
     pub(crate) fn find_inlined_functions(
         &self,
-        debug_info: &DebugInfo,
+        debug_info: &ObjectDebugInfo,
         address: u64,
         offset: UnitOffset,
     ) -> Result<Vec<FunctionDie>, DebugError> {
@@ -175,7 +175,7 @@ impl UnitInfo {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn process_tree_node_attributes(
         &self,
-        debug_info: &DebugInfo,
+        debug_info: &ObjectDebugInfo,
         tree_node: &mut gimli::EntriesTreeNode<GimliReader>,
         parent_variable: &mut Variable,
         mut child_variable: Variable,
@@ -505,7 +505,7 @@ impl UnitInfo {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn process_tree(
         &self,
-        debug_info: &DebugInfo,
+        debug_info: &ObjectDebugInfo,
         parent_node: gimli::EntriesTreeNode<GimliReader>,
         mut parent_variable: Variable,
         memory: &mut dyn MemoryInterface,
@@ -518,11 +518,12 @@ impl UnitInfo {
             return Ok(parent_variable);
         }
 
+        // The scopes in the debug information use addresses in the object file.
         let program_counter = if let Some(program_counter) = stack_frame_registers
             .get_program_counter()
             .and_then(|reg| reg.value)
         {
-            program_counter.try_into()?
+            debug_info.object_address(program_counter.try_into()?)
         } else {
             return Err(DebugError::UnwindIncompleteResults {
                 message: "Cannot unwind `Variable` without a valid PC (program_counter)"
@@ -822,7 +823,7 @@ impl UnitInfo {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn extract_type(
         &self,
-        debug_info: &DebugInfo,
+        debug_info: &ObjectDebugInfo,
         node: gimli::EntriesTreeNode<GimliReader>,
         parent_variable: &Variable,
         mut child_variable: Variable,
@@ -1275,7 +1276,7 @@ impl UnitInfo {
     #[allow(clippy::too_many_arguments)]
     fn expand_array_member(
         &self,
-        debug_info: &DebugInfo,
+        debug_info: &ObjectDebugInfo,
         unit_ref: UnitOffset,
         cache: &mut VariableCache,
         child_variable: &mut Variable,
@@ -1352,7 +1353,7 @@ impl UnitInfo {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn process_memory_location(
         &self,
-        debug_info: &DebugInfo,
+        debug_info: &ObjectDebugInfo,
         node_die: &gimli::DebuggingInformationEntry<GimliReader>,
         parent_variable: &Variable,
         child_variable: &mut Variable,
@@ -1444,7 +1445,7 @@ impl UnitInfo {
     /// - Result<ExpressionResult::Location(),_>:  One of the variants of VariableLocation, and needs to be interpreted for handling the 'expected' errors we encounter during evaluation.
     pub(crate) fn extract_location(
         &self,
-        debug_info: &DebugInfo,
+        debug_info: &ObjectDebugInfo,
         node_die: &gimli::DebuggingInformationEntry<GimliReader>,
         parent_location: &VariableLocation,
         memory: &mut dyn MemoryInterface,
@@ -1458,7 +1459,7 @@ impl UnitInfo {
                 | gimli::DW_AT_frame_base
                 | gimli::DW_AT_data_member_location => match attr.value() {
                     gimli::AttributeValue::Exprloc(expression) => {
-                        return match self.evaluate_expression(memory , expression, stack_frame_registers, frame_base) {
+                        return match self.evaluate_expression(debug_info, memory , expression, stack_frame_registers, frame_base) {
                             Ok(result) => Ok(result),
                             Err(error) => {
                                 if matches!(error, DebugError::UnwindIncompleteResults { message: _ }) {
@@ -1498,6 +1499,8 @@ impl UnitInfo {
                                 if let Some(program_counter) = stack_frame_registers
                                     .get_program_counter()
                                     .and_then(|reg| reg.value)
+                                    .and_then(|pc| pc.try_into().ok())
+                                    .map(|pc| debug_info.object_address(pc))
                                 {
                                     let mut expression: Option<gimli::Expression<GimliReader>> =
                                         None;
@@ -1507,10 +1510,8 @@ impl UnitInfo {
                                             return Ok(ExpressionResult::Location(VariableLocation::Error(format!("Error: Iterating LocationLists for this variable: {:?}", &error))));
                                         }
                                     } {
-                                        if program_counter
-                                            >= RegisterValue::from(location.range.begin)
-                                            && program_counter
-                                                < RegisterValue::from(location.range.end)
+                                        if program_counter >= location.range.begin
+                                            && program_counter < location.range.end
                                         {
                                             expression = Some(location.data);
                                             break;
@@ -1518,6 +1519,7 @@ impl UnitInfo {
                                     }
                                     if let Some(valid_expression) = expression {
                                         return match self.evaluate_expression(
+                                            debug_info,
                                             memory,
                                             valid_expression,
                                             stack_frame_registers,
@@ -1589,13 +1591,19 @@ impl UnitInfo {
     /// - Result<ExpressionResult::Location(),_>:  One of the variants of VariableLocation, and needs to be interpreted for handling the 'expected' errors we encounter during evaluation.
     pub(crate) fn evaluate_expression(
         &self,
+        debug_info: &ObjectDebugInfo,
         memory: &mut dyn MemoryInterface,
         expression: gimli::Expression<GimliReader>,
         stack_frame_registers: &registers::DebugRegisters,
         frame_base: Option<u64>,
     ) -> Result<ExpressionResult, DebugError> {
-        let pieces =
-            self.expression_to_piece(memory, expression, stack_frame_registers, frame_base)?;
+        let pieces = self.expression_to_piece(
+            debug_info,
+            memory,
+            expression,
+            stack_frame_registers,
+            frame_base,
+        )?;
         if pieces.is_empty() {
             Ok(ExpressionResult::Location(VariableLocation::Error(
                 format!("Error: expr_to_piece() returned 0 results: {pieces:?}"),
@@ -1708,6 +1716,7 @@ impl UnitInfo {
     /// Tries to get the result of a DWARF expression in the form of a Piece.
     pub(crate) fn expression_to_piece(
         &self,
+        debug_info: &ObjectDebugInfo,
         memory: &mut dyn MemoryInterface,
         expression: gimli::Expression<GimliReader>,
         stack_frame_registers: &registers::DebugRegisters,
@@ -1733,8 +1742,9 @@ impl UnitInfo {
                     base_type,
                 } => provide_register(stack_frame_registers, register, base_type, &mut evaluation)?,
                 EvaluationResult::RequiresRelocatedAddress(address_index) => {
-                    // The address_index as an offset from 0, so just pass it into the next step.
-                    evaluation.resume_with_relocated_address(address_index)?
+                    // The address_index is an address in the object file, which is relocated to target memory.
+                    evaluation
+                        .resume_with_relocated_address(debug_info.target_address(address_index))?
                 }
                 unimplemented_expression => {
                     return Err(DebugError::UnwindIncompleteResults {
@@ -130,6 +130,16 @@ impl VariableCache {
         VariableCache::new(device_root_variable)
     }
 
+    /// Create a cache for variables which are created while evaluating an expression,
+    /// e.g. by reading a type from the debug information at an address.
+    pub(crate) fn new_expression_cache() -> Self {
+        let mut expression_root_variable = Variable::new(None, None);
+        expression_root_variable.variable_node_type = VariableNodeType::DoNotRecurse;
+        expression_root_variable.name = VariableName::Artifical;
+
+        VariableCache::new(expression_root_variable)
+    }
+
     /// Get the root variable of the cache
     pub fn root_variable(&self) -> Variable {
         self.variable_hash_map[&self.root_variable_key].clone()
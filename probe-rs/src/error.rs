@@ -56,6 +56,14 @@ pub enum Error {
     /// A timeout occurred during an operation
     #[error("A timeout occurred.")]
     Timeout,
+    /// The core can not watch the requested memory range.
+    #[error("A watchpoint on {len} bytes at {address:#010x} is not supported by this core.")]
+    UnsupportedWatchpoint {
+        /// The address of the first byte to watch.
+        address: u64,
+        /// The number of bytes to watch.
+        len: u64,
+    },
     /// Unaligned memory access
     #[error("Alignment error")]
     MemoryNotAligned {
@@ -4,7 +4,8 @@ use std::ops::Range;
 
 use probe_rs_target::{MemoryRange, NvmRegion, PageInfo};
 
-use super::{FlashAlgorithm, FlashError, FlashVisualizer};
+use super::{FlashAlgorithm, FlashDiffVisualizer, FlashError, FlashVisualizer};
+use crate::MemoryInterface;
 
 /// The description of a page in flash.
 #[derive(Clone, PartialEq, Eq)]
@@ -136,11 +137,123 @@ impl FlashLayout {
         &self.data_blocks
     }
 
+    /// Assembles the contents the given sector will have after programming.
+    ///
+    /// Bytes which are not covered by any page are expected to be erased.
+    /// Returns `None` if a page is only partially contained in the sector,
+    /// in which case the sector can not be handled on its own.
+    pub(super) fn sector_data(
+        &self,
+        sector: &FlashSector,
+        erased_byte_value: u8,
+    ) -> Option<Vec<u8>> {
+        let sector_range = sector.address..sector.address + sector.size;
+        let mut data = vec![erased_byte_value; sector.size as usize];
+
+        for page in &self.pages {
+            let page_range = page.address..page.address + page.size() as u64;
+            if !page_range.intersects_range(&sector_range) {
+                continue;
+            }
+            if !sector_range.contains_range(&page_range) {
+                return None;
+            }
+
+            let offset = (page.address - sector.address) as usize;
+            data[offset..][..page.data.len()].copy_from_slice(&page.data);
+        }
+
+        Some(data)
+    }
+
+    /// Returns the number of bytes of all pages inside `sector`.
+    pub(super) fn sector_page_size(&self, sector: &FlashSector) -> u64 {
+        let sector_range = sector.address..sector.address + sector.size;
+
+        self.pages
+            .iter()
+            .filter(|page| sector_range.contains(&page.address))
+            .map(|page| page.size() as u64)
+            .sum()
+    }
+
+    /// Removes the sector starting at `address` as well as all pages and fills inside of it,
+    /// so it is neither erased nor programmed.
+    pub(super) fn remove_sector(&mut self, address: u64) {
+        let Some(index) = self.sectors.iter().position(|s| s.address == address) else {
+            return;
+        };
+        let sector = self.sectors.remove(index);
+        let sector_range = sector.address..sector.address + sector.size;
+
+        // Fills refer to their page by index, so they have to be remapped.
+        let mut page_indices = Vec::with_capacity(self.pages.len());
+        let mut retained_pages = 0;
+        self.pages.retain(|page| {
+            let keep = !sector_range.contains(&page.address);
+            page_indices.push(keep.then_some(retained_pages));
+            retained_pages += keep as usize;
+            keep
+        });
+
+        self.fills
+            .retain_mut(|fill| match page_indices[fill.page_index] {
+                Some(new_index) => {
+                    fill.page_index = new_index;
+                    true
+                }
+                None => false,
+            });
+    }
+
     /// Get a visualizer for the flash layout, which can create
     /// a graphical representation of the layout.
     pub fn visualize(&self) -> FlashVisualizer {
         FlashVisualizer::new(self)
     }
+
+    /// Get a visualizer for the differences between this layout and a `new` layout,
+    /// e.g. of the next build of the firmware.
+    pub fn visualize_diff<'layout>(
+        &'layout self,
+        new: &'layout FlashLayout,
+    ) -> FlashDiffVisualizer<'layout> {
+        FlashDiffVisualizer::new(self, new)
+    }
+
+    /// Reads the current contents of the pages of this layout from the target.
+    ///
+    /// The returned layout has the same sectors and pages, and can be compared with
+    /// this layout using [`FlashLayout::visualize_diff`].
+    pub fn read_back(
+        &self,
+        memory: &mut impl MemoryInterface,
+    ) -> Result<FlashLayout, crate::Error> {
+        let mut pages = Vec::with_capacity(self.pages.len());
+        for page in &self.pages {
+            let mut data = vec![0; page.data.len()];
+            memory.read(page.address, &mut data)?;
+            pages.push(FlashPage {
+                address: page.address,
+                data,
+            });
+        }
+
+        let data_blocks = pages
+            .iter()
+            .map(|page| FlashDataBlockSpan {
+                address: page.address,
+                size: page.data.len() as u64,
+            })
+            .collect();
+
+        Ok(FlashLayout {
+            sectors: self.sectors.clone(),
+            pages,
+            fills: vec![],
+            data_blocks,
+        })
+    }
 }
 
 /// A block of data that is to be written to flash.
@@ -264,6 +377,16 @@ impl FlashBuilder {
             })
     }
 
+    /// Returns the first address range for which both `self` and `other` have staged data.
+    pub(super) fn overlapping_range(&self, other: &FlashBuilder) -> Option<Range<u64>> {
+        self.data.iter().find_map(|(&address, data)| {
+            other
+                .data_in_range(&(address..address + data.len() as u64))
+                .next()
+                .map(|(address, data)| address..address + data.len() as u64)
+        })
+    }
+
     /// Layouts the contents of a flash memory according to the contents of the flash loader.
     pub(super) fn build_sectors_and_pages(
         &self,
@@ -1145,4 +1268,75 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn remove_sector_with_fills() {
+        let (region, flash_algorithm) = assemble_demo_flash1();
+        let mut flash_builder = FlashBuilder::new();
+        flash_builder.add_data(0, &[42]).unwrap();
+        flash_builder.add_data(0x1000, &[43]).unwrap();
+        let mut flash_layout = flash_builder
+            .build_sectors_and_pages(&region, &flash_algorithm, true)
+            .unwrap();
+
+        let erased_byte_value = flash_algorithm.flash_properties.erased_byte_value;
+        let first_sector = flash_layout.sectors()[0].clone();
+        let sector_data = flash_layout
+            .sector_data(&first_sector, erased_byte_value)
+            .unwrap();
+        assert_eq!(sector_data.len(), 0x1000);
+        assert_eq!(sector_data[0], 42);
+        assert!(sector_data[1..].iter().all(|b| *b == erased_byte_value));
+
+        assert_eq!(flash_layout.sector_page_size(&first_sector), 0x1000);
+        flash_layout.remove_sector(0);
+
+        assert_eq!(
+            flash_layout.sectors(),
+            &[FlashSector {
+                address: 0x1000,
+                size: 0x1000,
+            }]
+        );
+        assert_eq!(flash_layout.pages().len(), 4);
+        assert_eq!(flash_layout.pages()[0].address(), 0x1000);
+        assert_eq!(flash_layout.fills().len(), 4);
+        assert_eq!(
+            flash_layout.fills()[0],
+            FlashFill {
+                address: 0x1001,
+                size: 0x03FF,
+                page_index: 0,
+            }
+        );
+        assert_eq!(flash_layout.fills()[3].page_index(), 3);
+    }
+
+    #[test]
+    fn sector_data_page_larger_than_sector() {
+        let (region, flash_algorithm) = assemble_demo_flash2();
+        let mut flash_builder = FlashBuilder::new();
+        flash_builder.add_data(0, &[42]).unwrap();
+        let flash_layout = flash_builder
+            .build_sectors_and_pages(&region, &flash_algorithm, false)
+            .unwrap();
+
+        let first_sector = flash_layout.sectors()[0].clone();
+        assert_eq!(flash_layout.sector_data(&first_sector, 0xFF), None);
+    }
+
+    #[test]
+    fn overlapping_range_between_builders() {
+        let mut first = FlashBuilder::new();
+        first.add_data(0x100, &[0; 0x20]).unwrap();
+        first.add_data(0x200, &[0; 0x10]).unwrap();
+
+        let mut second = FlashBuilder::new();
+        second.add_data(0x120, &[0; 0x10]).unwrap();
+        assert_eq!(first.overlapping_range(&second), None);
+
+        second.add_data(0x1f8, &[0; 0x10]).unwrap();
+        assert_eq!(first.overlapping_range(&second), Some(0x200..0x208));
+        assert_eq!(second.overlapping_range(&first), Some(0x200..0x208));
+    }
 }
@@ -0,0 +1,208 @@
+use std::sync::Arc;
+
+use probe_rs_target::{ConfigArea, ConfigField};
+
+use crate::config::DebugSequence;
+use crate::session::MissingPermissions;
+use crate::{MemoryInterface, Session};
+
+/// Reading and writing of device configuration areas by vendor specific means.
+///
+/// Configuration areas like option bytes, UICR or eFuses can usually be read like normal memory,
+/// but writing them requires a peripheral specific procedure, which is implemented by this trait.
+pub trait ConfigAreaSequence: Send + Sync {
+    /// Reads the 32-bit word at `address` of the configuration area `area`.
+    fn read_config_word(
+        &self,
+        memory: &mut dyn MemoryInterface,
+        _area: &ConfigArea,
+        address: u64,
+    ) -> Result<u32, crate::Error> {
+        memory.read_word_32(address)
+    }
+
+    /// Writes `value` to the 32-bit word at `address` of the configuration area `area`.
+    ///
+    /// Implementations should return an error if the value can not be written
+    /// without affecting other words, e.g. because it would require erasing the area.
+    fn write_config_word(
+        &self,
+        memory: &mut dyn MemoryInterface,
+        area: &ConfigArea,
+        address: u64,
+        value: u32,
+    ) -> Result<(), crate::Error>;
+}
+
+/// A finite list of all the errors that can occur when accessing configuration areas.
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigAreaError {
+    /// The target description does not describe any configuration areas.
+    ///
+    /// So far, only the nRF52 series describes its configuration areas.
+    #[error("Configuration areas are not supported for {0}.")]
+    NotSupported(String),
+    /// The target has no configuration area with the given name.
+    #[error("The target has no configuration area '{0}'.")]
+    UnknownArea(String),
+    /// The configuration area has no field with the given name.
+    #[error("The configuration area '{area}' has no field '{field}'.")]
+    UnknownField {
+        /// The name of the configuration area.
+        area: String,
+        /// The name of the missing field.
+        field: String,
+    },
+    /// The value is too large for the field.
+    #[error("The value {value:#x} does not fit into the {width} bits of the field '{field}'.")]
+    ValueTooLarge {
+        /// The name of the field.
+        field: String,
+        /// The value which should have been written.
+        value: u32,
+        /// The width of the field in bits.
+        width: u32,
+    },
+    /// The target does not implement writing its configuration areas.
+    #[error("Writing the configuration area '{0}' is not supported for this target.")]
+    WriteNotSupported(String),
+    /// The field is marked as dangerous, and writing it was not permitted.
+    #[error("Writing the field '{0}' may be irreversible and has to be allowed explicitly.")]
+    MissingPermissions(String, #[source] MissingPermissions),
+    /// The word read back after writing does not contain the written value.
+    #[error("Writing {expected:#010x} to {address:#010x} failed, read back {actual:#010x}.")]
+    Verify {
+        /// The address of the written word.
+        address: u64,
+        /// The value which was written.
+        expected: u32,
+        /// The value which was read back.
+        actual: u32,
+    },
+    /// An error occurred during the interaction with the target.
+    #[error("Something during the interaction with the target went wrong")]
+    Core(#[from] crate::Error),
+}
+
+/// The value of a field of a configuration area.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigFieldValue {
+    /// The description of the field.
+    pub field: ConfigField,
+    /// The current value of the field.
+    pub value: u32,
+}
+
+/// Reads all fields of the configuration area `area_name`.
+pub fn read_config_area(
+    session: &mut Session,
+    area_name: &str,
+) -> Result<Vec<ConfigFieldValue>, ConfigAreaError> {
+    let area = find_area(session, area_name)?;
+    let sequence = config_area_sequence(session);
+    let mut core = session.core(0)?;
+
+    area.fields
+        .iter()
+        .map(|field| {
+            let address = area.address + field.offset;
+            let word = match &sequence {
+                Some(sequence) => sequence.read_config_word(&mut core, &area, address)?,
+                None => core.read_word_32(address)?,
+            };
+
+            Ok(ConfigFieldValue {
+                field: field.clone(),
+                value: field.extract(word),
+            })
+        })
+        .collect()
+}
+
+/// Writes `value` to the field `field_name` of the configuration area `area_name`.
+///
+/// The other fields stored in the same word are left unchanged. Writing a field marked as
+/// dangerous requires [`Permissions::allow_dangerous_config`](crate::Permissions::allow_dangerous_config).
+pub fn write_config_field(
+    session: &mut Session,
+    area_name: &str,
+    field_name: &str,
+    value: u32,
+) -> Result<(), ConfigAreaError> {
+    let area = find_area(session, area_name)?;
+    let field = area
+        .field(field_name)
+        .ok_or_else(|| ConfigAreaError::UnknownField {
+            area: area.name.clone(),
+            field: field_name.to_string(),
+        })?;
+
+    if field.dangerous {
+        session
+            .permissions()
+            .dangerous_config()
+            .map_err(|error| ConfigAreaError::MissingPermissions(field.name.clone(), error))?;
+    }
+
+    let sequence = config_area_sequence(session)
+        .ok_or_else(|| ConfigAreaError::WriteNotSupported(area.name.clone()))?;
+
+    let mut core = session.core(0)?;
+
+    let address = area.address + field.offset;
+    let word = sequence.read_config_word(&mut core, &area, address)?;
+    let new_word = field
+        .insert(word, value)
+        .ok_or_else(|| ConfigAreaError::ValueTooLarge {
+            field: field.name.clone(),
+            value,
+            width: field.width(),
+        })?;
+
+    if new_word == word {
+        tracing::info!("{}.{} is already {:#x}", area.name, field.name, value);
+        return Ok(());
+    }
+
+    tracing::info!(
+        "Writing {:#010x} to {:#010x} ({}.{})",
+        new_word,
+        address,
+        area.name,
+        field.name
+    );
+    sequence.write_config_word(&mut core, &area, address, new_word)?;
+
+    let actual = sequence.read_config_word(&mut core, &area, address)?;
+    if actual != new_word {
+        return Err(ConfigAreaError::Verify {
+            address,
+            expected: new_word,
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
+fn find_area(session: &Session, area_name: &str) -> Result<ConfigArea, ConfigAreaError> {
+    let target = session.target();
+    if target.config_areas.is_empty() {
+        return Err(ConfigAreaError::NotSupported(target.name.clone()));
+    }
+
+    target
+        .config_areas
+        .iter()
+        .find(|area| area.name.eq_ignore_ascii_case(area_name))
+        .cloned()
+        .ok_or_else(|| ConfigAreaError::UnknownArea(area_name.to_string()))
+}
+
+fn config_area_sequence(session: &Session) -> Option<Arc<dyn ConfigAreaSequence>> {
+    match &session.target().debug_sequence {
+        DebugSequence::Arm(sequence) => sequence.config_area_sequence(),
+        DebugSequence::Riscv(sequence) => sequence.config_area_sequence(),
+        DebugSequence::Xtensa(sequence) => sequence.config_area_sequence(),
+    }
+}
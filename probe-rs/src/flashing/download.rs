@@ -4,7 +4,12 @@ use object::{
 };
 use probe_rs_target::MemoryRange;
 
-use std::{fs::File, path::Path, str::FromStr};
+use std::{
+    fs::File,
+    ops::Range,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use super::*;
 use crate::session::Session;
@@ -44,6 +49,12 @@ pub enum Format {
     Idf(IdfOptions),
     /// Marks a file in the [UF2](https://github.com/microsoft/uf2) format.
     Uf2,
+    /// Marks a file in the [Motorola S-record](https://en.wikipedia.org/wiki/SREC_(file_format)) format.
+    Srec,
+    /// Marks a file in the TI-TXT format, as generated by Texas Instruments toolchains.
+    TiTxt,
+    /// Marks a [`DownloadManifest`], which lists several images to be flashed together.
+    Manifest,
 }
 
 impl FromStr for Format {
@@ -59,6 +70,9 @@ impl FromStr for Format {
             "hex" | "ihex" | "intelhex" => Ok(Format::Hex),
             "elf" => Ok(Format::Elf),
             "uf2" => Ok(Format::Uf2),
+            "srec" | "s19" | "s28" | "s37" | "mot" | "motorola" => Ok(Format::Srec),
+            "titxt" | "ti-txt" => Ok(Format::TiTxt),
+            "manifest" => Ok(Format::Manifest),
             _ => Err(format!("Format '{s}' is unknown.")),
         }
     }
@@ -78,6 +92,12 @@ pub enum FileDownloadError {
     /// Reading and decoding the IHEX file has failed due to the given error.
     #[error("Could not read ihex format")]
     IhexRead(#[from] ihex::ReaderError),
+    /// Reading and decoding the S-record file has failed due to the given error.
+    #[error("Could not read S-record format")]
+    SrecRead(#[from] SrecError),
+    /// Reading and decoding the TI-TXT file has failed due to the given error.
+    #[error("Could not read TI-TXT format")]
+    TiTxtRead(#[from] TiTxtError),
     /// An IO error has occurred while reading the firmware file.
     #[error("I/O error")]
     IO(#[from] std::io::Error),
@@ -101,6 +121,26 @@ pub enum FileDownloadError {
     /// Some error returned by the flash size detection.
     #[error("Could not determine flash size.")]
     FlashSizeDetection(#[from] crate::Error),
+    /// Reading and decoding the download manifest has failed due to the given error.
+    #[error("Could not read download manifest")]
+    Manifest(#[from] serde_yaml::Error),
+    /// An image listed in a download manifest is a manifest itself.
+    #[error("The image '{0}' is a download manifest, manifests cannot be nested.")]
+    NestedManifest(PathBuf),
+    /// Two images of a download manifest contain data for the same addresses.
+    #[error(
+        "The images '{first}' and '{second}' overlap in the address range {:#010x}..{:#010x}.",
+        range.start,
+        range.end
+    )]
+    ImageOverlap {
+        /// The image which was loaded first.
+        first: PathBuf,
+        /// The image which overlaps the first one.
+        second: PathBuf,
+        /// The address range contained in both images.
+        range: Range<u64>,
+    },
 }
 
 /// Options for downloading a file onto a target chip.
@@ -136,10 +176,28 @@ pub struct DownloadOptions {
     /// If the chip was pre-erased with external erasers, this flag can set to true to skip erasing
     /// It may be useful for mass production.
     pub skip_erase: bool,
+    /// Before erasing, compare the contents of every affected sector with the data to be programmed
+    /// and skip the sectors which are already up to date.
+    ///
+    /// Where the flash algorithm supports it, this uses a checksum computed on the target,
+    /// otherwise the sector contents are read back.
+    /// This has no effect if `do_chip_erase` or `skip_erase` is set.
+    pub preverify: bool,
     /// After flashing, read back all the flashed data to verify it has been written correctly.
     pub verify: bool,
     /// Disable double buffering when loading flash.
     pub disable_double_buffering: bool,
+    /// Record the erased and programmed sectors in a journal file at the given path,
+    /// so the download can be resumed with `resume` if it is interrupted.
+    ///
+    /// The journal is removed once the download finished successfully.
+    pub journal: Option<PathBuf>,
+    /// Resume an interrupted download of the same data recorded in `journal`.
+    ///
+    /// The sectors which the journal records as programmed are verified, and skipped if they
+    /// contain the expected data. Programming continues from the first sector which could not
+    /// be verified. This has no effect if no `journal` is given.
+    pub resume: bool,
 }
 
 impl DownloadOptions {
@@ -183,6 +241,9 @@ pub fn download_file_with_options<P: AsRef<Path>>(
         Format::Hex => loader.load_hex_data(&mut file),
         Format::Idf(options) => loader.load_idf_data(session, &mut file, options),
         Format::Uf2 => loader.load_uf2_data(&mut file),
+        Format::Srec => loader.load_srec_data(&mut file),
+        Format::TiTxt => loader.load_titxt_data(&mut file),
+        Format::Manifest => loader.load_manifest(session, path.as_ref()),
     }?;
 
     loader
@@ -190,9 +251,20 @@ pub fn download_file_with_options<P: AsRef<Path>>(
         .map_err(FileDownloadError::Flash)
 }
 
+/// A section of an ELF file which is programmed into the memory of the target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageSection {
+    /// The name of the section, e.g. `.text`.
+    pub name: String,
+    /// The address the section is programmed to, i.e. its load address.
+    pub address: u64,
+    /// The size of the section in bytes.
+    pub size: u64,
+}
+
 /// Flash data which was extracted from an ELF file.
 pub(super) struct ExtractedFlashData<'data> {
-    pub(super) section_names: Vec<String>,
+    pub(super) sections: Vec<ImageSection>,
     pub(super) address: u32,
     pub(super) data: &'data [u8],
 }
@@ -202,7 +274,7 @@ impl std::fmt::Debug for ExtractedFlashData<'_> {
         let mut helper = f.debug_struct("ExtractedFlashData");
 
         helper
-            .field("name", &self.section_names)
+            .field("sections", &self.sections)
             .field("address", &self.address);
 
         if self.data.len() > 10 {
@@ -282,7 +354,11 @@ pub(super) fn extract_from_elf<'data>(
                         );
                     }
 
-                    elf_section.push(section.name()?.to_owned());
+                    elf_section.push(ImageSection {
+                        name: section.name()?.to_owned(),
+                        address: p_paddr + section_offset - segment_offset,
+                        size: section_filesize,
+                    });
                 }
             }
 
@@ -293,7 +369,7 @@ pub(super) fn extract_from_elf<'data>(
                     &elf_data[segment_offset as usize..][..segment_filesize as usize];
 
                 extracted_data.push(ExtractedFlashData {
-                    section_names: elf_section,
+                    sections: elf_section,
                     address: p_paddr as u32,
                     data: section_data,
                 });
@@ -353,6 +429,14 @@ mod tests {
         );
         assert_eq!(Format::from_str("Elf"), Ok(Format::Elf));
         assert_eq!(Format::from_str("elf"), Ok(Format::Elf));
+        assert_eq!(Format::from_str("srec"), Ok(Format::Srec));
+        assert_eq!(Format::from_str("S19"), Ok(Format::Srec));
+        assert_eq!(Format::from_str("s28"), Ok(Format::Srec));
+        assert_eq!(Format::from_str("s37"), Ok(Format::Srec));
+        assert_eq!(Format::from_str("mot"), Ok(Format::Srec));
+        assert_eq!(Format::from_str("TiTxt"), Ok(Format::TiTxt));
+        assert_eq!(Format::from_str("ti-txt"), Ok(Format::TiTxt));
+        assert_eq!(Format::from_str("Manifest"), Ok(Format::Manifest));
         assert_eq!(
             Format::from_str("elfbin"),
             Err("Format 'elfbin' is unknown.".to_string())
@@ -120,6 +120,26 @@ pub enum FlashError {
     /// Flash content verification failed.
     #[error("Flash content verification failed.")]
     Verify,
+    /// Data which should be loaded into RAM lies outside of the RAM regions of the target.
+    #[error("The data at {start:#010x}..{end:#010x} is not contained in RAM.")]
+    DataNotInRam {
+        /// The start of the data.
+        start: u64,
+        /// The end of the data.
+        end: u64,
+    },
+    /// The loaded image has no entry point at which the core could be started.
+    #[error("The loaded image has no entry point.")]
+    NoEntryPoint,
+    /// Reading or writing the journal of the download failed.
+    #[error("Failed to access the flash journal {path}.")]
+    Journal {
+        /// The path of the journal file.
+        path: std::path::PathBuf,
+        /// The source error of this error.
+        #[source]
+        source: std::io::Error,
+    },
     // TODO: 1 Add source of target definition
     // TOOD: 2 Do this at target load time.
     /// The given chip has no RAM defined.
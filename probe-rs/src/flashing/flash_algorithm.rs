@@ -168,6 +168,10 @@ impl FlashAlgorithm {
     // Header for RISC-V Flash Algorithms
     const RISCV_FLASH_BLOB_HEADER: [u32; 2] = [riscv::assembly::EBREAK, riscv::assembly::EBREAK];
 
+    // Header for ARM Flash Algorithms
+    //
+    // Apart from the breakpoint the flash algorithm routines return to, this contains a
+    // small Thumb routine which computes a CRC32 over a memory range (see [`crc32`]).
     const ARM_FLASH_BLOB_HEADER: [u32; 8] = [
         0xE00A_BE00,
         0x062D_780D,
@@ -176,9 +180,12 @@ impl FlashAlgorithm {
         0x1E64_4058,
         0x1C49_D1FA,
         0x2A00_1E52,
-        0x0477_0D1F,
+        0x4770_D1F2,
     ];
 
+    /// Offset of the CRC32 routine in [`Self::ARM_FLASH_BLOB_HEADER`].
+    const ARM_FLASH_BLOB_CRC32_OFFSET: u64 = 2;
+
     const XTENSA_FLASH_BLOB_HEADER: [u32; 0] = [];
 
     /// When the target architecture is not known, and we need to allocate space for the header,
@@ -193,6 +200,17 @@ impl FlashAlgorithm {
         algos.iter().copied().map(size_of_val).max().unwrap() as u64
     }
 
+    /// Returns the address of the on-target CRC32 routine, if the algorithm was assembled with one.
+    ///
+    /// The routine expects the initial CRC value in `r0`, the start address in `r1`,
+    /// the length in bytes in `r2` and the polynomial in `r3`. It returns the CRC in `r0`,
+    /// which can be compared with [`crc32`].
+    pub(super) fn crc32_routine(&self) -> Option<u64> {
+        self.instructions
+            .starts_with(&Self::ARM_FLASH_BLOB_HEADER)
+            .then_some(self.load_address + Self::ARM_FLASH_BLOB_CRC32_OFFSET)
+    }
+
     fn algorithm_header(architecture: Architecture) -> &'static [u32] {
         match architecture {
             Architecture::Arm => &Self::ARM_FLASH_BLOB_HEADER,
@@ -330,12 +348,53 @@ impl FlashAlgorithm {
     }
 }
 
+/// The polynomial used by the CRC32 routine in the ARM flash algorithm header.
+pub(super) const CRC32_POLYNOMIAL: u32 = 0x04C1_1DB7;
+
+/// The initial value passed to the CRC32 routine in the ARM flash algorithm header.
+pub(super) const CRC32_INITIAL_VALUE: u32 = 0xFFFF_FFFF;
+
+/// Computes the same non-reflected CRC32 (CRC-32/MPEG-2) as the routine
+/// in the ARM flash algorithm header, so results can be compared on the host.
+pub(super) fn crc32(data: &[u8]) -> u32 {
+    data.iter().fold(CRC32_INITIAL_VALUE, |mut crc, byte| {
+        crc ^= (*byte as u32) << 24;
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ CRC32_POLYNOMIAL
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
 #[cfg(test)]
 mod test {
     use probe_rs_target::{FlashProperties, SectorDescription, SectorInfo};
 
     use crate::flashing::FlashAlgorithm;
 
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(super::crc32(b"123456789"), 0x0376_E6E7);
+        assert_eq!(super::crc32(&[]), super::CRC32_INITIAL_VALUE);
+    }
+
+    #[test]
+    fn crc32_routine_only_for_arm_header() {
+        let mut algorithm = FlashAlgorithm {
+            load_address: 0x2000_0000,
+            instructions: FlashAlgorithm::ARM_FLASH_BLOB_HEADER.to_vec(),
+            ..Default::default()
+        };
+        assert_eq!(algorithm.crc32_routine(), Some(0x2000_0002));
+
+        algorithm.instructions = FlashAlgorithm::RISCV_FLASH_BLOB_HEADER.to_vec();
+        assert_eq!(algorithm.crc32_routine(), None);
+    }
+
     #[test]
     fn flash_sector_single_size() {
         let config = FlashAlgorithm {
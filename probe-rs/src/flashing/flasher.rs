@@ -1,7 +1,12 @@
-use probe_rs_target::{MemoryRegion, RawFlashAlgorithm};
+use probe_rs_target::{MemoryRegion, RawFlashAlgorithm, TransferEncoding};
 use tracing::Level;
 
-use super::{FlashAlgorithm, FlashBuilder, FlashError, FlashFill, FlashPage, FlashProgress};
+use super::flash_algorithm::{crc32, CRC32_INITIAL_VALUE, CRC32_POLYNOMIAL};
+use super::journal::FlashJournal;
+use super::{
+    FlashAlgorithm, FlashBuilder, FlashError, FlashFill, FlashLayout, FlashPage, FlashProgress,
+    FlashSector,
+};
 use crate::config::NvmRegion;
 use crate::flashing::encoder::FlashEncoder;
 use crate::memory::MemoryInterface;
@@ -278,6 +283,12 @@ impl<'session> Flasher<'session> {
     /// If `restore_unwritten_bytes` is `true`, all bytes of a sector,
     /// that are not to be written during flashing will be read from the flash first
     /// and written again once the sector is erased.
+    ///
+    /// If `preverify` is `true`, sectors which already contain the expected data are skipped.
+    ///
+    /// If a `journal` is given, the erased and programmed sectors are recorded in it. Sectors which
+    /// it records as programmed by an interrupted download are verified and skipped.
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn program(
         &mut self,
         region: &NvmRegion,
@@ -285,6 +296,8 @@ impl<'session> Flasher<'session> {
         restore_unwritten_bytes: bool,
         enable_double_buffering: bool,
         skip_erasing: bool,
+        preverify: bool,
+        mut journal: Option<&mut FlashJournal>,
     ) -> Result<(), FlashError> {
         tracing::debug!("Starting program procedure.");
         // Convert the list of flash operations into flash sectors and pages.
@@ -324,21 +337,69 @@ impl<'session> Flasher<'session> {
         // We successfully finished filling.
         self.progress.finished_filling();
 
+        if let Some(journal) = journal.as_deref() {
+            if journal.is_resuming() {
+                if restore_unwritten_bytes {
+                    for sector in flash_layout.sectors() {
+                        if journal.is_erased(sector.address())
+                            && !journal.is_programmed(sector.address())
+                        {
+                            tracing::warn!(
+                                "The sector at {:#010x} was erased by the interrupted download, its unwritten bytes can not be restored.",
+                                sector.address()
+                            );
+                        }
+                    }
+                }
+
+                self.skip_programmed_sectors(&mut flash_layout, journal)?;
+            }
+        }
+
+        // Comparing only makes sense if we are the ones erasing the sectors.
+        if preverify && !skip_erasing {
+            self.skip_unchanged_sectors(&mut flash_layout)?;
+        }
+
+        if flash_layout.pages().is_empty() {
+            tracing::info!("All sectors are up to date, nothing to program.");
+            self.progress.started_erasing();
+            self.progress.finished_erasing();
+            self.progress.started_programming(0);
+            self.progress.finished_programming();
+            return Ok(());
+        }
+
         let flash_encoder = FlashEncoder::new(self.flash_algorithm.transfer_encoding, flash_layout);
 
         // Skip erase if necessary
         if !skip_erasing {
             // Erase all necessary sectors
-            self.sector_erase(&flash_encoder)?;
+            self.sector_erase(&flash_encoder, journal.as_deref_mut())?;
         }
 
+        // Compressed pages can not be attributed to sectors, so those are only recorded
+        // once all pages have been programmed.
+        let mut programmed_sectors = match journal {
+            Some(journal) => Some(ProgrammedSectors::new(
+                journal,
+                &flash_encoder,
+                self.flash_algorithm.transfer_encoding == TransferEncoding::Raw,
+            )),
+            None => None,
+        };
+
         // Flash all necessary pages.
         if self.double_buffering_supported() && enable_double_buffering {
-            self.program_double_buffer(&flash_encoder)?;
+            self.program_double_buffer(&flash_encoder, programmed_sectors.as_mut())?;
         } else {
-            self.program_simple(&flash_encoder)?;
+            self.program_simple(&flash_encoder, programmed_sectors.as_mut())?;
         };
 
+        if let Some(programmed_sectors) = programmed_sectors {
+            programmed_sectors.finish()?;
+        }
+
         Ok(())
     }
 
@@ -362,8 +423,117 @@ impl<'session> Flasher<'session> {
         })
     }
 
+    /// Compares every sector of `flash_layout` with the contents of the flash
+    /// and removes the sectors which are already up to date from the layout.
+    fn skip_unchanged_sectors(&mut self, flash_layout: &mut FlashLayout) -> Result<(), FlashError> {
+        let erased_byte_value = self.flash_algorithm.flash_properties.erased_byte_value;
+        let sectors = flash_layout.sectors().to_vec();
+
+        let unchanged = self.run_verify(|active| {
+            let mut unchanged = Vec::new();
+            for sector in sectors {
+                let t = Instant::now();
+                let is_unchanged = match flash_layout.sector_data(&sector, erased_byte_value) {
+                    Some(expected) => active.contains_data(sector.address(), &expected)?,
+                    None => false,
+                };
+                tracing::debug!(
+                    "Sector at {:#010x} is {}",
+                    sector.address(),
+                    if is_unchanged {
+                        "up to date"
+                    } else {
+                        "outdated"
+                    }
+                );
+                active.progress.sector_compared(
+                    sector.address(),
+                    sector.size(),
+                    flash_layout.sector_page_size(&sector),
+                    is_unchanged,
+                    t.elapsed(),
+                );
+
+                if is_unchanged {
+                    unchanged.push(sector.address());
+                }
+            }
+            Ok(unchanged)
+        })?;
+
+        for address in unchanged {
+            flash_layout.remove_sector(address);
+        }
+
+        Ok(())
+    }
+
+    /// Verifies the sectors of `flash_layout` which `journal` records as programmed
+    /// and removes them from the layout.
+    ///
+    /// Verification stops at the first sector which does not contain the expected data,
+    /// the download is continued from there on.
+    fn skip_programmed_sectors(
+        &mut self,
+        flash_layout: &mut FlashLayout,
+        journal: &FlashJournal,
+    ) -> Result<(), FlashError> {
+        let erased_byte_value = self.flash_algorithm.flash_properties.erased_byte_value;
+        let sectors = flash_layout
+            .sectors()
+            .iter()
+            .filter(|sector| journal.is_programmed(sector.address()))
+            .cloned()
+            .collect::<Vec<_>>();
+        if sectors.is_empty() {
+            return Ok(());
+        }
+
+        let verified = self.run_verify(|active| {
+            let mut verified = Vec::new();
+            for sector in sectors {
+                let t = Instant::now();
+                let is_verified = match flash_layout.sector_data(&sector, erased_byte_value) {
+                    Some(expected) => active.contains_data(sector.address(), &expected)?,
+                    None => false,
+                };
+                active.progress.sector_compared(
+                    sector.address(),
+                    sector.size(),
+                    flash_layout.sector_page_size(&sector),
+                    is_verified,
+                    t.elapsed(),
+                );
+
+                if !is_verified {
+                    tracing::info!(
+                        "Resuming the download at the sector at {:#010x}",
+                        sector.address()
+                    );
+                    break;
+                }
+                verified.push(sector.address());
+            }
+            Ok(verified)
+        })?;
+
+        tracing::info!(
+            "Skipping {} sectors programmed by the interrupted download",
+            verified.len()
+        );
+        for address in verified {
+            flash_layout.remove_sector(address);
+        }
+
+        Ok(())
+    }
+
     /// Programs the pages given in `flash_layout` into the flash.
-    fn program_simple(&mut self, flash_encoder: &FlashEncoder) -> Result<(), FlashError> {
+    fn program_simple(
+        &mut self,
+        flash_encoder: &FlashEncoder,
+        mut programmed_sectors: Option<&mut ProgrammedSectors<'_>>,
+    ) -> Result<(), FlashError> {
         self.progress.started_programming(
             flash_encoder
                 .pages()
@@ -382,6 +552,9 @@ impl<'session> Flasher<'session> {
                         source: Box::new(error),
                     })?;
                 active.progress.page_programmed(page.size(), t.elapsed());
+                if let Some(programmed_sectors) = programmed_sectors.as_deref_mut() {
+                    programmed_sectors.page_programmed(page.address())?;
+                }
 
                 t = Instant::now();
             }
@@ -398,7 +571,11 @@ impl<'session> Flasher<'session> {
     }
 
     /// Perform an erase of all sectors given in `flash_layout`.
-    fn sector_erase(&mut self, flash_encoder: &FlashEncoder) -> Result<(), FlashError> {
+    fn sector_erase(
+        &mut self,
+        flash_encoder: &FlashEncoder,
+        mut journal: Option<&mut FlashJournal>,
+    ) -> Result<(), FlashError> {
         self.progress.started_erasing();
 
         let mut t = Instant::now();
@@ -411,6 +588,9 @@ impl<'session> Flasher<'session> {
                         source: Box::new(e),
                     })?;
                 active.progress.sector_erased(sector.size(), t.elapsed());
+                if let Some(journal) = journal.as_deref_mut() {
+                    journal.record_erased(sector.address())?;
+                }
 
                 t = Instant::now();
             }
@@ -435,7 +615,11 @@ impl<'session> Flasher<'session> {
     ///
     /// This is only possible if the RAM is large enough to
     /// fit at least two page buffers. See [Flasher::double_buffering_supported].
-    fn program_double_buffer(&mut self, flash_encoder: &FlashEncoder) -> Result<(), FlashError> {
+    fn program_double_buffer(
+        &mut self,
+        flash_encoder: &FlashEncoder,
+        mut programmed_sectors: Option<&mut ProgrammedSectors<'_>>,
+    ) -> Result<(), FlashError> {
         let mut current_buf = 0;
         self.progress.started_programming(
             flash_encoder
@@ -448,6 +632,7 @@ impl<'session> Flasher<'session> {
         let mut t = Instant::now();
         let result = self.run_program(|active| {
             let mut last_page_address = 0;
+            let mut started_page_address = None;
             for page in flash_encoder.pages() {
                 // At the start of each loop cycle load the next page buffer into RAM.
                 active.load_page_buffer(page.address(), page.data(), current_buf)?;
@@ -473,8 +658,16 @@ impl<'session> Flasher<'session> {
                     });
                 }
 
+                // The previous page is only known to be programmed once its copy process finished.
+                if let Some(programmed_sectors) = programmed_sectors.as_deref_mut() {
+                    if let Some(address) = started_page_address {
+                        programmed_sectors.page_programmed(address)?;
+                    }
+                }
+
                 // Start the next copy process.
                 active.start_program_page_with_buffer(page.address(), current_buf)?;
+                started_page_address = Some(page.address());
 
                 // Swap the buffers
                 if current_buf == 1 {
@@ -492,13 +685,19 @@ impl<'session> Flasher<'session> {
                 })?;
 
             if result != 0 {
-                Err(FlashError::RoutineCallFailed {
+                return Err(FlashError::RoutineCallFailed {
                     name: "wait_for_completion",
                     error_code: result,
-                })
-            } else {
-                Ok(0)
+                });
             }
+
+            if let (Some(programmed_sectors), Some(address)) =
+                (programmed_sectors, started_page_address)
+            {
+                programmed_sectors.page_programmed(address)?;
+            }
+
+            Ok(0)
         });
 
         if result.is_ok() {
@@ -513,6 +712,73 @@ impl<'session> Flasher<'session> {
     }
 }
 
+/// Records the sectors in the journal once all of their pages have been programmed.
+struct ProgrammedSectors<'journal> {
+    journal: &'journal mut FlashJournal,
+    /// The sectors with the number of their pages which have not been programmed yet.
+    remaining: Vec<(FlashSector, usize)>,
+    /// Whether the page addresses can be attributed to sectors.
+    track_pages: bool,
+}
+
+impl<'journal> ProgrammedSectors<'journal> {
+    fn new(
+        journal: &'journal mut FlashJournal,
+        flash_encoder: &FlashEncoder,
+        track_pages: bool,
+    ) -> Self {
+        let remaining = flash_encoder
+            .sectors()
+            .iter()
+            .map(|sector| {
+                let range = sector.address()..sector.address() + sector.size();
+                let pages = flash_encoder
+                    .pages()
+                    .iter()
+                    .filter(|page| range.contains(&page.address()))
+                    .count();
+                (sector.clone(), pages)
+            })
+            .collect();
+
+        Self {
+            journal,
+            remaining,
+            track_pages,
+        }
+    }
+
+    /// Marks the page at `address` as programmed.
+    fn page_programmed(&mut self, address: u64) -> Result<(), FlashError> {
+        if !self.track_pages {
+            return Ok(());
+        }
+
+        let Some(index) = self.remaining.iter().position(|(sector, _)| {
+            (sector.address()..sector.address() + sector.size()).contains(&address)
+        }) else {
+            return Ok(());
+        };
+
+        let (sector, pages) = &mut self.remaining[index];
+        *pages = pages.saturating_sub(1);
+        if *pages == 0 {
+            self.journal.record_programmed(sector.address())?;
+            self.remaining.remove(index);
+        }
+
+        Ok(())
+    }
+
+    /// Records all remaining sectors once all pages have been programmed.
+    fn finish(self) -> Result<(), FlashError> {
+        for (sector, _) in self.remaining {
+            self.journal.record_programmed(sector.address())?;
+        }
+        Ok(())
+    }
+}
+
 struct Registers {
     pc: u32,
     r0: Option<u32>,
@@ -784,6 +1050,41 @@ impl<'probe, O: Operation> ActiveFlasher<'probe, O> {
     }
 }
 
+impl<'probe> ActiveFlasher<'probe, Verify> {
+    /// Checks whether the memory at `address` already contains `data`.
+    ///
+    /// If the flash algorithm was loaded with the CRC32 routine, only the checksum is
+    /// transferred, otherwise the memory is read back.
+    pub(super) fn contains_data(&mut self, address: u64, data: &[u8]) -> Result<bool, FlashError> {
+        let routine = match self.flash_algorithm.crc32_routine() {
+            Some(routine) if self.core.instruction_set()? == InstructionSet::Thumb2 => routine,
+            _ => {
+                let mut actual = vec![0; data.len()];
+                self.core
+                    .read(address, &mut actual)
+                    .map_err(FlashError::Core)?;
+                return Ok(actual == data);
+            }
+        };
+
+        // The routine needs a few cycles per bit, so give it time relative to the data size.
+        let timeout = Duration::from_micros(data.len() as u64 * 50).max(Duration::from_secs(1));
+        let checksum = self.call_function_and_wait(
+            &Registers {
+                pc: into_reg(routine)?,
+                r0: Some(CRC32_INITIAL_VALUE),
+                r1: Some(into_reg(address)?),
+                r2: Some(data.len() as u32),
+                r3: Some(CRC32_POLYNOMIAL),
+            },
+            false,
+            timeout,
+        )?;
+
+        Ok(checksum == crc32(data))
+    }
+}
+
 impl<'probe> ActiveFlasher<'probe, Erase> {
     pub(super) fn erase_all(&mut self) -> Result<(), FlashError> {
         tracing::debug!("Erasing entire chip.");
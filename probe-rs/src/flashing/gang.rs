@@ -0,0 +1,260 @@
+use std::time::{Duration, Instant};
+
+use super::{DownloadOptions, FlashError, FlashLoader};
+use crate::config::TargetSelector;
+use crate::{DebugProbeError, DebugProbeSelector, Lister, Permissions, Probe, WireProtocol};
+
+/// Options for connecting to the targets of a gang download.
+#[derive(Debug, Default, Clone)]
+pub struct GangOptions {
+    /// The protocol used to connect to the targets. If not set, the default of each probe is used.
+    pub protocol: Option<WireProtocol>,
+    /// The protocol speed in kHz. If not set, the default of each probe is used.
+    pub speed: Option<u32>,
+    /// Attach to the targets under reset.
+    pub connect_under_reset: bool,
+    /// The permissions of the sessions with the targets.
+    pub permissions: Permissions,
+}
+
+/// Describes an error which occurred while programming a single target of a gang download.
+#[derive(Debug, thiserror::Error)]
+pub enum GangDownloadError {
+    /// The probe could not be opened or configured.
+    #[error("Failed to open the probe")]
+    Probe(#[source] DebugProbeError),
+    /// Attaching to the target failed.
+    #[error("Failed to attach to the target")]
+    Attach(#[source] crate::Error),
+    /// Flashing the target failed.
+    #[error("Failed to flash the target")]
+    Flash(#[source] FlashError),
+    /// The thread programming the target panicked.
+    #[error("The thread programming the target panicked")]
+    Panicked,
+}
+
+/// The result of programming a single target of a gang download.
+#[derive(Debug)]
+pub struct GangDownloadResult {
+    /// The selector of the probe connected to the target.
+    pub probe: DebugProbeSelector,
+    /// The time it took to attach to and program the target.
+    pub duration: Duration,
+    /// Whether programming the target succeeded.
+    pub result: Result<(), GangDownloadError>,
+}
+
+/// Programs the data of `loader` into the targets connected to all of the given `probes` at once.
+///
+/// The probes are opened one after another, after which every target is attached to and
+/// programmed on its own thread. `download_options` is called on that thread to create the
+/// options for the target, so every target can report its own progress.
+///
+/// Returns one result per probe, in the order of `probes`. A failure of one target does not
+/// affect the other targets.
+pub fn gang_download(
+    lister: &Lister,
+    probes: &[DebugProbeSelector],
+    target: impl Into<TargetSelector>,
+    loader: &FlashLoader,
+    options: &GangOptions,
+    download_options: impl Fn(&DebugProbeSelector) -> DownloadOptions + Sync,
+) -> Vec<GangDownloadResult> {
+    let target = target.into();
+
+    let opened = probes
+        .iter()
+        .map(|selector| {
+            let start = Instant::now();
+            let probe = lister
+                .open(selector.clone())
+                .and_then(|probe| configure_probe(probe, options));
+            (selector, start.elapsed(), probe)
+        })
+        .collect::<Vec<_>>();
+
+    std::thread::scope(|scope| {
+        let handles = opened
+            .into_iter()
+            .map(|(selector, open_duration, probe)| {
+                let target = target.clone();
+                let download_options = &download_options;
+                let handle = probe.map(|probe| {
+                    scope.spawn(move || {
+                        let start = Instant::now();
+                        let result = program_target(
+                            probe,
+                            target,
+                            loader,
+                            options,
+                            download_options(selector),
+                        );
+                        (result, open_duration + start.elapsed())
+                    })
+                });
+                (selector, open_duration, handle)
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .map(|(selector, open_duration, handle)| {
+                let (result, duration) = match handle {
+                    Ok(handle) => handle
+                        .join()
+                        .unwrap_or((Err(GangDownloadError::Panicked), open_duration)),
+                    Err(error) => (Err(GangDownloadError::Probe(error)), open_duration),
+                };
+
+                GangDownloadResult {
+                    probe: selector.clone(),
+                    duration,
+                    result,
+                }
+            })
+            .collect()
+    })
+}
+
+fn configure_probe(mut probe: Probe, options: &GangOptions) -> Result<Probe, DebugProbeError> {
+    if let Some(protocol) = options.protocol {
+        probe.select_protocol(protocol)?;
+    }
+    if let Some(speed) = options.speed {
+        probe.set_speed(speed)?;
+    }
+    Ok(probe)
+}
+
+fn program_target(
+    probe: Probe,
+    target: TargetSelector,
+    loader: &FlashLoader,
+    options: &GangOptions,
+    download_options: DownloadOptions,
+) -> Result<(), GangDownloadError> {
+    let permissions = options.permissions.clone();
+    let mut session = if options.connect_under_reset {
+        probe.attach_under_reset(target, permissions)
+    } else {
+        probe.attach(target, permissions)
+    }
+    .map_err(GangDownloadError::Attach)?;
+
+    loader
+        .commit(&mut session, download_options)
+        .map_err(GangDownloadError::Flash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integration::{FakeProbe, ProbeLister};
+    use crate::{DebugProbeInfo, ProbeCreationError};
+
+    #[derive(Debug)]
+    struct FakeLister;
+
+    impl ProbeLister for FakeLister {
+        fn open(&self, selector: &DebugProbeSelector) -> Result<Probe, DebugProbeError> {
+            match selector.serial_number.as_deref() {
+                Some("core") => Ok(FakeProbe::with_mocked_core().into_probe()),
+                Some("missing") => Err(DebugProbeError::ProbeCouldNotBeCreated(
+                    ProbeCreationError::NotFound,
+                )),
+                _ => Ok(FakeProbe::new().into_probe()),
+            }
+        }
+
+        fn list_all(&self) -> Vec<DebugProbeInfo> {
+            vec![]
+        }
+    }
+
+    /// The fake probes can't run a flash algorithm, so this only checks that every probe is
+    /// opened and gets a result, using dry runs which don't program the targets.
+    #[test]
+    fn gang_download_dry_runs_with_fake_probes() {
+        let lister = Lister::with_lister(Box::new(FakeLister));
+        let probes = ["0001:0002:first", "0001:0002:missing", "0001:0002:second"]
+            .map(|selector| DebugProbeSelector::try_from(selector).unwrap());
+
+        let target = crate::config::get_target_by_name("STM32F103C8").unwrap();
+        let mut loader = FlashLoader::new(target.memory_map.clone(), target.source().clone());
+        loader.add_data(0x0800_0000, &[1, 2, 3, 4]).unwrap();
+
+        let results = gang_download(
+            &lister,
+            &probes,
+            target,
+            &loader,
+            &GangOptions::default(),
+            |_| {
+                let mut options = DownloadOptions::new();
+                options.dry_run = true;
+                options
+            },
+        );
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].probe.serial_number.as_deref(), Some("first"));
+        assert!(results[0].result.is_ok(), "{:?}", results[0].result);
+        assert!(matches!(
+            results[1].result,
+            Err(GangDownloadError::Probe(_))
+        ));
+        assert!(results[2].result.is_ok(), "{:?}", results[2].result);
+    }
+
+    #[test]
+    fn gang_download_reports_the_errors_of_each_target() {
+        let lister = Lister::with_lister(Box::new(FakeLister));
+        let probes = [
+            "0001:0002:first",
+            "0001:0002:missing",
+            "0001:0002:core",
+            "0001:0002:dry",
+        ]
+        .map(|selector| DebugProbeSelector::try_from(selector).unwrap());
+
+        let target = crate::config::get_target_by_name("STM32F103C8").unwrap();
+        let mut loader = FlashLoader::new(target.memory_map.clone(), target.source().clone());
+        loader.add_data(0x0800_0000, &[1, 2, 3, 4]).unwrap();
+
+        // The fake probes can't run the flash algorithm, so only the dry run succeeds.
+        let results = gang_download(
+            &lister,
+            &probes,
+            target,
+            &loader,
+            &GangOptions::default(),
+            |selector| {
+                let mut options = DownloadOptions::new();
+                options.dry_run = selector.serial_number.as_deref() == Some("dry");
+                options
+            },
+        );
+
+        let serial_numbers = results
+            .iter()
+            .map(|result| result.probe.serial_number.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(serial_numbers, ["first", "missing", "core", "dry"]);
+        assert!(matches!(
+            results[0].result,
+            Err(GangDownloadError::Flash(_))
+        ));
+        assert!(matches!(
+            results[1].result,
+            Err(GangDownloadError::Probe(_))
+        ));
+        assert!(matches!(
+            results[2].result,
+            Err(GangDownloadError::Flash(
+                FlashError::FlashAlgorithmNotLoaded
+            ))
+        ));
+        assert!(results[3].result.is_ok(), "{:?}", results[3].result);
+    }
+}
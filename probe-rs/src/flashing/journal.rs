@@ -0,0 +1,219 @@
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use super::FlashError;
+
+const HEADER: &str = "probe-rs flash journal 1";
+
+/// Records which sectors of a download have been erased and programmed,
+/// so the download can be resumed if it is interrupted.
+///
+/// The journal is a small text file, which starts with a checksum of the downloaded image,
+/// followed by one line per erased or programmed sector. It is removed once the download
+/// finished successfully.
+pub(super) struct FlashJournal {
+    path: PathBuf,
+    file: BufWriter<File>,
+    /// Sectors which were erased by an earlier, interrupted download of the same image.
+    erased: BTreeSet<u64>,
+    /// Sectors which were programmed by an earlier, interrupted download of the same image.
+    programmed: BTreeSet<u64>,
+}
+
+impl FlashJournal {
+    /// Creates the journal at `path` for the image with the given checksum.
+    ///
+    /// If `resume` is set, the sectors recorded in an existing journal for the same image are
+    /// kept, so they can be verified and skipped.
+    pub(super) fn open(path: &Path, image_checksum: u32, resume: bool) -> Result<Self, FlashError> {
+        let error = |source| FlashError::Journal {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        let (erased, programmed) = if resume {
+            Self::read(path, image_checksum).map_err(error)?
+        } else {
+            Default::default()
+        };
+
+        let mut file = BufWriter::new(File::create(path).map_err(error)?);
+        writeln!(file, "{HEADER}").map_err(error)?;
+        writeln!(file, "image {image_checksum:#010x}").map_err(error)?;
+        for address in &erased {
+            writeln!(file, "erased {address:#x}").map_err(error)?;
+        }
+        for address in &programmed {
+            writeln!(file, "programmed {address:#x}").map_err(error)?;
+        }
+        file.flush().map_err(error)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            erased,
+            programmed,
+        })
+    }
+
+    fn read(path: &Path, image_checksum: u32) -> std::io::Result<(BTreeSet<u64>, BTreeSet<u64>)> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                tracing::warn!(
+                    "No flash journal found at {}, nothing to resume",
+                    path.display()
+                );
+                return Ok(Default::default());
+            }
+            Err(error) => return Err(error),
+        };
+
+        Ok(parse(&contents, image_checksum).unwrap_or_else(|| {
+            tracing::warn!(
+                "The flash journal {} does not belong to this image, starting from scratch",
+                path.display()
+            );
+            Default::default()
+        }))
+    }
+
+    /// Returns true if the journal contains sectors of an interrupted download.
+    pub(super) fn is_resuming(&self) -> bool {
+        !self.erased.is_empty() || !self.programmed.is_empty()
+    }
+
+    /// Returns true if the interrupted download recorded the sector at `address` as erased.
+    pub(super) fn is_erased(&self, address: u64) -> bool {
+        self.erased.contains(&address)
+    }
+
+    /// Returns true if the interrupted download recorded the sector at `address` as programmed.
+    pub(super) fn is_programmed(&self, address: u64) -> bool {
+        self.programmed.contains(&address)
+    }
+
+    /// Records that the sector at `address` has been erased.
+    pub(super) fn record_erased(&mut self, address: u64) -> Result<(), FlashError> {
+        self.record("erased", address)
+    }
+
+    /// Records that all pages of the sector at `address` have been programmed.
+    pub(super) fn record_programmed(&mut self, address: u64) -> Result<(), FlashError> {
+        self.record("programmed", address)
+    }
+
+    fn record(&mut self, operation: &str, address: u64) -> Result<(), FlashError> {
+        writeln!(self.file, "{operation} {address:#x}")
+            .and_then(|_| self.file.flush())
+            .map_err(|source| FlashError::Journal {
+                path: self.path.clone(),
+                source,
+            })
+    }
+
+    /// Removes the journal after the download finished successfully.
+    pub(super) fn finish(self) -> Result<(), FlashError> {
+        drop(self.file);
+        std::fs::remove_file(&self.path).map_err(|source| FlashError::Journal {
+            path: self.path,
+            source,
+        })
+    }
+}
+
+/// Parses the contents of a journal.
+///
+/// Returns `None` if the journal was written for another image. Lines which can not be parsed
+/// are ignored, as the last line may be incomplete if the download was interrupted.
+fn parse(contents: &str, image_checksum: u32) -> Option<(BTreeSet<u64>, BTreeSet<u64>)> {
+    let mut lines = contents.lines();
+    if lines.next() != Some(HEADER)
+        || lines.next() != Some(&format!("image {image_checksum:#010x}"))
+    {
+        return None;
+    }
+
+    let mut erased = BTreeSet::new();
+    let mut programmed = BTreeSet::new();
+    for line in lines {
+        let Some((operation, address)) = line.split_once(' ') else {
+            continue;
+        };
+        let Some(address) = address
+            .strip_prefix("0x")
+            .and_then(|address| u64::from_str_radix(address, 16).ok())
+        else {
+            continue;
+        };
+
+        match operation {
+            "erased" => erased.insert(address),
+            "programmed" => programmed.insert(address),
+            _ => continue,
+        };
+    }
+
+    Some((erased, programmed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, FlashJournal};
+
+    #[test]
+    fn parse_journal() {
+        let contents = "probe-rs flash journal 1\nimage 0x12345678\nerased 0x1000\nerased 0x2000\nprogrammed 0x1000\nprogr";
+
+        let (erased, programmed) = parse(contents, 0x1234_5678).unwrap();
+        assert_eq!(erased.into_iter().collect::<Vec<_>>(), [0x1000, 0x2000]);
+        assert_eq!(programmed.into_iter().collect::<Vec<_>>(), [0x1000]);
+
+        assert!(parse(contents, 0x8765_4321).is_none());
+        assert!(parse("", 0x1234_5678).is_none());
+    }
+
+    #[test]
+    fn resume_interrupted_download() {
+        let path = std::env::temp_dir().join(format!(
+            "probe-rs-journal-test-{}.journal",
+            std::process::id()
+        ));
+
+        // Interrupt a download after programming the first of two erased sectors.
+        let mut journal = FlashJournal::open(&path, 0x1234_5678, false).unwrap();
+        assert!(!journal.is_resuming());
+        journal.record_erased(0x1000).unwrap();
+        journal.record_erased(0x2000).unwrap();
+        journal.record_programmed(0x1000).unwrap();
+        drop(journal);
+
+        // The programmed sector is skipped, the other one is erased and programmed again.
+        let mut journal = FlashJournal::open(&path, 0x1234_5678, true).unwrap();
+        assert!(journal.is_resuming());
+        assert!(journal.is_programmed(0x1000));
+        assert!(journal.is_erased(0x2000));
+        assert!(!journal.is_programmed(0x2000));
+        journal.record_programmed(0x2000).unwrap();
+        drop(journal);
+
+        // The sectors of both downloads are kept, as long as the same image is resumed.
+        let journal = FlashJournal::open(&path, 0x1234_5678, true).unwrap();
+        assert!(journal.is_programmed(0x1000));
+        assert!(journal.is_programmed(0x2000));
+        drop(journal);
+
+        // Another image starts from scratch.
+        let journal = FlashJournal::open(&path, 0x8765_4321, true).unwrap();
+        assert!(!journal.is_resuming());
+        journal.finish().unwrap();
+        assert!(!path.exists());
+
+        // Without a journal there is nothing to resume.
+        let journal = FlashJournal::open(&path, 0x1234_5678, true).unwrap();
+        assert!(!journal.is_resuming());
+        journal.finish().unwrap();
+    }
+}
@@ -1,18 +1,27 @@
 use ihex::Record;
+use object::{Object, ObjectSymbol};
 use probe_rs_target::{
-    MemoryRange, MemoryRegion, NvmRegion, RawFlashAlgorithm, TargetDescriptionSource,
+    CoreType, MemoryRange, MemoryRegion, NvmRegion, RawFlashAlgorithm, TargetDescriptionSource,
 };
 use std::collections::HashMap;
+use std::fs::File;
 use std::io::{Read, Seek, SeekFrom};
 use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 
 use super::builder::FlashBuilder;
+use super::flash_algorithm::crc32;
+use super::journal::FlashJournal;
 use super::{
-    extract_from_elf, BinOptions, DownloadOptions, FileDownloadError, FlashError, Flasher,
-    IdfOptions,
+    extract_from_elf, BinOptions, DownloadManifest, DownloadOptions, FileDownloadError,
+    FlashAlgorithm, FlashError, FlashLayout, Flasher, Format, IdfOptions, ImageSection,
 };
+use super::{srec, titxt};
+use crate::architecture::arm::core::armv7m::Vtor;
 use crate::config::DebugSequence;
+use crate::core::MemoryMappedRegister;
 use crate::memory::MemoryInterface;
 use crate::session::Session;
 use crate::Target;
@@ -27,6 +36,15 @@ pub struct FlashLoader {
     memory_map: Vec<MemoryRegion>,
     builder: FlashBuilder,
 
+    /// The entry point of the loaded ELF file, if any.
+    entry_point: Option<u64>,
+
+    /// The address of the Cortex-M vector table of the loaded ELF file, if it has one.
+    vector_table: Option<u64>,
+
+    /// The sections of the loaded ELF files.
+    sections: Vec<ImageSection>,
+
     /// Source of the flash description,
     /// used for diagnostics.
     source: TargetDescriptionSource,
@@ -38,6 +56,9 @@ impl FlashLoader {
         Self {
             memory_map,
             builder: FlashBuilder::new(),
+            entry_point: None,
+            vector_table: None,
+            sections: Vec::new(),
             source,
         }
     }
@@ -218,6 +239,30 @@ impl FlashLoader {
         Ok(())
     }
 
+    /// Reads the S-record data records and adds them as loadable data blocks to the loader.
+    /// This does not create and flash loader instructions yet.
+    pub fn load_srec_data<T: Read>(&mut self, file: &mut T) -> Result<(), FileDownloadError> {
+        let mut data = String::new();
+        file.read_to_string(&mut data)?;
+
+        for record in srec::parse(&data)? {
+            self.add_data(record.address, &record.data)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the TI-TXT sections and adds them as loadable data blocks to the loader.
+    /// This does not create and flash loader instructions yet.
+    pub fn load_titxt_data<T: Read>(&mut self, file: &mut T) -> Result<(), FileDownloadError> {
+        let mut data = String::new();
+        file.read_to_string(&mut data)?;
+
+        for section in titxt::parse(&data)? {
+            self.add_data(section.address, &section.data)?;
+        }
+        Ok(())
+    }
+
     /// Prepares the data sections that have to be loaded into flash from an ELF file.
     /// This will validate the ELF file and transform all its data into sections but no flash loader commands yet.
     pub fn load_elf_data<T: Read>(&mut self, file: &mut T) -> Result<(), FileDownloadError> {
@@ -236,10 +281,10 @@ impl FlashLoader {
         tracing::info!("Found {} loadable sections:", num_sections);
 
         for section in &extracted_data {
-            let source = if section.section_names.is_empty() {
+            let source = if section.sections.is_empty() {
                 "Unknown".to_string()
-            } else if section.section_names.len() == 1 {
-                section.section_names[0].to_owned()
+            } else if section.sections.len() == 1 {
+                section.sections[0].name.to_owned()
             } else {
                 "Multiple sections".to_owned()
             };
@@ -255,7 +300,19 @@ impl FlashLoader {
 
         for data in extracted_data {
             self.add_data(data.address.into(), data.data)?;
+            self.sections.extend(data.sections);
+        }
+
+        let elf = object::File::parse(&elf_buffer[..])?;
+        if elf.entry() != 0 {
+            self.entry_point = Some(elf.entry());
         }
+        // The vector table does not have to be at the start of the image, e.g. if a second stage
+        // bootloader precedes it, so use the symbol which cortex-m-rt places at it.
+        self.vector_table = elf
+            .symbols()
+            .find(|symbol| symbol.name() == Ok("__vector_table"))
+            .map(|symbol| symbol.address());
 
         Ok(())
     }
@@ -284,6 +341,76 @@ impl FlashLoader {
         }
     }
 
+    /// Reads the [`DownloadManifest`] at `path` and adds the data of all listed images to the loader.
+    ///
+    /// Relative image paths are resolved relative to the directory of the manifest.
+    /// If two images contain data for the same addresses, [`FileDownloadError::ImageOverlap`] is returned
+    /// and no data is added.
+    /// This does not create and flash loader instructions yet.
+    pub fn load_manifest(
+        &mut self,
+        session: &mut Session,
+        path: &Path,
+    ) -> Result<(), FileDownloadError> {
+        let mut contents = String::new();
+        File::open(path)?.read_to_string(&mut contents)?;
+        let manifest = DownloadManifest::from_yaml(&contents)?;
+
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+
+        // Load every image separately first, so overlaps can be attributed to the images causing them.
+        let mut images: Vec<(PathBuf, FlashBuilder)> = Vec::new();
+        for image in &manifest.images {
+            let image_path = base_dir.join(&image.path);
+
+            tracing::info!("Loading image {}", image_path.display());
+
+            let mut image_loader = FlashLoader::new(self.memory_map.clone(), self.source.clone());
+            image_loader.load_manifest_image(session, &image_path, image.resolved_format())?;
+
+            for (other_path, other) in &images {
+                if let Some(range) = other.overlapping_range(&image_loader.builder) {
+                    return Err(FileDownloadError::ImageOverlap {
+                        first: other_path.clone(),
+                        second: image_path,
+                        range,
+                    });
+                }
+            }
+
+            images.push((image_path, image_loader.builder));
+        }
+
+        for (_, builder) in images {
+            for (address, data) in &builder.data {
+                self.add_data(*address, data)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Loads a single image listed in a download manifest.
+    fn load_manifest_image(
+        &mut self,
+        session: &mut Session,
+        path: &Path,
+        format: Format,
+    ) -> Result<(), FileDownloadError> {
+        let mut file = File::open(path)?;
+
+        match format {
+            Format::Bin(options) => self.load_bin_data(&mut file, options),
+            Format::Elf => self.load_elf_data(&mut file),
+            Format::Hex => self.load_hex_data(&mut file),
+            Format::Idf(options) => self.load_idf_data(session, &mut file, options),
+            Format::Uf2 => self.load_uf2_data(&mut file),
+            Format::Srec => self.load_srec_data(&mut file),
+            Format::TiTxt => self.load_titxt_data(&mut file),
+            Format::Manifest => Err(FileDownloadError::NestedManifest(path.to_path_buf())),
+        }
+    }
+
     /// Writes all the stored data chunks to flash.
     ///
     /// Requires a session with an attached target that has a known flash algorithm.
@@ -383,6 +510,16 @@ impl FlashLoader {
             return Ok(());
         }
 
+        let mut journal = match &options.journal {
+            Some(path) => Some(FlashJournal::open(
+                path,
+                self.image_checksum(),
+                options.resume,
+            )?),
+            None => None,
+        };
+        let resuming = journal.as_ref().is_some_and(FlashJournal::is_resuming);
+
         // Iterate all flash algorithms we need to use.
         for ((algo_name, core_name), regions) in algos {
             tracing::debug!("Flashing ranges for algo: {}", algo_name);
@@ -401,6 +538,12 @@ impl FlashLoader {
 
             let mut do_chip_erase = options.do_chip_erase;
 
+            // A chip erase would also erase the sectors which are already programmed.
+            if do_chip_erase && resuming {
+                do_chip_erase = false;
+                tracing::info!("Resuming the interrupted download, the sectors are erased individually instead of a chip erase.");
+            }
+
             // If the flash algo doesn't support erase all, disable chip erase.
             if do_chip_erase && !flasher.is_chip_erase_supported() {
                 do_chip_erase = false;
@@ -434,6 +577,8 @@ impl FlashLoader {
                     options.keep_unwritten_bytes,
                     do_use_double_buffering,
                     options.skip_erase || do_chip_erase,
+                    options.preverify,
+                    journal.as_mut(),
                 )?;
             }
         }
@@ -483,41 +628,198 @@ impl FlashLoader {
 
         if options.verify {
             tracing::debug!("Verifying!");
-            for (&address, data) in &self.builder.data {
-                tracing::debug!(
-                    "    data: {:08x}-{:08x} ({} bytes)",
-                    address,
-                    address + data.len() as u64,
-                    data.len()
-                );
+            if !self.verify(session)?.is_empty() {
+                return Err(FlashError::Verify);
+            }
+        }
 
-                let associated_region = session
-                    .target()
-                    .get_memory_region_by_address(address)
-                    .unwrap();
-                let core_name = match associated_region {
-                    MemoryRegion::Ram(r) => &r.cores,
-                    MemoryRegion::Generic(r) => &r.cores,
-                    MemoryRegion::Nvm(r) => &r.cores,
+        if let Some(journal) = journal {
+            journal.finish()?;
+        }
+
+        Ok(())
+    }
+
+    /// Computes a checksum of all stored data chunks and their addresses,
+    /// which identifies the image in a flash journal.
+    fn image_checksum(&self) -> u32 {
+        let mut contents = Vec::new();
+        for (address, data) in &self.builder.data {
+            contents.extend_from_slice(&address.to_le_bytes());
+            contents.extend_from_slice(&(data.len() as u64).to_le_bytes());
+            contents.extend_from_slice(data);
+        }
+        crc32(&contents)
+    }
+
+    /// Writes all the stored data chunks to RAM and prepares the core `core_index` to execute them,
+    /// without using a flash algorithm or touching non-volatile memory.
+    ///
+    /// All data has to be located in RAM. The core is reset and halted before the data is written,
+    /// and its program counter is set to the entry point of the loaded ELF file.
+    ///
+    /// On Cortex-M cores, VTOR is pointed to the `__vector_table` of the ELF file, and the stack
+    /// pointer is taken from it, as is the program counter if the image has no entry point.
+    /// VTOR is left alone on ARMv6-M cores, where it is optional.
+    ///
+    /// Use [`Core::run()`](crate::Core::run) afterwards to start the program.
+    pub fn commit_to_ram(
+        &self,
+        session: &mut Session,
+        core_index: usize,
+    ) -> Result<(), FlashError> {
+        for (&address, data) in &self.builder.data {
+            let range = address..address + data.len() as u64;
+
+            let mut start = range.start;
+            while start < range.end {
+                match Self::get_region_for_address(&self.memory_map, start) {
+                    Some(MemoryRegion::Ram(region)) => start = region.range.end,
+                    _ => {
+                        return Err(FlashError::DataNotInRam {
+                            start: range.start,
+                            end: range.end,
+                        })
+                    }
                 }
-                .first()
-                .unwrap();
-                let core_index = session.target().core_index_by_name(core_name).unwrap();
-                let mut core = session.core(core_index).map_err(FlashError::Core)?;
+            }
+        }
+
+        let mut core = session.core(core_index).map_err(FlashError::Core)?;
+        let is_cortex_m = core.core_type().is_cortex_m();
+        let registers = self.ram_start_registers(is_cortex_m)?;
 
-                let mut written_data = vec![0; data.len()];
-                core.read(address, &mut written_data)
+        // Start from a clean state, the program expects to be started like after a reset.
+        core.reset_and_halt(Duration::from_millis(100))
+            .map_err(FlashError::Core)?;
+
+        for (&address, data) in &self.builder.data {
+            tracing::debug!(
+                "     -- writing: {:08x}-{:08x} ({} bytes)",
+                address,
+                address + data.len() as u64,
+                data.len()
+            );
+            core.write_8(address, data).map_err(FlashError::Core)?;
+        }
+
+        if let Some(vector_table) = registers.vector_table {
+            // Writing VTOR faults on ARMv6-M cores without one, like the Cortex-M0.
+            if core.core_type() == CoreType::Armv6m {
+                tracing::debug!(
+                    "Not setting VTOR to {:#010x} on an ARMv6-M core",
+                    vector_table
+                );
+            } else {
+                core.write_word_32(Vtor::get_mmio_address(), vector_table as u32)
                     .map_err(FlashError::Core)?;
+            }
+        }
+        if let Some(stack_pointer) = registers.stack_pointer {
+            core.write_core_reg(core.stack_pointer(), stack_pointer)
+                .map_err(FlashError::Core)?;
+        }
 
-                if data != &written_data {
-                    return Err(FlashError::Verify);
-                }
+        if is_cortex_m {
+            // Make sure the core executes in Thumb state.
+            if let Some(psr) = core.registers().psr() {
+                core.write_core_reg(psr, 1u32 << 24)
+                    .map_err(FlashError::Core)?;
             }
         }
 
+        tracing::debug!(
+            "Setting program counter to {:#010x}",
+            registers.program_counter
+        );
+        core.write_core_reg(core.program_counter(), registers.program_counter)
+            .map_err(FlashError::Core)?;
+
         Ok(())
     }
 
+    /// Determines the registers with which the loaded image is started by [`Self::commit_to_ram`].
+    fn ram_start_registers(&self, is_cortex_m: bool) -> Result<RamStartRegisters, FlashError> {
+        if !is_cortex_m {
+            return Ok(RamStartRegisters {
+                vector_table: None,
+                stack_pointer: None,
+                program_counter: self.entry_point.ok_or(FlashError::NoEntryPoint)?,
+            });
+        }
+
+        let vectors = self.vector_table.and_then(|vector_table| {
+            let vectors = self.loaded_data(vector_table, 8)?;
+            Some((vector_table, vectors))
+        });
+        let Some((vector_table, vectors)) = vectors else {
+            tracing::warn!(
+                "The image has no loaded `__vector_table`, the stack pointer and VTOR are not set."
+            );
+            return Ok(RamStartRegisters {
+                vector_table: None,
+                stack_pointer: None,
+                program_counter: self.entry_point.ok_or(FlashError::NoEntryPoint)? & !1,
+            });
+        };
+
+        let stack_pointer = u32::from_le_bytes(vectors[0..4].try_into().unwrap());
+        let reset_vector = u32::from_le_bytes(vectors[4..8].try_into().unwrap());
+        tracing::debug!(
+            "Vector table at {:#010x}: SP = {:#010x}, reset = {:#010x}",
+            vector_table,
+            stack_pointer,
+            reset_vector
+        );
+
+        Ok(RamStartRegisters {
+            vector_table: Some(vector_table),
+            stack_pointer: Some(stack_pointer),
+            program_counter: self.entry_point.unwrap_or(reset_vector as u64) & !1,
+        })
+    }
+
+    /// Returns the `len` bytes of loaded data at `address`, if they are all contained in one chunk.
+    fn loaded_data(&self, address: u64, len: usize) -> Option<&[u8]> {
+        let (&start, data) = self.builder.data.range(..=address).next_back()?;
+        let offset = (address - start) as usize;
+        data.get(offset..offset + len)
+    }
+
+    /// Compares the stored data chunks with the contents of the target memory,
+    /// without erasing or programming anything.
+    ///
+    /// Returns all address ranges whose contents differ, in ascending order.
+    /// An empty list means the target memory matches the loaded data.
+    pub fn verify(&self, session: &mut Session) -> Result<Vec<Range<u64>>, FlashError> {
+        let mut mismatches = Vec::new();
+
+        for (&address, data) in &self.builder.data {
+            tracing::debug!(
+                "    data: {:08x}-{:08x} ({} bytes)",
+                address,
+                address + data.len() as u64,
+                data.len()
+            );
+
+            let associated_region = session
+                .target()
+                .get_memory_region_by_address(address)
+                .unwrap();
+            let core_name = associated_region.cores().first().unwrap();
+            let core_index = session.target().core_index_by_name(core_name).unwrap();
+            let mut core = session.core(core_index).map_err(FlashError::Core)?;
+
+            let mut written_data = vec![0; data.len()];
+            core.read(address, &mut written_data)
+                .map_err(FlashError::Core)?;
+
+            mismatches.extend(mismatching_ranges(address, data, &written_data));
+        }
+
+        Ok(mismatches)
+    }
+
     /// Try to find a flash algorithm for the given NvmRegion.
     /// Errors when:
     /// - there's no algo for the region.
@@ -563,6 +865,82 @@ impl FlashLoader {
         }
     }
 
+    /// Builds the flash layouts of the loaded data for `target` without connecting to it,
+    /// one for each non-volatile memory region containing data.
+    ///
+    /// The layouts contain the contents of the whole sectors which would be programmed,
+    /// and can be compared using [`FlashLayout::visualize_diff`](super::FlashLayout::visualize_diff).
+    pub fn layouts(&self, target: &Target) -> Result<Vec<FlashLayout>, FlashError> {
+        let mut layouts = Vec::new();
+
+        for region in &self.memory_map {
+            let MemoryRegion::Nvm(region) = region else {
+                continue;
+            };
+            if !self.builder.has_data_in_range(&region.range) {
+                continue;
+            }
+
+            let algorithm = Self::layout_algorithm(region, target)?;
+            layouts.push(
+                self.builder
+                    .build_sectors_and_pages(region, &algorithm, true)?,
+            );
+        }
+
+        Ok(layouts)
+    }
+
+    /// Builds the flash layouts of the loaded data and of the data of the `new` loader for
+    /// `target` without connecting to it, e.g. of two builds of a firmware.
+    ///
+    /// There is a pair of layouts for each non-volatile memory region containing data of either
+    /// loader, which can be compared using
+    /// [`FlashLayout::visualize_diff`](super::FlashLayout::visualize_diff).
+    pub fn layout_pairs(
+        &self,
+        new: &FlashLoader,
+        target: &Target,
+    ) -> Result<Vec<(FlashLayout, FlashLayout)>, FlashError> {
+        let mut pairs = Vec::new();
+
+        for region in &self.memory_map {
+            let MemoryRegion::Nvm(region) = region else {
+                continue;
+            };
+            if !self.builder.has_data_in_range(&region.range)
+                && !new.builder.has_data_in_range(&region.range)
+            {
+                continue;
+            }
+
+            let algorithm = Self::layout_algorithm(region, target)?;
+            pairs.push((
+                self.builder
+                    .build_sectors_and_pages(region, &algorithm, true)?,
+                new.builder
+                    .build_sectors_and_pages(region, &algorithm, true)?,
+            ));
+        }
+
+        Ok(pairs)
+    }
+
+    /// Returns a flash algorithm with the properties of the one for `region`, which is
+    /// sufficient to build the layout of the region.
+    fn layout_algorithm(region: &NvmRegion, target: &Target) -> Result<FlashAlgorithm, FlashError> {
+        let raw = Self::get_flash_algorithm_for_region(region, target)?;
+        Ok(FlashAlgorithm {
+            flash_properties: raw.flash_properties.clone(),
+            ..Default::default()
+        })
+    }
+
+    /// Returns the sections of the loaded ELF files, which are empty for the other formats.
+    pub fn sections(&self) -> &[ImageSection] {
+        &self.sections
+    }
+
     /// Return data chunks stored in the `FlashLoader` as pairs of address and bytes.
     pub fn data(&self) -> impl Iterator<Item = (u64, &[u8])> {
         self.builder
@@ -571,3 +949,132 @@ impl FlashLoader {
             .map(|(address, data)| (*address, data.as_slice()))
     }
 }
+
+/// The registers with which an image loaded into RAM is started.
+#[derive(Debug, PartialEq, Eq)]
+struct RamStartRegisters {
+    /// The address of the vector table, which VTOR is set to.
+    vector_table: Option<u64>,
+    stack_pointer: Option<u32>,
+    program_counter: u64,
+}
+
+/// Returns the address ranges in which `expected` and `actual`, both starting at `address`, differ.
+fn mismatching_ranges(address: u64, expected: &[u8], actual: &[u8]) -> Vec<Range<u64>> {
+    let mut ranges: Vec<Range<u64>> = Vec::new();
+
+    for (offset, _) in expected
+        .iter()
+        .zip(actual)
+        .enumerate()
+        .filter(|(_, (expected, actual))| expected != actual)
+    {
+        let byte_address = address + offset as u64;
+        match ranges.last_mut() {
+            Some(range) if range.end == byte_address => range.end += 1,
+            _ => ranges.push(byte_address..byte_address + 1),
+        }
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mismatching_ranges, FlashLoader, RamStartRegisters};
+    use crate::flashing::FlashError;
+
+    #[test]
+    fn mismatching_ranges_are_merged() {
+        let expected = [0, 1, 2, 3, 4, 5, 6, 7];
+        let actual = [0, 0xFF, 0xFF, 3, 4, 0xFF, 6, 0xFF];
+
+        assert_eq!(
+            mismatching_ranges(0x1000, &expected, &actual),
+            vec![0x1001..0x1003, 0x1005..0x1006, 0x1007..0x1008]
+        );
+        assert!(mismatching_ranges(0x1000, &expected, &expected).is_empty());
+    }
+
+    fn rp2040_loader() -> FlashLoader {
+        let target = crate::config::get_target_by_name("RP2040").unwrap();
+        FlashLoader::new(target.memory_map.clone(), target.source().clone())
+    }
+
+    #[test]
+    fn ram_start_from_vector_table_symbol() {
+        // The vector table follows the second stage bootloader, so it is not at the lowest address.
+        let mut loader = rp2040_loader();
+        let mut elf = std::fs::File::open("tests/debug-unwind-tests/RP2040.elf").unwrap();
+        loader.load_elf_data(&mut elf).unwrap();
+
+        assert_eq!(
+            loader.ram_start_registers(true).unwrap(),
+            RamStartRegisters {
+                vector_table: Some(0x1000_0100),
+                stack_pointer: Some(0x2000_4000),
+                program_counter: 0x1000_01C0,
+            }
+        );
+    }
+
+    #[test]
+    fn ram_start_registers() {
+        let mut loader = rp2040_loader();
+        loader.add_data(0x2000_0000, &[0xFF; 16]).unwrap();
+        // SP = 0x2004_0000, reset = 0x2000_0201
+        loader
+            .add_data(
+                0x2000_0100,
+                &[0x00, 0x00, 0x04, 0x20, 0x01, 0x02, 0x00, 0x20],
+            )
+            .unwrap();
+
+        // Without a vector table, only the entry point is known.
+        assert!(matches!(
+            loader.ram_start_registers(true),
+            Err(FlashError::NoEntryPoint)
+        ));
+        loader.entry_point = Some(0x2000_0301);
+        assert_eq!(
+            loader.ram_start_registers(true).unwrap(),
+            RamStartRegisters {
+                vector_table: None,
+                stack_pointer: None,
+                program_counter: 0x2000_0300,
+            }
+        );
+
+        loader.vector_table = Some(0x2000_0100);
+        assert_eq!(
+            loader.ram_start_registers(true).unwrap(),
+            RamStartRegisters {
+                vector_table: Some(0x2000_0100),
+                stack_pointer: Some(0x2004_0000),
+                program_counter: 0x2000_0300,
+            }
+        );
+
+        // The reset vector is used if there is no entry point.
+        loader.entry_point = None;
+        assert_eq!(
+            loader.ram_start_registers(true).unwrap().program_counter,
+            0x2000_0200
+        );
+
+        // Other cores start at the entry point.
+        assert!(matches!(
+            loader.ram_start_registers(false),
+            Err(FlashError::NoEntryPoint)
+        ));
+        loader.entry_point = Some(0x2000_0000);
+        assert_eq!(
+            loader.ram_start_registers(false).unwrap(),
+            RamStartRegisters {
+                vector_table: None,
+                stack_pointer: None,
+                program_counter: 0x2000_0000,
+            }
+        );
+    }
+}
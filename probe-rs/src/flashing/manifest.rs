@@ -0,0 +1,152 @@
+//! Download manifests, which describe several images that are flashed together.
+
+use serde::{Deserialize, Deserializer};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use super::{BinOptions, Format};
+
+/// A list of images which are loaded into a single [`FlashLoader`](super::FlashLoader)
+/// and programmed in one pass.
+///
+/// Manifests are written in YAML:
+///
+/// ```yaml
+/// images:
+///   - path: bootloader.hex
+///     format: hex
+///   - path: application.elf
+///   - path: settings.bin
+///     format: bin
+///     base_address: 0x0800f000
+///     skip: 16
+/// ```
+///
+/// Relative image paths are resolved relative to the directory containing the manifest.
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DownloadManifest {
+    /// The images to be flashed.
+    pub images: Vec<ManifestImage>,
+}
+
+impl DownloadManifest {
+    /// Parses a manifest from its YAML representation.
+    pub fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+}
+
+/// A single image of a [`DownloadManifest`].
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestImage {
+    /// The path of the image file.
+    pub path: PathBuf,
+    /// The format of the image file, using the same names as the `--format` option.
+    ///
+    /// Defaults to ELF.
+    #[serde(default, deserialize_with = "deserialize_format")]
+    pub format: Format,
+    /// The address in memory where a binary image will be put at.
+    #[serde(default)]
+    pub base_address: Option<u64>,
+    /// The number of bytes to skip at the start of a binary image.
+    #[serde(default)]
+    pub skip: u32,
+}
+
+impl ManifestImage {
+    /// Returns the format of the image, with the binary options of the manifest entry applied.
+    pub fn resolved_format(&self) -> Format {
+        match &self.format {
+            Format::Bin(_) => Format::Bin(BinOptions {
+                base_address: self.base_address,
+                skip: self.skip,
+            }),
+            format => {
+                if self.base_address.is_some() || self.skip != 0 {
+                    tracing::warn!(
+                        "Ignoring base address and skip of image '{}', they only apply to binary images.",
+                        self.path.display()
+                    );
+                }
+                format.clone()
+            }
+        }
+    }
+}
+
+fn deserialize_format<'de, D>(deserializer: D) -> Result<Format, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let name = String::deserialize(deserializer)?;
+    Format::from_str(&name).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{DownloadManifest, ManifestImage};
+    use crate::flashing::{BinOptions, Format};
+
+    #[test]
+    fn parse_manifest() {
+        let manifest = DownloadManifest::from_yaml(
+            "images:\n\
+             \x20 - path: bootloader.hex\n\
+             \x20   format: ihex\n\
+             \x20 - path: application.elf\n\
+             \x20 - path: settings.bin\n\
+             \x20   format: bin\n\
+             \x20   base_address: 0x0800f000\n\
+             \x20   skip: 16\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.images,
+            vec![
+                ManifestImage {
+                    path: PathBuf::from("bootloader.hex"),
+                    format: Format::Hex,
+                    base_address: None,
+                    skip: 0,
+                },
+                ManifestImage {
+                    path: PathBuf::from("application.elf"),
+                    format: Format::Elf,
+                    base_address: None,
+                    skip: 0,
+                },
+                ManifestImage {
+                    path: PathBuf::from("settings.bin"),
+                    format: Format::Bin(BinOptions {
+                        base_address: None,
+                        skip: 0,
+                    }),
+                    base_address: Some(0x0800_f000),
+                    skip: 16,
+                },
+            ]
+        );
+
+        assert_eq!(
+            manifest.images[2].resolved_format(),
+            Format::Bin(BinOptions {
+                base_address: Some(0x0800_f000),
+                skip: 16,
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_format_is_rejected() {
+        let error =
+            DownloadManifest::from_yaml("images:\n  - path: a.xyz\n    format: xyz\n").unwrap_err();
+
+        assert!(error.to_string().contains("Format 'xyz' is unknown."));
+    }
+}
@@ -2,8 +2,8 @@
 //!
 //! This modules provides a means to do flash unlocking, erasing and programming.
 //!
-//! It provides a convenient high level interface that can flash an ELF, IHEX or BIN file
-//! as well as a lower level block based interface.
+//! It provides a convenient high level interface that can flash an ELF, IHEX, S-record, TI-TXT or BIN file,
+//! or several of them listed in a [`DownloadManifest`], as well as a lower level block based interface.
 //!
 //!
 //! ## Examples
@@ -44,24 +44,37 @@
 //!
 
 mod builder;
+mod config_area;
 mod download;
 mod encoder;
 mod erase;
 mod error;
 mod flash_algorithm;
 mod flasher;
+mod gang;
+mod journal;
 mod loader;
+mod manifest;
 mod progress;
+mod reader;
+mod srec;
+mod titxt;
 mod visualizer;
 
 use builder::*;
 use flasher::*;
 
 pub use builder::{FlashDataBlockSpan, FlashFill, FlashLayout, FlashPage, FlashSector};
+pub use config_area::*;
 pub use download::*;
 pub use erase::*;
 pub use error::*;
 pub use flash_algorithm::*;
+pub use gang::*;
 pub use loader::*;
+pub use manifest::{DownloadManifest, ManifestImage};
 pub use progress::*;
+pub use reader::*;
+pub use srec::SrecError;
+pub use titxt::TiTxtError;
 pub use visualizer::*;
@@ -62,6 +62,24 @@ impl FlashProgress {
         self.emit(ProgressEvent::SectorErased { size, time });
     }
 
+    /// Signalize that a sector has been compared against the data to be programmed.
+    pub(super) fn sector_compared(
+        &self,
+        address: u64,
+        size: u64,
+        page_size: u64,
+        unchanged: bool,
+        time: Duration,
+    ) {
+        self.emit(ProgressEvent::SectorCompared {
+            address,
+            size,
+            page_size,
+            unchanged,
+            time,
+        });
+    }
+
     /// Signalize that the page filling procedure has made progress.
     pub(super) fn page_filled(&self, size: u64, time: Duration) {
         self.emit(ProgressEvent::PageFilled { size, time });
@@ -112,6 +130,7 @@ impl FlashProgress {
 /// * `StartedFilling`
 /// * `PageFilled` for every page
 /// * `FinishedFilling`
+/// * `SectorCompared` for every sector, if [`DownloadOptions::preverify`](super::DownloadOptions::preverify) is set
 /// * `StartedErasing`
 /// * `SectorErased` for every sector
 /// * `FinishedErasing`
@@ -144,6 +163,22 @@ pub enum ProgressEvent {
     FailedFilling,
     /// Filling of the pages has finished successfully.
     FinishedFilling,
+    /// A sector has been compared against the data which is to be programmed into it.
+    ///
+    /// Sectors which are `unchanged` will neither be erased nor programmed.
+    SectorCompared {
+        /// The start address of the sector.
+        address: u64,
+        /// The size of the sector in bytes.
+        size: u64,
+        /// The size of the pages in the sector in bytes, which are not programmed
+        /// if the sector is skipped.
+        page_size: u64,
+        /// Whether the target already contains the data, meaning the sector is skipped.
+        unchanged: bool,
+        /// The time it took to compare this sector.
+        time: Duration,
+    },
     /// Erasing of flash has started.
     StartedErasing,
     /// A sector has been erased successfully.
@@ -0,0 +1,548 @@
+use ihex::Record;
+use probe_rs_target::{Architecture, MemoryRegion};
+use std::io::Write;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::MemoryInterface;
+
+/// The number of bytes read from the target with a single memory access.
+const READ_CHUNK_SIZE: u64 = 0x1_0000;
+
+/// The number of data bytes in a single Intel HEX record.
+const HEX_RECORD_SIZE: usize = 16;
+
+/// A finite list of the file formats a [`MemoryDump`] can be written as.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DumpFormat {
+    /// The raw memory contents. Only possible if the dump is contiguous.
+    Bin,
+    /// An [Intel HEX](https://en.wikipedia.org/wiki/Intel_HEX) file.
+    Hex,
+    /// A minimal [ELF](https://en.wikipedia.org/wiki/Executable_and_Linkable_Format) file,
+    /// containing one loadable segment and one section per dumped range.
+    Elf,
+}
+
+impl FromStr for DumpFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &s.to_lowercase()[..] {
+            "bin" | "binary" => Ok(DumpFormat::Bin),
+            "hex" | "ihex" | "intelhex" => Ok(DumpFormat::Hex),
+            "elf" => Ok(DumpFormat::Elf),
+            _ => Err(format!("Format '{s}' is unknown.")),
+        }
+    }
+}
+
+/// A finite list of all the errors that can occur when dumping target memory.
+#[derive(Debug, thiserror::Error)]
+pub enum DumpError {
+    /// Reading the target memory failed.
+    #[error("Failed to read memory at {address:#010x}")]
+    Read {
+        /// The start address of the failed read.
+        address: u64,
+        /// The underlying error.
+        #[source]
+        source: crate::Error,
+    },
+    /// An IO error has occurred while writing the output file.
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+    /// The dumped ranges are not adjacent, so they cannot be written as a single binary.
+    #[error("The dumped ranges {:#010x}..{:#010x} and {:#010x}..{:#010x} are not contiguous, which the binary format requires. Use the hex or ELF format instead.", first.start, first.end, second.start, second.end)]
+    NotContiguous {
+        /// The lower of the two ranges.
+        first: Range<u64>,
+        /// The higher of the two ranges.
+        second: Range<u64>,
+    },
+    /// The dumped data lies above the 4 GiB which Intel HEX can address.
+    #[error("The address {0:#x} cannot be represented in the Intel HEX format.")]
+    AddressOutOfRange(u64),
+    /// Creating the Intel HEX records failed.
+    #[error("Failed to create Intel HEX file")]
+    Hex(#[from] ihex::WriterError),
+}
+
+/// A contiguous block of memory read from the target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpSegment {
+    /// The name of the memory region the data was read from, if known.
+    pub name: Option<String>,
+    /// The address of the first byte.
+    pub address: u64,
+    /// The memory contents.
+    pub data: Vec<u8>,
+}
+
+impl DumpSegment {
+    /// The address range covered by the segment.
+    pub fn range(&self) -> Range<u64> {
+        self.address..self.address + self.data.len() as u64
+    }
+}
+
+/// The memory contents read by a [`FlashReader`], sorted by address.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MemoryDump {
+    /// The dumped memory blocks, in ascending address order.
+    pub segments: Vec<DumpSegment>,
+}
+
+impl MemoryDump {
+    /// Writes the dump in the given `format`.
+    ///
+    /// The `architecture` is used to fill in the machine type of ELF files.
+    pub fn write(
+        &self,
+        writer: &mut impl Write,
+        format: DumpFormat,
+        architecture: Architecture,
+    ) -> Result<(), DumpError> {
+        match format {
+            DumpFormat::Bin => self.write_bin(writer),
+            DumpFormat::Hex => self.write_hex(writer),
+            DumpFormat::Elf => self.write_elf(writer, architecture),
+        }
+    }
+
+    /// Writes the raw memory contents.
+    ///
+    /// Fails with [`DumpError::NotContiguous`] if there are gaps between the segments.
+    pub fn write_bin(&self, writer: &mut impl Write) -> Result<(), DumpError> {
+        for pair in self.segments.windows(2) {
+            if pair[0].range().end != pair[1].address {
+                return Err(DumpError::NotContiguous {
+                    first: pair[0].range(),
+                    second: pair[1].range(),
+                });
+            }
+        }
+
+        for segment in &self.segments {
+            writer.write_all(&segment.data)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the dump as an Intel HEX file.
+    pub fn write_hex(&self, writer: &mut impl Write) -> Result<(), DumpError> {
+        let mut records = Vec::new();
+        let mut upper_address = None;
+
+        for segment in &self.segments {
+            if segment.range().end > 1 << 32 {
+                return Err(DumpError::AddressOutOfRange(segment.range().end - 1));
+            }
+
+            let mut address = segment.address;
+            let mut data = &segment.data[..];
+
+            while !data.is_empty() {
+                let upper = (address >> 16) as u16;
+                if upper_address != Some(upper) {
+                    records.push(Record::ExtendedLinearAddress(upper));
+                    upper_address = Some(upper);
+                }
+
+                // Records must not cross a 64 KiB boundary.
+                let offset = address as u16;
+                let len = data
+                    .len()
+                    .min(HEX_RECORD_SIZE)
+                    .min(0x1_0000 - offset as usize);
+
+                records.push(Record::Data {
+                    offset,
+                    value: data[..len].to_vec(),
+                });
+
+                address += len as u64;
+                data = &data[len..];
+            }
+        }
+
+        records.push(Record::EndOfFile);
+
+        writer.write_all(ihex::create_object_file_representation(&records)?.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Writes the dump as a little endian ELF file.
+    ///
+    /// Each segment is stored in its own `PT_LOAD` program header and a matching section,
+    /// so the file can be downloaded again and inspected with the usual tools.
+    /// A 32-bit ELF file is created unless the dump contains addresses above 4 GiB.
+    pub fn write_elf(
+        &self,
+        writer: &mut impl Write,
+        architecture: Architecture,
+    ) -> Result<(), DumpError> {
+        writer.write_all(&self.elf_bytes(architecture))?;
+
+        Ok(())
+    }
+
+    fn elf_bytes(&self, architecture: Architecture) -> Vec<u8> {
+        const PT_LOAD: u32 = 1;
+        const PF_RWX: u32 = 0x7;
+        const SHT_PROGBITS: u32 = 1;
+        const SHT_STRTAB: u32 = 3;
+        const SHF_WRITE_ALLOC_EXECINSTR: u64 = 0x7;
+
+        let is_64 = self.segments.iter().any(|s| s.range().end > 1 << 32);
+        let word = if is_64 { 8 } else { 4 };
+        let header_size = if is_64 { 64 } else { 52 };
+        let program_header_size = if is_64 { 56 } else { 32 };
+        let section_header_size = if is_64 { 64 } else { 40 };
+
+        let (machine, flags) = match architecture {
+            // EABI version 5
+            Architecture::Arm => (40u16, 0x0500_0000u32),
+            Architecture::Riscv => (243, 0),
+            Architecture::Xtensa => (94, 0),
+        };
+
+        // Section name string table, starting with the empty name of the null section.
+        let mut names = vec![0u8];
+        let mut name_offsets = Vec::new();
+        for (index, segment) in self.segments.iter().enumerate() {
+            name_offsets.push(names.len() as u32);
+            match &segment.name {
+                Some(name) => names.extend_from_slice(format!(".{name}").as_bytes()),
+                None => names.extend_from_slice(format!(".dump{index}").as_bytes()),
+            }
+            names.push(0);
+        }
+        let shstrtab_name = names.len() as u32;
+        names.extend_from_slice(b".shstrtab\0");
+
+        // File layout: header, program headers, segment data, string table, section headers.
+        let mut offset = header_size + program_header_size * self.segments.len();
+        let mut data_offsets = Vec::new();
+        for segment in &self.segments {
+            offset = offset.next_multiple_of(4);
+            data_offsets.push(offset);
+            offset += segment.data.len();
+        }
+        let names_offset = offset;
+        let section_headers_offset = (names_offset + names.len()).next_multiple_of(word);
+        let section_count = self.segments.len() + 2;
+
+        let mut elf = Vec::new();
+        let push_word = |elf: &mut Vec<u8>, value: u64| {
+            if is_64 {
+                elf.extend_from_slice(&value.to_le_bytes());
+            } else {
+                elf.extend_from_slice(&(value as u32).to_le_bytes());
+            }
+        };
+
+        // ELF header
+        elf.extend_from_slice(&[0x7f, b'E', b'L', b'F']);
+        elf.push(if is_64 { 2 } else { 1 }); // class
+        elf.push(1); // little endian
+        elf.push(1); // version
+        elf.resize(16, 0);
+        elf.extend_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+        elf.extend_from_slice(&machine.to_le_bytes());
+        elf.extend_from_slice(&1u32.to_le_bytes()); // version
+        push_word(&mut elf, 0); // entry
+        push_word(&mut elf, header_size as u64); // program header offset
+        push_word(&mut elf, section_headers_offset as u64);
+        elf.extend_from_slice(&flags.to_le_bytes());
+        elf.extend_from_slice(&(header_size as u16).to_le_bytes());
+        elf.extend_from_slice(&(program_header_size as u16).to_le_bytes());
+        elf.extend_from_slice(&(self.segments.len() as u16).to_le_bytes());
+        elf.extend_from_slice(&(section_header_size as u16).to_le_bytes());
+        elf.extend_from_slice(&(section_count as u16).to_le_bytes());
+        elf.extend_from_slice(&((section_count - 1) as u16).to_le_bytes()); // string table index
+
+        // Program headers
+        for (segment, &data_offset) in self.segments.iter().zip(&data_offsets) {
+            let size = segment.data.len() as u64;
+            elf.extend_from_slice(&PT_LOAD.to_le_bytes());
+            if is_64 {
+                elf.extend_from_slice(&PF_RWX.to_le_bytes());
+            }
+            push_word(&mut elf, data_offset as u64);
+            push_word(&mut elf, segment.address); // virtual address
+            push_word(&mut elf, segment.address); // physical address
+            push_word(&mut elf, size); // file size
+            push_word(&mut elf, size); // memory size
+            if !is_64 {
+                elf.extend_from_slice(&PF_RWX.to_le_bytes());
+            }
+            push_word(&mut elf, 4); // alignment
+        }
+
+        // Segment data and section name string table
+        for (segment, &data_offset) in self.segments.iter().zip(&data_offsets) {
+            elf.resize(data_offset, 0);
+            elf.extend_from_slice(&segment.data);
+        }
+        elf.extend_from_slice(&names);
+        elf.resize(section_headers_offset, 0);
+
+        // Section headers
+        let mut push_section_header =
+            |name: u32, kind: u32, flags: u64, address: u64, offset: usize, size: usize| {
+                elf.extend_from_slice(&name.to_le_bytes());
+                elf.extend_from_slice(&kind.to_le_bytes());
+                push_word(&mut elf, flags);
+                push_word(&mut elf, address);
+                push_word(&mut elf, offset as u64);
+                push_word(&mut elf, size as u64);
+                elf.extend_from_slice(&0u32.to_le_bytes()); // link
+                elf.extend_from_slice(&0u32.to_le_bytes()); // info
+                push_word(&mut elf, 1); // alignment
+                push_word(&mut elf, 0); // entry size
+            };
+
+        push_section_header(0, 0, 0, 0, 0, 0);
+        for ((segment, &data_offset), &name) in
+            self.segments.iter().zip(&data_offsets).zip(&name_offsets)
+        {
+            push_section_header(
+                name,
+                SHT_PROGBITS,
+                SHF_WRITE_ALLOC_EXECINSTR,
+                segment.address,
+                data_offset,
+                segment.data.len(),
+            );
+        }
+        push_section_header(shstrtab_name, SHT_STRTAB, 0, 0, names_offset, names.len());
+
+        elf
+    }
+}
+
+/// `FlashReader` reads back memory contents from a target, e.g. to archive or compare them.
+///
+/// Use [add_region()](FlashReader::add_region) or [add_range()](FlashReader::add_range)
+/// to select what should be read, then use [read()](FlashReader::read) to read it through
+/// any [`MemoryInterface`], usually a [`Core`](crate::Core).
+#[derive(Debug, Clone, Default)]
+pub struct FlashReader {
+    ranges: Vec<(Option<String>, Range<u64>)>,
+}
+
+impl FlashReader {
+    /// Creates a new, empty flash reader.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Selects the full address range of a memory region to be read.
+    pub fn add_region(&mut self, region: &MemoryRegion) {
+        let (name, range) = match region {
+            MemoryRegion::Ram(region) => (&region.name, &region.range),
+            MemoryRegion::Nvm(region) => (&region.name, &region.range),
+            MemoryRegion::Generic(region) => (&region.name, &region.range),
+        };
+
+        self.ranges.push((name.clone(), range.clone()));
+    }
+
+    /// Selects an address range to be read.
+    pub fn add_range(&mut self, range: Range<u64>) {
+        self.ranges.push((None, range));
+    }
+
+    /// Reads all selected ranges from the target.
+    ///
+    /// `progress` is called with the number of bytes read so far and the total number of bytes to read.
+    pub fn read(
+        &self,
+        memory: &mut impl MemoryInterface,
+        mut progress: impl FnMut(u64, u64),
+    ) -> Result<MemoryDump, DumpError> {
+        let total = self
+            .ranges
+            .iter()
+            .map(|(_, range)| range.end.saturating_sub(range.start))
+            .sum();
+        let mut done = 0;
+
+        let mut segments = Vec::new();
+
+        for (name, range) in &self.ranges {
+            if range.is_empty() {
+                continue;
+            }
+
+            tracing::info!(
+                "Reading {:#010x}..{:#010x} ({})",
+                range.start,
+                range.end,
+                name.as_deref().unwrap_or("unnamed")
+            );
+
+            let mut data = vec![0; (range.end - range.start) as usize];
+
+            for (index, chunk) in data.chunks_mut(READ_CHUNK_SIZE as usize).enumerate() {
+                let address = range.start + index as u64 * READ_CHUNK_SIZE;
+                memory
+                    .read(address, chunk)
+                    .map_err(|source| DumpError::Read { address, source })?;
+
+                done += chunk.len() as u64;
+                progress(done, total);
+            }
+
+            segments.push(DumpSegment {
+                name: name.clone(),
+                address: range.start,
+                data,
+            });
+        }
+
+        segments.sort_by_key(|segment| segment.address);
+
+        Ok(MemoryDump { segments })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object::{Object, ObjectSection, ObjectSegment};
+    use probe_rs_target::{Architecture, MemoryRegion, NvmRegion};
+
+    use super::{DumpError, DumpSegment, FlashReader, MemoryDump};
+    use crate::test::MockMemory;
+
+    fn dump() -> MemoryDump {
+        MemoryDump {
+            segments: vec![
+                DumpSegment {
+                    name: Some("flash".to_string()),
+                    address: 0x0800_0000,
+                    data: vec![0x01, 0x02, 0x03, 0x04, 0x05],
+                },
+                DumpSegment {
+                    name: None,
+                    address: 0x2000_0000,
+                    data: vec![0xAA; 4],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn read_regions_and_ranges() {
+        let mut memory = MockMemory::new();
+        memory.add_range(0x2000_0000, vec![0xAA; 8]);
+        memory.add_range(0x0800_0000, (0..32).collect());
+
+        let mut reader = FlashReader::new();
+        reader.add_range(0x2000_0004..0x2000_0008);
+        reader.add_region(&MemoryRegion::Nvm(NvmRegion {
+            name: Some("flash".to_string()),
+            range: 0x0800_0000..0x0800_0010,
+            is_boot_memory: true,
+            cores: vec!["main".to_string()],
+        }));
+
+        let mut reported = Vec::new();
+        let dump = reader
+            .read(&mut memory, |done, total| reported.push((done, total)))
+            .unwrap();
+
+        assert_eq!(
+            dump.segments,
+            vec![
+                DumpSegment {
+                    name: Some("flash".to_string()),
+                    address: 0x0800_0000,
+                    data: (0..16).collect(),
+                },
+                DumpSegment {
+                    name: None,
+                    address: 0x2000_0004,
+                    data: vec![0xAA; 4],
+                },
+            ]
+        );
+        assert_eq!(reported, vec![(4, 20), (20, 20)]);
+    }
+
+    #[test]
+    fn binary_must_be_contiguous() {
+        let mut output = Vec::new();
+        let error = dump().write_bin(&mut output).unwrap_err();
+
+        assert!(matches!(
+            error,
+            DumpError::NotContiguous { first, second }
+                if first == (0x0800_0000..0x0800_0005) && second == (0x2000_0000..0x2000_0004)
+        ));
+
+        let mut dump = dump();
+        dump.segments[1].address = 0x0800_0005;
+        dump.write_bin(&mut output).unwrap();
+        assert_eq!(output, [1, 2, 3, 4, 5, 0xAA, 0xAA, 0xAA, 0xAA]);
+    }
+
+    #[test]
+    fn write_hex() {
+        let mut output = Vec::new();
+        dump().write_hex(&mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ":020000040800F2\n\
+             :050000000102030405EC\n\
+             :020000042000DA\n\
+             :04000000AAAAAAAA54\n\
+             :00000001FF\n"
+        );
+    }
+
+    #[test]
+    fn write_elf() {
+        let mut output = Vec::new();
+        dump().write_elf(&mut output, Architecture::Arm).unwrap();
+
+        let elf = object::read::elf::ElfFile32::<object::Endianness>::parse(&output[..]).unwrap();
+
+        let segments: Vec<_> = elf
+            .segments()
+            .map(|segment| (segment.address(), segment.data().unwrap().to_vec()))
+            .collect();
+        assert_eq!(
+            segments,
+            vec![
+                (0x0800_0000, vec![0x01, 0x02, 0x03, 0x04, 0x05]),
+                (0x2000_0000, vec![0xAA; 4]),
+            ]
+        );
+
+        let sections: Vec<_> = elf
+            .sections()
+            .map(|section| (section.name().unwrap().to_string(), section.address()))
+            .collect();
+        assert_eq!(
+            sections,
+            vec![
+                (String::new(), 0),
+                (".flash".to_string(), 0x0800_0000),
+                (".dump1".to_string(), 0x2000_0000),
+                (".shstrtab".to_string(), 0),
+            ]
+        );
+
+        // The dump can be flashed again.
+        let mut extracted = Vec::new();
+        assert_eq!(
+            crate::flashing::extract_from_elf(&mut extracted, &output).unwrap(),
+            2
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! Parser for the [Motorola S-record](https://en.wikipedia.org/wiki/SREC_(file_format)) format.
+
+/// Describes an error that happened while reading a Motorola S-record file.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum SrecError {
+    /// A line did not start with the record mark `S`.
+    #[error("Line {line}: record does not start with 'S'.")]
+    MissingRecordMark {
+        /// The line number (starting at 1) of the malformed record.
+        line: usize,
+    },
+    /// The record type is not one of `S0` to `S9`, or is the reserved `S4`.
+    #[error("Line {line}: unsupported record type 'S{record_type}'.")]
+    UnsupportedRecordType {
+        /// The line number (starting at 1) of the malformed record.
+        line: usize,
+        /// The record type character following the `S`.
+        record_type: char,
+    },
+    /// The record contains characters which are not hexadecimal digits.
+    #[error("Line {line}: record contains invalid hexadecimal characters.")]
+    InvalidHex {
+        /// The line number (starting at 1) of the malformed record.
+        line: usize,
+    },
+    /// The byte count of the record does not match the length of the record.
+    #[error(
+        "Line {line}: record byte count {expected} does not match the record length {actual}."
+    )]
+    ByteCountMismatch {
+        /// The line number (starting at 1) of the malformed record.
+        line: usize,
+        /// The byte count given in the record.
+        expected: usize,
+        /// The number of bytes actually present in the record.
+        actual: usize,
+    },
+    /// The checksum of the record is wrong.
+    #[error("Line {line}: record checksum {expected:#04x} does not match the calculated checksum {actual:#04x}.")]
+    ChecksumMismatch {
+        /// The line number (starting at 1) of the malformed record.
+        line: usize,
+        /// The checksum given in the record.
+        expected: u8,
+        /// The checksum calculated from the record contents.
+        actual: u8,
+    },
+}
+
+/// A data record of an S-record file.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct SrecData {
+    pub(super) address: u64,
+    pub(super) data: Vec<u8>,
+}
+
+/// Parses the given S-record file contents and returns all data records in file order.
+///
+/// Header, count and termination records are validated, but otherwise ignored.
+pub(super) fn parse(contents: &str) -> Result<Vec<SrecData>, SrecError> {
+    let mut records = Vec::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let Some(record) = line.strip_prefix(['S', 's']) else {
+            return Err(SrecError::MissingRecordMark { line: line_number });
+        };
+
+        let mut chars = record.chars();
+        let record_type = chars
+            .next()
+            .ok_or(SrecError::MissingRecordMark { line: line_number })?;
+
+        // Number of address bytes for each record type.
+        let address_length = match record_type {
+            '0' | '1' | '5' | '9' => 2,
+            '2' | '6' | '8' => 3,
+            '3' | '7' => 4,
+            _ => {
+                return Err(SrecError::UnsupportedRecordType {
+                    line: line_number,
+                    record_type,
+                })
+            }
+        };
+
+        let bytes =
+            decode_hex(chars.as_str()).ok_or(SrecError::InvalidHex { line: line_number })?;
+
+        // The byte count covers address, data and checksum.
+        let Some((&count, rest)) = bytes.split_first() else {
+            return Err(SrecError::ByteCountMismatch {
+                line: line_number,
+                expected: 0,
+                actual: 0,
+            });
+        };
+        if count as usize != rest.len() || rest.len() < address_length + 1 {
+            return Err(SrecError::ByteCountMismatch {
+                line: line_number,
+                expected: count as usize,
+                actual: rest.len(),
+            });
+        }
+
+        let (&checksum, payload) = bytes
+            .split_last()
+            .expect("the record contains at least the checksum byte");
+        let sum = payload
+            .iter()
+            .fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+        if !sum != checksum {
+            return Err(SrecError::ChecksumMismatch {
+                line: line_number,
+                expected: checksum,
+                actual: !sum,
+            });
+        }
+
+        if let '1' | '2' | '3' = record_type {
+            let (address, data) = payload[1..].split_at(address_length);
+            let address = address
+                .iter()
+                .fold(0u64, |address, byte| (address << 8) | *byte as u64);
+
+            records.push(SrecData {
+                address,
+                data: data.to_vec(),
+            });
+        }
+    }
+
+    Ok(records)
+}
+
+/// Decodes a string of hexadecimal digit pairs.
+fn decode_hex(digits: &str) -> Option<Vec<u8>> {
+    digits
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [high, low] if high.is_ascii_hexdigit() && low.is_ascii_hexdigit() => {
+                u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, SrecData, SrecError};
+
+    #[test]
+    fn parse_all_address_sizes() {
+        let contents = "S00F000068656C6C6F202020202000003C\n\
+                        S1130000285F245F2212226A000424290008237C2A\n\
+                        S20801000000010203F0\n\
+                        S30900001000DEADBEEFAE\n\
+                        S5030003F9\n\
+                        S9030000FC\n";
+
+        assert_eq!(
+            parse(contents),
+            Ok(vec![
+                SrecData {
+                    address: 0x0000,
+                    data: vec![
+                        0x28, 0x5F, 0x24, 0x5F, 0x22, 0x12, 0x22, 0x6A, 0x00, 0x04, 0x24, 0x29,
+                        0x00, 0x08, 0x23, 0x7C
+                    ],
+                },
+                SrecData {
+                    address: 0x01_0000,
+                    data: vec![0x00, 0x01, 0x02, 0x03],
+                },
+                SrecData {
+                    address: 0x0000_1000,
+                    data: vec![0xDE, 0xAD, 0xBE, 0xEF],
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn checksum_mismatch() {
+        assert_eq!(
+            parse("S30900001000DEADBEEFAF\n"),
+            Err(SrecError::ChecksumMismatch {
+                line: 1,
+                expected: 0xAF,
+                actual: 0xAE
+            })
+        );
+    }
+
+    #[test]
+    fn malformed_records() {
+        assert_eq!(
+            parse("S9030000FC\n:00000001FF"),
+            Err(SrecError::MissingRecordMark { line: 2 })
+        );
+        assert_eq!(
+            parse("S4030000FC"),
+            Err(SrecError::UnsupportedRecordType {
+                line: 1,
+                record_type: '4'
+            })
+        );
+        assert_eq!(
+            parse("S1050000FC"),
+            Err(SrecError::ByteCountMismatch {
+                line: 1,
+                expected: 5,
+                actual: 3
+            })
+        );
+        assert_eq!(parse("S10300G0FC"), Err(SrecError::InvalidHex { line: 1 }));
+    }
+}
@@ -0,0 +1,139 @@
+//! Parser for the TI-TXT format used by Texas Instruments toolchains.
+//!
+//! A TI-TXT file consists of sections, each starting with an `@ADDR` line giving the
+//! hexadecimal start address, followed by lines of space separated hexadecimal bytes.
+//! The file is terminated by a `q` line.
+
+/// Describes an error that happened while reading a TI-TXT file.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum TiTxtError {
+    /// The address of an `@ADDR` line is not a valid hexadecimal number.
+    #[error("Line {line}: invalid section address.")]
+    InvalidAddress {
+        /// The line number (starting at 1) of the malformed line.
+        line: usize,
+    },
+    /// A data line contains something that is not a hexadecimal byte.
+    #[error("Line {line}: data contains an invalid hexadecimal byte.")]
+    InvalidByte {
+        /// The line number (starting at 1) of the malformed line.
+        line: usize,
+    },
+    /// Data was found before the first `@ADDR` line.
+    #[error("Line {line}: data without a preceding section address.")]
+    MissingAddress {
+        /// The line number (starting at 1) of the malformed line.
+        line: usize,
+    },
+    /// Data was found after the `q` termination line.
+    #[error("Line {line}: data after the end of file marker 'q'.")]
+    DataAfterEnd {
+        /// The line number (starting at 1) of the malformed line.
+        line: usize,
+    },
+}
+
+/// A section of a TI-TXT file.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct TiTxtSection {
+    pub(super) address: u64,
+    pub(super) data: Vec<u8>,
+}
+
+/// Parses the given TI-TXT file contents and returns all sections in file order.
+pub(super) fn parse(contents: &str) -> Result<Vec<TiTxtSection>, TiTxtError> {
+    let mut sections: Vec<TiTxtSection> = Vec::new();
+    let mut ended = false;
+
+    for (index, line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if ended {
+            return Err(TiTxtError::DataAfterEnd { line: line_number });
+        }
+
+        if line.eq_ignore_ascii_case("q") {
+            ended = true;
+        } else if let Some(address) = line.strip_prefix('@') {
+            let address = u64::from_str_radix(address.trim(), 16)
+                .map_err(|_| TiTxtError::InvalidAddress { line: line_number })?;
+
+            sections.push(TiTxtSection {
+                address,
+                data: Vec::new(),
+            });
+        } else {
+            let section = sections
+                .last_mut()
+                .ok_or(TiTxtError::MissingAddress { line: line_number })?;
+
+            for value in line.split_whitespace() {
+                if value.len() != 2 || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(TiTxtError::InvalidByte { line: line_number });
+                }
+                let byte =
+                    u8::from_str_radix(value, 16).expect("Two hex digits always fit a byte.");
+                section.data.push(byte);
+            }
+        }
+    }
+
+    Ok(sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, TiTxtError, TiTxtSection};
+
+    #[test]
+    fn parse_sections() {
+        let contents = "@F000\n\
+                        31 40 00 03 B2 40 80 5A 20 01 D2 D3 22 00 D2 E3\n\
+                        21 00 3F 40\n\
+                        @FFFE\n\
+                        00 F0\n\
+                        q\n";
+
+        assert_eq!(
+            parse(contents),
+            Ok(vec![
+                TiTxtSection {
+                    address: 0xF000,
+                    data: vec![
+                        0x31, 0x40, 0x00, 0x03, 0xB2, 0x40, 0x80, 0x5A, 0x20, 0x01, 0xD2, 0xD3,
+                        0x22, 0x00, 0xD2, 0xE3, 0x21, 0x00, 0x3F, 0x40
+                    ],
+                },
+                TiTxtSection {
+                    address: 0xFFFE,
+                    data: vec![0x00, 0xF0],
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn malformed_files() {
+        assert_eq!(
+            parse("00 01\n"),
+            Err(TiTxtError::MissingAddress { line: 1 })
+        );
+        assert_eq!(
+            parse("@F00X\n"),
+            Err(TiTxtError::InvalidAddress { line: 1 })
+        );
+        assert_eq!(
+            parse("@F000\n00 1\n"),
+            Err(TiTxtError::InvalidByte { line: 2 })
+        );
+        assert_eq!(
+            parse("@F000\n00\nq\n01\n"),
+            Err(TiTxtError::DataAfterEnd { line: 4 })
+        );
+    }
+}
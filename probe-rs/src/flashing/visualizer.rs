@@ -1,5 +1,8 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
 use svg::{
-    node::element::{Group, Rectangle, Text},
+    node::element::{Group, Rectangle, Text, Title},
     node::Text as Content,
     Document, Node,
 };
@@ -16,53 +19,13 @@ impl<'layout> FlashVisualizer<'layout> {
         Self { flash_layout }
     }
 
-    /// Calculates the position in a [0, 100] range
-    /// depending on the given address and the highest known sector end address.
-    fn memory_to_local(&self, address: u64) -> f32 {
-        let top_sector_address = self
-            .flash_layout
-            .sectors()
-            .last()
-            .map_or(0, |s| s.address() + s.size());
-
-        address as f32 / top_sector_address as f32 * 100.0
+    /// Returns the highest known sector end address.
+    fn top_sector_address(&self) -> u64 {
+        top_sector_address(self.flash_layout.sectors())
     }
 
     fn memory_block(&self, address: u64, size: u64, dimensions: (u32, u32)) -> Group {
-        let height = self.memory_to_local(size);
-        let start = 100.0 - self.memory_to_local(address) - height;
-
-        let mut group = Group::new();
-
-        group.append(
-            Rectangle::new()
-                .set("x", dimensions.0)
-                .set("y", start)
-                .set("width", dimensions.1)
-                .set("height", height),
-        );
-
-        group.append(
-            Text::new()
-                .set("x", dimensions.0 + 1)
-                .set("y", start + height - 2.0)
-                .set("font-size", 5)
-                .set("font-family", "Arial")
-                .set("fill", "Black")
-                .add(Content::new(format!("{address:#08X?}"))),
-        );
-
-        group.append(
-            Text::new()
-                .set("x", dimensions.0 + 1)
-                .set("y", start + 5.0)
-                .set("font-size", 5)
-                .set("font-family", "Arial")
-                .set("fill", "Black")
-                .add(Content::new(format!("{:#08X?}", address + size))),
-        );
-
-        group
+        memory_block(address, size, self.top_sector_address(), dimensions)
     }
 
     /// Generates an SVG in string form which visualizes the given flash contents.
@@ -132,3 +95,506 @@ impl<'layout> FlashVisualizer<'layout> {
         file.write_all(svg.as_bytes())
     }
 }
+
+/// Returns the highest end address of the given sectors.
+fn top_sector_address(sectors: &[FlashSector]) -> u64 {
+    sectors.last().map_or(0, |s| s.address() + s.size())
+}
+
+/// Calculates the position in a [0, 100] range
+/// depending on the given address and the highest known sector end address.
+fn memory_to_local(address: u64, top_sector_address: u64) -> f32 {
+    address as f32 / top_sector_address as f32 * 100.0
+}
+
+fn memory_block(address: u64, size: u64, top_sector_address: u64, dimensions: (u32, u32)) -> Group {
+    let height = memory_to_local(size, top_sector_address);
+    let start = 100.0 - memory_to_local(address, top_sector_address) - height;
+
+    let mut group = Group::new();
+
+    group.append(
+        Rectangle::new()
+            .set("x", dimensions.0)
+            .set("y", start)
+            .set("width", dimensions.1)
+            .set("height", height),
+    );
+
+    group.append(
+        Text::new()
+            .set("x", dimensions.0 + 1)
+            .set("y", start + height - 2.0)
+            .set("font-size", 5)
+            .set("font-family", "Arial")
+            .set("fill", "Black")
+            .add(Content::new(format!("{address:#08X?}"))),
+    );
+
+    group.append(
+        Text::new()
+            .set("x", dimensions.0 + 1)
+            .set("y", start + 5.0)
+            .set("font-size", 5)
+            .set("font-family", "Arial")
+            .set("fill", "Black")
+            .add(Content::new(format!("{:#08X?}", address + size))),
+    );
+
+    group
+}
+
+/// How the contents of a sector differ between two flash layouts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectorChange {
+    /// The sector has the same contents in both layouts.
+    Unchanged,
+    /// The sector is contained in both layouts, but its contents differ.
+    Modified,
+    /// The sector is only contained in the new layout.
+    Added,
+    /// The sector is only contained in the old layout.
+    Erased,
+}
+
+impl SectorChange {
+    fn color(self) -> &'static str {
+        match self {
+            SectorChange::Unchanged => "LightGray",
+            SectorChange::Modified => "Gold",
+            SectorChange::Added => "MediumSeaGreen",
+            SectorChange::Erased => "Crimson",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SectorChange::Unchanged => "unchanged",
+            SectorChange::Modified => "modified",
+            SectorChange::Added => "added",
+            SectorChange::Erased => "erased",
+        }
+    }
+}
+
+/// The difference of a single sector between two flash layouts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectorDiff {
+    /// The start address of the sector.
+    pub address: u64,
+    /// The size of the sector in bytes.
+    pub size: u64,
+    /// How the sector differs between the layouts.
+    pub change: SectorChange,
+    /// The number of bytes of the sector which differ between the layouts.
+    ///
+    /// For added and erased sectors, this is the number of bytes programmed in the layout
+    /// which contains the sector.
+    pub changed_bytes: u64,
+}
+
+/// The difference of a section of an image between two flash layouts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionDiff {
+    /// The name of the section, e.g. `.text`.
+    pub name: String,
+    /// The address the section is programmed to.
+    pub address: u64,
+    /// The size of the section in bytes.
+    pub size: u64,
+    /// The number of bytes of the section which differ between the layouts.
+    pub changed_bytes: u64,
+}
+
+/// A structure which can be used to visualize the differences between two flash layouts,
+/// e.g. of two builds of a firmware, or of a firmware and the contents read back from a target.
+pub struct FlashDiffVisualizer<'layout> {
+    old: &'layout FlashLayout,
+    new: &'layout FlashLayout,
+    sectors: Vec<SectorDiff>,
+    sections: Vec<SectionDiff>,
+}
+
+impl<'layout> FlashDiffVisualizer<'layout> {
+    pub(super) fn new(old: &'layout FlashLayout, new: &'layout FlashLayout) -> Self {
+        let mut sectors = BTreeMap::new();
+        for sector in old.sectors() {
+            sectors.insert(sector.address(), (Some(sector), None));
+        }
+        for sector in new.sectors() {
+            sectors.entry(sector.address()).or_insert((None, None)).1 = Some(sector);
+        }
+
+        let sectors = sectors
+            .into_values()
+            .map(|sectors| match sectors {
+                (Some(old_sector), Some(new_sector)) => {
+                    let size = old_sector.size().max(new_sector.size());
+                    let old_data = sector_contents(old, old_sector.address(), size);
+                    let new_data = sector_contents(new, new_sector.address(), size);
+                    let changed_bytes = old_data
+                        .iter()
+                        .zip(&new_data)
+                        .filter(|(old, new)| old != new)
+                        .count() as u64;
+
+                    SectorDiff {
+                        address: old_sector.address(),
+                        size,
+                        change: if changed_bytes == 0 {
+                            SectorChange::Unchanged
+                        } else {
+                            SectorChange::Modified
+                        },
+                        changed_bytes,
+                    }
+                }
+                (Some(sector), None) => single_sector_diff(old, sector, SectorChange::Erased),
+                (None, Some(sector)) => single_sector_diff(new, sector, SectorChange::Added),
+                (None, None) => unreachable!("Every entry contains at least one sector"),
+            })
+            .collect();
+
+        Self {
+            old,
+            new,
+            sectors,
+            sections: Vec::new(),
+        }
+    }
+
+    /// Additionally accounts the changed bytes of each of the given sections of the image,
+    /// e.g. the ones of [`FlashLoader::sections`](super::FlashLoader::sections).
+    ///
+    /// Sections outside of the sectors of both layouts are ignored.
+    pub fn with_sections(mut self, sections: &[ImageSection]) -> Self {
+        self.sections = sections
+            .iter()
+            .filter(|section| {
+                self.sectors.iter().any(|sector| {
+                    section.address < sector.address + sector.size
+                        && sector.address < section.address + section.size
+                })
+            })
+            .map(|section| {
+                let old = sector_contents(self.old, section.address, section.size);
+                let new = sector_contents(self.new, section.address, section.size);
+
+                SectionDiff {
+                    name: section.name.clone(),
+                    address: section.address,
+                    size: section.size,
+                    changed_bytes: old.iter().zip(&new).filter(|(old, new)| old != new).count()
+                        as u64,
+                }
+            })
+            .collect();
+
+        self
+    }
+
+    /// Returns the differences of the sections given to [`FlashDiffVisualizer::with_sections`].
+    pub fn sections(&self) -> &[SectionDiff] {
+        &self.sections
+    }
+
+    /// Returns the differences of all sectors contained in either layout, ordered by address.
+    pub fn sectors(&self) -> &[SectorDiff] {
+        &self.sectors
+    }
+
+    /// Returns the total number of bytes which differ between the layouts.
+    pub fn changed_bytes(&self) -> u64 {
+        self.sectors.iter().map(|sector| sector.changed_bytes).sum()
+    }
+
+    /// Generates an SVG in string form which visualizes the differences between the layouts.
+    ///
+    /// The sectors of the old and new layout are shown side by side,
+    /// and the sectors are colored by how they changed.
+    pub fn generate_svg(&self) -> String {
+        let top_sector_address =
+            top_sector_address(self.old.sectors()).max(top_sector_address(self.new.sectors()));
+
+        let mut document = Document::new();
+        let mut group = Group::new().set("transform", "scale(1, 1)");
+
+        for sector in &self.sectors {
+            let columns: &[u32] = match sector.change {
+                SectorChange::Unchanged | SectorChange::Modified => &[50, 100],
+                SectorChange::Erased => &[50],
+                SectorChange::Added => &[100],
+            };
+
+            for &column in columns {
+                let mut rectangle = memory_block(
+                    sector.address,
+                    sector.size,
+                    top_sector_address,
+                    (column, 50),
+                )
+                .set("fill", sector.change.color());
+                rectangle.append(Title::new().add(Content::new(format!(
+                    "{:#010x}..{:#010x}: {}, {} bytes changed",
+                    sector.address,
+                    sector.address + sector.size,
+                    sector.change.name(),
+                    sector.changed_bytes
+                ))));
+
+                group.append(rectangle);
+            }
+        }
+
+        for (index, change) in [
+            SectorChange::Unchanged,
+            SectorChange::Modified,
+            SectorChange::Added,
+            SectorChange::Erased,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let y = index as u32 * 8;
+            group.append(
+                Rectangle::new()
+                    .set("x", 160)
+                    .set("y", y)
+                    .set("width", 5)
+                    .set("height", 5)
+                    .set("fill", change.color()),
+            );
+            group.append(
+                Text::new()
+                    .set("x", 168)
+                    .set("y", y + 5)
+                    .set("font-size", 5)
+                    .set("font-family", "Arial")
+                    .set("fill", "Black")
+                    .add(Content::new(change.name())),
+            );
+        }
+
+        document.append(group);
+        document.assign("viewBox", (0, -20, 300, 140));
+
+        format!("{document}")
+    }
+
+    /// Generates an HTML page which contains the SVG of [FlashDiffVisualizer::generate_svg]
+    /// and tables with the number of changed bytes per sector and per section.
+    pub fn generate_html(&self) -> String {
+        let mut html = String::new();
+
+        // Writing to a `String` can not fail.
+        let _ = writeln!(html, "<!DOCTYPE html>");
+        let _ = writeln!(
+            html,
+            "<html><head><meta charset=\"utf-8\"><title>Flash layout diff</title></head><body>"
+        );
+        let _ = writeln!(
+            html,
+            "<div style=\"max-width: 900px\">{}</div>",
+            self.generate_svg()
+        );
+        let _ = writeln!(html, "<table>");
+        let _ = writeln!(
+            html,
+            "<tr><th>Start</th><th>End</th><th>Size</th><th>Change</th><th>Changed bytes</th></tr>"
+        );
+        for sector in &self.sectors {
+            let _ = writeln!(
+                html,
+                "<tr style=\"background-color: {}\"><td>{:#010x}</td><td>{:#010x}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                sector.change.color(),
+                sector.address,
+                sector.address + sector.size,
+                sector.size,
+                sector.change.name(),
+                sector.changed_bytes
+            );
+        }
+        let _ = writeln!(html, "</table>");
+        if !self.sections.is_empty() {
+            let _ = writeln!(html, "<table>");
+            let _ = writeln!(
+                html,
+                "<tr><th>Section</th><th>Start</th><th>End</th><th>Size</th><th>Changed bytes</th></tr>"
+            );
+            for section in &self.sections {
+                let _ = writeln!(
+                    html,
+                    "<tr><td>{}</td><td>{:#010x}</td><td>{:#010x}</td><td>{}</td><td>{}</td></tr>",
+                    section.name,
+                    section.address,
+                    section.address + section.size,
+                    section.size,
+                    section.changed_bytes
+                );
+            }
+            let _ = writeln!(html, "</table>");
+        }
+        let _ = writeln!(
+            html,
+            "<p>{} bytes changed in total</p>",
+            self.changed_bytes()
+        );
+        let _ = writeln!(html, "</body></html>");
+
+        html
+    }
+
+    /// Generates an SVG which visualizes the differences between the layouts
+    /// and writes the SVG into the file at the given `path`.
+    pub fn write_svg(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.generate_svg())
+    }
+
+    /// Generates an HTML page which visualizes the differences between the layouts
+    /// and writes it into the file at the given `path`.
+    pub fn write_html(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.generate_html())
+    }
+}
+
+/// Returns the contents of `size` bytes starting at `address` in the layout.
+///
+/// Bytes which are not part of any page of the layout are `None`.
+fn sector_contents(layout: &FlashLayout, address: u64, size: u64) -> Vec<Option<u8>> {
+    let mut contents = vec![None; size as usize];
+
+    for page in layout.pages() {
+        let page_end = page.address() + page.size() as u64;
+        let start = page.address().max(address);
+        let end = page_end.min(address + size);
+        if start >= end {
+            continue;
+        }
+
+        let data = &page.data()[(start - page.address()) as usize..(end - page.address()) as usize];
+        for (byte, value) in contents[(start - address) as usize..].iter_mut().zip(data) {
+            *byte = Some(*value);
+        }
+    }
+
+    contents
+}
+
+fn single_sector_diff(
+    layout: &FlashLayout,
+    sector: &FlashSector,
+    change: SectorChange,
+) -> SectorDiff {
+    let changed_bytes = sector_contents(layout, sector.address(), sector.size())
+        .iter()
+        .filter(|byte| byte.is_some())
+        .count() as u64;
+
+    SectorDiff {
+        address: sector.address(),
+        size: sector.size(),
+        change,
+        changed_bytes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use probe_rs_target::{FlashProperties, NvmRegion, SectorDescription};
+
+    use super::super::builder::FlashBuilder;
+    use super::*;
+
+    fn layout(data: &[(u64, &[u8])]) -> FlashLayout {
+        let flash_algorithm = FlashAlgorithm {
+            flash_properties: FlashProperties {
+                address_range: 0..0x1000,
+                page_size: 0x100,
+                erased_byte_value: 0xFF,
+                program_page_timeout: 200,
+                erase_sector_timeout: 200,
+                sectors: vec![SectorDescription {
+                    size: 0x400,
+                    address: 0,
+                }],
+            },
+            ..Default::default()
+        };
+
+        let region = NvmRegion {
+            name: Some("FLASH".into()),
+            is_boot_memory: true,
+            range: 0..0x1000,
+            cores: vec!["main".into()],
+        };
+
+        let mut builder = FlashBuilder::new();
+        for (address, data) in data {
+            builder.add_data(*address, data).unwrap();
+        }
+        builder
+            .build_sectors_and_pages(&region, &flash_algorithm, true)
+            .unwrap()
+    }
+
+    #[test]
+    fn sectors_are_classified() {
+        let old = layout(&[(0x000, &[1, 2, 3, 4]), (0x400, &[5; 8]), (0x800, &[6; 4])]);
+        let new = layout(&[
+            (0x000, &[1, 2, 3, 4]),
+            (0x400, &[5, 0, 5, 0]),
+            (0xC00, &[7; 2]),
+        ]);
+
+        let diff = old.visualize_diff(&new);
+        let changes = diff
+            .sectors()
+            .iter()
+            .map(|sector| (sector.address, sector.change, sector.changed_bytes))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            changes,
+            [
+                (0x000, SectorChange::Unchanged, 0),
+                (0x400, SectorChange::Modified, 6),
+                (0x800, SectorChange::Erased, 0x400),
+                (0xC00, SectorChange::Added, 0x400),
+            ]
+        );
+        assert_eq!(diff.changed_bytes(), 0x806);
+
+        let html = diff.generate_html();
+        assert!(html.contains("modified"));
+        assert!(html.contains("<svg"));
+    }
+
+    #[test]
+    fn changed_bytes_are_accounted_per_section() {
+        let old = layout(&[(0x000, &[1, 2, 3, 4, 5, 6, 7, 8])]);
+        let new = layout(&[(0x000, &[1, 2, 3, 4, 0, 0, 7, 8]), (0x400, &[9; 4])]);
+
+        let section = |name: &str, address, size| ImageSection {
+            name: name.to_string(),
+            address,
+            size,
+        };
+        let diff = old.visualize_diff(&new).with_sections(&[
+            section(".vector_table", 0x000, 4),
+            section(".text", 0x004, 4),
+            section(".rodata", 0x400, 4),
+            section(".data", 0x2000, 4),
+        ]);
+
+        let changes = diff
+            .sections()
+            .iter()
+            .map(|section| (section.name.as_str(), section.changed_bytes))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            [(".vector_table", 0), (".text", 2), (".rodata", 4)]
+        );
+        assert!(diff.generate_html().contains(".rodata"));
+    }
+}
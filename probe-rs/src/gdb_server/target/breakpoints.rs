@@ -0,0 +1,169 @@
+use super::{GdbErrorExt, RuntimeTarget};
+
+use crate::{Error, WatchpointKind};
+
+use gdbstub::target::ext::breakpoints::{
+    Breakpoints, HwBreakpoint, HwBreakpointOps, HwWatchpoint, HwWatchpointOps, SwBreakpoint,
+    SwBreakpointOps, WatchKind,
+};
+use gdbstub::target::TargetError;
+
+impl Breakpoints for RuntimeTarget<'_> {
+    fn support_sw_breakpoint(&mut self) -> Option<SwBreakpointOps<'_, Self>> {
+        Some(self)
+    }
+
+    fn support_hw_breakpoint(&mut self) -> Option<HwBreakpointOps<'_, Self>> {
+        Some(self)
+    }
+
+    fn support_hw_watchpoint(&mut self) -> Option<HwWatchpointOps<'_, Self>> {
+        Some(self)
+    }
+}
+
+impl SwBreakpoint for RuntimeTarget<'_> {
+    fn add_sw_breakpoint(
+        &mut self,
+        addr: u64,
+        _kind: <Self::Arch as gdbstub::arch::Arch>::BreakpointKind,
+    ) -> gdbstub::target::TargetResult<bool, Self> {
+        let mut session = self.session.lock().unwrap();
+
+        for core_id in &self.cores {
+            let mut core = session.core(*core_id).into_target_result()?;
+
+            // Breakpoints outside of RAM, e.g. in flash, still need a hardware unit.
+            core.set_breakpoint(addr).into_target_result()?;
+        }
+
+        Ok(true)
+    }
+
+    fn remove_sw_breakpoint(
+        &mut self,
+        addr: u64,
+        _kind: <Self::Arch as gdbstub::arch::Arch>::BreakpointKind,
+    ) -> gdbstub::target::TargetResult<bool, Self> {
+        let mut session = self.session.lock().unwrap();
+
+        for core_id in &self.cores {
+            let mut core = session.core(*core_id).into_target_result()?;
+
+            core.clear_breakpoint(addr).into_target_result()?;
+        }
+
+        Ok(true)
+    }
+}
+
+impl HwBreakpoint for RuntimeTarget<'_> {
+    fn add_hw_breakpoint(
+        &mut self,
+        addr: u64,
+        _kind: <Self::Arch as gdbstub::arch::Arch>::BreakpointKind,
+    ) -> gdbstub::target::TargetResult<bool, Self> {
+        let mut session = self.session.lock().unwrap();
+
+        for core_id in &self.cores {
+            let mut core = session.core(*core_id).into_target_result()?;
+
+            core.set_hw_breakpoint(addr).into_target_result()?;
+        }
+
+        Ok(true)
+    }
+
+    fn remove_hw_breakpoint(
+        &mut self,
+        addr: u64,
+        _kind: <Self::Arch as gdbstub::arch::Arch>::BreakpointKind,
+    ) -> gdbstub::target::TargetResult<bool, Self> {
+        let mut session = self.session.lock().unwrap();
+
+        for core_id in &self.cores {
+            let mut core = session.core(*core_id).into_target_result()?;
+
+            core.clear_hw_breakpoint(addr).into_target_result()?;
+        }
+
+        Ok(true)
+    }
+}
+
+impl HwWatchpoint for RuntimeTarget<'_> {
+    fn add_hw_watchpoint(
+        &mut self,
+        addr: u64,
+        len: u64,
+        kind: WatchKind,
+    ) -> gdbstub::target::TargetResult<bool, Self> {
+        let mut session = self.session.lock().unwrap();
+
+        let kind = match kind {
+            WatchKind::Write => WatchpointKind::Write,
+            WatchKind::Read => WatchpointKind::Read,
+            WatchKind::ReadWrite => WatchpointKind::Access,
+        };
+
+        let mut cores_with_watchpoint = Vec::new();
+        for core_id in &self.cores {
+            let result = session.core(*core_id).and_then(|mut core| {
+                // Let GDB fall back to software watchpoints if the core can not set this one.
+                if !core.can_set_watchpoint(addr)? {
+                    return Ok(false);
+                }
+                core.set_watchpoint(addr, len, kind)?;
+                Ok(true)
+            });
+
+            let result = match result {
+                Ok(true) => {
+                    cores_with_watchpoint.push(*core_id);
+                    continue;
+                }
+                Ok(false)
+                | Err(Error::NotImplemented(_))
+                | Err(Error::UnsupportedWatchpoint { .. }) => Ok(false),
+                Err(error) => Err(TargetError::Fatal(error)),
+            };
+
+            // GDB does not remove a watchpoint which could not be added,
+            // so it must not stay behind on the cores where it was set.
+            for core_id in cores_with_watchpoint {
+                if let Err(error) = session
+                    .core(core_id)
+                    .and_then(|mut core| core.clear_watchpoint(addr))
+                {
+                    tracing::warn!(
+                        "Failed to clear the watchpoint at {:#010x} on core {}: {}",
+                        addr,
+                        core_id,
+                        error
+                    );
+                }
+            }
+
+            return result;
+        }
+
+        Ok(true)
+    }
+
+    fn remove_hw_watchpoint(
+        &mut self,
+        addr: u64,
+        _len: u64,
+        _kind: WatchKind,
+    ) -> gdbstub::target::TargetResult<bool, Self> {
+        let mut session = self.session.lock().unwrap();
+
+        for core_id in &self.cores {
+            let mut core = session.core(*core_id).into_target_result()?;
+
+            core.clear_watchpoint(addr).into_target_result()?;
+        }
+
+        Ok(true)
+    }
+}
@@ -89,6 +89,8 @@ mod probe;
 #[cfg(feature = "rtt")]
 pub mod rtt;
 #[warn(missing_docs)]
+pub mod semihosting;
+#[warn(missing_docs)]
 mod session;
 #[cfg(test)]
 mod test;
@@ -98,7 +100,7 @@ pub use crate::core::{
     exception_handler_for_core, Architecture, BreakpointCause, Core, CoreDump, CoreDumpError,
     CoreInformation, CoreInterface, CoreRegister, CoreRegisters, CoreState, CoreStatus, HaltReason,
     MemoryMappedRegister, RegisterId, RegisterRole, RegisterValue, SemihostingCommand,
-    SpecificCoreState, VectorCatchCondition,
+    SpecificCoreState, VectorCatchCondition, Watchpoint, WatchpointKind,
 };
 pub use crate::error::Error;
 pub use crate::memory::MemoryInterface;
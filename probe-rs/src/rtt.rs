@@ -54,6 +54,9 @@ pub use syscall::*;
 pub mod channels;
 pub use channels::Channels;
 
+mod socket;
+pub use socket::*;
+
 use crate::{config::MemoryRegion, Core, MemoryInterface};
 use scroll::{Pread, LE};
 use std::borrow::Cow;
@@ -114,6 +117,9 @@ impl Rtt {
     // Minimum size of the ControlBlock struct in target memory in bytes with empty arrays
     const MIN_SIZE: usize = Self::O_CHANNEL_ARRAYS;
 
+    /// The number of bytes which are read at once when scanning memory for control blocks.
+    pub const SCAN_CHUNK_SIZE: usize = 64 * 1024;
+
     // Offsets of fields in target memory in bytes
     const O_ID: usize = 0;
     const O_MAX_UP_CHANNELS: usize = 16;
@@ -224,32 +230,54 @@ impl Rtt {
     /// Attempts to detect an RTT control block in the specified RAM region(s) and returns an
     /// instance if a valid control block was found.
     ///
+    /// If there are several valid control blocks, e.g. of a bootloader and an application,
+    /// [`Error::MultipleControlBlocksFound`] lists their addresses, so one of them can be selected
+    /// with [`ScanRegion::Exact`].
+    ///
     /// `core` can be e.g. an owned `Core` or a shared `Rc<Core>`.
     pub fn attach_region(
         core: &mut Core,
         memory_map: &[MemoryRegion],
         region: &ScanRegion,
     ) -> Result<Rtt, Error> {
-        let ranges: Vec<Range<u64>> = match region {
-            ScanRegion::Exact(addr) => {
-                tracing::debug!("Scanning at exact address: 0x{:X}", addr);
+        if let ScanRegion::Exact(addr) = region {
+            tracing::debug!("Scanning at exact address: 0x{:X}", addr);
 
-                return Rtt::from(core, memory_map, *addr, None)?
-                    .ok_or(Error::ControlBlockNotFound);
-            }
+            return Rtt::from(core, memory_map, *addr, None)?.ok_or(Error::ControlBlockNotFound);
+        }
+
+        let mut control_blocks = Self::find_control_blocks(core, memory_map, region)?;
+
+        match control_blocks.len() {
+            0 => Err(Error::ControlBlockNotFound),
+            1 => Ok(control_blocks.remove(0)),
+            _ => Err(Error::MultipleControlBlocksFound(
+                control_blocks.iter().map(|rtt| rtt.ptr).collect(),
+            )),
+        }
+    }
+
+    /// Searches the specified RAM region(s) for RTT control blocks, and returns all of them which
+    /// look valid.
+    ///
+    /// The memory is read in chunks of [`Self::SCAN_CHUNK_SIZE`] bytes. Every occurrence of the
+    /// RTT ID is a candidate, which is only accepted if its channel descriptors are plausible: the
+    /// buffers of the channels have to be in RAM, with the read and write offsets inside of them.
+    /// Regions which cannot be read are skipped.
+    pub fn find_control_blocks(
+        core: &mut Core,
+        memory_map: &[MemoryRegion],
+        region: &ScanRegion,
+    ) -> Result<Vec<Rtt>, Error> {
+        let ranges: Vec<Range<u64>> = match region {
+            ScanRegion::Exact(addr) => vec![Range {
+                start: *addr as u64,
+                end: *addr as u64 + Self::RTT_ID.len() as u64,
+            }],
             ScanRegion::Ram => {
                 tracing::debug!("Scanning RAM");
 
-                memory_map
-                    .iter()
-                    .filter_map(|r| match r {
-                        MemoryRegion::Ram(r) => Some(Range {
-                            start: r.range.start,
-                            end: r.range.end,
-                        }),
-                        _ => None,
-                    })
-                    .collect()
+                ram_ranges(memory_map).collect()
             }
             ScanRegion::Ranges(regions) => regions.clone(),
             ScanRegion::Range(region) => {
@@ -262,73 +290,69 @@ impl Rtt {
             }
         };
 
-        let mut instances = ranges
-            .into_iter()
-            .filter_map(|range| {
-                let range_len = match range.end.checked_sub(range.start) {
-                    Some(v) => if v < (Self::MIN_SIZE as u64) {
-                        return None;
-                    } else {
-                        v
-                    },
-                    None => return None,
-                };
-
-                let range_len_usize: usize = match range_len.try_into() {
-                    Ok(v) => v,
-                    Err(_) => {
-                        // FIXME: This is not ideal because it means that we
-                        // won't consider a >4GiB region if probe-rs is running
-                        // on a 32-bit host, but it would be relatively unusual
-                        // to use a 32-bit host to debug a 64-bit target.
-                        tracing::warn!("ignoring region of length {} because it is too long to buffer in host memory", range_len);
-                        return None;
-                    }
-                };
-
-                let mut mem = vec![0; range_len_usize];
-                {
-                    core.read(range.start, mem.as_mut()).ok()?;
+        let mut candidates = Vec::new();
+        for range in ranges {
+            for chunk in scan_chunks(range) {
+                let mut mem = vec![0; (chunk.end - chunk.start) as usize];
+                if let Err(error) = core.read(chunk.start, &mut mem) {
+                    tracing::debug!(
+                        "Skipping unreadable memory {:#010x}..{:#010x} while scanning for RTT: {}",
+                        chunk.start,
+                        chunk.end,
+                        error
+                    );
+                    continue;
                 }
 
-                match kmp::kmp_find(&Self::RTT_ID, mem.as_slice()) {
-                    Some(offset) => {
-                        let target_ptr = range.start + (offset as u64);
-                        let target_ptr: u32 = match target_ptr.try_into() {
-                            Ok(v) => v,
-                            Err(_) => {
-                                // FIXME: The RTT API currently supports only
-                                // 32-bit addresses, and so it can't accept
-                                // an RTT block at an address >4GiB.
-                                tracing::warn!("can't use RTT block at {:#010x}; must be at a location reachable by 32-bit addressing", target_ptr);
-                                return None;
-                            },
-                        };
-
-                        Rtt::from(
-                            core,
-                            memory_map,
-                            target_ptr,
-                            Some(&mem[offset..]),
-                        )
-                        .transpose()
-                    },
-                    None => None,
-                }
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+                candidates.extend(
+                    find_rtt_ids(&mem)
+                        .into_iter()
+                        .map(|offset| chunk.start + offset as u64),
+                );
+            }
+        }
 
-        if instances.is_empty() {
-            return Err(Error::ControlBlockNotFound);
+        let mut control_blocks = Vec::new();
+        for candidate in candidates {
+            let Ok(ptr) = u32::try_from(candidate) else {
+                // FIXME: The RTT API currently supports only 32-bit addresses, and so it can't
+                // accept an RTT block at an address >4GiB.
+                tracing::warn!("can't use RTT block at {:#010x}; must be at a location reachable by 32-bit addressing", candidate);
+                continue;
+            };
+
+            match Rtt::from(core, memory_map, ptr, None) {
+                Ok(Some(rtt)) => match rtt.check_channels(core, memory_map) {
+                    Ok(()) => {
+                        tracing::debug!("Found RTT control block at {:#010x}", ptr);
+                        control_blocks.push(rtt);
+                    }
+                    Err(Error::ControlBlockCorrupted(reason)) => {
+                        tracing::debug!("Ignoring RTT control block at {:#010x}: {}", ptr, reason);
+                    }
+                    Err(error) => return Err(error),
+                },
+                Ok(None) => {}
+                Err(Error::ControlBlockCorrupted(reason)) => {
+                    tracing::debug!("Ignoring RTT control block at {:#010x}: {}", ptr, reason);
+                }
+                Err(error) => return Err(error),
+            }
         }
 
-        if instances.len() > 1 {
-            return Err(Error::MultipleControlBlocksFound(
-                instances.into_iter().map(|i| i.ptr).collect(),
-            ));
+        Ok(control_blocks)
+    }
+
+    /// Checks that the descriptors of all channels are plausible.
+    fn check_channels(&self, core: &mut Core, memory_map: &[MemoryRegion]) -> Result<(), Error> {
+        for channel in self.up_channels.0.values() {
+            channel.0.check(core, memory_map, "up")?;
+        }
+        for channel in self.down_channels.0.values() {
+            channel.0.check(core, memory_map, "down")?;
         }
 
-        Ok(instances.remove(0))
+        Ok(())
     }
 
     /// Returns the memory address of the control block in target memory.
@@ -347,6 +371,35 @@ impl Rtt {
     }
 }
 
+/// Returns the address ranges of all RAM regions in `memory_map`.
+pub(crate) fn ram_ranges(memory_map: &[MemoryRegion]) -> impl Iterator<Item = Range<u64>> + '_ {
+    memory_map.iter().filter_map(|region| match region {
+        MemoryRegion::Ram(region) => Some(region.range.clone()),
+        _ => None,
+    })
+}
+
+/// Splits `range` into chunks of `Rtt::SCAN_CHUNK_SIZE` bytes. The chunks overlap by one byte less
+/// than the RTT ID, so that every RTT ID is found in exactly one chunk.
+fn scan_chunks(range: Range<u64>) -> impl Iterator<Item = Range<u64>> {
+    let overlap = Rtt::RTT_ID.len() as u64 - 1;
+    (range.start..range.end)
+        .step_by(Rtt::SCAN_CHUNK_SIZE)
+        .map(move |start| start..range.end.min(start + Rtt::SCAN_CHUNK_SIZE as u64 + overlap))
+        .filter(|chunk| chunk.end - chunk.start >= Rtt::RTT_ID.len() as u64)
+}
+
+/// Returns the offsets of all RTT IDs in `mem`.
+fn find_rtt_ids(mem: &[u8]) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    let mut start = 0;
+    while let Some(offset) = kmp::kmp_find(&Rtt::RTT_ID, &mem[start..]) {
+        offsets.push(start + offset);
+        start += offset + 1;
+    }
+    offsets
+}
+
 /// Used to specify which memory regions to scan for the RTT control block.
 #[derive(Clone, Debug, Default)]
 pub enum ScanRegion {
@@ -386,8 +439,11 @@ pub enum Error {
     )]
     ControlBlockNotFound,
 
-    /// Multiple control blocks found in target memory. The data contains the control block addresses (up to 5).
-    #[error("Multiple control blocks found in target memory.")]
+    /// Multiple control blocks found in target memory. The data contains the control block addresses.
+    #[error(
+        "Multiple control blocks found in target memory, at {}. Select one of them by its address.",
+        .0.iter().map(|address| format!("{address:#010x}")).collect::<Vec<_>>().join(", ")
+    )]
     MultipleControlBlocksFound(Vec<u32>),
 
     /// The control block has been corrupted. The data contains a detailed error.
@@ -406,3 +462,30 @@ pub enum Error {
     #[error("Unexpected error while reading {0} from target memory. Please report this as a bug.")]
     MemoryRead(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan_chunks_overlap() {
+        let chunk_size = Rtt::SCAN_CHUNK_SIZE as u64;
+        let chunks = scan_chunks(0x2000_0000..0x2000_0000 + 2 * chunk_size + 8).collect::<Vec<_>>();
+        assert_eq!(
+            chunks,
+            [
+                0x2000_0000..0x2000_0000 + chunk_size + 15,
+                0x2000_0000 + chunk_size..0x2000_0000 + 2 * chunk_size + 8,
+            ]
+        );
+    }
+
+    #[test]
+    fn find_all_rtt_ids() {
+        let mut mem = vec![0u8; 256];
+        mem[8..24].copy_from_slice(&Rtt::RTT_ID);
+        mem[200..216].copy_from_slice(&Rtt::RTT_ID);
+        assert_eq!(find_rtt_ids(&mem), [8, 200]);
+        assert_eq!(find_rtt_ids(&mem[..215]), [8]);
+    }
+}
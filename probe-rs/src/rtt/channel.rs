@@ -2,6 +2,9 @@ use crate::rtt::Error;
 use crate::{config::MemoryRegion, Core, MemoryInterface};
 use scroll::{Pread, LE};
 use std::cmp::min;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 /// Trait for channel information shared between up and down channels.
 pub trait RttChannel {
@@ -16,6 +19,103 @@ pub trait RttChannel {
     fn buffer_size(&self) -> usize;
 }
 
+/// Statistics about the data transferred through a channel, as observed by the host each time it
+/// reads from or writes to the channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChannelStats {
+    /// The total number of bytes which the host read from or wrote to the channel.
+    pub total_bytes: u64,
+    /// The number of bytes per second, measured over the last second.
+    pub bytes_per_second: f64,
+    /// The number of bytes in the buffer, which were not consumed by the reading side yet, when the
+    /// host last accessed the channel. For an up channel, this is how far the host lags behind the target.
+    pub lag: usize,
+    /// The highest number of bytes in the buffer which was observed.
+    pub high_water_mark: usize,
+    /// The usable size of the buffer, which is one byte less than its size.
+    pub capacity: usize,
+    /// How often the buffer was observed to be full.
+    pub full_count: u64,
+    /// The estimated number of bytes which the target dropped, because the buffer was full while
+    /// the channel was in a non-blocking mode.
+    ///
+    /// The host cannot see the dropped data, so the estimate assumes that the target kept writing
+    /// at the rate which was measured before the buffer became full.
+    pub dropped_bytes: u64,
+}
+
+impl fmt::Display for ChannelStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.0} B/s, lag {}/{} B, high water {} B",
+            self.bytes_per_second, self.lag, self.capacity, self.high_water_mark
+        )?;
+        if self.full_count > 0 {
+            write!(f, ", full {}x", self.full_count)?;
+        }
+        if self.dropped_bytes > 0 {
+            write!(f, ", ~{} B dropped", self.dropped_bytes)?;
+        }
+        Ok(())
+    }
+}
+
+/// The period over which `ChannelStats::bytes_per_second` is measured.
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(1);
+
+/// Collects the `ChannelStats` of a channel.
+#[derive(Debug, Default)]
+struct StatsRecorder {
+    stats: ChannelStats,
+    /// The start of the current throughput window, and the bytes transferred since.
+    window: Option<(Instant, u64)>,
+    /// The last time the channel was accessed.
+    last_access: Option<Instant>,
+}
+
+impl StatsRecorder {
+    /// Records an access to the channel at `now`, which found `fill` bytes in the buffer and
+    /// transferred `count` bytes.
+    ///
+    /// `dropping` is called if the buffer was full, and returns whether the target drops data
+    /// in that case.
+    fn record(
+        &mut self,
+        now: Instant,
+        capacity: usize,
+        fill: usize,
+        count: usize,
+        dropping: impl FnOnce() -> bool,
+    ) {
+        let stats = &mut self.stats;
+        stats.capacity = capacity;
+        stats.total_bytes += count as u64;
+        stats.lag = fill;
+        stats.high_water_mark = stats.high_water_mark.max(fill);
+
+        if capacity > 0 && fill >= capacity {
+            stats.full_count += 1;
+            if let Some(last_access) = self.last_access {
+                if dropping() {
+                    let expected = stats.bytes_per_second * (now - last_access).as_secs_f64();
+                    stats.dropped_bytes += (expected as u64).saturating_sub(fill as u64);
+                }
+            }
+        }
+
+        let (start, bytes) = self.window.get_or_insert((now, 0));
+        *bytes += count as u64;
+        let elapsed = now - *start;
+        if elapsed >= THROUGHPUT_WINDOW {
+            stats.bytes_per_second = *bytes as f64 / elapsed.as_secs_f64();
+            self.window = Some((now, 0));
+        }
+
+        self.last_access = Some(now);
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct Channel {
     number: usize,
@@ -24,6 +124,7 @@ pub(crate) struct Channel {
     name: Option<String>,
     buffer_ptr: u32,
     size: u32,
+    stats: Mutex<StatsRecorder>,
 }
 
 // Chanels must follow this data layout when reading/writing memory in order to be compatible with
@@ -86,6 +187,7 @@ impl Channel {
             name,
             buffer_ptr,
             size: mem.pread_with(Self::O_SIZE, LE).unwrap(),
+            stats: Mutex::new(StatsRecorder::default()),
         }))
     }
 
@@ -102,10 +204,54 @@ impl Channel {
         self.name.as_ref().map(|s| s.as_ref())
     }
 
+    /// Checks that the descriptor of the channel is plausible: the buffer has to be in RAM, and
+    /// the read and write offsets have to be inside of it.
+    pub(crate) fn check(
+        &self,
+        core: &mut Core,
+        memory_map: &[MemoryRegion],
+        dir: &'static str,
+    ) -> Result<(), Error> {
+        let buffer = self.buffer_ptr as u64..self.buffer_ptr as u64 + self.size as u64;
+        let in_ram = crate::rtt::ram_ranges(memory_map)
+            .any(|ram| ram.start <= buffer.start && buffer.end <= ram.end);
+        if self.size == 0 || !in_ram {
+            return Err(Error::ControlBlockCorrupted(format!(
+                "Buffer of {:?} channel {} at {:#010x} with size {} is not in RAM",
+                dir, self.number, self.buffer_ptr, self.size
+            )));
+        }
+
+        self.read_pointers(core, dir)?;
+        Ok(())
+    }
+
     pub fn buffer_size(&self) -> usize {
         self.size as usize
     }
 
+    pub fn stats(&self) -> ChannelStats {
+        self.stats.lock().unwrap().stats
+    }
+
+    /// Records an access which found `fill` bytes in the buffer and transferred `count` bytes.
+    fn record_access(&self, fill: usize, count: usize, dropping: impl FnOnce() -> bool) {
+        let capacity = self.buffer_size().saturating_sub(1);
+        self.stats
+            .lock()
+            .unwrap()
+            .record(Instant::now(), capacity, fill, count, dropping);
+    }
+
+    /// The number of bytes in the buffer, given its write and read pointer.
+    fn fill(&self, write: u32, read: u32) -> usize {
+        (if write >= read {
+            write - read
+        } else {
+            self.size - read + write
+        }) as usize
+    }
+
     fn read_pointers(&self, core: &mut Core, dir: &'static str) -> Result<(u32, u32), Error> {
         self.validate_core_id(core)?;
         let mut block = [0u32; 2];
@@ -158,6 +304,13 @@ impl UpChannel {
         self.0.buffer_size()
     }
 
+    /// Returns the statistics of the data which the host transferred through the channel.
+    ///
+    /// The statistics are updated every time the channel is read from or written to.
+    pub fn stats(&self) -> ChannelStats {
+        self.0.stats()
+    }
+
     /// Reads the current channel mode from the target and returns its.
     ///
     /// See [`ChannelMode`] for more information on what the modes mean.
@@ -189,9 +342,13 @@ impl UpChannel {
         Ok(())
     }
 
-    fn read_core(&self, core: &mut Core, mut buf: &mut [u8]) -> Result<(u32, usize), Error> {
+    /// Reads data from the buffer without updating the read pointer on the target.
+    ///
+    /// Returns the new read pointer, the number of bytes read and the number of bytes which were in the buffer.
+    fn read_core(&self, core: &mut Core, mut buf: &mut [u8]) -> Result<(u32, usize, usize), Error> {
         self.0.validate_core_id(core)?;
         let (write, mut read) = self.0.read_pointers(core, "up")?;
+        let fill = self.0.fill(write, read);
 
         let mut total = 0;
 
@@ -215,7 +372,7 @@ impl UpChannel {
             buf = &mut buf[count..];
         }
 
-        Ok((read, total))
+        Ok((read, total, fill))
     }
 
     /// Reads some bytes from the channel to the specified buffer and returns how many bytes were
@@ -225,13 +382,17 @@ impl UpChannel {
     /// than would fit in `buf`.
     pub fn read(&self, core: &mut Core, buf: &mut [u8]) -> Result<usize, Error> {
         self.0.validate_core_id(core)?;
-        let (read, total) = self.read_core(core, buf)?;
+        let (read, total, fill) = self.read_core(core, buf)?;
 
         if total > 0 {
             // Write read pointer back to target if something was read
             core.write_word_32((self.0.ptr + Channel::O_READ as u32).into(), read)?;
         }
 
+        self.0.record_access(fill, total, || {
+            !matches!(self.mode(core), Ok(ChannelMode::BlockIfFull))
+        });
+
         Ok(total)
     }
 
@@ -290,6 +451,13 @@ impl DownChannel {
         self.0.buffer_size()
     }
 
+    /// Returns the statistics of the data which the host transferred through the channel.
+    ///
+    /// The statistics are updated every time the channel is read from or written to.
+    pub fn stats(&self) -> ChannelStats {
+        self.0.stats()
+    }
+
     /// Writes some bytes into the channel buffer and returns the number of bytes written.
     ///
     /// This method will not block waiting for space to become available in the channel buffer, and
@@ -297,9 +465,11 @@ impl DownChannel {
     pub fn write(&self, core: &mut Core, mut buf: &[u8]) -> Result<usize, Error> {
         self.0.validate_core_id(core)?;
         let (mut write, read) = self.0.read_pointers(core, "down")?;
+        let fill = self.0.fill(write, read);
 
         if self.writable_contiguous(write, read) == 0 {
             // Buffer is full - do nothing.
+            self.0.record_access(fill, 0, || false);
             return Ok(0);
         }
 
@@ -328,6 +498,9 @@ impl DownChannel {
         // Write write pointer back to target
         core.write_word_32((self.0.ptr + Channel::O_WRITE as u32).into(), write)?;
 
+        // The host never drops data, it is up to the caller to write the rest later.
+        self.0.record_access(fill + total, total, || false);
+
         Ok(total)
     }
 
@@ -411,3 +584,61 @@ pub enum ChannelMode {
     /// is not read by the host.
     BlockIfFull = 2,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_throughput_and_fill() {
+        let mut recorder = StatsRecorder::default();
+        let start = Instant::now();
+
+        recorder.record(start, 1023, 100, 100, || unreachable!());
+        recorder.record(
+            start + Duration::from_millis(500),
+            1023,
+            400,
+            400,
+            || unreachable!(),
+        );
+        recorder.record(
+            start + Duration::from_secs(1),
+            1023,
+            20,
+            20,
+            || unreachable!(),
+        );
+
+        let stats = recorder.stats;
+        assert_eq!(stats.total_bytes, 520);
+        assert_eq!(stats.bytes_per_second, 520.0);
+        assert_eq!(stats.lag, 20);
+        assert_eq!(stats.high_water_mark, 400);
+        assert_eq!(stats.full_count, 0);
+    }
+
+    #[test]
+    fn estimate_dropped_bytes_when_full() {
+        let mut recorder = StatsRecorder::default();
+        let start = Instant::now();
+
+        recorder.record(start, 1023, 0, 0, || unreachable!());
+        recorder.record(
+            start + Duration::from_secs(1),
+            1023,
+            1000,
+            1000,
+            || unreachable!(),
+        );
+        // At 1000 B/s, the target wrote about 2000 bytes in 2 s, but only 1023 fit in the buffer.
+        recorder.record(start + Duration::from_secs(3), 1023, 1023, 1023, || true);
+        assert_eq!(recorder.stats.full_count, 1);
+        assert_eq!(recorder.stats.dropped_bytes, 977);
+
+        // In blocking mode, the target waits for the host instead of dropping data.
+        recorder.record(start + Duration::from_secs(5), 1023, 1023, 1023, || false);
+        assert_eq!(recorder.stats.full_count, 2);
+        assert_eq!(recorder.stats.dropped_bytes, 977);
+    }
+}
@@ -0,0 +1,458 @@
+use crate::rtt::{DownChannel, Error};
+use crate::Core;
+use std::fmt;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// The amount of data which is kept for a client that does not read it, before the client is disconnected.
+const MAX_PENDING_BYTES: usize = 1024 * 1024;
+
+/// The amount of data from the clients which is kept while the down channel is full. Beyond it,
+/// the clients are no longer read from, so they block once the buffers of the operating system are full.
+const MAX_INPUT_BYTES: usize = 1024 * 1024;
+
+/// The address of a socket on which an RTT channel is served.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum RttSocketAddress {
+    /// A TCP socket, written as `tcp:127.0.0.1:19021`, or just `127.0.0.1:19021`.
+    Tcp(SocketAddr),
+    /// A Unix domain socket, written as `unix:/tmp/rtt0`. Only available on Unix platforms.
+    Unix(PathBuf),
+}
+
+impl FromStr for RttSocketAddress {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = s.strip_prefix("unix:") {
+            return Ok(Self::Unix(PathBuf::from(path)));
+        }
+
+        s.strip_prefix("tcp:")
+            .unwrap_or(s)
+            .parse()
+            .map(Self::Tcp)
+            .map_err(|_| {
+                format!("'{s}' is not a valid socket address. Use 'tcp:HOST:PORT' or 'unix:PATH'.")
+            })
+    }
+}
+
+impl TryFrom<String> for RttSocketAddress {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<RttSocketAddress> for String {
+    fn from(value: RttSocketAddress) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for RttSocketAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(address) => write!(f, "tcp:{address}"),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl Listener {
+    fn accept(&self) -> io::Result<Stream> {
+        match self {
+            Self::Tcp(listener) => listener.accept().map(|(stream, _)| Stream::Tcp(stream)),
+            #[cfg(unix)]
+            Self::Unix(listener) => listener.accept().map(|(stream, _)| Stream::Unix(stream)),
+        }
+    }
+}
+
+enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Stream {
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        match self {
+            Self::Tcp(stream) => stream.set_nonblocking(nonblocking),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.set_nonblocking(nonblocking),
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => stream.read(buf),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => stream.write(buf),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Tcp(stream) => stream.flush(),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.flush(),
+        }
+    }
+}
+
+/// A connected client, with the data that could not be sent to it yet.
+struct Client {
+    stream: Stream,
+    pending: Vec<u8>,
+}
+
+impl Client {
+    /// Sends as much of the pending data as the client accepts without blocking.
+    ///
+    /// Returns `false` if the client disconnected.
+    fn flush(&mut self) -> bool {
+        while !self.pending.is_empty() {
+            match self.stream.write(&self.pending) {
+                Ok(0) => return false,
+                Ok(count) => {
+                    self.pending.drain(..count);
+                }
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => return false,
+            }
+        }
+
+        if self.pending.len() > MAX_PENDING_BYTES {
+            tracing::warn!("Disconnecting RTT socket client, which does not read its data.");
+            return false;
+        }
+
+        true
+    }
+
+    /// Reads the data which the client sent, without blocking, until `input` holds
+    /// [`MAX_INPUT_BYTES`].
+    ///
+    /// Returns `false` if the client disconnected.
+    fn read(&mut self, input: &mut Vec<u8>) -> bool {
+        let mut buffer = [0u8; 1024];
+        loop {
+            let space = MAX_INPUT_BYTES
+                .saturating_sub(input.len())
+                .min(buffer.len());
+            if space == 0 {
+                return true;
+            }
+
+            match self.stream.read(&mut buffer[..space]) {
+                Ok(0) => return false,
+                Ok(count) => input.extend_from_slice(&buffer[..count]),
+                Err(error) if error.kind() == ErrorKind::WouldBlock => return true,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => return false,
+            }
+        }
+    }
+}
+
+/// A socket which serves an RTT channel to any number of clients.
+///
+/// The data of the up channel is sent to every connected client, and the data which the clients
+/// send is written to the down channel. The socket never blocks, so it can be polled together with
+/// the RTT channels.
+pub struct RttSocket {
+    address: RttSocketAddress,
+    listener: Listener,
+    clients: Vec<Client>,
+    /// Data received from the clients, which is not yet written to the down channel.
+    input: Vec<u8>,
+}
+
+impl RttSocket {
+    /// Start listening for clients on `address`.
+    ///
+    /// A stale Unix domain socket at the same path, which no process listens on anymore, is replaced.
+    pub fn bind(address: &RttSocketAddress) -> io::Result<Self> {
+        let listener = match address {
+            RttSocketAddress::Tcp(socket_address) => {
+                let listener = TcpListener::bind(socket_address)?;
+                listener.set_nonblocking(true)?;
+                Listener::Tcp(listener)
+            }
+            #[cfg(unix)]
+            RttSocketAddress::Unix(path) => {
+                use std::os::unix::fs::FileTypeExt;
+
+                if std::fs::metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+                    if UnixStream::connect(path).is_ok() {
+                        return Err(io::Error::new(
+                            ErrorKind::AddrInUse,
+                            format!("{} is in use by another process", path.display()),
+                        ));
+                    }
+                    std::fs::remove_file(path)?;
+                }
+                let listener = UnixListener::bind(path)?;
+                listener.set_nonblocking(true)?;
+                Listener::Unix(listener)
+            }
+            #[cfg(not(unix))]
+            RttSocketAddress::Unix(_) => {
+                return Err(io::Error::new(
+                    ErrorKind::Unsupported,
+                    "Unix domain sockets are not supported on this platform",
+                ))
+            }
+        };
+
+        tracing::info!("Serving RTT on {}", address);
+
+        Ok(Self {
+            address: address.clone(),
+            listener,
+            clients: Vec::new(),
+            input: Vec::new(),
+        })
+    }
+
+    /// The address on which the socket listens.
+    pub fn address(&self) -> &RttSocketAddress {
+        &self.address
+    }
+
+    /// The number of connected clients.
+    pub fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Accepts new clients, sends pending data to the clients, and collects the data which the clients sent.
+    pub fn poll(&mut self) {
+        loop {
+            match self.listener.accept() {
+                Ok(stream) => {
+                    if let Err(error) = stream.set_nonblocking(true) {
+                        tracing::warn!("Failed to accept RTT socket client: {}", error);
+                        continue;
+                    }
+                    tracing::debug!("RTT socket client connected to {}", self.address);
+                    self.clients.push(Client {
+                        stream,
+                        pending: Vec::new(),
+                    });
+                }
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) => {
+                    tracing::warn!("Failed to accept RTT socket client: {}", error);
+                    break;
+                }
+            }
+        }
+
+        let input = &mut self.input;
+        let client_count = self.clients.len();
+        self.clients
+            .retain_mut(|client| client.flush() && client.read(input));
+        if self.clients.len() < client_count {
+            tracing::debug!("RTT socket client disconnected from {}", self.address);
+        }
+    }
+
+    /// Sends `data` to every connected client.
+    pub fn send(&mut self, data: &[u8]) {
+        for client in &mut self.clients {
+            client.pending.extend_from_slice(data);
+        }
+        self.poll();
+    }
+
+    /// Takes the data which the clients sent, when there is no down channel to write it to.
+    pub fn take_input(&mut self) -> Vec<u8> {
+        self.poll();
+        std::mem::take(&mut self.input)
+    }
+
+    /// Writes the data which the clients sent to `channel`, as far as it fits in the buffer of the
+    /// channel. The rest is written on the next call.
+    ///
+    /// Returns the number of bytes written.
+    pub fn write_input(&mut self, core: &mut Core, channel: &DownChannel) -> Result<usize, Error> {
+        self.poll();
+        if self.input.is_empty() {
+            return Ok(0);
+        }
+
+        let count = channel.write(core, &self.input)?;
+        self.input.drain(..count);
+        Ok(count)
+    }
+}
+
+impl fmt::Debug for RttSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RttSocket")
+            .field("address", &self.address)
+            .field("clients", &self.clients.len())
+            .finish()
+    }
+}
+
+impl Drop for RttSocket {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let RttSocketAddress::Unix(path) = &self.address {
+            std::fs::remove_file(path).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn parse_socket_address() {
+        assert_eq!(
+            "tcp:127.0.0.1:19021".parse(),
+            Ok(RttSocketAddress::Tcp(([127, 0, 0, 1], 19021).into()))
+        );
+        assert_eq!(
+            "127.0.0.1:19021".parse(),
+            Ok(RttSocketAddress::Tcp(([127, 0, 0, 1], 19021).into()))
+        );
+        assert_eq!(
+            "unix:/tmp/rtt0".parse(),
+            Ok(RttSocketAddress::Unix(PathBuf::from("/tmp/rtt0")))
+        );
+        assert!("19021".parse::<RttSocketAddress>().is_err());
+
+        let address = RttSocketAddress::Tcp(([127, 0, 0, 1], 19021).into());
+        assert_eq!(address.to_string().parse(), Ok(address));
+    }
+
+    #[test]
+    fn tcp_clients_receive_and_send_data() {
+        let mut socket =
+            RttSocket::bind(&RttSocketAddress::Tcp(([127, 0, 0, 1], 0).into())).unwrap();
+        let Listener::Tcp(listener) = &socket.listener else {
+            unreachable!()
+        };
+        let local_address = listener.local_addr().unwrap();
+
+        let mut clients = [
+            TcpStream::connect(local_address).unwrap(),
+            TcpStream::connect(local_address).unwrap(),
+        ];
+        for _ in 0..100 {
+            socket.poll();
+            if socket.client_count() == clients.len() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(socket.client_count(), clients.len());
+
+        socket.send(b"Hello, host!");
+        for client in &mut clients {
+            client
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            let mut buffer = [0u8; 12];
+            client.read_exact(&mut buffer).unwrap();
+            assert_eq!(&buffer, b"Hello, host!");
+        }
+
+        clients[1].write_all(b"Hello, target!").unwrap();
+        let mut input = Vec::new();
+        for _ in 0..100 {
+            input.extend(socket.take_input());
+            if input.len() == 14 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(input, b"Hello, target!");
+    }
+
+    #[test]
+    fn input_is_limited_while_it_is_not_taken() {
+        let mut socket =
+            RttSocket::bind(&RttSocketAddress::Tcp(([127, 0, 0, 1], 0).into())).unwrap();
+        let Listener::Tcp(listener) = &socket.listener else {
+            unreachable!()
+        };
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+
+        // The client blocks once the socket stops reading its data.
+        let writer = std::thread::spawn(move || {
+            client.write_all(&vec![0x55; 2 * MAX_INPUT_BYTES]).ok();
+        });
+        for _ in 0..500 {
+            socket.poll();
+            if socket.input.len() == MAX_INPUT_BYTES {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(socket.input.len(), MAX_INPUT_BYTES);
+
+        // Taking the input makes room for the rest.
+        let mut received = socket.take_input().len();
+        for _ in 0..500 {
+            received += socket.take_input().len();
+            if received == 2 * MAX_INPUT_BYTES {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(received, 2 * MAX_INPUT_BYTES);
+        writer.join().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn only_stale_unix_sockets_are_replaced() {
+        let path = std::env::temp_dir().join(format!("rtt-socket-{}", std::process::id()));
+        let address = RttSocketAddress::Unix(path.clone());
+
+        // A socket which was left behind by a crashed process is replaced.
+        drop(UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+        let socket = RttSocket::bind(&address).unwrap();
+
+        // A socket which is still in use is left alone.
+        let error = RttSocket::bind(&address).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::AddrInUse);
+        assert!(path.exists());
+
+        drop(socket);
+        assert!(!path.exists());
+    }
+}
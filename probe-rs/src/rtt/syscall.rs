@@ -1,12 +1,38 @@
 use crate::SemihostingCommand;
 
-/// Decode a semihosting syscall. Only SYS_EXIT is supported at the moment
+/// Decode a semihosting syscall.
+///
+/// The I/O operations can be performed with a [`Semihosting`](crate::semihosting::Semihosting) instance.
 pub fn decode_semihosting_syscall(operation: u32, parameter: u32) -> SemihostingCommand {
     // This is defined by the ARM Semihosting Specification:
     // <https://github.com/ARM-software/abi-aa/blob/main/semihosting/semihosting.rst#semihosting-operations>
+    const SYS_OPEN: u32 = 0x01;
+    const SYS_CLOSE: u32 = 0x02;
+    const SYS_WRITEC: u32 = 0x03;
+    const SYS_WRITE0: u32 = 0x04;
+    const SYS_WRITE: u32 = 0x05;
+    const SYS_READ: u32 = 0x06;
+    const SYS_SEEK: u32 = 0x0A;
+    const SYS_FLEN: u32 = 0x0C;
+    const SYS_CLOCK: u32 = 0x10;
+    const SYS_TIME: u32 = 0x11;
+    const SYS_ERRNO: u32 = 0x13;
+    const SYS_GET_CMDLINE: u32 = 0x15;
     const SYS_EXIT: u32 = 0x18;
     const SYS_EXIT_ADP_STOPPED_APPLICATIONEXIT: u32 = 0x20026;
     match (operation, parameter) {
+        (SYS_OPEN, parameter) => SemihostingCommand::Open { parameter },
+        (SYS_CLOSE, parameter) => SemihostingCommand::Close { parameter },
+        (SYS_WRITEC, parameter) => SemihostingCommand::WriteC { parameter },
+        (SYS_WRITE0, parameter) => SemihostingCommand::Write0 { parameter },
+        (SYS_WRITE, parameter) => SemihostingCommand::Write { parameter },
+        (SYS_READ, parameter) => SemihostingCommand::Read { parameter },
+        (SYS_SEEK, parameter) => SemihostingCommand::Seek { parameter },
+        (SYS_FLEN, parameter) => SemihostingCommand::FileLen { parameter },
+        (SYS_CLOCK, _) => SemihostingCommand::Clock,
+        (SYS_TIME, _) => SemihostingCommand::Time,
+        (SYS_ERRNO, _) => SemihostingCommand::Errno,
+        (SYS_GET_CMDLINE, parameter) => SemihostingCommand::GetCommandLine { parameter },
         (SYS_EXIT, SYS_EXIT_ADP_STOPPED_APPLICATIONEXIT) => SemihostingCommand::ExitSuccess,
         (SYS_EXIT, code) => SemihostingCommand::ExitError { code: code as u64 },
         _ => {
@@ -0,0 +1,799 @@
+//! Host side implementation of the semihosting operations
+//!
+//! Semihosting allows the target to use the I/O facilities of the host, e.g. to print to the
+//! console or to access files, by halting on a special breakpoint. The operations requested by
+//! the target are reported as [`SemihostingCommand`]s, and are performed by a
+//! [`SemihostingHandler`].
+//!
+//! The operations are defined by the
+//! [Arm Semihosting Specification](https://github.com/ARM-software/abi-aa/blob/main/semihosting/semihosting.rst),
+//! which is also used by RISC-V.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::{Core, CoreRegister, Error, MemoryInterface, SemihostingCommand};
+
+/// The file modes of `SYS_OPEN`, in the order of their numbers.
+const OPEN_MODES: [&str; 12] = [
+    "r", "rb", "r+", "r+b", "w", "wb", "w+", "w+b", "a", "ab", "a+", "a+b",
+];
+
+/// The name of the console for `SYS_OPEN`.
+const CONSOLE_NAME: &str = ":tt";
+
+/// The longest file name accepted by `SYS_OPEN`, like `PATH_MAX` on Linux.
+const MAX_PATH_LEN: usize = 4096;
+
+/// The largest number of bytes transferred between the target and the host at once
+/// by `SYS_READ` and `SYS_WRITE`. Longer transfers are split into chunks of this size.
+const CHUNK_SIZE: usize = 0x10000;
+
+/// Performs the I/O requested by the target through semihosting.
+///
+/// All methods have a default implementation which reports the operation as unsupported,
+/// so an implementation only has to provide the operations it supports.
+pub trait SemihostingHandler: Send {
+    /// Writes `data` to the console of the host.
+    fn write_console(&mut self, _data: &[u8]) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    /// Opens the file `path` with the ISO C `fopen` mode `mode`, e.g. `"rb"`.
+    ///
+    /// The path `":tt"` refers to the console. Returns the handle of the opened file,
+    /// which must not be 0.
+    fn open(&mut self, _path: &str, _mode: &str) -> io::Result<u32> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    /// Closes the file `handle`.
+    fn close(&mut self, _handle: u32) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    /// Writes `data` to the file `handle`, and returns the number of bytes written.
+    fn write(&mut self, _handle: u32, _data: &[u8]) -> io::Result<usize> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    /// Reads from the file `handle` into `buffer`, and returns the number of bytes read.
+    ///
+    /// Returns 0 at the end of the file.
+    fn read(&mut self, _handle: u32, _buffer: &mut [u8]) -> io::Result<usize> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    /// Moves the position of the file `handle` to `position` bytes from its start.
+    fn seek(&mut self, _handle: u32, _position: u64) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    /// Returns the length of the file `handle` in bytes.
+    fn file_len(&mut self, _handle: u32) -> io::Result<u64> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    /// Returns the execution time of the target, if it is known.
+    fn clock(&mut self) -> Option<Duration> {
+        None
+    }
+
+    /// Returns the current time of the host.
+    fn time(&mut self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    /// Returns the command line passed to the target, including the program name.
+    fn command_line(&mut self) -> String {
+        String::new()
+    }
+}
+
+/// The handle of the console opened for reading.
+const STDIN_HANDLE: u32 = 1;
+/// The handle of the console opened for writing.
+const STDOUT_HANDLE: u32 = 2;
+/// The handle of the console opened for appending.
+const STDERR_HANDLE: u32 = 3;
+
+/// A [`SemihostingHandler`] which uses the console of the host process,
+/// and gives access to the files in a directory of the host.
+///
+/// Paths are resolved relative to the directory, and paths leaving it are rejected,
+/// also if they do so through a symbolic link. Without a directory, opening files is not
+/// allowed at all.
+pub struct DefaultSemihostingHandler {
+    root: Option<PathBuf>,
+    command_line: String,
+    console: Box<dyn Write + Send>,
+    files: HashMap<u32, File>,
+    next_handle: u32,
+    start: Instant,
+}
+
+impl std::fmt::Debug for DefaultSemihostingHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DefaultSemihostingHandler")
+            .field("root", &self.root)
+            .field("command_line", &self.command_line)
+            .field("files", &self.files)
+            .finish()
+    }
+}
+
+impl Default for DefaultSemihostingHandler {
+    fn default() -> Self {
+        Self {
+            root: None,
+            command_line: String::new(),
+            console: Box::new(io::stdout()),
+            files: HashMap::new(),
+            next_handle: STDERR_HANDLE + 1,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl DefaultSemihostingHandler {
+    /// Creates a handler which does not give access to any files.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gives the target access to the files in `root`.
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Sets the command line returned to the target.
+    pub fn with_command_line(mut self, command_line: impl Into<String>) -> Self {
+        self.command_line = command_line.into();
+        self
+    }
+
+    /// Sets where the console output of the target is written to, instead of stdout.
+    pub fn with_console(mut self, console: impl Write + Send + 'static) -> Self {
+        self.console = Box::new(console);
+        self
+    }
+
+    fn resolve(&self, path: &str) -> io::Result<PathBuf> {
+        let Some(root) = &self.root else {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "no semihosting directory configured",
+            ));
+        };
+
+        let path = Path::new(path);
+        if !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{} is outside the semihosting directory", path.display()),
+            ));
+        }
+
+        // A symbolic link inside the directory can still point outside of it, so the
+        // path is only accepted if it stays in the directory once the links are resolved.
+        // Files which are about to be created don't exist yet, so their parent is resolved.
+        let root = root.canonicalize()?;
+        let full_path = root.join(path);
+        let resolved = match full_path.symlink_metadata() {
+            Ok(_) => full_path.canonicalize()?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                match (full_path.parent(), full_path.file_name()) {
+                    (Some(parent), Some(name)) => parent.canonicalize()?.join(name),
+                    _ => return Err(error),
+                }
+            }
+            Err(error) => return Err(error),
+        };
+
+        if !resolved.starts_with(&root) {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{} is outside the semihosting directory", path.display()),
+            ));
+        }
+
+        Ok(resolved)
+    }
+
+    fn file(&mut self, handle: u32) -> io::Result<&mut File> {
+        self.files
+            .get_mut(&handle)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "invalid file handle"))
+    }
+}
+
+impl SemihostingHandler for DefaultSemihostingHandler {
+    fn write_console(&mut self, data: &[u8]) -> io::Result<()> {
+        self.console.write_all(data)?;
+        self.console.flush()
+    }
+
+    fn open(&mut self, path: &str, mode: &str) -> io::Result<u32> {
+        if path == CONSOLE_NAME {
+            return Ok(match mode.as_bytes()[0] {
+                b'r' => STDIN_HANDLE,
+                b'w' => STDOUT_HANDLE,
+                _ => STDERR_HANDLE,
+            });
+        }
+
+        let path = self.resolve(path)?;
+        let mut options = OpenOptions::new();
+        match mode.trim_end_matches('b') {
+            "r" => options.read(true),
+            "r+" => options.read(true).write(true),
+            "w" => options.write(true).create(true).truncate(true),
+            "w+" => options.read(true).write(true).create(true).truncate(true),
+            "a" => options.append(true).create(true),
+            _ => options.read(true).append(true).create(true),
+        };
+
+        let file = options.open(path)?;
+        let handle = self.next_handle;
+        self.next_handle += 1;
+        self.files.insert(handle, file);
+
+        Ok(handle)
+    }
+
+    fn close(&mut self, handle: u32) -> io::Result<()> {
+        if (STDIN_HANDLE..=STDERR_HANDLE).contains(&handle) {
+            return Ok(());
+        }
+
+        self.files
+            .remove(&handle)
+            .map(drop)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "invalid file handle"))
+    }
+
+    fn write(&mut self, handle: u32, data: &[u8]) -> io::Result<usize> {
+        match handle {
+            STDOUT_HANDLE | STDERR_HANDLE => self.write_console(data).map(|()| data.len()),
+            handle => self.file(handle)?.write(data),
+        }
+    }
+
+    fn read(&mut self, handle: u32, buffer: &mut [u8]) -> io::Result<usize> {
+        match handle {
+            STDIN_HANDLE => io::stdin().read(buffer),
+            handle => self.file(handle)?.read(buffer),
+        }
+    }
+
+    fn seek(&mut self, handle: u32, position: u64) -> io::Result<()> {
+        self.file(handle)?.seek(SeekFrom::Start(position))?;
+        Ok(())
+    }
+
+    fn file_len(&mut self, handle: u32) -> io::Result<u64> {
+        Ok(self.file(handle)?.metadata()?.len())
+    }
+
+    fn clock(&mut self) -> Option<Duration> {
+        Some(self.start.elapsed())
+    }
+
+    fn command_line(&mut self) -> String {
+        self.command_line.clone()
+    }
+}
+
+/// Performs the semihosting operations requested by a target with a [`SemihostingHandler`].
+#[derive(Debug)]
+pub struct Semihosting<H> {
+    handler: H,
+    /// The error of the last failed operation, reported by `SYS_ERRNO`.
+    errno: i32,
+}
+
+impl<H: SemihostingHandler> Semihosting<H> {
+    /// Creates a new instance performing the operations with `handler`.
+    pub fn new(handler: H) -> Self {
+        Self { handler, errno: 0 }
+    }
+
+    /// Returns the handler performing the operations.
+    pub fn handler_mut(&mut self) -> &mut H {
+        &mut self.handler
+    }
+
+    /// Performs the operation `command`, which the halted `core` requested,
+    /// and writes its result to the core.
+    ///
+    /// Returns `false` if the command is not an I/O operation, i.e. it is one of the exit
+    /// commands or unknown, so the caller has to handle it. Otherwise, the core can be resumed
+    /// afterwards.
+    pub fn handle(
+        &mut self,
+        core: &mut Core<'_>,
+        command: SemihostingCommand,
+    ) -> Result<bool, Error> {
+        let result = match command {
+            SemihostingCommand::ExitSuccess
+            | SemihostingCommand::ExitError { .. }
+            | SemihostingCommand::Unknown { .. } => return Ok(false),
+            SemihostingCommand::Open { parameter } => {
+                let [name, mode, len] = read_arguments(core, parameter)?;
+                if len as usize > MAX_PATH_LEN {
+                    self.errno = ENAMETOOLONG;
+                    -1
+                } else {
+                    let mut name_buffer = vec![0; len as usize];
+                    core.read_8(name, &mut name_buffer)?;
+                    let name = String::from_utf8_lossy(&name_buffer);
+
+                    let result = match OPEN_MODES.get(mode as usize) {
+                        Some(mode) => self.handler.open(&name, mode).map(|handle| handle as i32),
+                        None => Err(io::ErrorKind::InvalidInput.into()),
+                    };
+                    self.result(result)
+                }
+            }
+            SemihostingCommand::Close { parameter } => {
+                let [handle] = read_arguments(core, parameter)?;
+                let result = self.handler.close(handle as u32).map(|()| 0);
+                self.result(result)
+            }
+            SemihostingCommand::WriteC { parameter } => {
+                let mut character = [0];
+                core.read_8(parameter as u64, &mut character)?;
+                self.console(&character);
+                return Ok(true);
+            }
+            SemihostingCommand::Write0 { parameter } => {
+                let string = read_c_string(core, parameter as u64)?;
+                self.console(&string);
+                return Ok(true);
+            }
+            SemihostingCommand::Write { parameter } => {
+                let [handle, buffer, len] = read_arguments(core, parameter)?;
+                let mut data = vec![0; (len as usize).min(CHUNK_SIZE)];
+                let mut written = 0;
+
+                while written < len {
+                    let chunk = &mut data[..(len - written).min(CHUNK_SIZE as u64) as usize];
+                    core.read_8(buffer + written, chunk)?;
+
+                    match self.handler.write(handle as u32, chunk) {
+                        Ok(count) => {
+                            written += count as u64;
+                            if count < chunk.len() {
+                                break;
+                            }
+                        }
+                        Err(error) => {
+                            self.set_errno(&error);
+                            break;
+                        }
+                    }
+                }
+
+                // The number of bytes which were not written is returned.
+                (len - written) as i32
+            }
+            SemihostingCommand::Read { parameter } => {
+                let [handle, buffer, len] = read_arguments(core, parameter)?;
+                let mut data = vec![0; (len as usize).min(CHUNK_SIZE)];
+                let mut read = 0;
+
+                while read < len {
+                    let chunk = &mut data[..(len - read).min(CHUNK_SIZE as u64) as usize];
+
+                    match self.handler.read(handle as u32, chunk) {
+                        Ok(count) => {
+                            core.write_8(buffer + read, &chunk[..count])?;
+                            read += count as u64;
+                            // Stop at the end of the file, or when no more input is available.
+                            if count < chunk.len() {
+                                break;
+                            }
+                        }
+                        Err(error) => {
+                            self.set_errno(&error);
+                            break;
+                        }
+                    }
+                }
+
+                // The number of bytes which were not read is returned.
+                (len - read) as i32
+            }
+            SemihostingCommand::Seek { parameter } => {
+                let [handle, position] = read_arguments(core, parameter)?;
+                let result = self.handler.seek(handle as u32, position).map(|()| 0);
+                self.result(result)
+            }
+            SemihostingCommand::FileLen { parameter } => {
+                let [handle] = read_arguments(core, parameter)?;
+                let result = self.handler.file_len(handle as u32).map(|len| len as i32);
+                self.result(result)
+            }
+            SemihostingCommand::Clock => match self.handler.clock() {
+                Some(clock) => (clock.as_millis() / 10) as i32,
+                None => -1,
+            },
+            SemihostingCommand::Time => {
+                let time = self.handler.time();
+                time.duration_since(SystemTime::UNIX_EPOCH)
+                    .map_or(0, |time| time.as_secs() as i32)
+            }
+            SemihostingCommand::Errno => self.errno,
+            SemihostingCommand::GetCommandLine { parameter } => {
+                let [buffer, len] = read_arguments(core, parameter)?;
+                let mut command_line = self.handler.command_line().into_bytes();
+                command_line.push(0);
+
+                if command_line.len() as u64 > len {
+                    self.errno = ERANGE;
+                    -1
+                } else {
+                    // The length is returned in the second word of the argument block.
+                    let length = command_line.len() as u64 - 1;
+                    core.write_8(buffer, &command_line)?;
+                    if is_64_bit(core) {
+                        core.write_word_64(parameter as u64 + 8, length)?;
+                    } else {
+                        core.write_word_32(parameter as u64 + 4, length as u32)?;
+                    }
+                    0
+                }
+            }
+        };
+
+        let return_register = return_register(core).id();
+        if is_64_bit(core) {
+            core.write_core_reg(return_register, result as i64 as u64)?;
+        } else {
+            core.write_core_reg(return_register, result as u32)?;
+        }
+
+        Ok(true)
+    }
+
+    fn console(&mut self, data: &[u8]) {
+        if let Err(error) = self.handler.write_console(data) {
+            tracing::warn!("Failed to write semihosting console output: {error}");
+        }
+    }
+
+    /// Converts the result of an operation to the value returned to the target,
+    /// which is -1 if the operation failed.
+    fn result(&mut self, result: io::Result<i32>) -> i32 {
+        result.unwrap_or_else(|error| {
+            self.set_errno(&error);
+            -1
+        })
+    }
+
+    fn set_errno(&mut self, error: &io::Error) {
+        tracing::debug!("Semihosting operation failed: {error}");
+        self.errno = error.raw_os_error().unwrap_or(match error.kind() {
+            io::ErrorKind::NotFound => ENOENT,
+            io::ErrorKind::PermissionDenied => EACCES,
+            io::ErrorKind::InvalidInput => EINVAL,
+            _ => EIO,
+        });
+    }
+}
+
+const ENOENT: i32 = 2;
+const EIO: i32 = 5;
+const EACCES: i32 = 13;
+const EINVAL: i32 = 22;
+const ERANGE: i32 = 34;
+const ENAMETOOLONG: i32 = 36;
+
+/// Returns the register receiving the return value of an operation.
+fn return_register(core: &Core<'_>) -> &'static CoreRegister {
+    core.registers()
+        .get_argument_register(0)
+        .expect("Semihosting requires a register for the return value")
+}
+
+/// Returns true if the words of the semihosting operations have 64 bits,
+/// i.e. the registers of the core have 64 bits, like on RV64 harts.
+fn is_64_bit(core: &Core<'_>) -> bool {
+    return_register(core).size_in_bits() == 64
+}
+
+/// Reads the argument block of an operation, which consists of `N` words as wide as
+/// the registers of the core.
+fn read_arguments<const N: usize>(core: &mut Core<'_>, address: u32) -> Result<[u64; N], Error> {
+    let mut arguments = [0; N];
+    if is_64_bit(core) {
+        core.read_64(address as u64, &mut arguments)?;
+    } else {
+        let mut words = [0; N];
+        core.read_32(address as u64, &mut words)?;
+        for (argument, word) in arguments.iter_mut().zip(words) {
+            *argument = word as u64;
+        }
+    }
+    Ok(arguments)
+}
+
+/// Reads a null-terminated string starting at `address`.
+fn read_c_string(core: &mut Core<'_>, mut address: u64) -> Result<Vec<u8>, Error> {
+    let mut string = Vec::new();
+    let mut chunk = [0; 64];
+
+    loop {
+        // Don't read across a 64 byte boundary, so we never read past the end of the memory region.
+        let len = 64 - (address & 63) as usize;
+        core.read_8(address, &mut chunk[..len])?;
+
+        match chunk[..len].iter().position(|&byte| byte == 0) {
+            Some(end) => {
+                string.extend_from_slice(&chunk[..end]);
+                return Ok(string);
+            }
+            None => string.extend_from_slice(&chunk[..len]),
+        }
+        address += len as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::SoftwareBreakpoints;
+    use crate::test::MockCore;
+
+    /// Creates an empty directory for a test, which is removed again by the test.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "probe-rs-semihosting-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn paths_stay_in_root() {
+        let root = test_dir("paths");
+        let handler = DefaultSemihostingHandler::new().with_root(&root);
+        std::fs::create_dir(root.join("vectors")).unwrap();
+
+        assert_eq!(
+            handler.resolve("vectors/input.bin").unwrap(),
+            root.canonicalize().unwrap().join("vectors/input.bin")
+        );
+        assert!(handler.resolve("../secret").is_err());
+        assert!(handler.resolve("/etc/passwd").is_err());
+        assert!(handler.resolve("missing/input.bin").is_err());
+        assert!(DefaultSemihostingHandler::new()
+            .resolve("input.bin")
+            .is_err());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_stay_in_root() {
+        let root = test_dir("symlinks");
+        let outside = test_dir("symlinks-outside");
+        std::fs::write(outside.join("secret"), b"secret").unwrap();
+        std::fs::write(root.join("input.bin"), b"input").unwrap();
+
+        std::os::unix::fs::symlink(&outside, root.join("outside")).unwrap();
+        std::os::unix::fs::symlink(outside.join("secret"), root.join("secret")).unwrap();
+        std::os::unix::fs::symlink(outside.join("new"), root.join("dangling")).unwrap();
+        std::os::unix::fs::symlink("input.bin", root.join("inside")).unwrap();
+
+        let mut handler = DefaultSemihostingHandler::new().with_root(&root);
+        for path in ["outside/secret", "outside/new", "secret", "dangling"] {
+            assert!(handler.open(path, "r+").is_err(), "{path} was opened");
+            assert!(handler.open(path, "w").is_err(), "{path} was created");
+        }
+        assert!(!outside.join("new").exists());
+
+        // Links which stay in the directory are fine.
+        let handle = handler.open("inside", "rb").unwrap();
+        let mut buffer = [0; 8];
+        assert_eq!(handler.read(handle, &mut buffer).unwrap(), 5);
+
+        std::fs::remove_dir_all(root).unwrap();
+        std::fs::remove_dir_all(outside).unwrap();
+    }
+
+    #[test]
+    fn file_io() {
+        let root = test_dir("file-io");
+        let mut handler = DefaultSemihostingHandler::new().with_root(&root);
+
+        let handle = handler.open("test.txt", "w+b").unwrap();
+        assert_eq!(handler.write(handle, b"hello").unwrap(), 5);
+        assert_eq!(handler.file_len(handle).unwrap(), 5);
+        handler.seek(handle, 1).unwrap();
+
+        let mut buffer = [0; 8];
+        assert_eq!(handler.read(handle, &mut buffer).unwrap(), 4);
+        assert_eq!(&buffer[..4], b"ello");
+        handler.close(handle).unwrap();
+        assert!(handler.close(handle).is_err());
+
+        assert_eq!(handler.open(CONSOLE_NAME, "w").unwrap(), STDOUT_HANDLE);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    const RAM: u64 = 0x2000_0000;
+    /// The address of the argument block of the operations.
+    const ARGUMENTS: u32 = RAM as u32;
+    /// The address of the data passed to and from the operations.
+    const DATA: u64 = RAM + 0x100;
+
+    /// Performs `command` with the argument block `arguments`, and returns the value
+    /// returned to the target.
+    fn call(
+        semihosting: &mut Semihosting<DefaultSemihostingHandler>,
+        core: &mut Core<'_>,
+        command: SemihostingCommand,
+        arguments: &[u32],
+    ) -> i32 {
+        core.write_32(ARGUMENTS as u64, arguments).unwrap();
+        assert!(semihosting.handle(core, command).unwrap());
+
+        let result: u32 = core.read_core_reg(return_register(core).id()).unwrap();
+        result as i32
+    }
+
+    #[test]
+    fn handle_file_operations() {
+        let root = test_dir("handle");
+        let mut semihosting = Semihosting::new(DefaultSemihostingHandler::new().with_root(&root));
+        let mut software_breakpoints = SoftwareBreakpoints::default();
+        let mut core = Core::new(&mut software_breakpoints, MockCore::new(RAM, 0x200, 2));
+        let parameter = ARGUMENTS;
+
+        // Create the file with mode "wb", and write to it.
+        core.write_8(DATA, b"output.bin").unwrap();
+        let handle = call(
+            &mut semihosting,
+            &mut core,
+            SemihostingCommand::Open { parameter },
+            &[DATA as u32, 5, 10],
+        );
+        assert!(handle > 0);
+
+        core.write_8(DATA, b"hello").unwrap();
+        let not_written = call(
+            &mut semihosting,
+            &mut core,
+            SemihostingCommand::Write { parameter },
+            &[handle as u32, DATA as u32, 5],
+        );
+        assert_eq!(not_written, 0);
+        assert_eq!(
+            call(
+                &mut semihosting,
+                &mut core,
+                SemihostingCommand::Close { parameter },
+                &[handle as u32],
+            ),
+            0
+        );
+        assert_eq!(std::fs::read(root.join("output.bin")).unwrap(), b"hello");
+
+        // Open it again with mode "rb", and read more than it contains.
+        core.write_8(DATA, b"output.bin").unwrap();
+        let handle = call(
+            &mut semihosting,
+            &mut core,
+            SemihostingCommand::Open { parameter },
+            &[DATA as u32, 1, 10],
+        );
+        assert!(handle > 0);
+
+        core.write_8(DATA, &[0; 8]).unwrap();
+        let not_read = call(
+            &mut semihosting,
+            &mut core,
+            SemihostingCommand::Read { parameter },
+            &[handle as u32, DATA as u32, 8],
+        );
+        assert_eq!(not_read, 3);
+        let mut data = [0; 8];
+        core.read_8(DATA, &mut data).unwrap();
+        assert_eq!(&data, b"hello\0\0\0");
+
+        // A closed handle can't be closed again.
+        for expected in [0, -1] {
+            let result = call(
+                &mut semihosting,
+                &mut core,
+                SemihostingCommand::Close { parameter },
+                &[handle as u32],
+            );
+            assert_eq!(result, expected);
+        }
+        assert_eq!(
+            call(&mut semihosting, &mut core, SemihostingCommand::Errno, &[]),
+            ENOENT
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn handle_rejects_long_names() {
+        let mut semihosting = Semihosting::new(DefaultSemihostingHandler::new());
+        let mut software_breakpoints = SoftwareBreakpoints::default();
+        let mut core = Core::new(&mut software_breakpoints, MockCore::new(RAM, 0x200, 2));
+
+        // The name is not read at all, so it doesn't matter that it is not in memory.
+        let result = call(
+            &mut semihosting,
+            &mut core,
+            SemihostingCommand::Open {
+                parameter: ARGUMENTS,
+            },
+            &[DATA as u32, 0, u32::MAX],
+        );
+        assert_eq!(result, -1);
+        assert_eq!(
+            call(&mut semihosting, &mut core, SemihostingCommand::Errno, &[]),
+            ENAMETOOLONG
+        );
+    }
+
+    #[test]
+    fn handle_leaves_other_operations_to_the_caller() {
+        let mut semihosting = Semihosting::new(DefaultSemihostingHandler::new());
+        let mut software_breakpoints = SoftwareBreakpoints::default();
+        let mut core = Core::new(&mut software_breakpoints, MockCore::new(RAM, 0x200, 2));
+
+        for command in [
+            SemihostingCommand::Unknown { operation: 0x1234 },
+            SemihostingCommand::ExitSuccess,
+        ] {
+            assert!(!semihosting.handle(&mut core, command).unwrap());
+        }
+    }
+
+    #[test]
+    fn handle_uses_64_bit_words_on_rv64() {
+        let mut semihosting =
+            Semihosting::new(DefaultSemihostingHandler::new().with_command_line("test -v"));
+        let mut software_breakpoints = SoftwareBreakpoints::default();
+        let mut mock_core = MockCore::new(RAM, 0x200, 2);
+        mock_core.rv64 = true;
+        let mut core = Core::new(&mut software_breakpoints, mock_core);
+
+        core.write_64(ARGUMENTS as u64, &[DATA, 0x40]).unwrap();
+        let command = SemihostingCommand::GetCommandLine {
+            parameter: ARGUMENTS,
+        };
+        assert!(semihosting.handle(&mut core, command).unwrap());
+
+        let result: u64 = core.read_core_reg(return_register(&core).id()).unwrap();
+        assert_eq!(result, 0);
+        assert_eq!(core.read_word_64(ARGUMENTS as u64 + 8).unwrap(), 7);
+        let mut command_line = [0; 8];
+        core.read_8(DATA, &mut command_line).unwrap();
+        assert_eq!(&command_line, b"test -v\0");
+
+        // Errors are returned as -1, extended to the width of the register.
+        core.write_64(ARGUMENTS as u64, &[DATA, 4]).unwrap();
+        assert!(semihosting.handle(&mut core, command).unwrap());
+        let result: u64 = core.read_core_reg(return_register(&core).id()).unwrap();
+        assert_eq!(result, u64::MAX);
+    }
+}
@@ -5,7 +5,7 @@ use crate::architecture::riscv::communication_interface::RiscvError;
 use crate::architecture::xtensa::communication_interface::{
     XtensaCommunicationInterface, XtensaError,
 };
-use crate::config::{ChipInfo, CoreExt, RegistryError, Target, TargetSelector};
+use crate::config::{ChipInfo, CoreExt, MemoryRegion, RegistryError, Target, TargetSelector};
 use crate::core::{Architecture, CombinedCoreState};
 use crate::probe::fake_probe::FakeProbe;
 use crate::{
@@ -19,6 +19,7 @@ use crate::{
     config::DebugSequence,
 };
 use crate::{AttachMethod, Core, CoreType, Error, Lister, Probe};
+use probe_rs_target::{CoreAccessOptions, RiscvCoreAccessOptions};
 use std::ops::DerefMut;
 use std::{fmt, sync::Arc, time::Duration};
 
@@ -46,6 +47,7 @@ pub struct Session {
     interface: ArchitectureInterface,
     cores: Vec<CombinedCoreState>,
     configured_trace_sink: Option<TraceSink>,
+    permissions: Permissions,
 }
 
 pub(crate) enum ArchitectureInterface {
@@ -228,6 +230,7 @@ impl Session {
                 interface: ArchitectureInterface::Arm(interface),
                 cores,
                 configured_trace_sink: None,
+                permissions,
             };
 
             {
@@ -251,16 +254,17 @@ impl Session {
                 interface: ArchitectureInterface::Arm(interface),
                 cores,
                 configured_trace_sink: None,
+                permissions,
             })
         }
     }
 
     fn attach_riscv(
         mut probe: Probe,
-        target: Target,
+        mut target: Target,
         _attach_method: AttachMethod,
-        _permissions: Permissions,
-        cores: Vec<CombinedCoreState>,
+        permissions: Permissions,
+        mut cores: Vec<CombinedCoreState>,
     ) -> Result<Self, Error> {
         // TODO: Handle attach under reset
 
@@ -275,20 +279,37 @@ impl Session {
 
         probe.attach_to_unspecified()?;
 
-        let interface = probe
+        let mut interface = probe
             .try_into_riscv_interface()
             .map_err(|(_probe, err)| err)?;
 
+        add_riscv_harts(&mut target, interface.num_harts());
+        if riscv_halt_group(&target) && !interface.set_halt_group(true)? {
+            tracing::warn!(
+                "The debug module does not support halt groups, the harts will halt independently."
+            );
+        }
+        for id in cores.len()..target.cores.len() {
+            let core = &target.cores[id];
+            cores.push(Core::create_state(
+                id,
+                core.core_access_options.clone(),
+                &target,
+                core.core_type,
+            ));
+        }
+
         let mut session = Session {
             target,
             interface: ArchitectureInterface::Riscv(Box::new(interface)),
             cores,
             configured_trace_sink: None,
+            permissions,
         };
 
-        {
-            // Todo: Add multicore support. How to deal with any cores that are not active and won't respond?
-            let mut core = session.core(0)?;
+        // Todo: How to deal with any harts that are not active and won't respond?
+        for core_index in 0..session.cores.len() {
+            let mut core = session.core(core_index)?;
 
             core.halt(Duration::from_millis(100))?;
         }
@@ -302,7 +323,7 @@ impl Session {
         mut probe: Probe,
         target: Target,
         _attach_method: AttachMethod,
-        _permissions: Permissions,
+        permissions: Permissions,
         cores: Vec<CombinedCoreState>,
     ) -> Result<Self, Error> {
         let sequence_handle = match &target.debug_sequence {
@@ -325,6 +346,7 @@ impl Session {
             interface: ArchitectureInterface::Xtensa(Box::new(interface)),
             cores,
             configured_trace_sink: None,
+            permissions,
         };
 
         {
@@ -569,6 +591,11 @@ impl Session {
         &self.target
     }
 
+    /// Returns the permissions the session was created with.
+    pub fn permissions(&self) -> &Permissions {
+        &self.permissions
+    }
+
     /// Configure the target and probe for serial wire view (SWV) tracing.
     pub fn setup_tracing(
         &mut self,
@@ -648,6 +675,55 @@ impl Session {
     }
 }
 
+/// Adds a core for each hart of the debug module, if the target only describes a single core.
+///
+/// The added cores are copies of the described core, which share its memory regions.
+fn add_riscv_harts(target: &mut Target, num_harts: u32) {
+    let [core] = target.cores.as_slice() else {
+        return;
+    };
+    let core = core.clone();
+    let options = match &core.core_access_options {
+        CoreAccessOptions::Riscv(options) => options.clone(),
+        _ => return,
+    };
+    let described_hart = options.hart_id.unwrap_or(0);
+
+    for hart in (0..num_harts).filter(|hart| *hart != described_hart) {
+        let name = format!("{}_hart{hart}", core.name);
+
+        for region in &mut target.memory_map {
+            let cores = match region {
+                MemoryRegion::Ram(region) => &mut region.cores,
+                MemoryRegion::Generic(region) => &mut region.cores,
+                MemoryRegion::Nvm(region) => &mut region.cores,
+            };
+            if cores.contains(&core.name) {
+                cores.push(name.clone());
+            }
+        }
+
+        target.cores.push(probe_rs_target::Core {
+            name,
+            core_type: core.core_type,
+            core_access_options: CoreAccessOptions::Riscv(RiscvCoreAccessOptions {
+                hart_id: Some(hart),
+                ..options.clone()
+            }),
+        });
+    }
+}
+
+/// Returns true if the harts of the target should be put into one halt group.
+fn riscv_halt_group(target: &Target) -> bool {
+    target.cores.iter().any(|core| {
+        matches!(
+            &core.core_access_options,
+            CoreAccessOptions::Riscv(options) if options.halt_group
+        )
+    })
+}
+
 // This test ensures that [Session] is fully [Send] + [Sync].
 static_assertions::assert_impl_all!(Session: Send);
 
@@ -655,6 +731,13 @@ static_assertions::assert_impl_all!(Session: Send);
 impl Drop for Session {
     #[tracing::instrument(name = "session_drop", skip(self))]
     fn drop(&mut self) {
+        if let Err(err) = { 0..self.cores.len() }.try_for_each(|i| {
+            self.core(i)
+                .and_then(|mut core| core.clear_all_sw_breakpoints())
+        }) {
+            tracing::warn!("Could not clear all software breakpoints: {:?}", err);
+        }
+
         if let Err(err) = { 0..self.cores.len() }.try_for_each(|i| {
             self.core(i)
                 .and_then(|mut core| core.clear_all_hw_breakpoints())
@@ -662,6 +745,13 @@ impl Drop for Session {
             tracing::warn!("Could not clear all hardware breakpoints: {:?}", err);
         }
 
+        if let Err(err) = { 0..self.cores.len() }.try_for_each(|i| {
+            self.core(i)
+                .and_then(|mut core| core.clear_all_watchpoints())
+        }) {
+            tracing::warn!("Could not clear all watchpoints: {:?}", err);
+        }
+
         // Call any necessary deconfiguration/shutdown hooks.
         if let Err(err) = { 0..self.cores.len() }
             .try_for_each(|i| self.core(i).and_then(|mut core| core.debug_core_stop()))
@@ -774,6 +864,8 @@ fn get_target_from_selector(
 pub struct Permissions {
     /// When set to true, all memory of the chip may be erased or reset to factory default
     erase_all: bool,
+    /// When set to true, configuration fields marked as dangerous may be written
+    dangerous_config: bool,
 }
 
 impl Permissions {
@@ -802,8 +894,80 @@ impl Permissions {
             Err(MissingPermissions("erase_all".into()))
         }
     }
+
+    /// Allow the session to write configuration fields which are marked as dangerous,
+    /// like read-out protection bits or one-time programmable fuses.
+    ///
+    /// # Warning
+    /// Writing such fields may be irreversible and can render the device unusable or permanently
+    /// disable the debug access.
+    #[must_use]
+    pub fn allow_dangerous_config(self) -> Self {
+        Self {
+            dangerous_config: true,
+            ..self
+        }
+    }
+
+    pub(crate) fn dangerous_config(&self) -> Result<(), MissingPermissions> {
+        if self.dangerous_config {
+            Ok(())
+        } else {
+            Err(MissingPermissions("dangerous_config".into()))
+        }
+    }
 }
 
 #[derive(Debug, Clone, thiserror::Error)]
 #[error("An operation could not be performed because it lacked the permission to do so: {0}")]
 pub struct MissingPermissions(pub String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn riscv_harts_are_added_as_cores() {
+        let mut target = crate::config::get_target_by_name("esp32c3").unwrap();
+        let name = target.cores[0].name.clone();
+
+        add_riscv_harts(&mut target, 2);
+
+        assert_eq!(target.cores.len(), 2);
+        assert_eq!(target.cores[1].name, format!("{name}_hart1"));
+        assert!(matches!(
+            target.cores[1].core_access_options,
+            CoreAccessOptions::Riscv(RiscvCoreAccessOptions {
+                hart_id: Some(1),
+                halt_group: false
+            })
+        ));
+        assert!(!riscv_halt_group(&target));
+        assert!(target
+            .memory_map
+            .iter()
+            .filter(|region| region.cores().contains(&name))
+            .all(|region| region.cores().contains(&target.cores[1].name)));
+
+        // Targets describing multiple cores are left unchanged.
+        add_riscv_harts(&mut target, 4);
+        assert_eq!(target.cores.len(), 2);
+    }
+
+    #[test]
+    fn riscv_halt_group_is_copied_to_harts() {
+        let mut target = crate::config::get_target_by_name("esp32c3").unwrap();
+        let CoreAccessOptions::Riscv(options) = &mut target.cores[0].core_access_options else {
+            panic!("The ESP32-C3 has a RISC-V core");
+        };
+        options.halt_group = true;
+
+        add_riscv_harts(&mut target, 2);
+
+        assert!(riscv_halt_group(&target));
+        assert!(target.cores.iter().all(|core| matches!(
+            &core.core_access_options,
+            CoreAccessOptions::Riscv(options) if options.halt_group
+        )));
+    }
+}
@@ -1,6 +1,18 @@
 //! Helpers for testing the crate
 
-use crate::MemoryInterface;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::{
+    architecture::riscv::registers::{PC, RA, RISCV64_CORE_REGSISTERS, RISCV_CORE_REGSISTERS, SP},
+    core::{
+        registers::{CoreRegister, CoreRegisters, RegisterId, RegisterValue},
+        CoreInformation, CoreInterface, Watchpoint, WatchpointKind,
+    },
+    Architecture, CoreStatus, CoreType, HaltReason, InstructionSet, MemoryInterface,
+};
 
 #[derive(Debug)]
 pub(crate) struct MockMemory {
@@ -185,6 +197,360 @@ impl MemoryInterface for MockMemory {
     }
 }
 
+/// What a unit of [`MockCore`] is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MockUnit {
+    Breakpoint(u64),
+    Watchpoint(Watchpoint),
+}
+
+/// A halted RISC-V core with a single RAM region, which can only step.
+///
+/// Like the triggers of RISC-V cores, its units are shared by hardware breakpoints and watchpoints.
+/// Breakpoint units are mapped to the units starting with the first one, and watchpoint units to
+/// the units starting with the last one.
+#[derive(Debug)]
+pub(crate) struct MockCore {
+    pub(crate) ram_start: u64,
+    pub(crate) ram: Vec<u8>,
+    pub(crate) pc: u64,
+    /// The registers other than the PC, which read as 0 until they are written.
+    pub(crate) registers: HashMap<RegisterId, RegisterValue>,
+    /// Whether the core is a 64-bit (RV64) hart.
+    pub(crate) rv64: bool,
+    pub(crate) units: Vec<Option<MockUnit>>,
+    /// The address ranges passed to [`CoreInterface::invalidate_instruction_cache`], shared
+    /// so they can be inspected after the core was moved into a [`crate::Core`].
+    pub(crate) invalidated: Rc<RefCell<Vec<(u64, u64)>>>,
+}
+
+impl MockCore {
+    pub(crate) fn new(ram_start: u64, ram_size: usize, units: usize) -> Self {
+        Self {
+            ram_start,
+            ram: vec![0; ram_size],
+            pc: ram_start,
+            registers: HashMap::new(),
+            rv64: false,
+            units: vec![None; units],
+            invalidated: Rc::default(),
+        }
+    }
+
+    fn ram_range(&self, address: u64, len: usize) -> std::ops::Range<usize> {
+        let start = (address - self.ram_start) as usize;
+        start..start + len
+    }
+
+    fn watchpoint_unit(&self, unit_index: usize) -> usize {
+        self.units.len() - 1 - unit_index
+    }
+}
+
+impl MemoryInterface for MockCore {
+    fn supports_native_64bit_access(&mut self) -> bool {
+        false
+    }
+
+    fn read_word_64(&mut self, address: u64) -> Result<u64, crate::Error> {
+        let mut bytes = [0u8; 8];
+        self.read_8(address, &mut bytes)?;
+
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn read_word_32(&mut self, address: u64) -> Result<u32, crate::Error> {
+        let mut bytes = [0u8; 4];
+        self.read_8(address, &mut bytes)?;
+
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn read_word_8(&mut self, address: u64) -> Result<u8, crate::Error> {
+        Ok(self.ram[self.ram_range(address, 1)][0])
+    }
+
+    fn read_64(&mut self, address: u64, data: &mut [u64]) -> Result<(), crate::Error> {
+        for (word, address) in data.iter_mut().zip((address..).step_by(8)) {
+            *word = self.read_word_64(address)?;
+        }
+        Ok(())
+    }
+
+    fn read_32(&mut self, address: u64, data: &mut [u32]) -> Result<(), crate::Error> {
+        for (word, address) in data.iter_mut().zip((address..).step_by(4)) {
+            *word = self.read_word_32(address)?;
+        }
+        Ok(())
+    }
+
+    fn read_8(&mut self, address: u64, data: &mut [u8]) -> Result<(), crate::Error> {
+        data.copy_from_slice(&self.ram[self.ram_range(address, data.len())]);
+        Ok(())
+    }
+
+    fn supports_8bit_transfers(&self) -> Result<bool, crate::Error> {
+        Ok(true)
+    }
+
+    fn write_word_64(&mut self, address: u64, data: u64) -> Result<(), crate::Error> {
+        self.write_8(address, &data.to_le_bytes())
+    }
+
+    fn write_word_32(&mut self, address: u64, data: u32) -> Result<(), crate::Error> {
+        self.write_8(address, &data.to_le_bytes())
+    }
+
+    fn write_word_8(&mut self, address: u64, data: u8) -> Result<(), crate::Error> {
+        self.write_8(address, &[data])
+    }
+
+    fn write_64(&mut self, address: u64, data: &[u64]) -> Result<(), crate::Error> {
+        for (word, address) in data.iter().zip((address..).step_by(8)) {
+            self.write_word_64(address, *word)?;
+        }
+        Ok(())
+    }
+
+    fn write_32(&mut self, address: u64, data: &[u32]) -> Result<(), crate::Error> {
+        for (word, address) in data.iter().zip((address..).step_by(4)) {
+            self.write_word_32(address, *word)?;
+        }
+        Ok(())
+    }
+
+    fn write_8(&mut self, address: u64, data: &[u8]) -> Result<(), crate::Error> {
+        let range = self.ram_range(address, data.len());
+        self.ram[range].copy_from_slice(data);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), crate::Error> {
+        Ok(())
+    }
+}
+
+impl CoreInterface for MockCore {
+    fn wait_for_core_halted(&mut self, _timeout: Duration) -> Result<(), crate::Error> {
+        Ok(())
+    }
+
+    fn core_halted(&mut self) -> Result<bool, crate::Error> {
+        Ok(true)
+    }
+
+    fn status(&mut self) -> Result<CoreStatus, crate::Error> {
+        Ok(CoreStatus::Halted(HaltReason::Request))
+    }
+
+    fn halt(&mut self, _timeout: Duration) -> Result<CoreInformation, crate::Error> {
+        Ok(CoreInformation { pc: self.pc })
+    }
+
+    fn run(&mut self) -> Result<(), crate::Error> {
+        todo!()
+    }
+
+    fn reset(&mut self) -> Result<(), crate::Error> {
+        todo!()
+    }
+
+    fn reset_and_halt(&mut self, _timeout: Duration) -> Result<CoreInformation, crate::Error> {
+        todo!()
+    }
+
+    fn step(&mut self) -> Result<CoreInformation, crate::Error> {
+        let mut instruction = [0; 4];
+        self.read_8(self.pc, &mut instruction)?;
+
+        // The core halts on breakpoint instructions without executing them.
+        match instruction {
+            [0x02, 0x90, _, _] | [0x73, 0x00, 0x10, 0x00] => {}
+            [first, ..] if first & 0b11 != 0b11 => self.pc += 2,
+            _ => self.pc += 4,
+        }
+
+        Ok(CoreInformation { pc: self.pc })
+    }
+
+    fn read_core_reg(&mut self, address: RegisterId) -> Result<RegisterValue, crate::Error> {
+        if address == PC.id() {
+            return Ok(RegisterValue::U32(self.pc as u32));
+        }
+
+        Ok(self
+            .registers
+            .get(&address)
+            .copied()
+            .unwrap_or(RegisterValue::U32(0)))
+    }
+
+    fn write_core_reg(
+        &mut self,
+        address: RegisterId,
+        value: RegisterValue,
+    ) -> Result<(), crate::Error> {
+        if address == PC.id() {
+            self.pc = value.try_into()?;
+        } else {
+            self.registers.insert(address, value);
+        }
+        Ok(())
+    }
+
+    fn available_breakpoint_units(&mut self) -> Result<u32, crate::Error> {
+        Ok(self.units.len() as u32)
+    }
+
+    fn hw_breakpoints(&mut self) -> Result<Vec<Option<u64>>, crate::Error> {
+        Ok(self
+            .units
+            .iter()
+            .map(|unit| match unit {
+                Some(MockUnit::Breakpoint(address)) => Some(*address),
+                _ => None,
+            })
+            .collect())
+    }
+
+    fn enable_breakpoints(&mut self, _state: bool) -> Result<(), crate::Error> {
+        Ok(())
+    }
+
+    fn set_hw_breakpoint(&mut self, unit_index: usize, addr: u64) -> Result<(), crate::Error> {
+        self.units[unit_index] = Some(MockUnit::Breakpoint(addr));
+        Ok(())
+    }
+
+    fn clear_hw_breakpoint(&mut self, unit_index: usize) -> Result<(), crate::Error> {
+        self.units[unit_index] = None;
+        Ok(())
+    }
+
+    fn available_watchpoint_units(&mut self) -> Result<u32, crate::Error> {
+        Ok(self.units.len() as u32)
+    }
+
+    fn watchpoints(&mut self) -> Result<Vec<Option<Watchpoint>>, crate::Error> {
+        Ok(self
+            .units
+            .iter()
+            .rev()
+            .map(|unit| match unit {
+                Some(MockUnit::Watchpoint(watchpoint)) => Some(*watchpoint),
+                _ => None,
+            })
+            .collect())
+    }
+
+    fn watchpoint_unit_in_use_by_breakpoint(
+        &mut self,
+        unit_index: usize,
+    ) -> Result<bool, crate::Error> {
+        Ok(matches!(
+            self.units[self.watchpoint_unit(unit_index)],
+            Some(MockUnit::Breakpoint(_))
+        ))
+    }
+
+    fn breakpoint_unit_in_use_by_watchpoint(
+        &mut self,
+        unit_index: usize,
+    ) -> Result<bool, crate::Error> {
+        Ok(matches!(
+            self.units[unit_index],
+            Some(MockUnit::Watchpoint(_))
+        ))
+    }
+
+    fn set_watchpoint(
+        &mut self,
+        unit_index: usize,
+        address: u64,
+        len: u64,
+        kind: WatchpointKind,
+    ) -> Result<(), crate::Error> {
+        let unit_index = self.watchpoint_unit(unit_index);
+        self.units[unit_index] = Some(MockUnit::Watchpoint(Watchpoint { address, len, kind }));
+        Ok(())
+    }
+
+    fn clear_watchpoint(&mut self, unit_index: usize) -> Result<(), crate::Error> {
+        let unit_index = self.watchpoint_unit(unit_index);
+        self.units[unit_index] = None;
+        Ok(())
+    }
+
+    fn registers(&self) -> &'static CoreRegisters {
+        if self.rv64 {
+            &RISCV64_CORE_REGSISTERS
+        } else {
+            &RISCV_CORE_REGSISTERS
+        }
+    }
+
+    fn program_counter(&self) -> &'static CoreRegister {
+        &PC
+    }
+
+    fn frame_pointer(&self) -> &'static CoreRegister {
+        todo!()
+    }
+
+    fn stack_pointer(&self) -> &'static CoreRegister {
+        &SP
+    }
+
+    fn return_address(&self) -> &'static CoreRegister {
+        &RA
+    }
+
+    fn hw_breakpoints_enabled(&self) -> bool {
+        true
+    }
+
+    fn invalidate_instruction_cache(&mut self, address: u64, len: u64) -> Result<(), crate::Error> {
+        self.invalidated.borrow_mut().push((address, len));
+        Ok(())
+    }
+
+    fn architecture(&self) -> Architecture {
+        Architecture::Riscv
+    }
+
+    fn core_type(&self) -> CoreType {
+        CoreType::Riscv
+    }
+
+    fn instruction_set(&mut self) -> Result<InstructionSet, crate::Error> {
+        Ok(InstructionSet::RV32C)
+    }
+
+    fn fpu_support(&mut self) -> Result<bool, crate::Error> {
+        Ok(false)
+    }
+
+    fn floating_point_register_count(&mut self) -> Result<usize, crate::Error> {
+        Ok(0)
+    }
+
+    fn reset_catch_set(&mut self) -> Result<(), crate::Error> {
+        todo!()
+    }
+
+    fn reset_catch_clear(&mut self) -> Result<(), crate::Error> {
+        todo!()
+    }
+
+    fn debug_core_stop(&mut self) -> Result<(), crate::Error> {
+        todo!()
+    }
+
+    fn id(&self) -> usize {
+        0
+    }
+}
+
 #[test]
 fn mock_memory_read() {
     let mut mock_memory = MockMemory::new();
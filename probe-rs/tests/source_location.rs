@@ -114,3 +114,62 @@ fn find_non_existing_unit_by_path() {
         .get_breakpoint_location(&unit_path, 14, None)
         .is_err());
 }
+
+#[test]
+fn function_breakpoint_locations() {
+    let debug_info = DebugInfo::from_file("tests/inlined-functions").unwrap();
+
+    let addresses = |function_name| {
+        debug_info
+            .get_function_breakpoint_locations(function_name)
+            .expect("Failed to find function breakpoint locations.")
+            .into_iter()
+            .map(|breakpoint| breakpoint.address)
+            .collect::<Vec<_>>()
+    };
+
+    // The breakpoint is set after the prologue of the function.
+    assert_eq!(addresses("__cortex_m_rt_main"), [0x166]);
+    assert_eq!(addresses("inlined_functions::__cortex_m_rt_main"), [0x166]);
+
+    // Every inlined instance of the function gets a breakpoint.
+    assert_eq!(addresses("cortex_m::interrupt::disable"), [0x16c, 0x196]);
+    assert_eq!(addresses("interrupt::disable"), [0x16c, 0x196]);
+
+    assert!(debug_info
+        .get_function_breakpoint_locations("errupt::disable")
+        .is_err());
+}
+
+#[test]
+fn additional_file_with_load_offset() {
+    let mut debug_info = DebugInfo::from_file("tests/probe-rs-debugger-test").unwrap();
+    debug_info
+        .add_file("tests/inlined-functions", 0x2000_0000)
+        .unwrap();
+
+    // Addresses in the first file are not affected.
+    assert_eq!(
+        debug_info
+            .get_source_location(TEST_DATA[0].0)
+            .and_then(|location| location.line),
+        Some(TEST_DATA[0].1)
+    );
+
+    // Addresses in the additional file are moved by the load offset.
+    assert_eq!(
+        debug_info
+            .function_name(0x2000_0166, false)
+            .expect("Failed to find function name."),
+        Some("__cortex_m_rt_main".to_owned())
+    );
+    assert!(debug_info
+        .get_function_breakpoint_locations("inlined_functions::__cortex_m_rt_main")
+        .expect("Failed to find function breakpoint locations.")
+        .iter()
+        .any(|breakpoint| breakpoint.address == 0x2000_0166
+            && breakpoint
+                .source_location
+                .low_pc
+                .is_some_and(|low_pc| low_pc >= 0x2000_0000)));
+}
@@ -1,4 +1,4 @@
-use probe_rs::rtt::{Channels, Rtt, RttChannel, ScanRegion};
+use probe_rs::rtt::{Channels, Rtt, RttChannel, RttSocket, RttSocketAddress, ScanRegion};
 use probe_rs::{config::TargetSelector, DebugProbeInfo};
 use probe_rs::{Lister, Permissions};
 
@@ -103,6 +103,12 @@ struct Opts {
         value_parser = parse_scan_region,
         help = "Memory region to scan for control block. You can specify either an exact starting address '0x1000' or a range such as '0x0000..0x1000'. Both decimal and hex are accepted.")]
     scan_region: ScanRegion,
+
+    #[clap(
+        long,
+        help = "Also serve the up and down channel on a socket, e.g. 'tcp:127.0.0.1:19021' or 'unix:/tmp/rtt'."
+    )]
+    socket: Option<RttSocketAddress>,
 }
 
 fn main() -> Result<()> {
@@ -213,6 +219,13 @@ fn main() -> Result<()> {
 
     let stdin = down_channel.as_ref().map(|_| stdin_channel());
 
+    let mut socket = match opts.socket.as_ref().map(RttSocket::bind).transpose() {
+        Ok(socket) => socket,
+        Err(err) => {
+            bail!("Error opening socket: {err}");
+        }
+    };
+
     eprintln!("Found control block at 0x{:08x}", rtt.ptr());
 
     let mut up_buf = [0u8; 1024];
@@ -239,12 +252,23 @@ fn main() -> Result<()> {
                     bail!("Error writing to stdout: {err}");
                 }
             }
+
+            if let Some(socket) = socket.as_mut() {
+                socket.send(&up_buf[..count]);
+            }
         }
 
+        // Input from the socket is dropped when there is no down channel.
+        let socket_input = socket
+            .as_mut()
+            .map(|socket| socket.take_input())
+            .unwrap_or_default();
+
         if let (Some(down_channel), Some(stdin)) = (down_channel.as_ref(), &stdin) {
             if let Ok(bytes) = stdin.try_recv() {
                 down_buf.extend_from_slice(bytes.as_slice());
             }
+            down_buf.extend_from_slice(&socket_input);
 
             if !down_buf.is_empty() {
                 let count = match down_channel.write(&mut core, down_buf.as_mut()) {
@@ -108,8 +108,10 @@ pub fn cmd_elf(
                 rtt_scan_ranges: None,
                 scan_chain: None,
                 default_binary_format: None,
+                config_areas: vec![],
             }],
             flash_algorithms: vec![algorithm],
+            config_areas: vec![],
             source: BuiltIn,
         };
 
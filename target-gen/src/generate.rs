@@ -76,6 +76,7 @@ where
                 pack_file_release: pack_file_release.clone(),
                 variants: Vec::new(),
                 flash_algorithms: Vec::new(),
+                config_areas: Vec::new(),
                 source: probe_rs::config::TargetDescriptionSource::BuiltIn,
             });
             // This unwrap is always safe as we insert at least one item previously.
@@ -166,6 +167,7 @@ where
             rtt_scan_ranges: None,
             scan_chain: None, // TODO, parse from sdf
             default_binary_format: None,
+            config_areas: vec![],
         });
     }
 
@@ -188,7 +190,10 @@ fn create_core(processor: &Processor) -> Result<ProbeCore> {
                 debug_base: None,
                 cti_base: None,
             }),
-            Architecture::Riscv => CoreAccessOptions::Riscv(RiscvCoreAccessOptions {}),
+            Architecture::Riscv => CoreAccessOptions::Riscv(RiscvCoreAccessOptions {
+                hart_id: None,
+                halt_group: false,
+            }),
             Architecture::Xtensa => CoreAccessOptions::Xtensa(XtensaCoreAccessOptions {}),
         },
     })